@@ -8,15 +8,23 @@ mod indicatif_progress;
 use clap::{Parser, Subcommand};
 use indicatif_progress::IndicatifProgress;
 use lazy_static::lazy_static;
-use ewe_lib::automaton::ActionWrapper;
+use ewe_lib::automaton::{changelog_recent, Action, ActionWrapper, ChangeLogEntry};
 use ewe_lib::change_manager;
 use ewe_lib::change_manager::ChangeList;
-use ewe_lib::progress::NullProgress;
+use ewe_lib::definitions::{check_definitions, opt_in_errors};
+use ewe_lib::diagnostic::{diagnostics, SourceLocator};
+use ewe_lib::genus::check_genus;
+use ewe_lib::hierarchy::{check_hierarchy, repair_batch};
+use ewe_lib::progress::{NullProgress, Progress};
 use ewe_lib::rels::{SenseRelType, SynsetRelType};
-use ewe_lib::validate::{fix, validate};
-use ewe_lib::wordnet::rdf::{write_lexicon_rdf, RdfExportOptions, RdfFormat};
+use ewe_lib::undo;
+use ewe_lib::validate::{fix, validate, ValidationError};
+use ewe_lib::user_rules::UserRules;
+use ewe_lib::validation_profile::{Severity, ValidationProfile};
+use ewe_lib::wordnet::json::{read_lexicon_json, write_lexicon_json};
+use ewe_lib::wordnet::rdf::{read_lexicon_rdf, write_lexicon_rdf, RdfExportOptions, RdfFormat};
 use ewe_lib::wordnet::xml::{read_lexicon_xml, write_lexicon_xml};
-use ewe_lib::wordnet::{write_wndb, Lexicon, LexiconHashMapBackend, LexiconMetadata, PosKey, Sense, SenseId, SenseOrSynsetId, Synset, SynsetId, WndbExportOptions};
+use ewe_lib::wordnet::{read_omw_tab, read_wndb, write_omw_tab, write_wndb, Lexicon, LexiconHashMapBackend, LexiconMetadata, PosKey, Sense, SenseId, SenseOrSynsetId, Synset, SynsetId, WndbExportOptions};
 use regex::Regex;
 use std::borrow::Cow;
 use std::collections::HashMap;
@@ -573,12 +581,74 @@ fn change_relation<L: Lexicon>(wn: &mut L, change_list: &mut ChangeList) {
     }
 }
 
+/// The validation profile for the wordnet at `path` (see `ValidationProfile::for_source`)
+fn validation_profile(path: &str) -> ValidationProfile {
+    ValidationProfile::for_source(path).unwrap_or_else(|e| {
+        eprintln!("{}", e);
+        exit(-1);
+    })
+}
+
+/// The project's own validation rules for the wordnet at `path` (see `UserRules::for_source`)
+fn user_rules(path: &str) -> UserRules {
+    UserRules::for_source(path).unwrap_or_else(|e| {
+        eprintln!("{}", e);
+        exit(-1);
+    })
+}
+
+/// What the project adds to `validate` for the wordnet at `path`: every breach of its own rules,
+/// and the opt-in checks its profile turns on
+fn project_checks<L: Lexicon, P: Progress>(
+    wn: &L,
+    path: &str,
+    progress: &mut P,
+) -> ewe_lib::wordnet::Result<Vec<ValidationError>> {
+    let mut found = user_rules(path).check(wn)?;
+    found.extend(opt_in_errors(wn, &validation_profile(path), progress)?);
+    Ok(found)
+}
+
+/// Everything `validate` finds in the wordnet at `path`, followed by what the project's own
+/// checks find (see `project_checks`)
+fn validate_project<L: Lexicon, P: Progress>(
+    wn: &L,
+    path: &str,
+    progress: &mut P,
+) -> ewe_lib::wordnet::Result<Vec<ValidationError>> {
+    let mut found = validate(wn, progress)?;
+    found.extend(project_checks(wn, path, progress)?);
+    Ok(found)
+}
+
+/// Prints what validation found, as sorted into errors and warnings by the project's profile
+fn print_validation(errors: &[ValidationError], warnings: &[ValidationError]) {
+    for error in errors.iter() {
+        println!("{}", error);
+    }
+    for warning in warnings.iter() {
+        println!("Warning: {}", warning);
+    }
+    if errors.is_empty() {
+        println!("No validation errors!");
+    } else {
+        println!("{} validation errors", errors.len());
+    }
+    if !warnings.is_empty() {
+        println!("{} validation warnings", warnings.len());
+    }
+}
+
 fn save<L: Lexicon>(
     wn: &L,
     path: &str,
 ) -> result::Result<bool, ewe_lib::wordnet::LexiconSaveError> {
     let mut progress = IndicatifProgress::new();
-    let errors = validate(wn, &mut progress)?;
+    let (errors, warnings) =
+        validation_profile(path).classify(validate_project(wn, path, &mut progress)?);
+    for warning in warnings {
+        println!("Warning: {}", warning);
+    }
     if !errors.is_empty() {
         println!("There were validation errors");
         for error in errors {
@@ -645,19 +715,17 @@ fn main_menu<L: Lexicon>(wn: &mut L, path: &str, ewe_changed: &mut ChangeList) -
         "5" => change_relation(wn, ewe_changed),
         "6" => {
             let mut progress = IndicatifProgress::new();
-            let errors = validate(wn, &mut progress).expect("Could not complete validation");
-            for error in errors.iter() {
-                println!("{}", error);
-            }
-            if errors.is_empty() {
-                println!("No validation errors!");
-            } else {
-                println!("{} validation errors", errors.len());
-            }
+            let errors =
+                validate_project(wn, path, &mut progress).expect("Could not complete validation");
+            let (errors, warnings) = validation_profile(path).classify(errors);
+            print_validation(&errors, &warnings);
         }
         "7" => {
             let mut progress = IndicatifProgress::new();
-            let errors = validate(wn, &mut progress).expect("Could not complete validation");
+            let errors =
+                validate_project(wn, path, &mut progress).expect("Could not complete validation");
+            let (mut errors, warnings) = validation_profile(path).classify(errors);
+            errors.extend(warnings);
             let mut fixed = 0;
             for error in errors.iter() {
                 if fix(wn, error, ewe_changed).expect("Could not fix error") {
@@ -713,6 +781,66 @@ enum Command {
         /// The path to the script file to execute
         script: String,
     },
+    /// List the changes made by automaton scripts (and undo/redo), newest first
+    History {
+        /// How many changes to list
+        #[arg(short, long, default_value_t = 20)]
+        limit: usize,
+    },
+    /// Undo a change made by an automaton script
+    Undo {
+        /// The id of the change to undo, as listed by `history`; defaults to the most
+        /// recent change not already undone
+        id: Option<u64>,
+    },
+    /// Re-apply the most recently undone change
+    Redo,
+    /// Validate the wordnet, exiting with a non-zero status if there are errors
+    Validate {
+        /// How to report what validation found
+        #[arg(long, value_enum, default_value_t = ReportFormat::Text)]
+        format: ReportFormat,
+    },
+    /// Apply every automatic fix for validation errors, as one change that can be undone
+    Fix,
+    /// Review definitions against hypernyms: list the noun and verb synsets whose definition's
+    /// genus is not a member of any hypernym within `--depth` steps, with the synsets that are
+    Genus {
+        /// Only review this lexicographer file (may be given more than once); all if omitted
+        #[arg(long)]
+        lexfile: Vec<String>,
+        /// How many hypernym steps up to look for the genus
+        #[arg(long, default_value_t = 3)]
+        depth: usize,
+        /// How to report the synsets found
+        #[arg(long, value_enum, default_value_t = ReportFormat::Text)]
+        format: ReportFormat,
+    },
+    /// Review the wording of definitions: list the circular definitions (that use one of the
+    /// synset's own members) and the words in definitions and examples that are not in the
+    /// wordnet. Both are listed unless one is asked for.
+    Definitions {
+        /// List the circular definitions
+        #[arg(long)]
+        circular: bool,
+        /// List the words that are not in the wordnet
+        #[arg(long)]
+        unknown: bool,
+        /// How to report what was found
+        #[arg(long, value_enum, default_value_t = ReportFormat::Text)]
+        format: ReportFormat,
+    },
+    /// Review the hypernym graph: list the redundant hypernym links (to a synset also reached
+    /// through others) and the cycles, with the fewest links whose removal breaks each
+    Hierarchy {
+        /// Write an automaton batch removing the redundant links, and the preferred links
+        /// breaking each cycle, to this file - to review, then apply with `automaton`
+        #[arg(long)]
+        batch: Option<PathBuf>,
+        /// How to report what was found
+        #[arg(long, value_enum, default_value_t = ReportFormat::Text)]
+        format: ReportFormat,
+    },
     /// Search for a specific word
     Word {
         /// The word you want to search for
@@ -779,6 +907,34 @@ enum ExportFormat {
         #[arg(long)]
         url: Option<String>,
     },
+    /// Export as a whole-lexicon WN-LMF JSON-LD document, with the same content and ids as
+    /// `export xml` (https://globalwordnet.github.io/schemas/#json)
+    Json {
+        /// Path to write the JSON document to
+        path: PathBuf,
+
+        /// The lexicon's `@id` prefix used to build every id in the document
+        #[arg(long, default_value = "oewn")]
+        id_prefix: String,
+        /// The lexicon's `label`
+        #[arg(long, default_value = "Open English Wordnet")]
+        label: String,
+        /// The lexicon's `language` (BCP 47 code)
+        #[arg(long, default_value = "en")]
+        language: String,
+        /// The lexicon's `email` contact address
+        #[arg(long)]
+        email: Option<String>,
+        /// The lexicon's `license` URL
+        #[arg(long, default_value = "https://creativecommons.org/licenses/by/4.0")]
+        license: String,
+        /// The lexicon's `version`
+        #[arg(long, default_value = "1")]
+        version: String,
+        /// The lexicon's `url` project homepage
+        #[arg(long)]
+        url: Option<String>,
+    },
     /// Export as the classic WNDB (Princeton WordNet database) file set
     /// (data.*/index.*/index.sense/*.exc)
     Wndb {
@@ -794,6 +950,11 @@ enum ExportFormat {
         /// together case-insensitively in `index.{pos}` (e.g. `afghani`/`Afghani`)
         #[arg(long)]
         sense_orders: Option<PathBuf>,
+
+        /// Tag counts for `cntlist`/`cntlist.rev`, in `cntlist.rev`'s
+        /// `sense_key sense_number tag_cnt` format (e.g. Princeton's own `cntlist.rev`)
+        #[arg(long)]
+        sense_counts: Option<PathBuf>,
     },
     /// Export as a whole-lexicon RDF document (Turtle or RDF/XML), suitable for an
     /// `en-word.net`-style RDF release - every `LexicalEntry` is declared exactly once,
@@ -830,6 +991,44 @@ enum ExportFormat {
         #[arg(long)]
         url: Option<String>,
     },
+    /// Export as an Open Multilingual Wordnet tab file (`wn-data-{lang}.tab`): the lemmas,
+    /// definitions and examples of every synset, by synset id
+    Omw {
+        /// Path to write the tab file to
+        path: PathBuf,
+
+        /// The language code every row is tagged with
+        #[arg(long, default_value = "en")]
+        language: String,
+        /// The wordnet's name, for the header
+        #[arg(long, default_value = "Open English Wordnet")]
+        label: String,
+        /// The project homepage, for the header
+        #[arg(long)]
+        url: Option<String>,
+        /// The license, for the header
+        #[arg(long, default_value = "https://creativecommons.org/licenses/by/4.0")]
+        license: String,
+    },
+}
+
+/// How a report (`validate`, `genus`) is written
+#[derive(clap::ValueEnum, Clone, Copy, Debug)]
+enum ReportFormat {
+    /// One line per finding, as the interactive editor prints them
+    Text,
+    /// A JSON array with one object per finding (for `validate`, a diagnostic with its code
+    /// and source file and line)
+    Json,
+}
+
+impl std::fmt::Display for ReportFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ReportFormat::Text => write!(f, "text"),
+            ReportFormat::Json => write!(f, "json"),
+        }
+    }
 }
 
 /// The RDF serialization to write - maps directly onto `ewe_lib::wordnet::rdf::RdfFormat`
@@ -867,6 +1066,69 @@ enum ImportFormat {
         /// Path to the XML document to import
         path: PathBuf,
     },
+    /// Import from a WN-LMF JSON-LD document (https://globalwordnet.github.io/schemas/#json),
+    /// saving the result as a YAML source tree
+    Json {
+        /// Path to the JSON document to import
+        path: PathBuf,
+    },
+    /// Import from a WNDB (Princeton WordNet database) directory, saving the result as a YAML
+    /// source tree. WNDB has no lexicon metadata of its own, so that is given here.
+    Wndb {
+        /// Directory holding the `data.*`, `index.sense` and `*.exc` files
+        path: PathBuf,
+
+        /// The id prefix of the new project
+        #[arg(long, default_value = "wn")]
+        id_prefix: String,
+        /// The new project's name
+        #[arg(long, default_value = "My Wordnet")]
+        label: String,
+        /// The language of the wordnet (BCP 47 code)
+        #[arg(long, default_value = "en")]
+        language: String,
+        /// A contact email address
+        #[arg(long)]
+        email: Option<String>,
+        /// The license URL
+        #[arg(long, default_value = "https://creativecommons.org/licenses/by/4.0")]
+        license: String,
+        /// The version
+        #[arg(long, default_value = "1")]
+        version: String,
+        /// The project homepage
+        #[arg(long)]
+        url: Option<String>,
+    },
+    /// Import from an OntoLex/`wn:` RDF document (https://globalwordnet.github.io/schemas/#rdf),
+    /// saving the result as a YAML source tree. The project's metadata is taken from the
+    /// document's `lime:Lexicon`, if it has one.
+    Rdf {
+        /// Path to the RDF document to import
+        path: PathBuf,
+
+        /// The RDF serialization the document is in
+        #[arg(long, value_enum, default_value_t = RdfSyntax::Turtle)]
+        format: RdfSyntax,
+        /// The id prefix of the new project, which RDF has no equivalent of
+        #[arg(long, default_value = "wn")]
+        id_prefix: String,
+    },
+    /// Import from an Open Multilingual Wordnet tab file (`wn-data-{lang}.tab`), saving the
+    /// result as a YAML source tree. Its lemmas, definitions and examples are attached to the
+    /// synsets of a base wordnet, by PWN offset or ILI, which give the new synsets their ids,
+    /// lexicographer files and relations.
+    Omw {
+        /// Path to the tab file to import
+        path: PathBuf,
+
+        /// The base wordnet (e.g. a checkout of OEWN) whose synsets the file refers to
+        #[arg(long)]
+        base: PathBuf,
+        /// The id prefix of the new project, which the tab file has no equivalent of
+        #[arg(long, default_value = "wn")]
+        id_prefix: String,
+    },
 }
 
 /// Whether `path` looks like a YAML wordnet source directory. `entries-a.yaml` is the
@@ -927,25 +1189,362 @@ fn run_automaton(script: &str, wordnet: Option<PathBuf>) {
         exit(-1);
     });
 
+    load_changelog(&mut wn, &path);
+
     let mut ewe_changed = ChangeList::new();
+    let profile = validation_profile(&path);
 
-    let (_, validation_report) = ewe_lib::automaton::apply_automaton(actions, &mut wn, &mut ewe_changed)
+    let (_, validation_report) = ewe_lib::automaton::apply_automaton_with_profile(
+            actions, &mut wn, &mut ewe_changed, &profile)
         .unwrap_or_else(|e| {
             eprintln!("Could not apply automaton: {}", e);
             exit(-1);
         });
     if let Some(report) = validation_report {
-        for error in report.errors.iter() {
-            println!("{}", error);
+        let (errors, warnings) = profile.classify(report.errors);
+        print_validation(&errors, &warnings);
+    }
+
+    if save(&wn, &path).expect("Could not save") {
+        save_changelog(&wn, &path);
+    }
+}
+
+/// Where the CLI keeps its change log between runs: next to `deprecations.csv`, i.e.
+/// `<path>/../changelog.yaml`. `LexiconHashMapBackend` only keeps the log in memory, so
+/// without this nothing `ewe automaton` applied could be undone by a later `ewe undo`.
+fn changelog_path(path: &str) -> PathBuf {
+    Path::new(path).join("../changelog.yaml")
+}
+
+/// Loads the change log saved by `save_changelog` (if any) into `wn`, which must have an
+/// empty log - entries get their ids back by being appended in order.
+fn load_changelog<L: Lexicon>(wn: &mut L, path: &str) {
+    let file = changelog_path(path);
+    if !file.exists() {
+        return;
+    }
+    let f = File::open(&file).unwrap_or_else(|e| {
+        eprintln!("Could not open {}: {}", file.display(), e);
+        exit(-1);
+    });
+    let entries: Vec<ChangeLogEntry> = serde_yaml::from_reader(f).unwrap_or_else(|e| {
+        eprintln!("Could not parse {}: {}", file.display(), e);
+        exit(-1);
+    });
+    for entry in entries {
+        let yaml = serde_yaml::to_string(&entry).expect("Cannot serialize change log");
+        wn.changelog_append(yaml).expect("Cannot write change log");
+    }
+}
+
+fn save_changelog<L: Lexicon>(wn: &L, path: &str) {
+    let mut entries: Vec<ChangeLogEntry> = changelog_recent(wn, usize::MAX, None)
+        .expect("Cannot read change log")
+        .into_iter()
+        .map(|(_, entry)| entry)
+        .collect();
+    entries.reverse();
+    let file = changelog_path(path);
+    let f = File::create(&file).unwrap_or_else(|e| {
+        eprintln!("Could not write {}: {}", file.display(), e);
+        exit(-1);
+    });
+    serde_yaml::to_writer(f, &entries).expect("Cannot write change log");
+}
+
+fn run_history(limit: usize, wordnet: Option<PathBuf>) {
+    let (path, mut wn) = locate_wordnet(wordnet).unwrap_or_else(|e| {
+        eprintln!("{}", e);
+        exit(-1);
+    });
+    load_changelog(&mut wn, &path);
+    let entries = changelog_recent(&wn, limit, None).unwrap_or_else(|e| {
+        eprintln!("Could not read change log: {}", e);
+        exit(-1);
+    });
+    if entries.is_empty() {
+        println!("No changes recorded");
+    }
+    for (id, entry) in entries {
+        let note = match (entry.undo_of, entry.redo_of) {
+            (Some(undone), _) => format!(" (undo of {})", undone),
+            (_, Some(redone)) => format!(" (redo of {})", redone),
+            _ => String::new(),
+        };
+        println!("{}{}:", id, note);
+        for action in entry.actions.iter() {
+            println!("    {}", action.summary());
         }
-        if report.errors.is_empty() {
-            println!("No validation errors!");
-        } else {
-            println!("{} validation errors", report.errors.len());
+    }
+}
+
+/// Validates the wordnet and reports what was found in `format`, exiting with status 1 if
+/// there are any errors (warnings alone do not fail)
+fn run_validate(format: ReportFormat, wordnet: Option<PathBuf>) {
+    let (path, wn) = locate_wordnet(wordnet).unwrap_or_else(|e| {
+        eprintln!("{}", e);
+        exit(-1);
+    });
+    let profile = validation_profile(&path);
+    let found = match format {
+        ReportFormat::Text => validate_project(&wn, &path, &mut IndicatifProgress::new()),
+        ReportFormat::Json => validate_project(&wn, &path, &mut NullProgress),
+    }
+    .unwrap_or_else(|e| {
+        eprintln!("Could not complete validation: {}", e);
+        exit(-1);
+    });
+    let failed = match format {
+        ReportFormat::Text => {
+            let (errors, warnings) = profile.classify(found);
+            print_validation(&errors, &warnings);
+            !errors.is_empty()
+        }
+        ReportFormat::Json => {
+            let diagnostics = diagnostics(&wn, &path, &profile, found);
+            println!(
+                "{}",
+                serde_json::to_string_pretty(&diagnostics).expect("Could not serialize diagnostics")
+            );
+            diagnostics.iter().any(|d| d.severity == Severity::Error)
+        }
+    };
+    if failed {
+        exit(1);
+    }
+}
+
+/// Applies every automatic fix for what validation finds as a single `fix_validation_errors`
+/// batch (so it is logged, and `ewe undo` reverses it), reports what is left, then saves.
+fn run_fix(wordnet: Option<PathBuf>) {
+    let (path, mut wn) = locate_wordnet(wordnet).unwrap_or_else(|e| {
+        eprintln!("{}", e);
+        exit(-1);
+    });
+    load_changelog(&mut wn, &path);
+
+    let mut ewe_changed = ChangeList::new();
+    let profile = validation_profile(&path);
+    let (_, report) = ewe_lib::automaton::apply_automaton_with_profile(
+        vec![Action::FixValidationErrors],
+        &mut wn,
+        &mut ewe_changed,
+        &profile,
+    )
+    .unwrap_or_else(|e| {
+        eprintln!("Could not fix validation errors: {}", e);
+        exit(-1);
+    });
+    let mut report = report.expect("fix_validation_errors always reports what is left");
+    println!("{} validation errors fixed", report.fixed);
+    report.errors.extend(project_checks(&wn, &path, &mut NullProgress).unwrap_or_else(|e| {
+        eprintln!("Could not check validation rules: {}", e);
+        exit(-1);
+    }));
+    let (errors, warnings) = profile.classify(report.errors);
+    print_validation(&errors, &warnings);
+    if report.fixed == 0 {
+        return;
+    }
+
+    if save(&wn, &path).expect("Could not save") {
+        save_changelog(&wn, &path);
+    }
+}
+
+/// Lists the synsets in `lexfiles` (all, if empty) whose definition's genus is not among their
+/// hypernyms within `depth` steps, with the synsets that have it as a member
+fn run_genus(lexfiles: &[String], depth: usize, format: ReportFormat, wordnet: Option<PathBuf>) {
+    let (_, wn) = locate_wordnet(wordnet).unwrap_or_else(|e| {
+        eprintln!("{}", e);
+        exit(-1);
+    });
+    let mismatches = match format {
+        ReportFormat::Text => check_genus(&wn, lexfiles, depth, &mut IndicatifProgress::new()),
+        ReportFormat::Json => check_genus(&wn, lexfiles, depth, &mut NullProgress),
+    }
+    .unwrap_or_else(|e| {
+        eprintln!("Could not complete the review: {}", e);
+        exit(-1);
+    });
+    match format {
+        ReportFormat::Text => {
+            for mismatch in mismatches.iter() {
+                let candidates: Vec<String> = mismatch
+                    .candidates
+                    .iter()
+                    .map(|id| {
+                        let members = wn.members_by_id(id).unwrap_or_default();
+                        format!("{} ({})", id.as_str(), members.join(", "))
+                    })
+                    .collect();
+                println!(
+                    "{}: \"{}\" - genus \"{}\" is not a hypernym; {}",
+                    mismatch.synset.as_str(),
+                    mismatch.definition,
+                    mismatch.genus,
+                    if candidates.is_empty() {
+                        "no synset has it as a member".to_string()
+                    } else {
+                        format!("candidates: {}", candidates.join("; "))
+                    }
+                );
+            }
+            println!("{} definitions to review", mismatches.len());
+        }
+        ReportFormat::Json => {
+            println!(
+                "{}",
+                serde_json::to_string_pretty(&mismatches).expect("Could not serialize the review")
+            );
+        }
+    }
+}
+
+/// Lists the circular definitions (with `circular`) and the words of definitions and examples
+/// not in the wordnet (with `unknown`), whatever the project's profile says of these checks
+fn run_definitions(circular: bool, unknown: bool, format: ReportFormat, wordnet: Option<PathBuf>) {
+    let (path, wn) = locate_wordnet(wordnet).unwrap_or_else(|e| {
+        eprintln!("{}", e);
+        exit(-1);
+    });
+    let found = match format {
+        ReportFormat::Text => {
+            check_definitions(&wn, circular, unknown, &mut IndicatifProgress::new())
+        }
+        ReportFormat::Json => check_definitions(&wn, circular, unknown, &mut NullProgress),
+    }
+    .unwrap_or_else(|e| {
+        eprintln!("Could not complete the review: {}", e);
+        exit(-1);
+    });
+    match format {
+        ReportFormat::Text => {
+            for error in found.iter() {
+                println!("{}", error);
+            }
+            println!("{} definitions to review", found.len());
+        }
+        ReportFormat::Json => {
+            let mut locator = SourceLocator::new(&path);
+            let diagnostics: Vec<_> = found
+                .iter()
+                .map(|error| locator.diagnostic(&wn, error, Severity::Warning))
+                .collect();
+            println!(
+                "{}",
+                serde_json::to_string_pretty(&diagnostics).expect("Could not serialize the review")
+            );
+        }
+    }
+}
+
+/// Lists the redundant links and the cycles of the hypernym graph, writing the links to remove
+/// as an automaton batch to `batch` if given
+fn run_hierarchy(batch: Option<&Path>, format: ReportFormat, wordnet: Option<PathBuf>) {
+    let (_, wn) = locate_wordnet(wordnet).unwrap_or_else(|e| {
+        eprintln!("{}", e);
+        exit(-1);
+    });
+    let report = match format {
+        ReportFormat::Text => check_hierarchy(&wn, &mut IndicatifProgress::new()),
+        ReportFormat::Json => check_hierarchy(&wn, &mut NullProgress),
+    }
+    .unwrap_or_else(|e| {
+        eprintln!("Could not complete the review: {}", e);
+        exit(-1);
+    });
+    match format {
+        ReportFormat::Text => {
+            let ids = |ids: &[SynsetId]| {
+                ids.iter().map(|id| id.as_str()).collect::<Vec<_>>().join(", ")
+            };
+            for redundant in report.redundant.iter() {
+                println!(
+                    "{} -{}-> {}: redundant, already through {}",
+                    redundant.link.source.as_str(),
+                    redundant.link.relation,
+                    redundant.link.target.as_str(),
+                    ids(&redundant.via)
+                );
+            }
+            for cycle in report.cycles.iter() {
+                println!("Cycle of {}; remove any of:", ids(&cycle.synsets));
+                for cut in cycle.cuts.iter() {
+                    let links: Vec<String> = cut
+                        .iter()
+                        .map(|link| {
+                            format!(
+                                "{} -{}-> {}",
+                                link.source.as_str(),
+                                link.relation,
+                                link.target.as_str()
+                            )
+                        })
+                        .collect();
+                    println!("  {}", links.join(" and "));
+                }
+            }
+            println!(
+                "{} redundant links, {} cycles",
+                report.redundant.len(),
+                report.cycles.len()
+            );
+        }
+        ReportFormat::Json => {
+            println!(
+                "{}",
+                serde_json::to_string_pretty(&report).expect("Could not serialize the review")
+            );
+        }
+    }
+    if let Some(batch) = batch {
+        let actions: Vec<ActionWrapper> =
+            repair_batch(&report).into_iter().map(ActionWrapper).collect();
+        let yaml = serde_yaml::to_string(&actions).expect("Could not serialize the batch");
+        std::fs::write(batch, yaml).unwrap_or_else(|e| {
+            eprintln!("Could not write {}: {}", batch.display(), e);
+            exit(-1);
+        });
+        eprintln!("Wrote {} removals to {}", actions.len(), batch.display());
+    }
+}
+
+/// Undoes change `id` (or, if `None`, the most recent change not already undone) - or, with
+/// `redo`, re-applies the most recently undone change - then saves.
+fn run_undo(id: Option<u64>, redo: bool, wordnet: Option<PathBuf>) {
+    let (path, mut wn) = locate_wordnet(wordnet).unwrap_or_else(|e| {
+        eprintln!("{}", e);
+        exit(-1);
+    });
+    load_changelog(&mut wn, &path);
+
+    let mut ewe_changed = ChangeList::new();
+    let result = if redo {
+        undo::redo_last(&mut wn, &mut ewe_changed)
+    } else if let Some(id) = id {
+        undo::undo(&mut wn, id, &mut ewe_changed).map(|_| Some(id))
+    } else {
+        undo::undo_last(&mut wn, &mut ewe_changed)
+    };
+    match result {
+        Ok(Some(id)) => {
+            println!("{} change {}", if redo { "Redid" } else { "Undid" }, id);
+        }
+        Ok(None) => {
+            println!("Nothing to {}", if redo { "redo" } else { "undo" });
+            return;
+        }
+        Err(e) => {
+            eprintln!("Could not {}: {}", if redo { "redo" } else { "undo" }, e);
+            exit(-1);
         }
     }
 
-    save(&wn, &path).expect("Could not save");
+    if save(&wn, &path).expect("Could not save") {
+        save_changelog(&wn, &path);
+    }
 }
 
 fn group_by_key<A, B>(pairs: Vec<(A, B)>) -> HashMap<A, Vec<B>>
@@ -1083,6 +1682,7 @@ fn run_export_wndb(
     path: &Path,
     license_file: Option<PathBuf>,
     sense_orders: Option<PathBuf>,
+    sense_counts: Option<PathBuf>,
     wordnet: Option<PathBuf>,
 ) {
     let (_, wn) = locate_wordnet(wordnet).unwrap_or_else(|e| {
@@ -1092,6 +1692,7 @@ fn run_export_wndb(
     let options = WndbExportOptions {
         license_file,
         sense_orders,
+        sense_counts,
     };
     write_wndb(&wn, path, &options).unwrap_or_else(|e| {
         eprintln!("Could not generate WNDB: {}", e);
@@ -1116,6 +1717,38 @@ fn run_export_xml(path: &Path, metadata: LexiconMetadata, wordnet: Option<PathBu
     println!("Wrote {}", path.display());
 }
 
+fn run_export_json(path: &Path, metadata: LexiconMetadata, wordnet: Option<PathBuf>) {
+    let (_, wn) = locate_wordnet(wordnet).unwrap_or_else(|e| {
+        eprintln!("{}", e);
+        exit(-1);
+    });
+    let json = write_lexicon_json(&wn, &metadata).unwrap_or_else(|e| {
+        eprintln!("Could not generate JSON: {}", e);
+        exit(-1);
+    });
+    std::fs::write(path, json).unwrap_or_else(|e| {
+        eprintln!("Could not write {}: {}", path.display(), e);
+        exit(-1);
+    });
+    println!("Wrote {}", path.display());
+}
+
+fn run_export_omw(path: &Path, metadata: LexiconMetadata, wordnet: Option<PathBuf>) {
+    let (_, wn) = locate_wordnet(wordnet).unwrap_or_else(|e| {
+        eprintln!("{}", e);
+        exit(-1);
+    });
+    let tab = write_omw_tab(&wn, &metadata).unwrap_or_else(|e| {
+        eprintln!("Could not generate OMW tab file: {}", e);
+        exit(-1);
+    });
+    std::fs::write(path, tab).unwrap_or_else(|e| {
+        eprintln!("Could not write {}: {}", path.display(), e);
+        exit(-1);
+    });
+    println!("Wrote {}", path.display());
+}
+
 fn run_export_rdf(path: &Path, options: RdfExportOptions, wordnet: Option<PathBuf>) {
     let (_, wn) = locate_wordnet(wordnet).unwrap_or_else(|e| {
         eprintln!("{}", e);
@@ -1203,6 +1836,116 @@ fn run_import_xml(path: &Path, out_dir: Option<PathBuf>) {
     println!("Saved project to {}", out_dir.display());
 }
 
+fn run_import_json(path: &Path, out_dir: Option<PathBuf>) {
+    let out_dir = out_dir.unwrap_or_else(|| PathBuf::from("./"));
+    let file = File::open(path).unwrap_or_else(|e| {
+        eprintln!("Could not open {}: {}", path.display(), e);
+        exit(-1);
+    });
+    let (wn, metadata) = read_lexicon_json(LexiconHashMapBackend::new(), io::BufReader::new(file))
+        .unwrap_or_else(|e| {
+            eprintln!("Could not import {}: {}", path.display(), e);
+            exit(-1);
+        });
+    println!(
+        "Imported {} entries, {} synsets from lexicon {:?} ({})",
+        wn.n_entries().expect("Cannot read imported lexicon"),
+        wn.n_synsets().expect("Cannot read imported lexicon"),
+        metadata.label,
+        metadata.id_prefix
+    );
+
+    write_project_structure(&wn, &metadata, &out_dir);
+    println!("Saved project to {}", out_dir.display());
+}
+
+fn run_import_wndb(path: &Path, metadata: LexiconMetadata, out_dir: Option<PathBuf>) {
+    let out_dir = out_dir.unwrap_or_else(|| PathBuf::from("./"));
+    let wn = read_wndb(LexiconHashMapBackend::new(), path).unwrap_or_else(|e| {
+        eprintln!("Could not import {}: {}", path.display(), e);
+        exit(-1);
+    });
+    println!(
+        "Imported {} entries, {} synsets from {}",
+        wn.n_entries().expect("Cannot read imported lexicon"),
+        wn.n_synsets().expect("Cannot read imported lexicon"),
+        path.display()
+    );
+
+    write_project_structure(&wn, &metadata, &out_dir);
+    println!("Saved project to {}", out_dir.display());
+}
+
+fn run_import_rdf(path: &Path, format: RdfFormat, id_prefix: String, out_dir: Option<PathBuf>) {
+    let out_dir = out_dir.unwrap_or_else(|| PathBuf::from("./"));
+    let file = File::open(path).unwrap_or_else(|e| {
+        eprintln!("Could not open {}: {}", path.display(), e);
+        exit(-1);
+    });
+    let (wn, metadata) = read_lexicon_rdf(LexiconHashMapBackend::new(), io::BufReader::new(file), format)
+        .unwrap_or_else(|e| {
+            eprintln!("Could not import {}: {}", path.display(), e);
+            exit(-1);
+        });
+    // Without a `lime:Lexicon`, the defaults `ewe init` offers
+    let metadata = match metadata {
+        Some(metadata) => LexiconMetadata { id_prefix, ..metadata },
+        None => default_metadata(id_prefix),
+    };
+    println!(
+        "Imported {} entries, {} synsets from {}",
+        wn.n_entries().expect("Cannot read imported lexicon"),
+        wn.n_synsets().expect("Cannot read imported lexicon"),
+        path.display()
+    );
+
+    write_project_structure(&wn, &metadata, &out_dir);
+    println!("Saved project to {}", out_dir.display());
+}
+
+fn run_import_omw(path: &Path, base: &Path, id_prefix: String, out_dir: Option<PathBuf>) {
+    let out_dir = out_dir.unwrap_or_else(|| PathBuf::from("./"));
+    let (_, base) = locate_wordnet(Some(base.to_path_buf())).unwrap_or_else(|e| {
+        eprintln!("{}", e);
+        exit(-1);
+    });
+    let file = File::open(path).unwrap_or_else(|e| {
+        eprintln!("Could not open {}: {}", path.display(), e);
+        exit(-1);
+    });
+    let (wn, metadata) = read_omw_tab(LexiconHashMapBackend::new(), file, &base).unwrap_or_else(|e| {
+        eprintln!("Could not import {}: {}", path.display(), e);
+        exit(-1);
+    });
+    // What the file doesn't give (it may have no header, or tag its rows with a language and
+    // give nothing else), the defaults `ewe init` offers
+    let defaults = default_metadata(id_prefix);
+    let metadata = match metadata {
+        Some(metadata) => {
+            let or_default = |value: String, default: String| if value.is_empty() { default } else { value };
+            LexiconMetadata {
+                id_prefix: defaults.id_prefix,
+                label: or_default(metadata.label, defaults.label),
+                language: or_default(metadata.language, defaults.language),
+                email: metadata.email,
+                license: or_default(metadata.license, defaults.license),
+                version: or_default(metadata.version, defaults.version),
+                url: metadata.url,
+            }
+        }
+        None => defaults,
+    };
+    println!(
+        "Imported {} entries, {} synsets from {}",
+        wn.n_entries().expect("Cannot read imported lexicon"),
+        wn.n_synsets().expect("Cannot read imported lexicon"),
+        path.display()
+    );
+
+    write_project_structure(&wn, &metadata, &out_dir);
+    println!("Saved project to {}", out_dir.display());
+}
+
 fn input_with_default(prompt: &str, default: &str) -> String {
     let value = input(&format!("{} [{}]: ", prompt, default));
     if value.is_empty() {
@@ -1221,16 +1964,30 @@ fn input_optional(prompt: &str) -> Option<String> {
     }
 }
 
+/// The metadata `ewe init` offers, and an import falls back on where its source has none
+fn default_metadata(id_prefix: String) -> LexiconMetadata {
+    LexiconMetadata {
+        id_prefix,
+        label: "My Wordnet".to_string(),
+        language: "en".to_string(),
+        email: None,
+        license: "https://creativecommons.org/licenses/by/4.0".to_string(),
+        version: "1".to_string(),
+        url: None,
+    }
+}
+
 fn run_init(path: &Path) {
     println!("Creating a new wordnet project at {}", path.display());
+    let defaults = default_metadata(input_with_default("Id prefix", "wn"));
     let metadata = LexiconMetadata {
-        id_prefix: input_with_default("Id prefix", "wn"),
-        label: input_with_default("Project name", "My Wordnet"),
-        language: input_with_default("Language (BCP 47 code)", "en"),
-        license: input_with_default("License URL", "https://creativecommons.org/licenses/by/4.0"),
-        version: input_with_default("Version", "1"),
+        label: input_with_default("Project name", &defaults.label),
+        language: input_with_default("Language (BCP 47 code)", &defaults.language),
+        license: input_with_default("License URL", &defaults.license),
+        version: input_with_default("Version", &defaults.version),
         email: input_optional("Contact email"),
         url: input_optional("Source/homepage URL"),
+        ..defaults
     };
 
     write_project_structure(&LexiconHashMapBackend::new(), &metadata, path);
@@ -1281,6 +2038,31 @@ fn main() {
         Some(Command::Automaton { ref script }) => {
             run_automaton(script, cli.wordnet);
         }
+        Some(Command::History { limit }) => {
+            run_history(*limit, cli.wordnet);
+        }
+        Some(Command::Undo { id }) => {
+            run_undo(*id, false, cli.wordnet);
+        }
+        Some(Command::Redo) => {
+            run_undo(None, true, cli.wordnet);
+        }
+        Some(Command::Validate { format }) => {
+            run_validate(*format, cli.wordnet);
+        }
+        Some(Command::Fix) => {
+            run_fix(cli.wordnet);
+        }
+        Some(Command::Genus { lexfile, depth, format }) => {
+            run_genus(lexfile, *depth, *format, cli.wordnet);
+        }
+        Some(Command::Definitions { circular, unknown, format }) => {
+            let both = !*circular && !*unknown;
+            run_definitions(*circular || both, *unknown || both, *format, cli.wordnet);
+        }
+        Some(Command::Hierarchy { batch, format }) => {
+            run_hierarchy(batch.as_deref(), *format, cli.wordnet);
+        }
         Some(Command::Id { ref id }) => {
             run_id(id, cli.wordnet);
         }
@@ -1315,15 +2097,46 @@ fn main() {
             };
             run_export_xml(path, metadata, cli.wordnet);
         }
+        Some(Command::Export {
+            format:
+                ExportFormat::Json {
+                    ref path,
+                    id_prefix,
+                    label,
+                    language,
+                    email,
+                    license,
+                    version,
+                    url,
+                },
+        }) => {
+            let metadata = LexiconMetadata {
+                id_prefix: id_prefix.clone(),
+                label: label.clone(),
+                language: language.clone(),
+                email: email.clone(),
+                license: license.clone(),
+                version: version.clone(),
+                url: url.clone(),
+            };
+            run_export_json(path, metadata, cli.wordnet);
+        }
         Some(Command::Export {
             format:
                 ExportFormat::Wndb {
                     ref path,
                     license_file,
                     sense_orders,
+                    sense_counts,
                 },
         }) => {
-            run_export_wndb(path, license_file.clone(), sense_orders.clone(), cli.wordnet);
+            run_export_wndb(
+                path,
+                license_file.clone(),
+                sense_orders.clone(),
+                sense_counts.clone(),
+                cli.wordnet,
+            );
         }
         Some(Command::Export {
             format:
@@ -1356,11 +2169,82 @@ fn main() {
             };
             run_export_rdf(path, options, cli.wordnet);
         }
+        Some(Command::Export {
+            format:
+                ExportFormat::Omw {
+                    ref path,
+                    language,
+                    label,
+                    url,
+                    license,
+                },
+        }) => {
+            let metadata = LexiconMetadata {
+                // The tab file has no ids but the synsets'
+                id_prefix: String::new(),
+                label: label.clone(),
+                language: language.clone(),
+                email: None,
+                license: license.clone(),
+                version: String::new(),
+                url: url.clone(),
+            };
+            run_export_omw(path, metadata, cli.wordnet);
+        }
         Some(Command::Import {
             format: ImportFormat::Xml { ref path },
         }) => {
             run_import_xml(path, cli.wordnet);
         }
+        Some(Command::Import {
+            format: ImportFormat::Json { ref path },
+        }) => {
+            run_import_json(path, cli.wordnet);
+        }
+        Some(Command::Import {
+            format:
+                ImportFormat::Wndb {
+                    ref path,
+                    id_prefix,
+                    label,
+                    language,
+                    email,
+                    license,
+                    version,
+                    url,
+                },
+        }) => {
+            let metadata = LexiconMetadata {
+                id_prefix: id_prefix.clone(),
+                label: label.clone(),
+                language: language.clone(),
+                email: email.clone(),
+                license: license.clone(),
+                version: version.clone(),
+                url: url.clone(),
+            };
+            run_import_wndb(path, metadata, cli.wordnet);
+        }
+        Some(Command::Import {
+            format:
+                ImportFormat::Rdf {
+                    ref path,
+                    format,
+                    id_prefix,
+                },
+        }) => {
+            run_import_rdf(path, format.clone().into(), id_prefix.clone(), cli.wordnet);
+        }
+        Some(Command::Import {
+            format:
+                ImportFormat::Omw {
+                    ref path,
+                    base,
+                    id_prefix,
+                },
+        }) => {
+            run_import_omw(path, base, id_prefix.clone(), cli.wordnet);
+        }
         Some(Command::Init { ref path }) => {
             run_init(path);
         }
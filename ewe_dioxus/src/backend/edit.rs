@@ -10,7 +10,10 @@
 
 use dioxus::prelude::*;
 #[allow(unused_imports)]
-use ewe_lib::automaton::{apply_automaton, changelog_recent, has_unsaved_changes, Action, SynsetRef};
+use ewe_lib::automaton::{
+    apply_automaton, apply_automaton_with_profile, changelog_recent, has_unsaved_changes, Action,
+    SynsetRef,
+};
 #[allow(unused_imports)]
 use ewe_lib::change_manager::ChangeList;
 #[allow(unused_imports)]
@@ -18,7 +21,13 @@ use ewe_lib::wordnet::{Lexicon, MemberSynset, PartOfSpeech, PosKey, SynsetId};
 #[cfg(any(feature = "server", feature = "desktop"))]
 use ewe_lib::wordnet::ReDBLexicon;
 #[cfg(any(feature = "server", feature = "desktop"))]
-use ewe_lib::validate::validate;
+use ewe_lib::definitions::opt_in_errors;
+#[cfg(any(feature = "server", feature = "desktop"))]
+use ewe_lib::user_rules::UserRules;
+#[cfg(any(feature = "server", feature = "desktop"))]
+use ewe_lib::validation_profile::ValidationProfile;
+#[cfg(any(feature = "server", feature = "desktop"))]
+use ewe_lib::validate::{IncrementalValidator, ValidationError};
 #[cfg(any(feature = "server", feature = "desktop"))]
 use ewe_lib::progress::{NullProgress, Progress};
 #[cfg(any(feature = "server", feature = "desktop"))]
@@ -162,6 +171,7 @@ pub async fn add_synset(
         pos,
         lemmas,
         subcats,
+        name: None,
     }];
     let (new_id, _validation_report) = apply_automaton(actions, &mut *lexicon, &mut ChangeList::new())
         .map_err(EweEditError::Automaton)?;
@@ -215,6 +225,12 @@ pub struct ChangeLogEntryView {
     pub id: u64,
     pub timestamp: String,
     pub summaries: Vec<String>,
+    /// Whether `undo_change` has anything to apply for this entry.
+    pub undoable: bool,
+    /// The entry this one undid, if it was made by `undo_change`.
+    pub undo_of: Option<u64>,
+    /// The undo this one reversed, if it was made by `redo_change`.
+    pub redo_of: Option<u64>,
 }
 
 /// The `limit` most recent change log entries, newest first. `before` (exclusive), if given,
@@ -233,10 +249,36 @@ pub async fn get_changelog(
             id,
             timestamp: format_timestamp(entry.timestamp_ms),
             summaries: entry.actions.iter().map(Action::summary).collect(),
+            undoable: entry.is_undoable(),
+            undo_of: entry.undo_of,
+            redo_of: entry.redo_of,
         })
         .collect())
 }
 
+/// Undoes change log entry `id`, or if omitted the most recent entry not already undone - see
+/// `ewe_lib::undo`. The undo is logged as an entry of its own. Returns the id of the entry
+/// undone, `None` if there was nothing to undo.
+#[cfg_attr(not(feature = "desktop"), post("/api/edit/undo"))]
+pub async fn undo_change(id: Option<u64>) -> Result<Option<u64>> {
+    let mut lexicon = write_lexicon()?;
+    let mut changes = ChangeList::new();
+    let undone = match id {
+        Some(id) => ewe_lib::undo::undo(&mut *lexicon, id, &mut changes).map(|_| Some(id)),
+        None => ewe_lib::undo::undo_last(&mut *lexicon, &mut changes),
+    };
+    Ok(undone.map_err(EweEditError::Automaton)?)
+}
+
+/// Re-applies the most recently undone entry, as long as nothing else has changed since.
+/// Returns the id of the entry redone, `None` if there was nothing to redo.
+#[cfg_attr(not(feature = "desktop"), post("/api/edit/redo"))]
+pub async fn redo_change() -> Result<Option<u64>> {
+    let mut lexicon = write_lexicon()?;
+    Ok(ewe_lib::undo::redo_last(&mut *lexicon, &mut ChangeList::new())
+        .map_err(EweEditError::Automaton)?)
+}
+
 /// Whether there are edits not yet reflected in `settings.wordnet_source` - see
 /// `automaton::has_unsaved_changes`. Used to drive the "unsaved changes" toast.
 #[cfg_attr(not(feature = "desktop"), get("/api/edit/dirty"))]
@@ -299,6 +341,64 @@ impl Progress for SharedProgress {
     fn set_percent_mode(&mut self, _percent_mode: bool) {}
 }
 
+/// Validation results kept up to date with the live lexicon: built by the first validate/save
+/// (a full `validate`, with a progress bar), then caught up from the change log on each later
+/// one, so validating after an edit only rechecks what the edit touched - see
+/// `ewe_lib::validate::IncrementalValidator`. Cleared whenever the lexicon is replaced wholesale
+/// (`revert_lexicon`, `backend::setup::run_configure`), since the new one has a change log of
+/// its own.
+#[cfg(any(feature = "server", feature = "desktop"))]
+pub(crate) static VALIDATOR: std::sync::Mutex<Option<IncrementalValidator>> =
+    std::sync::Mutex::new(None);
+
+/// The project's own validation rules, read from beside the YAML source at `source` (see
+/// `ewe_lib::user_rules`) - none if no `wordnet_source` is configured.
+#[cfg(any(feature = "server", feature = "desktop"))]
+fn user_rules(source: Option<&str>) -> Result<UserRules> {
+    match source {
+        Some(source) => Ok(UserRules::for_source(source).map_err(EweEditError::Automaton)?),
+        None => Ok(UserRules::default()),
+    }
+}
+
+/// Everything currently wrong with `lexicon`, as found by [`VALIDATOR`], followed by every
+/// breach of `rules` - which the validator keeps up to date too, only checking the whole lexicon
+/// against them again if they have changed since it last did - and what the opt-in checks
+/// `profile` turns on find (checked over the whole lexicon each time, so only if it turns any
+/// on).
+#[cfg(any(feature = "server", feature = "desktop"))]
+fn current_errors<L: Lexicon>(
+    lexicon: &L,
+    profile: &ValidationProfile,
+    rules: &UserRules,
+    progress: &mut SharedProgress,
+) -> Result<Vec<ValidationError>> {
+    let mut validator = VALIDATOR.lock().unwrap();
+    let validator = match validator.as_mut() {
+        Some(validator) => {
+            validator.catch_up(lexicon).map_err(EweEditError::Automaton)?;
+            validator
+        }
+        None => validator.insert(
+            IncrementalValidator::new(lexicon, progress)
+                .map_err(|e| EweEditError::Automaton(e.to_string()))?,
+        ),
+    };
+    if validator.rules().rules() != rules.rules() {
+        validator
+            .set_rules(lexicon, rules.clone())
+            .map_err(|e| EweEditError::Automaton(e.to_string()))?;
+    }
+    let mut found = validator.errors();
+    if profile.opts_in() {
+        found.extend(
+            opt_in_errors(lexicon, profile, progress)
+                .map_err(|e| EweEditError::Automaton(e.to_string()))?,
+        );
+    }
+    Ok(found)
+}
+
 /// The current save/validate progress, if either is running.
 #[cfg_attr(not(feature = "desktop"), get("/api/edit/progress"))]
 pub async fn get_progress() -> Result<Option<ProgressStatus>> {
@@ -307,6 +407,10 @@ pub async fn get_progress() -> Result<Option<ProgressStatus>> {
 
 /// Runs the validator against the current lexicon without saving - a standalone sanity check,
 /// independent of the save flow below (which also runs it as a gate). Empty means no errors.
+/// Only the first call validates the whole lexicon; later ones recheck just what was edited
+/// since (see [`VALIDATOR`]).
+/// Errors the settings' `[validation]` profile downgrades come last, prefixed with "Warning: ";
+/// those it switches off are left out.
 ///
 /// Validating the full lexicon is a synchronous scan that can take well over a minute, so it runs
 /// on a blocking thread (`spawn_blocking`) rather than inline - otherwise it would occupy the
@@ -314,12 +418,16 @@ pub async fn get_progress() -> Result<Option<ProgressStatus>> {
 /// poll (which needs no lock this call holds) could end up waiting behind it for no reason.
 #[cfg_attr(not(feature = "desktop"), get("/api/edit/validate"))]
 pub async fn validate_lexicon() -> Result<Vec<String>> {
-    let outcome = tokio::task::spawn_blocking(|| -> Result<Vec<String>> {
+    let (profile, rules) = {
+        let settings = crate::db::read_settings();
+        (settings.validation.clone(), user_rules(settings.wordnet_source.as_deref())?)
+    };
+    let outcome = tokio::task::spawn_blocking(move || -> Result<Vec<String>> {
         let lexicon = read_lexicon()?;
         let mut progress = SharedProgress::new("Validating");
-        let errors = validate(&*lexicon, &mut progress)
-            .map_err(|e| EweEditError::Automaton(e.to_string()))?;
-        Ok(errors.into_iter().map(|e| e.to_string()).collect())
+        let (errors, warnings) =
+            profile.classify(current_errors(&*lexicon, &profile, &rules, &mut progress)?);
+        Ok(report_lines(errors, warnings))
     })
     .await;
 
@@ -334,22 +442,89 @@ pub async fn validate_lexicon() -> Result<Vec<String>> {
     }
 }
 
+/// Errors, then warnings prefixed with "Warning: ", as `validate_lexicon` returns them.
+#[cfg(any(feature = "server", feature = "desktop"))]
+fn report_lines(errors: Vec<ValidationError>, warnings: Vec<ValidationError>) -> Vec<String> {
+    errors
+        .into_iter()
+        .map(|e| e.to_string())
+        .chain(warnings.into_iter().map(|w| format!("Warning: {w}")))
+        .collect()
+}
+
+/// What `fix_all_lexicon` did.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FixAllResult {
+    pub fixed: usize,
+    /// What validation still finds, in `validate_lexicon`'s format.
+    pub remaining: Vec<String>,
+}
+
+/// Applies every automatic fix for what validation finds (see `ewe_lib::validate::fix`) as a
+/// single `fix_validation_errors` automaton batch - logged like any other edit, so one undo
+/// reverses the lot - and returns how many errors that fixed and what is left. Runs on a
+/// blocking thread for the same reason `validate_lexicon` does: it validates the whole lexicon,
+/// before and after fixing.
+#[cfg_attr(not(feature = "desktop"), post("/api/edit/fix"))]
+pub async fn fix_all_lexicon() -> Result<FixAllResult> {
+    let (profile, rules) = {
+        let settings = crate::db::read_settings();
+        (settings.validation.clone(), user_rules(settings.wordnet_source.as_deref())?)
+    };
+    let outcome = tokio::task::spawn_blocking(move || -> Result<FixAllResult> {
+        let mut lexicon = write_lexicon()?;
+        let (_, report) = apply_automaton_with_profile(
+            vec![Action::FixValidationErrors],
+            &mut *lexicon,
+            &mut ChangeList::new(),
+            &profile,
+        )
+        .map_err(EweEditError::Automaton)?;
+        let mut report = report.ok_or_else(|| {
+            EweEditError::Automaton("Fixing reported no validation results".to_string())
+        })?;
+        report.errors.extend(
+            rules.check(&*lexicon).map_err(|e| EweEditError::Automaton(e.to_string()))?,
+        );
+        if profile.opts_in() {
+            report.errors.extend(
+                opt_in_errors(&*lexicon, &profile, &mut NullProgress)
+                    .map_err(|e| EweEditError::Automaton(e.to_string()))?,
+            );
+        }
+        let (errors, warnings) = profile.classify(report.errors);
+        Ok(FixAllResult { fixed: report.fixed, remaining: report_lines(errors, warnings) })
+    })
+    .await;
+
+    match outcome {
+        Ok(inner) => inner,
+        Err(e) => Err(EweEditError::Automaton(format!("Fix task panicked: {e}")).into()),
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct SaveResult {
     pub saved: bool,
     pub validation_errors: Vec<String>,
+    /// Problems the `[validation]` profile reports as warnings - shown, but never block a save
+    #[serde(default)]
+    pub validation_warnings: Vec<String>,
 }
 
 /// Writes the current lexicon out to `settings.wordnet_source`'s YAML files (the actual
 /// `english-wordnet` source tree in a normal deployment). Validates first: if there are errors
 /// and `force` is false, returns them without writing anything - the client is expected to show
-/// them and let the user retry with `force: true` ("save anyway") if they still want to.
+/// them and let the user retry with `force: true` ("save anyway") if they still want to. Only
+/// checks the settings' `[validation]` profile leaves as errors count; warnings never block.
 #[cfg_attr(not(feature = "desktop"), post("/api/edit/save"))]
 pub async fn save_lexicon(force: bool) -> Result<SaveResult> {
     let settings = crate::db::read_settings();
     let source = settings.wordnet_source.clone().ok_or_else(|| {
         EweEditError::Save("No wordnet_source is configured - nowhere to save to".to_string())
     })?;
+    let profile = settings.validation.clone();
+    let rules = user_rules(Some(source.as_str()))?;
 
     // Runs on a blocking thread for the same reason `validate_lexicon` does - this holds the
     // write lock and does two long synchronous passes (validate, then the actual file writes),
@@ -357,14 +532,14 @@ pub async fn save_lexicon(force: bool) -> Result<SaveResult> {
     let outcome = tokio::task::spawn_blocking(move || -> Result<SaveResult> {
         let mut lexicon = write_lexicon()?;
         let mut progress = SharedProgress::new("Validating");
-        let validation_errors: Vec<String> = validate(&*lexicon, &mut progress)
-            .map_err(|e| EweEditError::Automaton(e.to_string()))?
-            .into_iter()
-            .map(|e| e.to_string())
-            .collect();
+        let (errors, warnings) =
+            profile.classify(current_errors(&*lexicon, &profile, &rules, &mut progress)?);
+        let validation_errors: Vec<String> = errors.into_iter().map(|e| e.to_string()).collect();
+        let validation_warnings: Vec<String> =
+            warnings.into_iter().map(|w| w.to_string()).collect();
 
         if !validation_errors.is_empty() && !force {
-            return Ok(SaveResult { saved: false, validation_errors });
+            return Ok(SaveResult { saved: false, validation_errors, validation_warnings });
         }
 
         let mut progress = SharedProgress::new("Saving");
@@ -382,7 +557,7 @@ pub async fn save_lexicon(force: bool) -> Result<SaveResult> {
                 .map_err(|e| EweEditError::Save(e.to_string()))?;
         }
 
-        Ok(SaveResult { saved: true, validation_errors })
+        Ok(SaveResult { saved: true, validation_errors, validation_warnings })
     })
     .await;
 
@@ -423,6 +598,7 @@ pub async fn revert_lexicon() -> Result<()> {
     {
         let mut lexicon = write_lexicon()?;
         *lexicon = fresh;
+        *VALIDATOR.lock().unwrap() = None;
     }
 
     std::fs::rename(&tmp_path, &settings.database).map_err(|e| {
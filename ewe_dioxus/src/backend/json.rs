@@ -0,0 +1,44 @@
+//! WN-LMF JSON-LD export web routes, for clients that would rather not parse Turtle or XML.
+//!
+//! The same documents as the XML routes in `backend::xml`, just in the JSON form of WN-LMF:
+//! a lexicon covering the requested synset (or every synset a lemma has a sense in), written by
+//! `ewe_lib::wordnet::json` (shared with `ewe_cli`'s whole-lexicon `export json` command).
+
+use crate::backend::rdf::{resolve_lemma_synsets, resolve_synset};
+use crate::backend::xml::{metadata, not_found, server_error};
+use crate::dioxus_fullstack::{body::Body, http::Response};
+use dioxus::prelude::*;
+use ewe_lib::wordnet::json::write_lexicon_json_subset;
+use ewe_lib::wordnet::SynsetId;
+
+#[get("/jsonld/synset/{id}")]
+pub async fn synset_json_ld(id: String) -> Result<Response<Body>> {
+    let id = SynsetId::new_owned(id);
+    match resolve_synset(&id) {
+        Ok(Some(ms)) => match write_lexicon_json_subset(std::slice::from_ref(&ms), &metadata(&crate::db::read_settings()), &[], &Default::default()) {
+            Ok(json) => Ok(json_ld_response(json)),
+            Err(e) => Ok(server_error(e)),
+        },
+        Ok(None) => Ok(not_found("Synset not found")),
+        Err(e) => Ok(server_error(e)),
+    }
+}
+
+#[get("/jsonld/lemma/{lemma}")]
+pub async fn lemma_json_ld(lemma: String) -> Result<Response<Body>> {
+    match resolve_lemma_synsets(&lemma) {
+        Ok(synsets) if synsets.is_empty() => Ok(not_found("Lemma not found")),
+        Ok(synsets) => match write_lexicon_json_subset(&synsets, &metadata(&crate::db::read_settings()), &[], &Default::default()) {
+            Ok(json) => Ok(json_ld_response(json)),
+            Err(e) => Ok(server_error(e)),
+        },
+        Err(e) => Ok(server_error(e)),
+    }
+}
+
+fn json_ld_response(json: Vec<u8>) -> Response<Body> {
+    Response::builder()
+        .header("Content-Type", "application/ld+json")
+        .body(Body::from(json))
+        .unwrap()
+}
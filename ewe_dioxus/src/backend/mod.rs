@@ -10,6 +10,9 @@ pub use rdf::synset_negotiated;
 #[cfg(feature="server")]
 pub mod xml;
 
+#[cfg(feature="server")]
+pub mod json;
+
 #[cfg(feature="server")]
 pub mod openapi;
 
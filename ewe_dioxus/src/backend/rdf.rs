@@ -79,6 +79,7 @@ pub async fn synset_negotiated(id: String) -> Result<Response<Body>> {
         ContentType::RDFXML => Redirect::to(&format!("/rdf/synset/{}", id)).into_response(),
         ContentType::Turtle => Redirect::to(&format!("/ttl/synset/{}", id)).into_response(),
         ContentType::XML => Redirect::to(&format!("/xml/synset/{}", id)).into_response(),
+        ContentType::JSONLD => Redirect::to(&format!("/jsonld/synset/{}", id)).into_response(),
         ContentType::JSON => Redirect::to(&format!("/api/synset/{}", id)).into_response(),
     };
     Ok(response)
@@ -133,6 +134,7 @@ pub async fn lemma_negotiated(lemma: String) -> Result<Response<Body>> {
         ContentType::RDFXML => Redirect::to(&format!("/rdf/lemma/{}", lemma)).into_response(),
         ContentType::Turtle => Redirect::to(&format!("/ttl/lemma/{}", lemma)).into_response(),
         ContentType::XML => Redirect::to(&format!("/xml/lemma/{}", lemma)).into_response(),
+        ContentType::JSONLD => Redirect::to(&format!("/jsonld/lemma/{}", lemma)).into_response(),
         ContentType::JSON => Redirect::to(&format!("/api/lemma/{}", lemma)).into_response(),
     };
     Ok(response)
@@ -224,6 +226,7 @@ enum ContentType {
     Turtle,
     XML,
     JSON,
+    JSONLD,
 }
 
 fn negotiate(headers: HeaderMap) -> ContentType {
@@ -261,6 +264,7 @@ fn negotiate(headers: HeaderMap) -> ContentType {
                 "text/turtle" => Some(ContentType::Turtle),
                 "application/xml" => Some(ContentType::XML),
                 "application/json" => Some(ContentType::JSON),
+                "application/ld+json" => Some(ContentType::JSONLD),
                 "*/*" => Some(ContentType::HTML), // Default to HTML if any type is accepted
                 _ => None,
             }
@@ -326,6 +330,13 @@ mod tests {
         assert!(matches!(negotiate(headers), ContentType::JSON));
     }
 
+    #[test]
+    fn test_negotiate_json_ld() {
+        let mut headers = HeaderMap::new();
+        headers.insert("Accept", "application/ld+json".parse().unwrap());
+        assert!(matches!(negotiate(headers), ContentType::JSONLD));
+    }
+
     #[test]
     fn test_negotiate_xml() {
         let mut headers = HeaderMap::new();
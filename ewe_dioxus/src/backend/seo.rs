@@ -122,6 +122,7 @@ pub async fn robots_txt() -> Result<Response<Body>> {
          Disallow: /rdf/\n\
          Disallow: /xml/\n\
          Disallow: /ttl/\n\
+         Disallow: /jsonld/\n\
          Disallow: /api/\n",
     );
     if let Some(base_url) = &settings.base_url {
@@ -286,6 +286,10 @@ async fn run_configure(new_settings: crate::settings::EweSettings) -> Result<(),
     };
 
     *crate::LEXICON.get().write().unwrap() = Some(lexicon);
+    #[cfg(feature = "edit")]
+    {
+        *crate::backend::edit::VALIDATOR.lock().unwrap() = None;
+    }
     *crate::CORPUS.get().write().unwrap() = corpus;
     *crate::db::write_settings() = new_settings;
 
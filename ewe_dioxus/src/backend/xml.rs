@@ -44,8 +44,8 @@ pub async fn lemma_xml(lemma: String) -> Result<Response<Body>> {
 /// `ewe_lib`'s writer has no notion of a running app's settings, so translate this app's
 /// `EweSettings` into the standalone metadata it expects. Language/license/version aren't
 /// currently user-configurable in `EweSettings`, so these carry forward the same hardcoded
-/// values the exporter has always used.
-fn metadata(settings: &EweSettings) -> LexiconMetadata {
+/// values the exporter has always used. Shared with the JSON-LD routes in `backend::json`.
+pub(crate) fn metadata(settings: &EweSettings) -> LexiconMetadata {
     LexiconMetadata {
         id_prefix: settings.id_prefix.clone(),
         label: settings.project_name.clone(),
@@ -64,14 +64,14 @@ fn xml_response(xml: Vec<u8>) -> Response<Body> {
         .unwrap()
 }
 
-fn not_found(msg: &'static str) -> Response<Body> {
+pub(crate) fn not_found(msg: &'static str) -> Response<Body> {
     Response::builder()
         .status(404)
         .body(Body::from(msg))
         .unwrap()
 }
 
-fn server_error(e: impl std::fmt::Display) -> Response<Body> {
+pub(crate) fn server_error(e: impl std::fmt::Display) -> Response<Body> {
     Response::builder()
         .status(500)
         .body(Body::from(format!("Internal server error: {}", e)))
@@ -20,8 +20,9 @@ const ID_ENCODE_SET: &AsciiSet = &CONTROLS
     .add(b'@')
     .add(b' ');
 
-/// The "Download As: JSON | RDF/XML | Turtle | XML" links shown on a synset or lemma page,
-/// pointing at the machine-readable exports served by `backend::api`/`backend::rdf`/`backend::xml`.
+/// The "Download As: JSON | JSON-LD | RDF/XML | Turtle | XML" links shown on a synset or lemma
+/// page, pointing at the machine-readable exports served by
+/// `backend::api`/`backend::json`/`backend::rdf`/`backend::xml`.
 /// Web-only (see the `desktop` variant below): these are plain `<a href>` links reaching routes
 /// only a real running HTTP server can serve, and `desktop` doesn't run one at all - see
 /// `backend::setup`'s module doc comment on the `cfg_attr` pattern used everywhere else in
@@ -39,6 +40,7 @@ pub fn DownloadLinks(kind: &'static str, id: String) -> Element {
             class: "download-links",
             b { "Download As: " }
             a { href: "/api/{kind}/{id}", "JSON" }
+            a { href: "/jsonld/{kind}/{id}", "JSON-LD" }
             a { href: "/rdf/{kind}/{id}", "RDF/XML" }
             a { href: "/ttl/{kind}/{id}", "Turtle" }
             a { href: "/xml/{kind}/{id}", "XML" }
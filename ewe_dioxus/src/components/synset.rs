@@ -126,6 +126,7 @@ fn build_actions(
         actions.push(Action::DeleteRelation {
             source: SynsetRef::Id(synset_id.clone()),
             source_sense: None,
+            relation: None,
             target: SynsetRef::Id(delete.target.clone()),
             target_sense: None,
             source_lemma: delete.source_lemma.clone(),
@@ -12,7 +12,9 @@ pub fn provide_dirty_state() -> Signal<bool> {
 
 /// A small fixed banner shown whenever the dirty-state context is `true`, offering "Save"
 /// (validates first, showing errors with a "Save anyway" override) and "Revert" (behind a
-/// confirmation modal, since it discards every unsaved edit). Mounted once in `WNLayout`.
+/// confirmation modal, since it discards every unsaved edit). Validation warnings are listed
+/// alongside, and stay up after a save they didn't block until dismissed. Mounted once in
+/// `WNLayout`.
 #[cfg(feature = "edit")]
 #[component]
 pub fn UnsavedChangesToast() -> Element {
@@ -25,6 +27,7 @@ pub fn UnsavedChangesToast() -> Element {
     let mut show_revert_confirm = use_signal(|| false);
     let mut saving = use_signal(|| false);
     let mut validation_errors = use_signal(Vec::<String>::new);
+    let mut validation_warnings = use_signal(Vec::<String>::new);
     let mut error = use_signal(|| None::<String>);
 
     let initial = use_loader(get_dirty);
@@ -42,7 +45,8 @@ pub fn UnsavedChangesToast() -> Element {
             saving.set(true);
             error.set(None);
             match save_lexicon(force).await {
-                Ok(SaveResult { saved, validation_errors: errs }) => {
+                Ok(SaveResult { saved, validation_errors: errs, validation_warnings: warnings }) => {
+                    validation_warnings.set(warnings);
                     if saved {
                         validation_errors.set(Vec::new());
                         dirty.set(false);
@@ -112,6 +116,20 @@ pub fn UnsavedChangesToast() -> Element {
                         }
                     }
                 }
+                if !validation_warnings().is_empty() {
+                    ValidationWarnings {
+                        warnings: validation_warnings(),
+                        on_dismiss: move |_| validation_warnings.set(Vec::new()),
+                    }
+                }
+            }
+        } else if !validation_warnings().is_empty() {
+            div {
+                class: "unsaved-toast",
+                ValidationWarnings {
+                    warnings: validation_warnings(),
+                    on_dismiss: move |_| validation_warnings.set(Vec::new()),
+                }
             }
         }
         if show_revert_confirm() {
@@ -132,6 +150,38 @@ pub fn UnsavedChangesToast() -> Element {
     rsx! {}
 }
 
+#[cfg(feature = "edit")]
+#[derive(Clone, PartialEq, Props)]
+struct ValidationWarningsProps {
+    warnings: Vec<String>,
+    on_dismiss: EventHandler<()>,
+}
+
+/// What the `[validation]` profile reports as warnings from the last save (attempted or not) -
+/// never a reason not to save, so just listed until dismissed.
+#[cfg(feature = "edit")]
+#[component]
+fn ValidationWarnings(props: ValidationWarningsProps) -> Element {
+    rsx! {
+        div {
+            class: "unsaved-toast-validation",
+            p { "Validation warnings:" }
+            ul {
+                class: "history-summaries",
+                for warning in props.warnings.iter() {
+                    li { "{warning}" }
+                }
+            }
+            button {
+                class: "edit-cancel",
+                r#type: "button",
+                onclick: move |_| props.on_dismiss.call(()),
+                "Dismiss"
+            }
+        }
+    }
+}
+
 #[cfg(feature = "edit")]
 #[derive(Clone, PartialEq, Props)]
 struct RevertConfirmModalProps {
@@ -2,8 +2,8 @@ use dioxus::prelude::*;
 
 /// The "Validate" footer link - runs the validator against the current lexicon on demand,
 /// independent of saving (`save_lexicon` also validates as a save-time gate; this is just a
-/// standalone sanity check). Owns its own modal-open state, same as the other footer/toolbar
-/// triggers.
+/// standalone sanity check), with a "Fix all" action for whatever it finds. Owns its own
+/// modal-open state, same as the other footer/toolbar triggers.
 #[cfg(feature = "edit")]
 #[component]
 pub fn ValidateButton() -> Element {
@@ -36,10 +36,14 @@ struct ValidateModalProps {
 #[cfg(feature = "edit")]
 #[component]
 fn ValidateModal(props: ValidateModalProps) -> Element {
-    use crate::backend::edit::validate_lexicon;
+    use crate::backend::edit::{fix_all_lexicon, validate_lexicon, FixAllResult};
     use crate::components::EditProgressBar;
 
     let results = use_loader(validate_lexicon);
+    // Once "Fix all" has run, what it left replaces the original results.
+    let mut fixed = use_signal(|| None::<FixAllResult>);
+    let mut fixing = use_signal(|| false);
+    let mut fix_error = use_signal(|| None::<String>);
 
     // `use_loader` returns `Err(Loading::Pending)` - not `Ok` with `.loading()` true - for as
     // long as the future hasn't resolved yet; validating the full lexicon can easily take over a
@@ -68,17 +72,23 @@ fn ValidateModal(props: ValidateModalProps) -> Element {
                         "×"
                     }
                 }
-                if is_validating {
-                    p { "Validating…" }
+                if is_validating || fixing() {
+                    p { if fixing() { "Fixing…" } else { "Validating…" } }
                     EditProgressBar { active: true }
                 } else {
                     match &results {
                         Ok(loaded) => {
-                            let errors = loaded.read().clone();
-                            if errors.is_empty() {
-                                rsx! { p { "No validation errors." } }
-                            } else {
-                                rsx! {
+                            let errors = match fixed() {
+                                Some(result) => result.remaining,
+                                None => loaded.read().clone(),
+                            };
+                            rsx! {
+                                if let Some(result) = fixed() {
+                                    p { "Fixed {result.fixed} validation errors - undo it from History." }
+                                }
+                                if errors.is_empty() {
+                                    p { "No validation errors." }
+                                } else {
                                     ul {
                                         class: "history-summaries",
                                         for err in errors.iter() {
@@ -86,6 +96,31 @@ fn ValidateModal(props: ValidateModalProps) -> Element {
                                         }
                                     }
                                 }
+                                if let Some(err) = fix_error() {
+                                    div { class: "edit-error", "{err}" }
+                                }
+                                if !errors.is_empty() && fixed().is_none() {
+                                    div {
+                                        class: "modal-actions",
+                                        button {
+                                            class: "edit-save",
+                                            r#type: "button",
+                                            title: "Apply every automatic fix as a single change",
+                                            onclick: move |_| {
+                                                spawn(async move {
+                                                    fixing.set(true);
+                                                    fix_error.set(None);
+                                                    match fix_all_lexicon().await {
+                                                        Ok(result) => fixed.set(Some(result)),
+                                                        Err(e) => fix_error.set(Some(e.to_string())),
+                                                    }
+                                                    fixing.set(false);
+                                                });
+                                            },
+                                            "Fix all"
+                                        }
+                                    }
+                                }
                             }
                         }
                         Err(_) => rsx! { p { "Failed to run the validator." } },
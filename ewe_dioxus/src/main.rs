@@ -224,9 +224,9 @@ fn main() {
 
 /// Every plain `<a href>` link that points straight at a `#[get(...)]`
 /// server function with no `Location` header hits the same dioxus-server
-/// bug: the "Download As: JSON | RDF/XML | Turtle | XML" links on a
+/// bug: the "Download As: JSON | JSON-LD | RDF/XML | Turtle | XML" links on a
 /// synset/lemma page (see `components::download_links::DownloadLinks`,
-/// `backend::api`/`backend::rdf`/`backend::xml`), and the footer's "JSON API
+/// `backend::api`/`backend::json`/`backend::rdf`/`backend::xml`), and the footer's "JSON API
 /// documentation" link (`views::wn_layout`, `backend::openapi::api_docs`).
 /// Clicking one is a real `<a href>` navigation, which sends
 /// `Accept: text/html` plus a `Referer` header - exactly the combination
@@ -254,7 +254,9 @@ async fn strip_referer_from_export_links(
         || path.starts_with("/ttl/synset/")
         || path.starts_with("/ttl/lemma/")
         || path.starts_with("/xml/synset/")
-        || path.starts_with("/xml/lemma/");
+        || path.starts_with("/xml/lemma/")
+        || path.starts_with("/jsonld/synset/")
+        || path.starts_with("/jsonld/lemma/");
     if is_export_link {
         req.headers_mut()
             .remove(dioxus_fullstack::http::header::REFERER);
@@ -1,4 +1,5 @@
 /// Setting for running the application
+use ewe_lib::validation_profile::ValidationProfile;
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -60,6 +61,10 @@ pub struct EweSettings {
     /// relative or missing URLs is invalid per the sitemap protocol.
     #[serde(default)]
     pub base_url: Option<String>,
+    /// The `[validation]` table: per-check severities (`"error"`, `"warning"` or `"off"`)
+    /// deciding which validation errors block a save. See `ewe_lib::validation_profile`.
+    #[serde(default)]
+    pub validation: ValidationProfile,
 }
 
 fn default_lexicon_cache_mb() -> usize {
@@ -204,6 +209,7 @@ impl EweSettings {
             contact_email: None,
             source_url: None,
             base_url: None,
+            validation: ValidationProfile::default(),
         }
     }
 
@@ -8,12 +8,13 @@ use crate::components::{ProjectName, WordNet};
 const PAGE_SIZE: usize = 50;
 
 /// A page of the Wordnet editor's change history - every batch of automaton actions ever applied,
-/// newest first, paginated with a "Load more" button. Not available in builds without the `edit`
-/// feature (the public, read-only en-word.net site never has anything to show here).
+/// newest first, paginated with a "Load more" button, with buttons to undo any entry (or just the
+/// latest) and to redo the latest undo. Not available in builds without the `edit` feature (the
+/// public, read-only en-word.net site never has anything to show here).
 #[cfg(feature = "edit")]
 #[component]
 pub fn History() -> Element {
-    use crate::backend::edit::{get_changelog, ChangeLogEntryView};
+    use crate::backend::edit::{get_changelog, redo_change, undo_change, ChangeLogEntryView};
 
     let project_name = use_context::<Signal<ProjectName>>();
 
@@ -62,6 +63,46 @@ pub fn History() -> Element {
         }
     });
 
+    // Undo/redo add an entry of their own to the log, so each one reloads the first page
+    // afterwards rather than patching `entries` in place.
+    let mut undo = use_action(move |id: Option<u64>| async move {
+        undo_change(id).await?;
+        get_changelog(Some(PAGE_SIZE), None).await
+    });
+    let mut redo = use_action(move |_: ()| async move {
+        redo_change().await?;
+        get_changelog(Some(PAGE_SIZE), None).await
+    });
+
+    use_effect(move || {
+        let page = match (undo.value(), redo.value()) {
+            (Some(Ok(page)), _) | (_, Some(Ok(page))) => page.cloned(),
+            _ => return,
+        };
+        exhausted.set(page.len() < PAGE_SIZE);
+        entries.set(page);
+    });
+
+    let busy = undo.pending() || redo.pending();
+    let error = match (undo.value(), redo.value()) {
+        (Some(Err(e)), _) | (_, Some(Err(e))) => Some(e.to_string()),
+        _ => None,
+    };
+    // Entries undone by a newer entry that is still in effect - good enough to grey out their
+    // Undo buttons among the entries loaded so far (the server has the final say).
+    let undone = {
+        let mut undone = std::collections::HashSet::new();
+        for entry in entries().iter() {
+            if undone.contains(&entry.id) {
+                continue;
+            }
+            if let Some(target) = entry.undo_of.or(entry.redo_of) {
+                undone.insert(target);
+            }
+        }
+        undone
+    };
+
     rsx! {
         div {
             if !project_name().0.is_empty() {
@@ -71,6 +112,24 @@ pub fn History() -> Element {
             div {
                 class: "history",
                 h2 { "Change history" }
+                div {
+                    class: "history-actions",
+                    button {
+                        r#type: "button",
+                        disabled: busy,
+                        onclick: move |_| undo.call(None),
+                        "Undo last change"
+                    }
+                    button {
+                        r#type: "button",
+                        disabled: busy,
+                        onclick: move |_| redo.call(()),
+                        "Redo"
+                    }
+                }
+                if let Some(error) = error {
+                    div { class: "edit-error", "{error}" }
+                }
                 if !seeded() {
                     p { "Loading…" }
                 } else if entries().is_empty() {
@@ -80,7 +139,24 @@ pub fn History() -> Element {
                         div {
                             key: "{entry.id}",
                             class: "history-entry",
-                            div { class: "history-timestamp", "{entry.timestamp}" }
+                            div {
+                                class: "history-timestamp",
+                                "{entry.timestamp}"
+                                if entry.undo_of.is_some() {
+                                    span { class: "history-note", " (undo)" }
+                                } else if entry.redo_of.is_some() {
+                                    span { class: "history-note", " (redo)" }
+                                }
+                                if entry.undoable {
+                                    button {
+                                        class: "history-undo",
+                                        r#type: "button",
+                                        disabled: busy || undone.contains(&entry.id),
+                                        onclick: move |_| undo.call(Some(entry.id)),
+                                        if undone.contains(&entry.id) { "Undone" } else { "Undo" }
+                                    }
+                                }
+                            }
                             ul {
                                 class: "history-summaries",
                                 for summary in entry.summaries.iter() {
@@ -2,47 +2,133 @@ use crate::change_manager;
 use crate::change_manager::{ChangeList, RelationUpdate};
 use crate::progress::NullProgress;
 use crate::rels::{SenseRelType, SynsetRelType};
-use crate::validate::{validate, ValidationError};
-use crate::wordnet::{Lexicon, PosKey, SenseId, SenseOrSynsetId, SynsetId, ILIID};
+use crate::undo;
+use crate::undo::SynsetSnapshot;
+use crate::validate::{fix, pos_change_errors, validate, ValidationError};
+use crate::validation_profile::{Severity, ValidationProfile};
+use crate::wordnet::{
+    Lexicon, PosKey, Pronunciation, SenseId, SenseOrSynsetId, SynsetId, UnresolvedSenseOrSynsetId,
+    ILIID,
+};
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::collections::HashMap;
 use std::time::{SystemTime, UNIX_EPOCH};
 
 /// What a `validate` action within an automaton batch found - carries what
 /// [`crate::validate::validate`] returned, for the caller to report however it likes (e.g.
-/// `ewe_cli` prints it the same way its own interactive "Validate" menu option does).
+/// `ewe_cli` prints it the same way its own interactive "Validate" menu option does, sorted by
+/// the project's [`crate::validation_profile::ValidationProfile`]). A
+/// `change_pos` action adds the relations the new part of speech does not allow to it.
 pub struct ValidationReport {
     pub errors: Vec<ValidationError>,
+    /// How many errors a `fix_validation_errors` action fixed (`errors` being what was left) -
+    /// zero for any other report.
+    pub fixed: usize,
 }
 
 /// Applies a batch of actions, returning the id of the last synset created or referenced
 /// (`SynsetRef::Last`'s target throughout the batch) - `None` if the batch never touched a
-/// synset - and, if the batch contained a `validate` action, the report it produced (the last
-/// one, if there were several). Lets callers that just added a synset find out what id it got,
-/// and lets callers report validation results without `apply_automaton` printing them itself.
+/// synset - and, if the batch contained a `validate` or `fix_validation_errors` action, the
+/// report it produced (the last one, if there were several), or if a `change_pos` action left
+/// relations its new part of speech does not allow, those. Lets callers that just added a
+/// synset find out what id it got, and lets callers report validation results without
+/// `apply_automaton` printing them itself.
 ///
-/// On full success, also appends the batch to the change log (see [`ChangeLogEntry`]) - a batch
-/// that fails partway through (via an early `?` return below) is not logged, since the point is a
-/// record of what was actually applied, not what was attempted.
+/// The batch is all-or-nothing: it runs inside a `Lexicon::checkpoint`, and if any action fails
+/// the lexicon is rolled back to exactly how it was before the first one, so a caller never has
+/// to work out which half of a batch landed. On full success, the batch is also appended to the
+/// change log (see [`ChangeLogEntry`]) - a failed batch is not logged, since the point is a record
+/// of what was actually applied, not what was attempted.
 pub fn apply_automaton<L: Lexicon>(
     actions: Vec<Action>,
     wn: &mut L,
     changes: &mut ChangeList,
 ) -> Result<(Option<SynsetId>, Option<ValidationReport>), String> {
+    apply_automaton_with_profile(actions, wn, changes, &ValidationProfile::default())
+}
+
+/// [`apply_automaton`] under a project's validation profile: a `fix_validation_errors` action
+/// leaves alone what the checks `profile` switches off find.
+pub fn apply_automaton_with_profile<L: Lexicon>(
+    actions: Vec<Action>,
+    wn: &mut L,
+    changes: &mut ChangeList,
+    profile: &ValidationProfile,
+) -> Result<(Option<SynsetId>, Option<ValidationReport>), String> {
+    apply_batch(actions, wn, changes, profile, None, None)
+}
+
+/// [`apply_automaton_with_profile`], also recording on the logged entry which earlier entry (if
+/// any) the batch undoes or redoes - see [`crate::undo`].
+pub(crate) fn apply_batch<L: Lexicon>(
+    actions: Vec<Action>,
+    wn: &mut L,
+    changes: &mut ChangeList,
+    profile: &ValidationProfile,
+    undo_of: Option<u64>,
+    redo_of: Option<u64>,
+) -> Result<(Option<SynsetId>, Option<ValidationReport>), String> {
+    let was_changed = changes.changed();
+    wn.checkpoint().map_err(|e| e.to_string())?;
     let actions_for_log = actions.clone();
-    let mut last_synset_id: Option<SynsetId> = None;
-    let mut last_sense_id: Option<SenseId> = None;
+    let mut undo = Vec::new();
+    let result = apply_actions(actions, wn, changes, profile, &mut undo).and_then(|(last_synset_id, report)| {
+        if !actions_for_log.is_empty() {
+            changelog_append(
+                wn,
+                ChangeLogEntry {
+                    timestamp_ms: 0,
+                    actions: actions_for_log,
+                    undo,
+                    undo_of,
+                    redo_of,
+                },
+            )?;
+        }
+        Ok((last_synset_id, report))
+    });
+    if result.is_ok() {
+        wn.release_checkpoint().map_err(|e| e.to_string())?;
+    } else {
+        wn.rollback_to_checkpoint().map_err(|e| e.to_string())?;
+        if !was_changed {
+            changes.reset();
+        }
+    }
+    result
+}
+
+/// The body of [`apply_automaton`], free to bail out with `?` at any point since its caller
+/// undoes whatever was applied before the failure. Also fills in `undo` with the batch that
+/// would reverse this one, built up action by action from the state each one found (see
+/// [`crate::undo::inverse`]).
+fn apply_actions<L: Lexicon>(
+    actions: Vec<Action>,
+    wn: &mut L,
+    changes: &mut ChangeList,
+    profile: &ValidationProfile,
+    undo: &mut Vec<Action>,
+) -> Result<(Option<SynsetId>, Option<ValidationReport>), String> {
+    let mut refs = Refs::default();
     let mut validation_report: Option<ValidationReport> = None;
+    let mut inverses: Vec<Vec<Action>> = Vec::new();
     for action in actions {
+        let Some(action) = unguard(wn, action, &refs)? else {
+            continue;
+        };
+        let mut inverse = undo::inverse(wn, &action, &refs)?;
         match action {
             Action::AddEntry {
                 synset,
                 lemma,
                 pos,
                 subcat,
+                name,
             } => {
-                last_sense_id = change_manager::add_entry(
+                let synset = synset.resolve(&refs)?;
+                refs.last_sense = change_manager::add_entry(
                     wn,
-                    synset.resolve(&last_synset_id)?,
+                    synset.clone(),
                     lemma,
                     pos,
                     subcat,
@@ -50,16 +136,19 @@ pub fn apply_automaton<L: Lexicon>(
                     changes,
                 )
                 .map_err(|e| e.to_string())?;
+                if let Some(name) = name {
+                    refs.bind(name, synset, refs.last_sense.clone())?;
+                }
             }
             Action::DeleteEntry { synset, lemma } => {
                 match wn
-                    .pos_for_entry_synset(&lemma, &synset.clone().resolve(&last_synset_id)?)
+                    .pos_for_entry_synset(&lemma, &synset.clone().resolve(&refs)?)
                     .map_err(|e| e.to_string())?
                 {
                     Some(pos) => {
                         change_manager::delete_entry(
                             wn,
-                            &synset.resolve(&last_synset_id)?,
+                            &synset.resolve(&refs)?,
                             &lemma,
                             &pos,
                             true,
@@ -82,14 +171,14 @@ pub fn apply_automaton<L: Lexicon>(
                 target_synset,
             } => {
                 match wn
-                    .pos_for_entry_synset(&lemma, &synset.clone().resolve(&last_synset_id)?)
+                    .pos_for_entry_synset(&lemma, &synset.clone().resolve(&refs)?)
                     .map_err(|e| e.to_string())?
                 {
                     Some(pos) => {
                         change_manager::move_entry(
                             wn,
-                            synset.resolve(&last_synset_id)?,
-                            target_synset.resolve(&last_synset_id)?,
+                            synset.resolve(&refs)?,
+                            target_synset.resolve(&refs)?,
                             lemma,
                             pos,
                             changes,
@@ -105,12 +194,20 @@ pub fn apply_automaton<L: Lexicon>(
                     }
                 }
             }
+            Action::RenameLemma {
+                lemma,
+                pos,
+                new_lemma,
+            } => {
+                change_manager::rename_lemma(wn, &lemma, &pos, new_lemma, changes)?;
+            }
             Action::AddSynset {
                 definition,
                 lexfile,
                 pos,
                 lemmas,
                 subcats,
+                name,
             } => {
                 let poses = wn.pos_for_lexfile(&lexfile).map_err(|e| e.to_string())?;
                 let pos = if let Some(pos) = pos {
@@ -143,9 +240,10 @@ pub fn apply_automaton<L: Lexicon>(
                     changes,
                 ) {
                     Ok(new_id) => {
+                        let mut senses = Vec::new();
                         if subcats.is_empty() {
                             for lemma in lemmas {
-                                change_manager::add_entry(
+                                senses.push(change_manager::add_entry(
                                     wn,
                                     new_id.clone(),
                                     lemma,
@@ -154,11 +252,11 @@ pub fn apply_automaton<L: Lexicon>(
                                     None,
                                     changes,
                                 )
-                                .map_err(|e| e.to_string())?;
+                                .map_err(|e| e.to_string())?);
                             }
                         } else {
                             for (lemma, subcat) in lemmas.into_iter().zip(subcats.into_iter()) {
-                                change_manager::add_entry(
+                                senses.push(change_manager::add_entry(
                                     wn,
                                     new_id.clone(),
                                     lemma,
@@ -167,10 +265,23 @@ pub fn apply_automaton<L: Lexicon>(
                                     None,
                                     changes,
                                 )
-                                .map_err(|e| e.to_string())?;
+                                .map_err(|e| e.to_string())?);
                             }
                         }
-                        last_synset_id = Some(new_id);
+                        inverse.push(Action::DeleteSynset {
+                            synset: SynsetRef::Id(new_id.clone()),
+                            reason: "Undo".to_string(),
+                            superseded_by: None,
+                        });
+                        if let Some(name) = name {
+                            // Only a one-lemma synset has an obvious sense to bind to.
+                            let sense = match senses.as_slice() {
+                                [Some(sense)] => Some(sense.clone()),
+                                _ => None,
+                            };
+                            refs.bind(name, new_id.clone(), sense)?;
+                        }
+                        refs.last_synset = Some(new_id);
                     }
                     Err(e) => return Err(e.to_string()),
                 }
@@ -181,22 +292,113 @@ pub fn apply_automaton<L: Lexicon>(
                 superseded_by,
             } => {
                 let superseded_by = match superseded_by {
-                    Some(superseded_by) => Some(superseded_by.resolve(&last_synset_id)?),
+                    Some(superseded_by) => Some(superseded_by.resolve(&refs)?),
                     None => None,
                 };
                 change_manager::delete_synset(
                     wn,
-                    &synset.resolve(&last_synset_id)?,
+                    &synset.resolve(&refs)?,
                     superseded_by.as_ref(),
                     reason,
                     changes,
                 )
                 .map_err(|e| e.to_string())?;
             }
+            Action::ChangeLexfile { synset, lexfile } => {
+                let synset = synset.resolve(&refs)?;
+                change_manager::change_lexfile(wn, &synset, lexfile, changes)?;
+                refs.last_synset = Some(synset);
+            }
+            Action::ChangePos {
+                synset,
+                pos,
+                lexfile,
+            } => {
+                let synset = synset.resolve(&refs)?;
+                let pos = pos
+                    .to_part_of_speech()
+                    .ok_or_else(|| format!("POS value not valid : {}", pos.as_str()))?;
+                let new_id = change_manager::change_pos(wn, &synset, pos, lexfile, changes)?;
+                if new_id != synset {
+                    // As for a split, the old synset can only be restored once the new one,
+                    // which holds its members' entries, is gone.
+                    inverse.insert(
+                        0,
+                        Action::DeleteSynset {
+                            synset: SynsetRef::Id(new_id.clone()),
+                            reason: "Undo".to_string(),
+                            superseded_by: None,
+                        },
+                    );
+                }
+                let errors = pos_change_errors(wn, &new_id).map_err(|e| e.to_string())?;
+                if !errors.is_empty() {
+                    validation_report
+                        .get_or_insert(ValidationReport {
+                            errors: Vec::new(),
+                            fixed: 0,
+                        })
+                        .errors
+                        .extend(errors);
+                }
+                refs.last_synset = Some(new_id);
+            }
+            Action::SplitSynset {
+                synset,
+                members,
+                definition,
+                lexfile,
+                relations,
+                name,
+            } => {
+                let synset = synset.resolve(&refs)?;
+                let relations = relations
+                    .into_iter()
+                    .map(|item| {
+                        let rel = SynsetRelType::from(&item.relation)
+                            .ok_or_else(|| format!("Unknown relation {}", item.relation))?;
+                        Ok((rel, item.target.resolve(&refs)?, item.mode == SplitMode::Move))
+                    })
+                    .collect::<Result<Vec<_>, String>>()?;
+                let new_id = change_manager::split_synset(
+                    wn, &synset, members, definition, lexfile, relations, changes,
+                )?;
+                // The original's restore (worked out before the split) has to wait until the
+                // new synset, which may hold sense ids it wants back, is gone.
+                inverse.insert(
+                    0,
+                    Action::DeleteSynset {
+                        synset: SynsetRef::Id(new_id.clone()),
+                        reason: "Undo".to_string(),
+                        superseded_by: None,
+                    },
+                );
+                if let Some(name) = name {
+                    refs.bind(name, new_id.clone(), None)?;
+                }
+                refs.last_synset = Some(new_id);
+            }
+            Action::MergeSynsets {
+                synset,
+                merged,
+                definition,
+                reason,
+            } => {
+                let synset = synset.resolve(&refs)?;
+                change_manager::merge_synsets(
+                    wn,
+                    &synset,
+                    &merged.resolve(&refs)?,
+                    definition,
+                    reason,
+                    changes,
+                )?;
+                refs.last_synset = Some(synset);
+            }
             Action::Definition { synset, definition } => {
                 change_manager::update_def(
                     wn,
-                    &synset.resolve(&last_synset_id)?,
+                    &synset.resolve(&refs)?,
                     definition,
                     false,
                 );
@@ -208,7 +410,7 @@ pub fn apply_automaton<L: Lexicon>(
             } => {
                 change_manager::add_ex(
                     wn,
-                    &synset.resolve(&last_synset_id)?,
+                    &synset.resolve(&refs)?,
                     example,
                     // An empty source is not a valid value - treat it the same as omitting
                     // `source` entirely.
@@ -224,7 +426,7 @@ pub fn apply_automaton<L: Lexicon>(
             } => {
                 change_manager::update_ex(
                     wn,
-                    &synset.resolve(&last_synset_id)?,
+                    &synset.resolve(&refs)?,
                     number - 1,
                     example,
                     source.filter(|s| !s.is_empty()),
@@ -234,7 +436,7 @@ pub fn apply_automaton<L: Lexicon>(
             Action::DeleteExample { synset, number } => {
                 change_manager::delete_ex(
                     wn,
-                    &synset.resolve(&last_synset_id)?,
+                    &synset.resolve(&refs)?,
                     number - 1,
                     changes,
                 );
@@ -248,40 +450,12 @@ pub fn apply_automaton<L: Lexicon>(
                 source_lemma,
                 target_lemma,
             } => {
-                let source = source.resolve(&last_synset_id)?;
-                let source_sense = if let Some(source_sense) = source_sense {
-                    Some(source_sense.resolve(&last_sense_id, wn, &source)?)
-                } else if let Some(lemma) = source_lemma.as_ref() {
-                    Some(
-                        wn.get_sense_id2(&lemma, &source)
-                            .map_err(|e| e.to_string())?
-                            .ok_or(format!(
-                                "No sense with lemma {} in {}",
-                                lemma,
-                                source.as_str()
-                            ))?
-                            .clone(),
-                    )
-                } else {
-                    None
-                };
-                let target = target.resolve(&last_synset_id)?;
-                let target_sense = if let Some(target_sense) = target_sense {
-                    Some(target_sense.resolve(&last_sense_id, wn, &target)?)
-                } else if let Some(lemma) = target_lemma.as_ref() {
-                    Some(
-                        wn.get_sense_id2(&lemma, &target)
-                            .map_err(|e| e.to_string())?
-                            .ok_or(format!(
-                                "No sense with lemma {} in {}",
-                                lemma,
-                                target.as_str()
-                            ))?
-                            .clone(),
-                    )
-                } else {
-                    None
-                };
+                let source = source.resolve(&refs)?;
+                let source_sense =
+                    resolve_sense(wn, &source_sense, &source_lemma, &source, &refs)?;
+                let target = target.resolve(&refs)?;
+                let target_sense =
+                    resolve_sense(wn, &target_sense, &target_lemma, &target, &refs)?;
                 match source_sense {
                     Some(sense) => {
                         let rel = SenseRelType::from(&relation)
@@ -328,47 +502,26 @@ pub fn apply_automaton<L: Lexicon>(
             Action::DeleteRelation {
                 source,
                 source_sense,
+                relation,
                 target,
                 target_sense,
                 source_lemma,
                 target_lemma,
             } => {
-                let source = source.resolve(&last_synset_id)?;
-                let source_sense = if let Some(source_sense) = source_sense {
-                    Some(source_sense.resolve(&last_sense_id, wn, &source)?)
-                } else if let Some(lemma) = source_lemma.as_ref() {
-                    Some(
-                        wn.get_sense_id2(&lemma, &source)
-                            .map_err(|e| e.to_string())?
-                            .ok_or(format!(
-                                "No sense with lemma {} in {}",
-                                lemma,
-                                source.as_str()
-                            ))?
-                            .clone(),
-                    )
-                } else {
-                    None
-                };
-                let target = target.resolve(&last_synset_id)?;
-                let target_sense = if let Some(target_sense) = target_sense {
-                    Some(target_sense.resolve(&last_sense_id, wn, &target)?)
-                } else if let Some(lemma) = target_lemma.as_ref() {
-                    Some(
-                        wn.get_sense_id2(&lemma, &target)
-                            .map_err(|e| e.to_string())?
-                            .ok_or(format!(
-                                "No sense with lemma {} in {}",
-                                lemma,
-                                target.as_str()
-                            ))?
-                            .clone(),
-                    )
-                } else {
-                    None
-                };
+                let source = source.resolve(&refs)?;
+                let source_sense =
+                    resolve_sense(wn, &source_sense, &source_lemma, &source, &refs)?;
+                let target = target.resolve(&refs)?;
+                let target_sense =
+                    resolve_sense(wn, &target_sense, &target_lemma, &target, &refs)?;
 
                 match source_sense {
+                    Some(_) if relation.is_some() => {
+                        return Err(
+                            "A relation can only be given to delete_relation between synsets"
+                                .to_string(),
+                        );
+                    }
                     Some(source_sense) => {
                         // `delete_sense_rel` removes whatever relation(s) exist
                         // between the pair, generically (not relation-type
@@ -382,9 +535,20 @@ pub fn apply_automaton<L: Lexicon>(
                         change_manager::delete_sense_rel(wn, &source_sense, &target_sense, changes)
                             .map_err(|e| e.to_string())?;
                     }
-                    None => {
-                        change_manager::delete_rel(wn, &source, &target, changes);
-                    }
+                    None => match relation {
+                        Some(relation) => {
+                            change_manager::delete_rel_type(
+                                wn,
+                                &source,
+                                &SynsetRelType::from(&relation)
+                                    .ok_or(format!("Bad relation {}.", relation))?,
+                                &target,
+                                changes,
+                            )
+                            .map_err(|e| e.to_string())?;
+                        }
+                        None => change_manager::delete_rel(wn, &source, &target, changes),
+                    },
                 }
             }
             Action::ReverseRelation {
@@ -395,11 +559,11 @@ pub fn apply_automaton<L: Lexicon>(
             } => match source_sense {
                 Some(source_sense) => {
                     let source_sense = source_sense.resolve(
-                        &last_sense_id,
+                        &refs,
                         wn,
-                        &source.resolve(&last_synset_id)?,
+                        &source.resolve(&refs)?,
                     )?;
-                    let target_synset = target.resolve(&last_synset_id)?;
+                    let target_synset = target.resolve(&refs)?;
                     // Unlike Add/DeleteRelation, a missing target sense stays an
                     // error here: reversing a sense-synset relation would need
                     // to store a relation *from* a synset, which isn't
@@ -407,7 +571,7 @@ pub fn apply_automaton<L: Lexicon>(
                     // rels.rs).
                     let target_sense = match target_sense {
                         Some(target_sense) => {
-                            target_sense.resolve(&last_sense_id, wn, &target_synset)?
+                            target_sense.resolve(&refs, wn, &target_synset)?
                         }
                         None => {
                             return Err(format!(
@@ -429,18 +593,18 @@ pub fn apply_automaton<L: Lexicon>(
                 None => {
                     change_manager::reverse_rel(
                         wn,
-                        &source.resolve(&last_synset_id)?,
-                        &target.resolve(&last_synset_id)?,
+                        &source.resolve(&refs)?,
+                        &target.resolve(&refs)?,
                         changes,
                     )
                     .map_err(|e| e.to_string())?;
                 }
             },
             Action::UpdateRelations { synset, relations } => {
-                let synset = synset.resolve(&last_synset_id)?;
+                let synset = synset.resolve(&refs)?;
                 let mut relations2 = Vec::new();
                 for item in relations.iter() {
-                    relations2.push(item.resolve(wn, &synset, &last_synset_id, &last_sense_id)?);
+                    relations2.push(item.resolve(wn, &synset, &refs)?);
                 }
                 change_manager::update_rels(wn, &synset, relations2, changes)
                     .map_err(|e| e.to_string())?;
@@ -448,20 +612,50 @@ pub fn apply_automaton<L: Lexicon>(
             Action::Validate => {
                 let mut progress = NullProgress;
                 let errors = validate(wn, &mut progress).map_err(|e| e.to_string())?;
-                validation_report = Some(ValidationReport { errors });
+                validation_report = Some(ValidationReport { errors, fixed: 0 });
             }
             Action::FixTransitivity => {
                 change_manager::fix_indirect_relations(wn, changes).map_err(|e| e.to_string())?;
             }
+            Action::FixValidationErrors => {
+                let mut fixed = 0;
+                for error in validate(wn, &mut NullProgress).map_err(|e| e.to_string())? {
+                    if profile.severity(&error) != Severity::Off
+                        && fix(wn, &error, changes).map_err(|e| e.to_string())?
+                    {
+                        fixed += 1;
+                    }
+                }
+                let errors = validate(wn, &mut NullProgress).map_err(|e| e.to_string())?;
+                validation_report = Some(ValidationReport { errors, fixed });
+            }
+            Action::AssertSynsetExists { synset } => {
+                Condition::exists(synset).check(wn, &refs)?;
+            }
+            Action::AssertDefinition { synset, definition } => {
+                Condition {
+                    definition: Some(definition),
+                    ..Condition::exists(synset)
+                }
+                .check(wn, &refs)?;
+            }
+            Action::AssertMember { synset, lemma } => {
+                Condition {
+                    member: Some(lemma),
+                    ..Condition::exists(synset)
+                }
+                .check(wn, &refs)?;
+            }
+            Action::Guarded { .. } => unreachable!("guards are removed by `unguard`"),
             Action::ChangeILI { synset, ili } => {
-                let synset = synset.resolve(&last_synset_id)?;
+                let synset = synset.resolve(&refs)?;
                 wn.update_synset(&synset, |s| {
-                    s.ili = Some(ILIID::new(&ili));
+                    s.ili = if ili.is_empty() { None } else { Some(ILIID::new(&ili)) };
                 })
                 .map_err(|e| e.to_string())?;
             }
             Action::ChangeWikidata { synset, wikidata } => {
-                let synset = synset.resolve(&last_synset_id)?;
+                let synset = synset.resolve(&refs)?;
                 let wikidata = wikidata
                     .into_iter()
                     .filter(|s| !s.is_empty())
@@ -472,40 +666,189 @@ pub fn apply_automaton<L: Lexicon>(
                 .map_err(|e| e.to_string())?;
             }
             Action::ChangeSource { synset, source } => {
-                let synset = synset.resolve(&last_synset_id)?;
+                let synset = synset.resolve(&refs)?;
                 wn.update_synset(&synset, |s| {
-                    s.source = Some(source);
+                    s.source = if source.is_empty() { None } else { Some(source) };
                 })
                 .map_err(|e| e.to_string())?;
             }
             Action::ChangeMembers { synset, members } => {
                 change_manager::change_members(
                     wn,
-                    &synset.resolve(&last_synset_id)?,
+                    &synset.resolve(&refs)?,
                     members,
                     changes,
                 )
                 .map_err(|e| e.to_string())?;
             }
+            Action::AddForm { synset, lemma, form } => {
+                let synset = synset.resolve(&refs)?;
+                let pos = entry_pos(wn, &lemma, &synset)?;
+                wn.add_form(&lemma, &pos, form).map_err(|e| e.to_string())?;
+                changes.mark();
+                refs.last_synset = Some(synset);
+            }
+            Action::DeleteForm { synset, lemma, form } => {
+                let synset = synset.resolve(&refs)?;
+                let pos = entry_pos(wn, &lemma, &synset)?;
+                if !wn.remove_form(&lemma, &pos, &form).map_err(|e| e.to_string())? {
+                    return Err(format!("Entry {} has no form {}", lemma, form));
+                }
+                changes.mark();
+                refs.last_synset = Some(synset);
+            }
+            Action::AddPronunciation {
+                synset,
+                lemma,
+                pronunciation,
+                variety,
+            } => {
+                let synset = synset.resolve(&refs)?;
+                let pos = entry_pos(wn, &lemma, &synset)?;
+                wn.add_pronunciation(
+                    &lemma,
+                    &pos,
+                    Pronunciation {
+                        value: pronunciation,
+                        variety,
+                    },
+                )
+                .map_err(|e| e.to_string())?;
+                changes.mark();
+                refs.last_synset = Some(synset);
+            }
+            Action::DeletePronunciation {
+                synset,
+                lemma,
+                pronunciation,
+                variety,
+            } => {
+                let synset = synset.resolve(&refs)?;
+                let pos = entry_pos(wn, &lemma, &synset)?;
+                let pronunciation = Pronunciation {
+                    value: pronunciation,
+                    variety,
+                };
+                if !wn
+                    .remove_pronunciation(&lemma, &pos, &pronunciation)
+                    .map_err(|e| e.to_string())?
+                {
+                    return Err(format!(
+                        "Entry {} has no pronunciation {}",
+                        lemma, pronunciation.value
+                    ));
+                }
+                changes.mark();
+                refs.last_synset = Some(synset);
+            }
+            Action::ChangeAdjposition {
+                synset,
+                lemma,
+                adjposition,
+            } => {
+                if !adjposition.is_empty() && !ADJPOSITIONS.contains(&adjposition.as_str()) {
+                    return Err(format!(
+                        "Invalid adjposition {} (expected one of {})",
+                        adjposition,
+                        ADJPOSITIONS.join(", ")
+                    ));
+                }
+                let synset = synset.resolve(&refs)?;
+                let sense = sense_for_lemma(wn, &lemma, &synset)?;
+                wn.update_sense(&sense, |s| {
+                    s.adjposition = if adjposition.is_empty() {
+                        None
+                    } else {
+                        Some(adjposition)
+                    };
+                })
+                .map_err(|e| e.to_string())?;
+                changes.mark();
+                refs.last_synset = Some(synset);
+                refs.last_sense = Some(sense);
+            }
+            Action::ChangeSubcat {
+                synset,
+                lemma,
+                subcat,
+            } => {
+                let frames = wn.frames_get().map_err(|e| e.to_string())?;
+                if !frames.is_empty() {
+                    if let Some(unknown) = subcat
+                        .iter()
+                        .find(|s| !frames.iter().any(|(key, _)| key == *s))
+                    {
+                        return Err(format!("Unknown subcat frame {}", unknown));
+                    }
+                }
+                let synset = synset.resolve(&refs)?;
+                let sense = sense_for_lemma(wn, &lemma, &synset)?;
+                wn.update_sense(&sense, |s| {
+                    s.subcat = subcat;
+                })
+                .map_err(|e| e.to_string())?;
+                changes.mark();
+                refs.last_synset = Some(synset);
+                refs.last_sense = Some(sense);
+            }
+            Action::RestoreSynset { synset, snapshot } => {
+                let synset = synset.resolve(&refs)?;
+                undo::restore_synset(wn, &synset, *snapshot, changes).map_err(|e| e.to_string())?;
+                refs.last_synset = Some(synset);
+            }
         }
+        inverses.push(inverse);
     }
-    if !actions_for_log.is_empty() {
-        changelog_append(wn, &actions_for_log)?;
+    undo.extend(inverses.into_iter().rev().flatten());
+    Ok((refs.last_synset, validation_report))
+}
+
+/// What `SynsetRef`/`SenseRef`s resolve against, as built up over a batch: the last synset
+/// and sense it created or referenced, and every name an action bound with `as: $name`.
+#[derive(Default)]
+pub(crate) struct Refs {
+    pub(crate) last_synset: Option<SynsetId>,
+    pub(crate) last_sense: Option<SenseId>,
+    named: HashMap<String, (SynsetId, Option<SenseId>)>,
+}
+
+impl Refs {
+    /// Binds `name` (with or without its leading `$`) to `synset` and, if there is an obvious
+    /// one, the sense the binding action created. A name can only be bound once per batch.
+    fn bind(&mut self, name: String, synset: SynsetId, sense: Option<SenseId>) -> Result<(), String> {
+        let name = name.strip_prefix('$').map(str::to_owned).unwrap_or(name);
+        if name.is_empty() {
+            return Err("Empty name in `as`".to_string());
+        }
+        if self.named.contains_key(&name) {
+            return Err(format!("${} is already bound in this batch", name));
+        }
+        self.named.insert(name, (synset, sense));
+        Ok(())
+    }
+
+    fn named(&self, name: &str) -> Result<&(SynsetId, Option<SenseId>), String> {
+        self.named
+            .get(name)
+            .ok_or(format!("${} is not bound by an earlier action in this batch", name))
     }
-    Ok((last_synset_id, validation_report))
 }
 
 #[derive(Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
 pub enum SynsetRef {
     Id(SynsetId),
     Last,
+    /// `$name`: the synset an earlier `add_synset`/`add_entry` in the same batch bound to
+    /// `name` with `as`.
+    Named(String),
 }
 
 impl SynsetRef {
-    fn resolve(self, last: &Option<SynsetId>) -> Result<SynsetId, String> {
+    pub(crate) fn resolve(self, refs: &Refs) -> Result<SynsetId, String> {
         match self {
             SynsetRef::Id(id) => Ok(id),
-            SynsetRef::Last => last.clone().ok_or("No last synset id".to_string()),
+            SynsetRef::Last => refs.last_synset.clone().ok_or("No last synset id".to_string()),
+            SynsetRef::Named(name) => Ok(refs.named(&name)?.0.clone()),
         }
     }
 
@@ -514,10 +857,11 @@ impl SynsetRef {
         SynsetRef::Id(SynsetId::new(s))
     }
 
-    fn as_str(&self) -> &str {
+    pub(crate) fn as_str(&self) -> String {
         match self {
-            SynsetRef::Id(id) => id.as_str(),
-            SynsetRef::Last => "last",
+            SynsetRef::Id(id) => id.as_str().to_string(),
+            SynsetRef::Last => "last".to_string(),
+            SynsetRef::Named(name) => format!("${}", name),
         }
     }
 }
@@ -530,6 +874,7 @@ impl Serialize for SynsetRef {
         match self {
             SynsetRef::Id(id) => id.serialize(serializer),
             SynsetRef::Last => serializer.serialize_str("last"),
+            SynsetRef::Named(name) => serializer.serialize_str(&format!("${}", name)),
         }
     }
 }
@@ -542,13 +887,16 @@ impl<'de> Deserialize<'de> for SynsetRef {
         let s = String::deserialize(deserializer)?;
         match s.as_str() {
             "last" => Ok(SynsetRef::Last),
-            _ => Ok(SynsetRef::Id(SynsetId::new_owned(s))),
+            _ => match s.strip_prefix('$') {
+                Some(name) => Ok(SynsetRef::Named(name.to_owned())),
+                None => Ok(SynsetRef::Id(SynsetId::new_owned(s))),
+            },
         }
     }
 }
 
 // Hand-written rather than derived: `SynsetRef` has a custom `Serialize`/`Deserialize` (a plain
-// id string, or the literal "last") that a derived schema wouldn't reflect.
+// id string, the literal "last", or "$name") that a derived schema wouldn't reflect.
 #[cfg(feature = "schema")]
 impl schemars::JsonSchema for SynsetRef {
     fn schema_name() -> std::borrow::Cow<'static, str> {
@@ -558,7 +906,7 @@ impl schemars::JsonSchema for SynsetRef {
     fn json_schema(_generator: &mut schemars::SchemaGenerator) -> schemars::Schema {
         schemars::json_schema!({
             "type": "string",
-            "description": "A synset id (e.g. \"00001740-n\"), or \"last\" to refer to the most recently created/referenced synset earlier in this action batch."
+            "description": "A synset id (e.g. \"00001740-n\"), \"last\" to refer to the most recently created/referenced synset earlier in this action batch, or \"$name\" to refer to the synset an earlier add_synset/add_entry in this batch bound to that name with `as`."
         })
     }
 }
@@ -568,12 +916,15 @@ pub enum SenseRef {
     Id(SenseId),
     Lemma(String),
     Last,
+    /// `$name`: the sense an earlier `add_entry` (or one-lemma `add_synset`) in the same batch
+    /// bound to `name` with `as`.
+    Named(String),
 }
 
 impl SenseRef {
-    fn resolve<L: Lexicon>(
+    pub(crate) fn resolve<L: Lexicon>(
         self,
-        last: &Option<SenseId>,
+        refs: &Refs,
         wn: &L,
         synset: &SynsetId,
     ) -> Result<SenseId, String> {
@@ -592,7 +943,11 @@ impl SenseRef {
                     lemma,
                     synset.as_str()
                 )),
-            SenseRef::Last => last.clone().ok_or("No last sense id".to_string()),
+            SenseRef::Last => refs.last_sense.clone().ok_or("No last sense id".to_string()),
+            SenseRef::Named(name) => refs.named(&name)?.1.clone().ok_or(format!(
+                "${} names a synset with several lemmas - pick one with lemma=",
+                name
+            )),
         }
     }
     #[cfg(test)]
@@ -615,6 +970,7 @@ impl Serialize for SenseRef {
             SenseRef::Id(id) => id.serialize(serializer),
             SenseRef::Lemma(lemma) => serializer.serialize_str(&format!("lemma={}", lemma)),
             SenseRef::Last => serializer.serialize_str("last"),
+            SenseRef::Named(name) => serializer.serialize_str(&format!("${}", name)),
         }
     }
 }
@@ -630,6 +986,8 @@ impl<'de> Deserialize<'de> for SenseRef {
             _ => {
                 if s.starts_with("lemma=") {
                     Ok(SenseRef::Lemma(s[6..].to_owned()))
+                } else if let Some(name) = s.strip_prefix('$') {
+                    Ok(SenseRef::Named(name.to_owned()))
                 } else {
                     Ok(SenseRef::Id(SenseId::new(s)))
                 }
@@ -639,7 +997,7 @@ impl<'de> Deserialize<'de> for SenseRef {
 }
 
 // Hand-written rather than derived: `SenseRef` has a custom `Serialize`/`Deserialize` (a plain
-// sense id, "lemma=..." , or the literal "last") that a derived schema wouldn't reflect.
+// sense id, "lemma=...", the literal "last", or "$name") that a derived schema wouldn't reflect.
 #[cfg(feature = "schema")]
 impl schemars::JsonSchema for SenseRef {
     fn schema_name() -> std::borrow::Cow<'static, str> {
@@ -649,14 +1007,214 @@ impl schemars::JsonSchema for SenseRef {
     fn json_schema(_generator: &mut schemars::SchemaGenerator) -> schemars::Schema {
         schemars::json_schema!({
             "type": "string",
-            "description": "A sense id (e.g. \"example%1:09:00::\"), \"lemma=<word>\" to pick the sense of the given lemma in the referenced synset, or \"last\" to refer to the most recently created/referenced sense earlier in this action batch."
+            "description": "A sense id (e.g. \"example%1:09:00::\"), \"lemma=<word>\" to pick the sense of the given lemma in the referenced synset, \"last\" to refer to the most recently created/referenced sense earlier in this action batch, or \"$name\" to refer to the sense an earlier add_entry (or single-lemma add_synset) in this batch bound to that name with `as`."
         })
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
-#[serde(transparent)]
-pub struct ActionWrapper(#[serde(with = "serde_yaml::with::singleton_map")] pub Action);
+/// An [`Action`] as written in an automaton script: a single-key map from the action's name
+/// to its fields (or just the name, for an action without any). A guarded action adds `when`
+/// and/or `unless` keys alongside:
+///
+/// ```yaml
+/// - add_entry:
+///     synset: 00001740-n
+///     lemma: bar
+///     pos: n
+///   unless:
+///     synset: 00001740-n
+///     member: bar
+/// ```
+#[derive(Debug)]
+pub struct ActionWrapper(pub Action);
+
+impl Serialize for ActionWrapper {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match &self.0 {
+            Action::Guarded {
+                when,
+                unless,
+                action,
+            } => {
+                if matches!(**action, Action::Guarded { .. }) {
+                    return Err(serde::ser::Error::custom(
+                        "Nested guards can't be written as an automaton script",
+                    ));
+                }
+                let mut map = match serde_yaml::with::singleton_map::serialize(
+                    action,
+                    serde_yaml::value::Serializer,
+                )
+                .map_err(serde::ser::Error::custom)?
+                {
+                    serde_yaml::Value::Mapping(map) => map,
+                    // A fieldless action is just its name, which needs to become a key too.
+                    name => {
+                        let mut map = serde_yaml::Mapping::new();
+                        map.insert(name, serde_yaml::Value::Null);
+                        map
+                    }
+                };
+                for (key, condition) in [("when", when), ("unless", unless)] {
+                    if let Some(condition) = condition {
+                        map.insert(
+                            key.into(),
+                            serde_yaml::to_value(condition).map_err(serde::ser::Error::custom)?,
+                        );
+                    }
+                }
+                map.serialize(serializer)
+            }
+            action => serde_yaml::with::singleton_map::serialize(action, serializer),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for ActionWrapper {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let mut value = serde_yaml::Value::deserialize(deserializer)?;
+        let mut condition = |key: &str| -> Result<Option<Condition>, D::Error> {
+            match value.as_mapping_mut().and_then(|map| map.remove(key)) {
+                Some(condition) => serde_yaml::from_value(condition)
+                    .map(Some)
+                    .map_err(|e| serde::de::Error::custom(format!("Bad `{}`: {}", key, e))),
+                None => Ok(None),
+            }
+        };
+        let when = condition("when")?;
+        let unless = condition("unless")?;
+        // `- validate:` (with a null value) as well as `- validate`, since that's what a
+        // fieldless action next to a guard has to look like.
+        if let serde_yaml::Value::Mapping(map) = &value {
+            if let Some((name, serde_yaml::Value::Null)) = map.iter().next().filter(|_| map.len() == 1) {
+                value = name.clone();
+            }
+        }
+        let action: Action =
+            serde_yaml::with::singleton_map::deserialize(value).map_err(serde::de::Error::custom)?;
+        if when.is_none() && unless.is_none() {
+            Ok(ActionWrapper(action))
+        } else {
+            Ok(ActionWrapper(Action::Guarded {
+                when,
+                unless,
+                action: Box::new(action),
+            }))
+        }
+    }
+}
+
+/// Something an automaton script can check about a synset before going ahead: that it exists
+/// and, optionally, that it has a given definition and/or member. Used by the `assert_*`
+/// actions, and as the `when`/`unless` of a guarded action.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct Condition {
+    pub synset: SynsetRef,
+    /// One of the synset's definitions, exactly.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub definition: Option<String>,
+    /// A lemma that is a member of the synset.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub member: Option<String>,
+}
+
+impl Condition {
+    fn exists(synset: SynsetRef) -> Condition {
+        Condition {
+            synset,
+            definition: None,
+            member: None,
+        }
+    }
+
+    /// Why the condition doesn't hold, or `None` if it does.
+    fn failure<L: Lexicon>(&self, wn: &L, refs: &Refs) -> Result<Option<String>, String> {
+        let id = self.synset.clone().resolve(refs)?;
+        let synset = match wn.synset_by_id(&id).map_err(|e| e.to_string())? {
+            Some(synset) => synset,
+            None => return Ok(Some(format!("synset {} does not exist", id.as_str()))),
+        };
+        if let Some(definition) = &self.definition {
+            if !synset.definition.contains(definition) {
+                return Ok(Some(format!(
+                    "synset {} is defined as \"{}\", not \"{}\"",
+                    id.as_str(),
+                    synset.definition.join("; "),
+                    definition
+                )));
+            }
+        }
+        if let Some(member) = &self.member {
+            if !synset.members.contains(member) {
+                return Ok(Some(format!(
+                    "\"{}\" is not a member of synset {}",
+                    member,
+                    id.as_str()
+                )));
+            }
+        }
+        Ok(None)
+    }
+
+    fn holds<L: Lexicon>(&self, wn: &L, refs: &Refs) -> Result<bool, String> {
+        Ok(self.failure(wn, refs)?.is_none())
+    }
+
+    /// Fails (with an error aborting the batch) unless the condition holds.
+    fn check<L: Lexicon>(&self, wn: &L, refs: &Refs) -> Result<(), String> {
+        match self.failure(wn, refs)? {
+            Some(failure) => Err(format!("Assertion failed: {}", failure)),
+            None => Ok(()),
+        }
+    }
+}
+
+impl std::fmt::Display for Condition {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} exists", self.synset.as_str())?;
+        if let Some(definition) = &self.definition {
+            write!(f, " with definition \"{}\"", definition)?;
+        }
+        if let Some(member) = &self.member {
+            write!(f, " with member \"{}\"", member)?;
+        }
+        Ok(())
+    }
+}
+
+/// Strips the guards off `action`, returning what's left to apply - or `None` if a guard says
+/// to skip it.
+fn unguard<L: Lexicon>(wn: &L, action: Action, refs: &Refs) -> Result<Option<Action>, String> {
+    let mut action = action;
+    while let Action::Guarded {
+        when,
+        unless,
+        action: inner,
+    } = action
+    {
+        if let Some(when) = when {
+            if !when.holds(wn, refs)? {
+                return Ok(None);
+            }
+        }
+        if let Some(unless) = unless {
+            if unless.holds(wn, refs)? {
+                return Ok(None);
+            }
+        }
+        action = *inner;
+    }
+    Ok(Some(action))
+}
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
@@ -669,6 +1227,11 @@ pub enum Action {
         #[serde(default)]
         #[serde(skip_serializing_if = "Vec::is_empty")]
         subcat: Vec<String>,
+        /// Binds the new sense (and its synset) to `$name` for later actions in the batch.
+        #[serde(rename = "as")]
+        #[serde(default)]
+        #[serde(skip_serializing_if = "Option::is_none")]
+        name: Option<String>,
     },
     #[serde(rename = "delete_entry")]
     DeleteEntry { synset: SynsetRef, lemma: String },
@@ -678,11 +1241,66 @@ pub enum Action {
         lemma: String,
         target_synset: SynsetRef,
     },
+    /// Respells the entry `lemma` (with part of speech key `pos`) as `new_lemma`, keeping its
+    /// senses' relations (see [`change_manager::rename_lemma`]).
+    #[serde(rename = "rename_lemma")]
+    RenameLemma {
+        lemma: String,
+        pos: PosKey,
+        new_lemma: String,
+    },
     #[serde(rename = "change_members")]
     ChangeMembers {
         synset: SynsetRef,
         members: Vec<String>,
     },
+    #[serde(rename = "add_form")]
+    AddForm {
+        synset: SynsetRef,
+        lemma: String,
+        form: String,
+    },
+    #[serde(rename = "delete_form")]
+    DeleteForm {
+        synset: SynsetRef,
+        lemma: String,
+        form: String,
+    },
+    #[serde(rename = "add_pronunciation")]
+    AddPronunciation {
+        synset: SynsetRef,
+        lemma: String,
+        pronunciation: String,
+        #[serde(default)]
+        #[serde(skip_serializing_if = "Option::is_none")]
+        variety: Option<String>,
+    },
+    /// Removes the pronunciation with exactly this `variety` (or none, if it is omitted).
+    #[serde(rename = "delete_pronunciation")]
+    DeletePronunciation {
+        synset: SynsetRef,
+        lemma: String,
+        pronunciation: String,
+        #[serde(default)]
+        #[serde(skip_serializing_if = "Option::is_none")]
+        variety: Option<String>,
+    },
+    /// Sets the adjposition (`a`, `ip` or `p`) of `lemma`'s sense in `synset`; an empty
+    /// `adjposition` removes it.
+    #[serde(rename = "change_adjposition")]
+    ChangeAdjposition {
+        synset: SynsetRef,
+        lemma: String,
+        adjposition: String,
+    },
+    /// Replaces the subcat frames of `lemma`'s sense in `synset`; an empty list removes them.
+    #[serde(rename = "change_subcat")]
+    ChangeSubcat {
+        synset: SynsetRef,
+        lemma: String,
+        #[serde(default)]
+        subcat: Vec<String>,
+    },
     #[serde(rename = "add_synset")]
     AddSynset {
         definition: String,
@@ -694,6 +1312,12 @@ pub enum Action {
         #[serde(default)]
         #[serde(skip_serializing_if = "Vec::is_empty")]
         subcats: Vec<Vec<String>>,
+        /// Binds the new synset (and, if it has a single lemma, its sense) to `$name` for later
+        /// actions in the batch.
+        #[serde(rename = "as")]
+        #[serde(default)]
+        #[serde(skip_serializing_if = "Option::is_none")]
+        name: Option<String>,
     },
     #[serde(rename = "delete_synset")]
     DeleteSynset {
@@ -707,11 +1331,61 @@ pub enum Action {
         #[serde(skip_serializing_if = "Option::is_none")]
         superseded_by: Option<SynsetRef>,
     },
+    /// Merges `merged` into `synset`, which keeps its id: see
+    /// [`change_manager::merge_synsets`]. `merged` is deprecated in favour of `synset`, for
+    /// `reason`. The surviving synset keeps its own definition unless `definition` is given.
+    #[serde(rename = "merge_synsets")]
+    MergeSynsets {
+        synset: SynsetRef,
+        merged: SynsetRef,
+        #[serde(default)]
+        #[serde(skip_serializing_if = "Option::is_none")]
+        definition: Option<String>,
+        reason: String,
+    },
+    /// Splits `members` off `synset` into a new synset with `definition` (see
+    /// [`change_manager::split_synset`]). Each of `relations` says whether a relation of the
+    /// original is copied to the new synset or moved there; any not listed stay where they are.
+    #[serde(rename = "split_synset")]
+    SplitSynset {
+        synset: SynsetRef,
+        members: Vec<String>,
+        definition: String,
+        /// The new synset's lexicographer file, if not the original's.
+        #[serde(default)]
+        #[serde(skip_serializing_if = "Option::is_none")]
+        lexfile: Option<String>,
+        #[serde(default)]
+        #[serde(skip_serializing_if = "Vec::is_empty")]
+        relations: Vec<SplitRelationItem>,
+        /// Binds the new synset to `$name` for later actions in the batch.
+        #[serde(rename = "as")]
+        #[serde(default)]
+        #[serde(skip_serializing_if = "Option::is_none")]
+        name: Option<String>,
+    },
+    /// Moves `synset` to the lexicographer file `lexfile`, renaming its senses to match (see
+    /// [`change_manager::change_lexfile`]).
+    #[serde(rename = "change_lexfile")]
+    ChangeLexfile { synset: SynsetRef, lexfile: String },
+    /// Changes the part of speech of `synset` (e.g. from head adjective `a` to satellite `s`),
+    /// giving it a new id and moving its members' entries and senses across (see
+    /// [`change_manager::change_pos`]). `lexfile` is only needed if the current one does not
+    /// take the new part of speech.
+    #[serde(rename = "change_pos")]
+    ChangePos {
+        synset: SynsetRef,
+        pos: PosKey,
+        #[serde(default)]
+        #[serde(skip_serializing_if = "Option::is_none")]
+        lexfile: Option<String>,
+    },
     #[serde(rename = "change_definition")]
     Definition {
         synset: SynsetRef,
         definition: String,
     },
+    /// An empty `ili` removes the synset's ILI.
     #[serde(rename = "change_ili")]
     ChangeILI { synset: SynsetRef, ili: String },
     #[serde(rename = "change_wikidata")]
@@ -720,6 +1394,7 @@ pub enum Action {
         #[serde(deserialize_with = "crate::wordnet::string_or_vec")]
         wikidata: Vec<String>,
     },
+    /// An empty `source` removes the synset's source.
     #[serde(rename = "change_source")]
     ChangeSource { synset: SynsetRef, source: String },
     #[serde(rename = "add_example")]
@@ -766,6 +1441,11 @@ pub enum Action {
         #[serde(default)]
         #[serde(skip_serializing_if = "Option::is_none")]
         source_sense: Option<SenseRef>,
+        /// Only this relation (and its inverse), if given, of those between two synsets -
+        /// otherwise every relation between the pair goes
+        #[serde(default)]
+        #[serde(skip_serializing_if = "Option::is_none")]
+        relation: Option<String>,
         target: SynsetRef,
         #[serde(default)]
         #[serde(skip_serializing_if = "Option::is_none")]
@@ -799,9 +1479,193 @@ pub enum Action {
     Validate,
     #[serde(rename = "fix_transitivity")]
     FixTransitivity,
+    /// Applies every automatic fix [`crate::validate::fix`] has for what validation finds -
+    /// except for checks the project's validation profile switches off (see
+    /// [`apply_automaton_with_profile`]) - reporting what is left (as a `validate` action would).
+    #[serde(rename = "fix_validation_errors")]
+    FixValidationErrors,
+    /// Aborts the batch unless `synset` exists.
+    #[serde(rename = "assert_synset_exists")]
+    AssertSynsetExists { synset: SynsetRef },
+    /// Aborts the batch unless `synset` exists and has `definition` among its definitions.
+    #[serde(rename = "assert_definition")]
+    AssertDefinition {
+        synset: SynsetRef,
+        definition: String,
+    },
+    /// Aborts the batch unless `synset` exists and has `lemma` as a member.
+    #[serde(rename = "assert_member")]
+    AssertMember { synset: SynsetRef, lemma: String },
+    /// Applies `action` only if `when` (if given) holds and `unless` (if given) doesn't -
+    /// otherwise skips it and carries on with the rest of the batch. In an automaton script
+    /// this is written as `when:`/`unless:` keys next to the action itself (see
+    /// [`ActionWrapper`]).
+    #[serde(rename = "guarded")]
+    Guarded {
+        #[serde(default)]
+        #[serde(skip_serializing_if = "Option::is_none")]
+        when: Option<Condition>,
+        #[serde(default)]
+        #[serde(skip_serializing_if = "Option::is_none")]
+        unless: Option<Condition>,
+        action: Box<Action>,
+    },
+    /// Puts `synset` back exactly as `snapshot` captured it - its members' senses and every
+    /// relation to or from it included - whether or not it currently exists. Not something a
+    /// script would normally spell out by hand: it's what undoing a `delete_synset` (among
+    /// others) applies, see [`crate::undo`].
+    #[serde(rename = "restore_synset")]
+    RestoreSynset {
+        synset: SynsetRef,
+        #[cfg_attr(feature = "schema", schemars(schema_with = "crate::undo::synset_snapshot_schema"))]
+        snapshot: Box<SynsetSnapshot>,
+    },
 }
 
 impl Action {
+    /// The action itself, with any guards around it stripped off.
+    pub fn unguarded(&self) -> &Action {
+        match self {
+            Action::Guarded { action, .. } => action.unguarded(),
+            action => action,
+        }
+    }
+
+    /// Adds the synset and sense ids this action names to `synsets` and `senses` (see
+    /// [`ChangeLogEntry::touched`]). References by name or to the last synset can't be
+    /// resolved after the fact, so are left out.
+    fn touched(&self, synsets: &mut Vec<SynsetId>, senses: &mut Vec<SenseId>) {
+        let mut synset = |synset: &SynsetRef| {
+            if let SynsetRef::Id(id) = synset {
+                synsets.push(id.clone());
+            }
+        };
+        let mut sense = |sense: &Option<SenseRef>| {
+            if let Some(SenseRef::Id(id)) = sense {
+                senses.push(id.clone());
+            }
+        };
+        match self.unguarded() {
+            Action::AddEntry { synset: s, .. }
+            | Action::DeleteEntry { synset: s, .. }
+            | Action::ChangeMembers { synset: s, .. }
+            | Action::AddForm { synset: s, .. }
+            | Action::DeleteForm { synset: s, .. }
+            | Action::AddPronunciation { synset: s, .. }
+            | Action::DeletePronunciation { synset: s, .. }
+            | Action::ChangeAdjposition { synset: s, .. }
+            | Action::ChangeSubcat { synset: s, .. }
+            | Action::ChangeLexfile { synset: s, .. }
+            | Action::ChangePos { synset: s, .. }
+            | Action::Definition { synset: s, .. }
+            | Action::ChangeILI { synset: s, .. }
+            | Action::ChangeWikidata { synset: s, .. }
+            | Action::ChangeSource { synset: s, .. }
+            | Action::AddExample { synset: s, .. }
+            | Action::UpdateExample { synset: s, .. }
+            | Action::DeleteExample { synset: s, .. } => synset(s),
+            Action::MoveEntry {
+                synset: s,
+                target_synset,
+                ..
+            } => {
+                synset(s);
+                synset(target_synset);
+            }
+            Action::DeleteSynset {
+                synset: s,
+                superseded_by,
+                ..
+            } => {
+                synset(s);
+                if let Some(superseded_by) = superseded_by {
+                    synset(superseded_by);
+                }
+            }
+            Action::MergeSynsets { synset: s, merged, .. } => {
+                synset(s);
+                synset(merged);
+            }
+            Action::SplitSynset {
+                synset: s,
+                relations,
+                ..
+            } => {
+                synset(s);
+                for item in relations {
+                    synset(&item.target);
+                }
+            }
+            Action::UpdateRelations { synset: s, relations } => {
+                synset(s);
+                for item in relations {
+                    synset(&item.target);
+                    sense(&item.source_sense);
+                    sense(&item.target_sense);
+                }
+            }
+            Action::AddRelation {
+                source,
+                source_sense,
+                target,
+                target_sense,
+                ..
+            }
+            | Action::DeleteRelation {
+                source,
+                source_sense,
+                target,
+                target_sense,
+                ..
+            }
+            | Action::ReverseRelation {
+                source,
+                source_sense,
+                target,
+                target_sense,
+            } => {
+                synset(source);
+                synset(target);
+                sense(source_sense);
+                sense(target_sense);
+            }
+            Action::RestoreSynset { synset: s, snapshot } => {
+                synset(s);
+                for (_, target) in snapshot.synset.links_from() {
+                    synset(&SynsetRef::Id(target));
+                }
+                for incoming in snapshot.incoming.iter() {
+                    synset(&SynsetRef::Id(incoming.source.clone()));
+                }
+                for member in snapshot.senses.iter() {
+                    sense(&Some(SenseRef::Id(member.sense.id.clone())));
+                    for (_, target) in member.sense.sense_links_from() {
+                        match target {
+                            UnresolvedSenseOrSynsetId::Sense(id) => {
+                                sense(&Some(SenseRef::Id(id)))
+                            }
+                            UnresolvedSenseOrSynsetId::Synset(id) => synset(&SynsetRef::Id(id)),
+                            UnresolvedSenseOrSynsetId::Unresolved(_) => {}
+                        }
+                    }
+                }
+                for incoming in snapshot.incoming_sense.iter() {
+                    sense(&Some(SenseRef::Id(incoming.source.clone())));
+                }
+            }
+            // Name no existing synset: the undo batch logged with them names what they changed.
+            Action::RenameLemma { .. }
+            | Action::AddSynset { .. }
+            | Action::Validate
+            | Action::FixTransitivity
+            | Action::FixValidationErrors
+            | Action::AssertSynsetExists { .. }
+            | Action::AssertDefinition { .. }
+            | Action::AssertMember { .. }
+            | Action::Guarded { .. } => {}
+        }
+    }
+
     /// A short, human-readable one-line description of this action, for the change log UI.
     pub fn summary(&self) -> String {
         fn sense_ref(r: &SenseRef) -> String {
@@ -809,12 +1673,13 @@ impl Action {
                 SenseRef::Id(id) => id.as_str().to_string(),
                 SenseRef::Lemma(lemma) => format!("lemma={}", lemma),
                 SenseRef::Last => "last".to_string(),
+                SenseRef::Named(name) => format!("${}", name),
             }
         }
         fn synset_with_sense(synset: &SynsetRef, sense: &Option<SenseRef>) -> String {
             match sense {
                 Some(s) => format!("{}[{}]", synset.as_str(), sense_ref(s)),
-                None => synset.as_str().to_string(),
+                None => synset.as_str(),
             }
         }
         match self {
@@ -833,11 +1698,55 @@ impl Action {
                 synset.as_str(),
                 target_synset.as_str()
             ),
+            Action::RenameLemma { lemma, pos, new_lemma } => format!(
+                "Renamed entry \"{}\" ({}) to \"{}\"",
+                lemma,
+                pos.as_str(),
+                new_lemma
+            ),
             Action::ChangeMembers { synset, members } => format!(
                 "Changed members of {} to [{}]",
                 synset.as_str(),
                 members.join(", ")
             ),
+            Action::AddForm { synset, lemma, form } => format!(
+                "Added form \"{}\" to entry \"{}\" in {}",
+                form,
+                lemma,
+                synset.as_str()
+            ),
+            Action::DeleteForm { synset, lemma, form } => format!(
+                "Deleted form \"{}\" from entry \"{}\" in {}",
+                form,
+                lemma,
+                synset.as_str()
+            ),
+            Action::AddPronunciation { synset, lemma, pronunciation, variety } => format!(
+                "Added pronunciation /{}/{} to entry \"{}\" in {}",
+                pronunciation,
+                variety.as_ref().map(|v| format!(" ({})", v)).unwrap_or_default(),
+                lemma,
+                synset.as_str()
+            ),
+            Action::DeletePronunciation { synset, lemma, pronunciation, variety } => format!(
+                "Deleted pronunciation /{}/{} from entry \"{}\" in {}",
+                pronunciation,
+                variety.as_ref().map(|v| format!(" ({})", v)).unwrap_or_default(),
+                lemma,
+                synset.as_str()
+            ),
+            Action::ChangeAdjposition { synset, lemma, adjposition } => format!(
+                "Changed adjposition of \"{}\" in {} to \"{}\"",
+                lemma,
+                synset.as_str(),
+                adjposition
+            ),
+            Action::ChangeSubcat { synset, lemma, subcat } => format!(
+                "Changed subcat frames of \"{}\" in {} to [{}]",
+                lemma,
+                synset.as_str(),
+                subcat.join(", ")
+            ),
             Action::AddSynset { definition, lexfile, lemmas, .. } => format!(
                 "Added synset in {} ({}): {}",
                 lexfile,
@@ -857,6 +1766,24 @@ impl Action {
                     reason
                 ),
             },
+            Action::ChangeLexfile { synset, lexfile } => {
+                format!("Moved {} to {}", synset.as_str(), lexfile)
+            }
+            Action::ChangePos { synset, pos, .. } => {
+                format!("Changed part of speech of {} to {}", synset.as_str(), pos.as_str())
+            }
+            Action::SplitSynset { synset, members, definition, .. } => format!(
+                "Split [{}] off {}: {}",
+                members.join(", "),
+                synset.as_str(),
+                definition
+            ),
+            Action::MergeSynsets { synset, merged, reason, .. } => format!(
+                "Merged synset {} into {} (reason: {})",
+                merged.as_str(),
+                synset.as_str(),
+                reason
+            ),
             Action::Definition { synset, definition } => {
                 format!("Changed definition of {} to \"{}\"", synset.as_str(), definition)
             }
@@ -891,8 +1818,16 @@ impl Action {
                     synset_with_sense(target, target_sense)
                 )
             }
-            Action::DeleteRelation { source, source_sense, target, target_sense, .. } => format!(
-                "Deleted relation from {} to {}",
+            Action::DeleteRelation {
+                source,
+                source_sense,
+                relation,
+                target,
+                target_sense,
+                ..
+            } => format!(
+                "Deleted relation {}from {} to {}",
+                relation.as_ref().map(|r| format!("{} ", r)).unwrap_or_default(),
                 synset_with_sense(source, source_sense),
                 synset_with_sense(target, target_sense)
             ),
@@ -908,6 +1843,33 @@ impl Action {
             ),
             Action::Validate => "Validated the lexicon".to_string(),
             Action::FixTransitivity => "Fixed transitivity of relations".to_string(),
+            Action::FixValidationErrors => "Fixed validation errors".to_string(),
+            Action::AssertSynsetExists { synset } => {
+                format!("Checked that synset {} exists", synset.as_str())
+            }
+            Action::AssertDefinition { synset, definition } => format!(
+                "Checked the definition of {}: {}",
+                synset.as_str(),
+                definition
+            ),
+            Action::AssertMember { synset, lemma } => {
+                format!("Checked that \"{}\" is in {}", lemma, synset.as_str())
+            }
+            Action::Guarded {
+                when,
+                unless,
+                action,
+            } => {
+                let mut summary = action.summary();
+                if let Some(when) = when {
+                    summary.push_str(&format!(" (when {})", when));
+                }
+                if let Some(unless) = unless {
+                    summary.push_str(&format!(" (unless {})", unless));
+                }
+                summary
+            }
+            Action::RestoreSynset { synset, .. } => format!("Restored synset {}", synset.as_str()),
         }
     }
 }
@@ -919,19 +1881,49 @@ impl Action {
 pub struct ChangeLogEntry {
     pub timestamp_ms: u64,
     pub actions: Vec<Action>,
+    /// The batch that reverses this one, worked out while it was applied (see
+    /// [`crate::undo`]) - empty if it changed nothing, or was logged before undo existed.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub undo: Vec<Action>,
+    /// The entry this batch undid, if it was applied by [`crate::undo::undo`].
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub undo_of: Option<u64>,
+    /// The undo entry this batch reversed, if it was applied by [`crate::undo::redo_last`].
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub redo_of: Option<u64>,
 }
 
-/// Serializes `actions` as a [`ChangeLogEntry`] timestamped with the current time and appends it
-/// to `wn`'s change log, returning the id it was stored under.
-fn changelog_append<L: Lexicon>(wn: &mut L, actions: &[Action]) -> Result<u64, String> {
-    let timestamp_ms = SystemTime::now()
+impl ChangeLogEntry {
+    /// Whether [`crate::undo::undo`] has anything to apply for this entry.
+    pub fn is_undoable(&self) -> bool {
+        !self.undo.is_empty()
+    }
+
+    /// The synsets and senses this batch changed, as far as the ids named in it and in its
+    /// undo batch tell - which between them name every synset it changed, the undo batch's
+    /// snapshots naming everything related to a synset beforehand as well. What
+    /// [`crate::undo::undo`] checks newer changes against before undoing an older one, and an
+    /// [`crate::validate::IncrementalValidator`] needs to recheck after it.
+    pub fn touched(&self) -> (Vec<SynsetId>, Vec<SenseId>) {
+        let mut synsets = Vec::new();
+        let mut senses = Vec::new();
+        for action in self.actions.iter().chain(self.undo.iter()) {
+            action.touched(&mut synsets, &mut senses);
+        }
+        (synsets, senses)
+    }
+}
+
+/// Timestamps `entry` with the current time, serializes it and appends it to `wn`'s change
+/// log, returning the id it was stored under.
+fn changelog_append<L: Lexicon>(wn: &mut L, mut entry: ChangeLogEntry) -> Result<u64, String> {
+    entry.timestamp_ms = SystemTime::now()
         .duration_since(UNIX_EPOCH)
         .map(|d| d.as_millis() as u64)
         .unwrap_or(0);
-    let entry = ChangeLogEntry {
-        timestamp_ms,
-        actions: actions.to_vec(),
-    };
     let yaml = serde_yaml::to_string(&entry).map_err(|e| e.to_string())?;
     wn.changelog_append(yaml).map_err(|e| e.to_string())
 }
@@ -971,6 +1963,74 @@ pub fn has_unsaved_changes<L: Lexicon>(wn: &L) -> Result<bool, String> {
     })
 }
 
+/// The values `Sense::adjposition` may take, as in the GWA schema.
+const ADJPOSITIONS: [&str; 3] = ["a", "ip", "p"];
+
+/// The part of speech of `lemma`'s entry in `synset`, for actions that edit the entry itself.
+fn entry_pos<L: Lexicon>(wn: &L, lemma: &str, synset: &SynsetId) -> Result<PosKey, String> {
+    wn.pos_for_entry_synset(lemma, synset)
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| format!("Entry {} not found in synset {}", lemma, synset.as_str()))
+}
+
+/// The id of `lemma`'s sense in `synset`, for actions that edit the sense itself.
+pub(crate) fn sense_for_lemma<L: Lexicon>(
+    wn: &L,
+    lemma: &str,
+    synset: &SynsetId,
+) -> Result<SenseId, String> {
+    wn.get_sense_id2(lemma, synset)
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| format!("No sense with lemma {} in {}", lemma, synset.as_str()))
+}
+
+/// Resolves the sense an action names within `synset`, either directly or by lemma - `None` if
+/// it names neither, i.e. the action is about `synset` itself.
+pub(crate) fn resolve_sense<L: Lexicon>(
+    wn: &L,
+    sense: &Option<SenseRef>,
+    lemma: &Option<String>,
+    synset: &SynsetId,
+    refs: &Refs,
+) -> Result<Option<SenseId>, String> {
+    if let Some(sense) = sense {
+        Ok(Some(sense.clone().resolve(refs, wn, synset)?))
+    } else if let Some(lemma) = lemma.as_ref() {
+        Ok(Some(
+            wn.get_sense_id2(lemma, synset)
+                .map_err(|e| e.to_string())?
+                .ok_or(format!(
+                    "No sense with lemma {} in {}",
+                    lemma,
+                    synset.as_str()
+                ))?,
+        ))
+    } else {
+        Ok(None)
+    }
+}
+
+/// A relation of the synset a `split_synset` action splits, from that synset's point of view
+/// (e.g. `hyponym` for a synset whose hypernym it is), and what happens to it.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct SplitRelationItem {
+    relation: String,
+    target: SynsetRef,
+    mode: SplitMode,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub enum SplitMode {
+    /// Both synsets have the relation.
+    #[serde(rename = "copy")]
+    Copy,
+    /// Only the new synset has the relation.
+    #[serde(rename = "move")]
+    Move,
+}
+
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct UpdateRelationItem {
@@ -995,42 +2055,13 @@ impl UpdateRelationItem {
         &self,
         wn: &mut L,
         source: &SynsetId,
-        last_synset_id: &Option<SynsetId>,
-        last_sense_id: &Option<SenseId>,
+        refs: &Refs,
     ) -> Result<RelationUpdate, String> {
-        let source_sense = if let Some(ref source_sense) = self.source_sense {
-            Some(source_sense.clone().resolve(&last_sense_id, wn, &source)?)
-        } else if let Some(lemma) = self.source_lemma.as_ref() {
-            Some(
-                wn.get_sense_id2(&lemma, &source)
-                    .map_err(|e| e.to_string())?
-                    .ok_or(format!(
-                        "No sense with lemma {} in {}",
-                        lemma,
-                        source.as_str()
-                    ))?
-                    .clone(),
-            )
-        } else {
-            None
-        };
-        let target = self.target.clone().resolve(&last_synset_id)?;
-        let target_sense = if let Some(ref target_sense) = self.target_sense {
-            Some(target_sense.clone().resolve(&last_sense_id, wn, &target)?)
-        } else if let Some(lemma) = self.target_lemma.as_ref() {
-            Some(
-                wn.get_sense_id2(&lemma, &target)
-                    .map_err(|e| e.to_string())?
-                    .ok_or(format!(
-                        "No sense with lemma {} in {}",
-                        lemma,
-                        target.as_str()
-                    ))?
-                    .clone(),
-            )
-        } else {
-            None
-        };
+        let source_sense =
+            resolve_sense(wn, &self.source_sense, &self.source_lemma, source, refs)?;
+        let target = self.target.clone().resolve(refs)?;
+        let target_sense =
+            resolve_sense(wn, &self.target_sense, &self.target_lemma, &target, refs)?;
         match source_sense {
             Some(sense) => {
                 let rel = SenseRelType::from(&self.relation)
@@ -1072,6 +2103,10 @@ mod tests {
             "Action's derived schema should be a union of its variants, got {:?}",
             action_schema
         );
+        let schema_text = action_schema.to_string();
+        for name in ["add_form", "delete_pronunciation", "change_adjposition", "change_subcat"] {
+            assert!(schema_text.contains(name), "{} missing from Action's schema", name);
+        }
 
         // SynsetRef/SenseRef have hand-written schemas (see their `impl JsonSchema` above) -
         // check they actually describe plain strings, matching their custom Serialize impls.
@@ -1120,6 +2155,7 @@ mod tests {
                 lemma: "bar".to_string(),
                 pos: PosKey::new("n".to_string()),
                 subcat: Vec::new(),
+                name: None,
             },
             Action::DeleteEntry {
                 synset: SynsetRef::id("00001740-n"),
@@ -1136,6 +2172,7 @@ mod tests {
                 pos: Some(PosKey::new("n".to_string())),
                 lemmas: vec!["bar".to_string()],
                 subcats: vec![],
+                name: None,
             },
             Action::DeleteSynset {
                 synset: SynsetRef::Last,
@@ -1173,6 +2210,7 @@ mod tests {
             Action::DeleteRelation {
                 source: SynsetRef::id("00001740-n"),
                 source_sense: Some(SenseRef::id("example%1:09:00::")),
+                relation: None,
                 target: SynsetRef::id("00001741-n"),
                 target_sense: Some(SenseRef::id("target%1:10:00::'")),
                 source_lemma: None,
@@ -1207,6 +2245,7 @@ mod tests {
                 pos: Some(PosKey::new("n".to_string())),
                 lemmas: vec!["bar".to_string()],
                 subcats: vec![],
+                name: None,
             },
             Action::AddRelation {
                 source: SynsetRef::Last,
@@ -1223,6 +2262,289 @@ mod tests {
         apply_automaton(actions, &mut lexicon, &mut ChangeList::new()).unwrap();
     }
 
+    #[test]
+    fn test_named_refs() {
+        let script = "
+- add_synset:
+    definition: a feline mammal
+    lexfile: noun.animal
+    lemmas: [cat]
+    as: $cat
+- add_synset:
+    definition: a young cat
+    lexfile: noun.animal
+    lemmas: [kitten]
+    as: $kitten
+- add_entry:
+    synset: $kitten
+    lemma: kitty
+    pos: n
+    as: $kitty
+- add_relation:
+    source: $kitten
+    relation: hypernym
+    target: $cat
+- add_relation:
+    source: $kitten
+    source_sense: $kitty
+    relation: derivation
+    target: $cat
+    target_sense: $cat
+";
+        let actions: Vec<Action> = serde_yaml::from_str::<Vec<ActionWrapper>>(script)
+            .unwrap()
+            .into_iter()
+            .map(|a| a.0)
+            .collect();
+        assert!(matches!(&actions[0], Action::AddSynset { name: Some(name), .. } if name == "$cat"));
+        assert!(matches!(&actions[3], Action::AddRelation { target: SynsetRef::Named(name), .. } if name == "cat"));
+
+        let mut lexicon = LexiconHashMapBackend::new();
+        lexicon.add_lexfile("noun.animal").unwrap();
+        apply_automaton(actions, &mut lexicon, &mut ChangeList::new()).unwrap();
+
+        let cat = lexicon.entry_by_lemma("cat").unwrap()[0].sense[0].clone();
+        let kitty = lexicon.entry_by_lemma("kitty").unwrap()[0].sense[0].clone();
+        let kitten = lexicon.synset_by_id(&kitty.synset).unwrap().unwrap();
+        assert_eq!(kitten.hypernym, vec![cat.synset.clone()]);
+        assert_eq!(kitty.derivation, vec![cat.id.clone()]);
+    }
+
+    #[test]
+    fn test_named_refs_errors() {
+        let add_synset = |lemmas: &[&str], name: &str| Action::AddSynset {
+            // Synset ids are derived from the definition, so each needs its own.
+            definition: format!("a test synset for {}", lemmas.join(" and ")),
+            lexfile: "noun.animal".to_string(),
+            pos: Some(PosKey::new("n".to_string())),
+            lemmas: lemmas.iter().map(|l| l.to_string()).collect(),
+            subcats: vec![],
+            name: Some(name.to_string()),
+        };
+        let relation = |source_sense: Option<SenseRef>, target: &str| Action::AddRelation {
+            source: SynsetRef::Named("a".to_string()),
+            source_sense,
+            relation: "also".to_string(),
+            target: SynsetRef::Named(target.to_string()),
+            target_sense: None,
+            source_lemma: None,
+            target_lemma: None,
+        };
+        let mut lexicon = LexiconHashMapBackend::new();
+        lexicon.add_lexfile("noun.animal").unwrap();
+
+        let err = apply_automaton(
+            vec![add_synset(&["foo"], "$a"), relation(None, "b")],
+            &mut lexicon,
+            &mut ChangeList::new(),
+        )
+        .err()
+        .unwrap();
+        assert!(err.contains("$b is not bound"), "{}", err);
+
+        let err = apply_automaton(
+            vec![add_synset(&["foo"], "$a"), add_synset(&["bar"], "$a")],
+            &mut lexicon,
+            &mut ChangeList::new(),
+        )
+        .err()
+        .unwrap();
+        assert!(err.contains("$a is already bound"), "{}", err);
+
+        let err = apply_automaton(
+            vec![
+                add_synset(&["foo", "bar"], "$a"),
+                add_synset(&["baz"], "$b"),
+                relation(Some(SenseRef::Named("a".to_string())), "b"),
+            ],
+            &mut lexicon,
+            &mut ChangeList::new(),
+        )
+        .err()
+        .unwrap();
+        assert!(err.contains("several lemmas"), "{}", err);
+    }
+
+    fn parse_script(script: &str) -> Vec<Action> {
+        serde_yaml::from_str::<Vec<ActionWrapper>>(script)
+            .unwrap()
+            .into_iter()
+            .map(|a| a.0)
+            .collect()
+    }
+
+    #[test]
+    fn test_guards_make_scripts_idempotent() {
+        let mut lexicon = LexiconHashMapBackend::new();
+        lexicon.add_lexfile("noun.animal").unwrap();
+        let (ssid, _) = apply_automaton(
+            parse_script("- add_synset:\n    definition: a feline mammal\n    lexfile: noun.animal\n    lemmas: [cat]\n"),
+            &mut lexicon,
+            &mut ChangeList::new(),
+        )
+        .unwrap();
+        let ssid = ssid.unwrap();
+
+        let script = format!(
+            "
+- assert_definition:
+    synset: {0}
+    definition: a feline mammal
+- add_entry:
+    synset: {0}
+    lemma: puss
+    pos: n
+  unless:
+    synset: {0}
+    member: puss
+- change_definition:
+    synset: {0}
+    definition: a small feline mammal
+  when:
+    synset: {0}
+    definition: a feline mammal
+",
+            ssid.as_str()
+        );
+        let actions = parse_script(&script);
+        assert!(matches!(&actions[1], Action::Guarded { unless: Some(_), when: None, .. }));
+
+        apply_automaton(actions.clone(), &mut lexicon, &mut ChangeList::new()).unwrap();
+        assert_eq!(
+            lexicon.synset_by_id(&ssid).unwrap().unwrap().definition,
+            vec!["a small feline mammal".to_string()]
+        );
+
+        // Run again, the assertion no longer holds, so nothing happens at all.
+        let err = apply_automaton(actions.clone(), &mut lexicon, &mut ChangeList::new())
+            .err()
+            .unwrap();
+        assert!(err.starts_with("Assertion failed"), "{}", err);
+
+        // Without it, the guards skip both changes rather than adding a second "puss".
+        apply_automaton(actions[1..].to_vec(), &mut lexicon, &mut ChangeList::new()).unwrap();
+        assert_eq!(lexicon.entry_by_lemma("puss").unwrap()[0].sense.len(), 1);
+        assert_eq!(
+            lexicon.synset_by_id(&ssid).unwrap().unwrap().definition,
+            vec!["a small feline mammal".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_entry_and_sense_attribute_actions() {
+        let mut lexicon = LexiconHashMapBackend::new();
+        lexicon.add_lexfile("noun.animal").unwrap();
+        let (ssid, _) = apply_automaton(
+            parse_script("- add_synset:\n    definition: a feline mammal\n    lexfile: noun.animal\n    lemmas: [cat]\n"),
+            &mut lexicon,
+            &mut ChangeList::new(),
+        )
+        .unwrap();
+        let ssid = ssid.unwrap();
+        let noun = PosKey::new("n".to_string());
+
+        let script = format!(
+            "
+- add_form:
+    synset: {0}
+    lemma: cat
+    form: cats
+- add_pronunciation:
+    synset: {0}
+    lemma: cat
+    pronunciation: kæt
+    variety: GB
+- add_pronunciation:
+    synset: {0}
+    lemma: cat
+    pronunciation: kat
+- change_adjposition:
+    synset: {0}
+    lemma: cat
+    adjposition: p
+- change_subcat:
+    synset: {0}
+    lemma: cat
+    subcat: [via]
+",
+            ssid.as_str()
+        );
+        let mut changes = ChangeList::new();
+        apply_automaton(parse_script(&script), &mut lexicon, &mut changes).unwrap();
+        assert!(changes.changed());
+        assert_eq!(lexicon.get_forms("cat", &noun).unwrap(), vec!["cats".to_string()]);
+        assert_eq!(lexicon.get_pronunciations("cat", &noun).unwrap().len(), 2);
+        let sense = lexicon.get_sense("cat", &ssid).unwrap()[0].clone().into_owned();
+        assert_eq!(sense.adjposition, Some("p".to_string()));
+        assert_eq!(sense.subcat, vec!["via".to_string()]);
+
+        let script = format!(
+            "
+- delete_form:
+    synset: {0}
+    lemma: cat
+    form: cats
+- delete_pronunciation:
+    synset: {0}
+    lemma: cat
+    pronunciation: kæt
+    variety: GB
+- change_adjposition:
+    synset: {0}
+    lemma: cat
+    adjposition: ''
+- change_subcat:
+    synset: {0}
+    lemma: cat
+    subcat: []
+",
+            ssid.as_str()
+        );
+        apply_automaton(parse_script(&script), &mut lexicon, &mut ChangeList::new()).unwrap();
+        assert!(lexicon.get_forms("cat", &noun).unwrap().is_empty());
+        assert_eq!(
+            lexicon.get_pronunciations("cat", &noun).unwrap(),
+            vec![Pronunciation {
+                value: "kat".to_string(),
+                variety: None
+            }]
+        );
+        let sense = lexicon.get_sense("cat", &ssid).unwrap()[0].clone().into_owned();
+        assert_eq!(sense.adjposition, None);
+        assert!(sense.subcat.is_empty());
+
+        // The variety is part of what identifies a pronunciation to delete.
+        for script in [
+            "- delete_form:\n    synset: {0}\n    lemma: cat\n    form: cats\n",
+            "- delete_pronunciation:\n    synset: {0}\n    lemma: cat\n    pronunciation: kat\n    variety: US\n",
+            "- change_adjposition:\n    synset: {0}\n    lemma: cat\n    adjposition: postnominal\n",
+            "- add_form:\n    synset: {0}\n    lemma: dog\n    form: dogs\n",
+        ] {
+            let script = script.replace("{0}", ssid.as_str());
+            assert!(
+                apply_automaton(parse_script(&script), &mut lexicon, &mut ChangeList::new()).is_err(),
+                "{}",
+                script
+            );
+        }
+    }
+
+    #[test]
+    fn test_serialize_guarded() {
+        let script = "- add_entry:\n    synset: 00001740-n\n    lemma: bar\n    pos: n\n  unless:\n    synset: 00001740-n\n    member: bar\n- validate: null\n  when:\n    synset: 00001740-n\n";
+        let actions = parse_script(script);
+        assert_eq!(
+            actions[1],
+            Action::Guarded {
+                when: Some(Condition::exists(SynsetRef::id("00001740-n"))),
+                unless: None,
+                action: Box::new(Action::Validate),
+            }
+        );
+        let wrapped: Vec<ActionWrapper> = actions.into_iter().map(ActionWrapper).collect();
+        assert_eq!(serde_yaml::to_string(&wrapped).unwrap(), script);
+    }
+
     #[test]
     fn test_sense_by_lemma() {
         let mut lexicon = LexiconHashMapBackend::new();
@@ -1577,6 +2899,7 @@ mod tests {
             pos: Some(PosKey::new("n".to_string())),
             lemmas: vec!["testword".to_string()],
             subcats: Vec::new(),
+            name: None,
         }];
         apply_automaton(actions.clone(), &mut lexicon, &mut ChangeList::new()).unwrap();
 
@@ -1599,6 +2922,79 @@ mod tests {
         assert_eq!(older[0].1.actions, actions);
     }
 
+    /// A batch whose last action fails must leave the lexicon exactly as it was before the
+    /// batch, on whichever backend it runs against.
+    fn check_failed_batch_rolls_back<L: Lexicon>(lexicon: &mut L) {
+        let n_synsets = lexicon.n_synsets().unwrap();
+        let n_entries = lexicon.n_entries().unwrap();
+        let actions = vec![
+            Action::AddSynset {
+                definition: "a test synset".to_string(),
+                lexfile: "noun.animal".to_string(),
+                pos: Some(PosKey::new("n".to_string())),
+                lemmas: vec!["testword".to_string()],
+                subcats: Vec::new(),
+                name: None,
+            },
+            Action::AddEntry {
+                synset: SynsetRef::Last,
+                lemma: "otherword".to_string(),
+                pos: PosKey::new("n".to_string()),
+                subcat: Vec::new(),
+                name: None,
+            },
+            Action::DeleteEntry {
+                synset: SynsetRef::Last,
+                lemma: "missingword".to_string(),
+            },
+        ];
+        let mut changes = ChangeList::new();
+        assert!(apply_automaton(actions, lexicon, &mut changes).is_err());
+
+        assert_eq!(lexicon.n_synsets().unwrap(), n_synsets);
+        assert_eq!(lexicon.n_entries().unwrap(), n_entries);
+        assert!(lexicon.entry_by_lemma("testword").unwrap().is_empty());
+        assert!(lexicon.entry_by_lemma("otherword").unwrap().is_empty());
+        assert!(changelog_recent(lexicon, 10, None).unwrap().is_empty(),
+            "a failed batch should not be logged");
+        assert!(!changes.changed(), "a rolled back batch should not leave the lexicon marked as changed");
+
+        // The lexicon should still take later batches normally.
+        let actions = vec![Action::AddSynset {
+            definition: "a test synset".to_string(),
+            lexfile: "noun.animal".to_string(),
+            pos: Some(PosKey::new("n".to_string())),
+            lemmas: vec!["testword".to_string()],
+            subcats: Vec::new(),
+            name: None,
+        }];
+        apply_automaton(actions, lexicon, &mut changes).unwrap();
+        assert_eq!(lexicon.n_synsets().unwrap(), n_synsets + 1);
+        assert_eq!(lexicon.entry_by_lemma("testword").unwrap().len(), 1);
+        assert_eq!(changelog_recent(lexicon, 10, None).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_failed_batch_rolls_back() {
+        let mut lexicon = LexiconHashMapBackend::new();
+        lexicon.add_lexfile("noun.animal").unwrap();
+        check_failed_batch_rolls_back(&mut lexicon);
+    }
+
+    #[cfg(feature = "redb")]
+    #[test]
+    fn test_failed_batch_rolls_back_redb() {
+        use crate::wordnet::{BTSynsets, ReDBLexicon};
+        let path = std::env::temp_dir()
+            .join(format!("ewe-test-rollback-{}.redb", std::process::id()));
+        {
+            let mut lexicon = ReDBLexicon::create(&path, 1 << 20).unwrap();
+            lexicon.synsets_insert("noun.animal".to_string(), BTSynsets::new()).unwrap();
+            check_failed_batch_rolls_back(&mut lexicon);
+        }
+        std::fs::remove_file(&path).ok();
+    }
+
     #[test]
     fn test_has_unsaved_changes() {
         let mut lexicon = LexiconHashMapBackend::new();
@@ -1611,6 +3007,7 @@ mod tests {
             pos: Some(PosKey::new("n".to_string())),
             lemmas: vec!["testword".to_string()],
             subcats: Vec::new(),
+            name: None,
         }];
         apply_automaton(actions, &mut lexicon, &mut ChangeList::new()).unwrap();
         assert!(has_unsaved_changes(&lexicon).unwrap(), "an applied batch should be unsaved");
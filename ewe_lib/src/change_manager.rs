@@ -1,9 +1,10 @@
 use crate::wordnet::*;
 use crate::rels::*;
-use crate::sense_keys::{get_sense_key, get_sense_key2};
+use crate::sense_keys::{get_sense_key, get_sense_key2, get_sense_key_with_head, head_word};
 use sha2::Sha256;
 use crate::sha2::Digest;
 use std::borrow::Cow;
+use std::collections::{HashMap, HashSet};
 use std::result;
 
 /// Monitors is any changes have been made
@@ -34,6 +35,18 @@ pub fn delete_rel<L : Lexicon>(wn : &mut L, source : &SynsetId,
     change_list.mark();
 }
 
+/// Remove the relation of type `rel_type` between synsets, and its inverse, leaving any other
+/// relations between them
+pub fn delete_rel_type<L : Lexicon>(wn : &mut L, source : &SynsetId, rel_type : &SynsetRelType,
+                  target : &SynsetId, change_list : &mut ChangeList) -> Result<()> {
+    wn.remove_rel_type(source, rel_type.clone(), target)?;
+    if let Some(inverse) = rel_type.inverse() {
+        wn.remove_rel_type(target, inverse, source)?;
+    }
+    change_list.mark();
+    Ok(())
+}
+
 /// Remove a relation between senses. `target` is the resolved type - a
 /// synset-targeted relation has no defined inverse, so the reverse removal
 /// only happens when `target` is itself a sense.
@@ -122,7 +135,7 @@ pub fn update_rels<L : Lexicon>(wn : &mut L,
     Ok(())
 }
 
-/// Add a new entry
+/// Add a new entry. The lemma is normalised first (see `normalise_text`), as lookups are.
 pub fn add_entry<L : Lexicon>(wn : &mut L,
                  synset_id : SynsetId, 
                  lemma : String, 
@@ -130,6 +143,7 @@ pub fn add_entry<L : Lexicon>(wn : &mut L,
                  subcat : Vec<String>,
                  old_sense_id : Option<&SenseId>,
                  change_list : &mut ChangeList) -> Result<Option<SenseId>> {
+    let lemma = normalise_text(&lemma).into_owned();
     let mut entries = wn.entry_by_lemma_with_pos(&lemma)?.iter_mut()
         .filter(|(pos, _)| synset_pos == *pos)
         .map(|x| x.1.clone())
@@ -404,6 +418,571 @@ pub fn delete_synset<L : Lexicon>(wn : &mut L,
     Ok(())
 }
 
+/// Merge synset `merged` into `synset_id`: every member and sense of `merged` moves over (a
+/// lemma both share keeps the surviving synset's sense, taking on the other sense's
+/// relations), its examples, Wikidata ids and relations are carried over, relations into it are
+/// rewritten to point at `synset_id`, and a deprecation record is left for it. Duplicate
+/// relations and any that would now loop back to `synset_id` are dropped. If `definition` is
+/// given, it replaces the surviving synset's definition.
+pub fn merge_synsets<L : Lexicon>(wn : &mut L,
+                 synset_id : &SynsetId,
+                 merged : &SynsetId,
+                 definition : Option<String>,
+                 reason : String,
+                 change_list : &mut ChangeList) -> result::Result<(), String> {
+    if synset_id == merged {
+        return Err(format!("Cannot merge synset {} into itself", synset_id.as_str()));
+    }
+    let target = wn.synset_by_id(synset_id).map_err(|e| e.to_string())?
+        .ok_or_else(|| format!("Synset {} not found", synset_id.as_str()))?.into_owned();
+    let source = wn.synset_by_id(merged).map_err(|e| e.to_string())?
+        .ok_or_else(|| format!("Synset {} not found", merged.as_str()))?.into_owned();
+    if target.part_of_speech != source.part_of_speech {
+        return Err(format!("Cannot merge {} into {}: they have different parts of speech",
+            merged.as_str(), synset_id.as_str()));
+    }
+    merge_synsets_unchecked(wn, synset_id, merged, source, definition, reason, change_list)
+        .map_err(|e| e.to_string())
+}
+
+fn merge_synsets_unchecked<L : Lexicon>(wn : &mut L,
+                 synset_id : &SynsetId,
+                 merged : &SynsetId,
+                 source : Synset,
+                 definition : Option<String>,
+                 reason : String,
+                 change_list : &mut ChangeList) -> Result<()> {
+    // Move the members the surviving synset lacks first, so that any sense relations
+    // between the merged synset's own senses follow them before the shared lemmas are
+    // folded in below.
+    let target_members = wn.members_by_id(synset_id)?;
+    let mut shared = Vec::new();
+    for member in wn.members_by_id(merged)? {
+        if let Some(pos) = wn.pos_for_entry_synset(&member, merged)? {
+            if target_members.contains(&member) {
+                shared.push((member, pos));
+            } else {
+                move_entry(wn, merged.clone(), synset_id.clone(), member, pos, change_list)?;
+            }
+        }
+    }
+    for (member, pos) in shared {
+        let kept = wn.get_sense_id(&member, &pos, synset_id)?;
+        let dropped = wn.get_sense_id(&member, &pos, merged)?;
+        if let (Some(kept), Some(dropped)) = (kept, dropped) {
+            let kept_target = SenseOrSynsetId::Sense(kept.clone());
+            let dropped_target = SenseOrSynsetId::Sense(dropped.clone());
+            let subcat = wn.get_sense(&member, merged)?.first()
+                .map(|s| s.subcat.clone()).unwrap_or_default();
+            for (rel, target) in wn.sense_links_from(&member, &pos, merged)? {
+                let target = target.resolve(wn)?;
+                if target != kept_target && target != dropped_target {
+                    wn.add_sense_rel(&kept, rel, &target)?;
+                }
+            }
+            for (rel, source) in wn.sense_links_to(&member, &pos, merged)? {
+                if source != kept && source != dropped {
+                    wn.add_sense_rel(&source, rel, &kept_target)?;
+                }
+            }
+            wn.update_sense(&kept, |sense| {
+                for frame in subcat {
+                    if !sense.subcat.contains(&frame) {
+                        sense.subcat.push(frame);
+                    }
+                }
+            })?;
+            delete_entry(wn, merged, &member, &pos, false, change_list)?;
+        }
+    }
+
+    wn.update_synset(synset_id, |ss| {
+        for example in source.example.iter() {
+            if !ss.example.iter().any(|e| e.text == example.text) {
+                ss.example.push(example.clone());
+            }
+        }
+        for wikidata in source.wikidata.iter() {
+            if !ss.wikidata.contains(wikidata) {
+                ss.wikidata.push(wikidata.clone());
+            }
+        }
+        if let Some(definition) = definition {
+            ss.definition = vec![definition];
+        }
+    })?;
+
+    // `delete_rel` removes both directions at once, so collect both before removing either.
+    let links_from = source.links_from();
+    let links_to = wn.links_to(merged)?;
+    for (_, target) in links_from.iter() {
+        delete_rel(wn, merged, target, change_list);
+    }
+    for (_, source) in links_to.iter() {
+        delete_rel(wn, source, merged, change_list);
+    }
+    for (rel, target) in links_from {
+        if target != *synset_id && target != *merged
+            && !wn.links_from(synset_id)?.contains(&(rel.clone(), target.clone())) {
+            wn.add_rel(synset_id, rel, &target)?;
+        }
+    }
+    for (rel, source) in links_to {
+        if source != *synset_id && source != *merged
+            && !wn.links_to(synset_id)?.contains(&(rel.clone(), source.clone())) {
+            wn.add_rel(&source, rel, synset_id)?;
+        }
+    }
+
+    wn.deprecate(merged, synset_id, reason)?;
+    wn.remove_synset(merged)?;
+    change_list.mark();
+    Ok(())
+}
+
+/// Split the `members` of synset `synset_id` off into a new synset with `definition`, in
+/// `lexfile` (by default the original's), returning the new synset's id. Their senses move
+/// with them (see `move_entry`), so their sense relations follow and their sense keys are
+/// recalculated for the new synset. Each of `relations` names a relation of the original
+/// synset, from its point of view, and whether the new synset gets a copy of it (`false`) or
+/// takes it over (`true`); every other relation stays with the original only.
+pub fn split_synset<L : Lexicon>(wn : &mut L,
+                 synset_id : &SynsetId,
+                 members : Vec<String>,
+                 definition : String,
+                 lexfile : Option<String>,
+                 relations : Vec<(SynsetRelType, SynsetId, bool)>,
+                 change_list : &mut ChangeList) -> result::Result<SynsetId, String> {
+    let synset = wn.synset_by_id(synset_id).map_err(|e| e.to_string())?
+        .ok_or_else(|| format!("Synset {} not found", synset_id.as_str()))?.into_owned();
+    if members.is_empty() {
+        return Err(format!("No members given to split off from {}", synset_id.as_str()));
+    }
+    for member in members.iter() {
+        if !synset.members.contains(member) {
+            return Err(format!("{} is not a member of {}", member, synset_id.as_str()));
+        }
+    }
+    if synset.members.iter().all(|m| members.contains(m)) {
+        return Err(format!("Splitting off every member of {} would leave it empty",
+            synset_id.as_str()));
+    }
+    let lexfile = match lexfile {
+        Some(lexfile) => {
+            if !wn.pos_for_lexfile(&lexfile).map_err(|e| e.to_string())?
+                .contains(&synset.part_of_speech) {
+                return Err(format!("{} is not a lexicographer file for {}", lexfile,
+                    synset_id.as_str()));
+            }
+            lexfile
+        },
+        None => wn.lex_name_for(synset_id).map_err(|e| e.to_string())?
+            .ok_or_else(|| format!("No lexicographer file for {}", synset_id.as_str()))?
+    };
+    let mut relation_changes = Vec::new();
+    for (rel, target, take) in relations {
+        let stored = relations_between(wn, synset_id, &target).map_err(|e| e.to_string())?;
+        let inverse = rel.inverse();
+        let matching : Vec<_> = stored.into_iter().filter(|(from_original, stored_rel)| {
+            if *from_original { *stored_rel == rel } else { Some(stored_rel) == inverse.as_ref() }
+        }).collect();
+        if matching.is_empty() {
+            return Err(format!("{} has no {} relation to {}", synset_id.as_str(),
+                rel.value(), target.as_str()));
+        }
+        relation_changes.push((target, matching, take));
+    }
+
+    let new_id = add_synset(wn, definition, lexfile,
+        synset.part_of_speech.to_pos_key(), None, change_list)?;
+    populate_split(wn, synset_id, &new_id, members, relation_changes, change_list)
+        .map_err(|e| e.to_string())?;
+    change_list.mark();
+    Ok(new_id)
+}
+
+/// The relations `split_synset` copies (`false`) or moves (`true`) between the original and one
+/// other synset, as found by `relations_between`.
+type SplitRelations = (SynsetId, Vec<(bool, SynsetRelType)>, bool);
+
+/// The second half of `split_synset`, once the new synset exists.
+fn populate_split<L : Lexicon>(wn : &mut L,
+                  synset_id : &SynsetId,
+                  new_id : &SynsetId,
+                  members : Vec<String>,
+                  relation_changes : Vec<SplitRelations>,
+                  change_list : &mut ChangeList) -> Result<()> {
+    for member in members {
+        if let Some(pos) = wn.pos_for_entry_synset(&member, synset_id)? {
+            move_entry(wn, synset_id.clone(), new_id.clone(), member, pos, change_list)?;
+        }
+    }
+    for (target, matching, take) in relation_changes {
+        if take {
+            let kept : Vec<_> = relations_between(wn, synset_id, &target)?.into_iter()
+                .filter(|r| !matching.contains(r)).collect();
+            delete_rel(wn, synset_id, &target, change_list);
+            for (from_original, rel) in kept {
+                if from_original {
+                    wn.add_rel(synset_id, rel, &target)?;
+                } else {
+                    wn.add_rel(&target, rel, synset_id)?;
+                }
+            }
+        }
+        for (from_original, rel) in matching {
+            if from_original {
+                wn.add_rel(new_id, rel, &target)?;
+            } else {
+                wn.add_rel(&target, rel, new_id)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// The relations stored between `synset_id` and `other`, in either direction: `true` for
+/// those stored on `synset_id` (pointing at `other`), `false` for those stored on `other`.
+fn relations_between<L : Lexicon>(wn : &L, synset_id : &SynsetId,
+                     other : &SynsetId) -> Result<Vec<(bool, SynsetRelType)>> {
+    let mut relations = Vec::new();
+    for (rel, target) in wn.links_from(synset_id)? {
+        if target == *other {
+            relations.push((true, rel));
+        }
+    }
+    for (rel, target) in wn.links_from(other)? {
+        if target == *synset_id {
+            relations.push((false, rel));
+        }
+    }
+    Ok(relations)
+}
+
+/// Move synset `synset_id` to the lexicographer file `lexfile`. Its id stays the same, but the
+/// lexicographer file number is part of every sense key, so its members' senses are renamed
+/// to match (keeping their lex ids where that doesn't clash with another sense in the new
+/// file), along with every relation and index that refers to them.
+pub fn change_lexfile<L : Lexicon>(wn : &mut L,
+                 synset_id : &SynsetId,
+                 lexfile : String,
+                 change_list : &mut ChangeList) -> result::Result<(), String> {
+    let synset = wn.synset_by_id(synset_id).map_err(|e| e.to_string())?
+        .ok_or_else(|| format!("Synset {} not found", synset_id.as_str()))?.into_owned();
+    if wn.lex_name_for(synset_id).map_err(|e| e.to_string())?.as_ref() == Some(&lexfile) {
+        return Ok(());
+    }
+    if !wn.pos_for_lexfile(&lexfile).map_err(|e| e.to_string())?
+        .contains(&synset.part_of_speech) {
+        return Err(format!("{} is not a lexicographer file for {}", lexfile,
+            synset_id.as_str()));
+    }
+    move_to_lexfile(wn, synset_id, synset, lexfile).map_err(|e| e.to_string())?;
+    change_list.mark();
+    Ok(())
+}
+
+fn move_to_lexfile<L : Lexicon>(wn : &mut L, synset_id : &SynsetId,
+                   synset : Synset, lexfile : String) -> Result<()> {
+    wn.remove_synset(synset_id)?;
+    wn.insert_synset(lexfile, synset_id.clone(), synset.clone())?;
+    for member in synset.members.iter() {
+        let senses : Vec<SenseId> = wn.get_sense(member, synset_id)?.iter()
+            .map(|s| s.id.clone()).collect();
+        for sense_id in senses {
+            let mut new_key = get_sense_key(wn, member, Some(&sense_id), &synset, synset_id)?;
+            let taken = wn.entry_by_lemma_ignore_case(member)?.iter()
+                .any(|e| e.sense.iter().any(|s| s.id == new_key));
+            if taken {
+                new_key = get_sense_key(wn, member, None, &synset, synset_id)?;
+            }
+            wn.update_sense_key(&sense_id, &new_key)?;
+        }
+    }
+    Ok(())
+}
+
+/// Change the part of speech of synset `synset_id` to `pos` (e.g. between a head adjective
+/// and a satellite, or a noun filed as a verb), returning its new id, which differs in its
+/// part-of-speech suffix. Its members' entries move to the new part of speech and their
+/// senses are renamed to match, every relation to or from the synset or its senses is carried
+/// over, and a deprecation record is left for the old id. `lexfile` is only needed if the
+/// current lexicographer file does not take `pos`. A synset made a satellite has to be
+/// `similar` to exactly one head adjective, which its new sense keys name. Relations that the
+/// new part of speech does not allow are kept - see `validate::pos_change_errors` to find them.
+pub fn change_pos<L : Lexicon>(wn : &mut L,
+                 synset_id : &SynsetId,
+                 pos : PartOfSpeech,
+                 lexfile : Option<String>,
+                 change_list : &mut ChangeList) -> result::Result<SynsetId, String> {
+    let synset = wn.synset_by_id(synset_id).map_err(|e| e.to_string())?
+        .ok_or_else(|| format!("Synset {} not found", synset_id.as_str()))?.into_owned();
+    if synset.part_of_speech == pos {
+        return Ok(synset_id.clone());
+    }
+    let lexfile = match lexfile {
+        Some(lexfile) => lexfile,
+        None => wn.lex_name_for(synset_id).map_err(|e| e.to_string())?
+            .ok_or_else(|| format!("No lexicographer file for {}", synset_id.as_str()))?
+    };
+    if !wn.pos_for_lexfile(&lexfile).map_err(|e| e.to_string())?.contains(&pos) {
+        return Err(format!("{} is not a lexicographer file for part of speech {}", lexfile,
+            pos.value()));
+    }
+    let new_id = match synset_id.as_str().rsplit_once('-') {
+        Some((prefix, _)) => SynsetId::new_owned(format!("{}-{}", prefix, pos.value())),
+        None => return Err(format!("Invalid synset id {}", synset_id.as_str()))
+    };
+    if wn.synset_by_id(&new_id).map_err(|e| e.to_string())?.is_some() {
+        return Err(format!("Cannot change the part of speech of {}: {} already exists",
+            synset_id.as_str(), new_id.as_str()));
+    }
+    if pos == PartOfSpeech::s && satellite_head(wn, &synset).map_err(|e| e.to_string())?.is_none() {
+        return Err(format!("Cannot make {} a satellite: it is not similar to exactly one head \
+            adjective with a sense", synset_id.as_str()));
+    }
+    rehome_synset(wn, synset_id, synset, &new_id, pos, lexfile, change_list)
+        .map_err(|e| e.to_string())?;
+    Ok(new_id)
+}
+
+/// The head word and lex id a satellite of `synset`'s head adjective names it by in its sense
+/// keys - `None` unless `synset` is `similar` to exactly one head adjective, with a sense.
+fn satellite_head<L : Lexicon>(wn : &L, synset : &Synset) -> Result<Option<(String, String)>> {
+    let mut heads = Vec::new();
+    for target in synset.similar.iter() {
+        if let Some(target_synset) = wn.synset_by_id(target)? {
+            if target_synset.part_of_speech == PartOfSpeech::a {
+                heads.push(target);
+            }
+        }
+    }
+    match heads.as_slice() {
+        [head] => head_word(wn, head),
+        _ => Ok(None)
+    }
+}
+
+/// The body of `change_pos`, once it is known the change can be made.
+fn rehome_synset<L : Lexicon>(wn : &mut L,
+                 synset_id : &SynsetId,
+                 synset : Synset,
+                 new_id : &SynsetId,
+                 pos : PartOfSpeech,
+                 lexfile : String,
+                 change_list : &mut ChangeList) -> Result<()> {
+    // Everything pointing at the synset or its senses, captured before any of it is removed.
+    let mut incoming = Vec::new();
+    let mut sources = HashSet::new();
+    for (_, source) in wn.links_to(synset_id)? {
+        if source != *synset_id && sources.insert(source.clone()) {
+            for (rel, target) in wn.links_from(&source)? {
+                if target == *synset_id {
+                    incoming.push((source.clone(), rel));
+                }
+            }
+        }
+    }
+    let mut members = Vec::new();
+    for member in synset.members.iter() {
+        if let Some(pos_key) = wn.pos_for_entry_synset(member, synset_id)? {
+            if let Some(sense) = wn.get_sense(member, synset_id)?.first() {
+                members.push((member.clone(), pos_key, sense.clone().into_owned()));
+            }
+        }
+    }
+    let mut incoming_sense = Vec::new();
+    for (_, _, sense) in members.iter() {
+        let target = UnresolvedSenseOrSynsetId::Sense(sense.id.clone());
+        let mut sources = HashSet::new();
+        for (_, source) in wn.sense_links_to_get(&sense.id)?.iter().flat_map(|v| v.iter()) {
+            if sources.insert(source.clone()) {
+                for (rel, stored_target) in wn.sense_links_from_id(source)? {
+                    if stored_target == target {
+                        incoming_sense.push((source.clone(), rel, sense.id.clone()));
+                    }
+                }
+            }
+        }
+    }
+
+    let mut new_synset = synset.clone();
+    new_synset.part_of_speech = pos.clone();
+    wn.insert_synset(lexfile, new_id.clone(), new_synset.clone())?;
+    wn.deprecate(synset_id, new_id, format!("Changed part of speech to {}", pos.value()))?;
+    for (_, target) in synset.links_from() {
+        delete_rel(wn, synset_id, &target, change_list);
+    }
+    for (source, _) in incoming.iter() {
+        delete_rel(wn, source, synset_id, change_list);
+    }
+    let mut entries = Vec::new();
+    for (member, pos_key, sense) in members {
+        let forms = wn.get_forms(&member, &pos_key)?;
+        let pronunciations = wn.get_pronunciations(&member, &pos_key)?;
+        delete_entry(wn, synset_id, &member, &pos_key, false, change_list)?;
+        entries.push((member, pos_key, sense, forms, pronunciations));
+    }
+    wn.remove_synset(synset_id)?;
+
+    let mut renamed = HashMap::new();
+    let head = if pos == PartOfSpeech::s { satellite_head(wn, &synset)? } else { None };
+    let sense_key = |wn : &L, member : &str, sense_id : Option<&SenseId>| match &head {
+        Some((head_word, head_id)) => get_sense_key_with_head(wn, member, sense_id, &new_synset,
+            new_id, head_word, head_id),
+        None => get_sense_key(wn, member, sense_id, &new_synset, new_id)
+    };
+    for (member, _, sense, _, _) in entries.iter() {
+        let mut new_key = sense_key(wn, member, Some(&sense.id))?;
+        let taken = wn.entry_by_lemma_ignore_case(member)?.iter()
+            .any(|e| e.sense.iter().any(|s| s.id == new_key));
+        if taken {
+            new_key = sense_key(wn, member, None)?;
+        }
+        renamed.insert(sense.id.clone(), new_key);
+    }
+    for (member, old_pos_key, mut sense, forms, pronunciations) in entries {
+        for (rel, target) in sense.sense_links_from() {
+            if let UnresolvedSenseOrSynsetId::Sense(target) = target {
+                if let Some(new_target) = renamed.get(&target) {
+                    sense.remove_rel(&SenseOrSynsetId::Sense(target));
+                    sense.add_rel(rel, SenseOrSynsetId::Sense(new_target.clone()));
+                }
+            }
+        }
+        sense.id = renamed[&sense.id].clone();
+        sense.synset = new_id.clone();
+        // Keep any homograph number, e.g. `a2` becomes `s2`
+        let pos_key = PosKey::new(format!("{}{}", pos.value(), &old_pos_key.as_str()[1..]));
+        if !wn.entry_by_lemma_with_pos(&member)?.iter().any(|(p, _)| *p == pos_key) {
+            let mut entry = Entry::new();
+            entry.form = forms;
+            entry.pronunciation = pronunciations;
+            wn.insert_entry(member.clone(), pos_key.clone(), entry)?;
+        }
+        wn.insert_sense(member, pos_key, sense)?;
+    }
+    for (source, rel, target) in incoming_sense {
+        // Relations between the synset's own senses came across with the senses themselves.
+        if !renamed.contains_key(&source) {
+            wn.add_sense_rel(&source, rel, &SenseOrSynsetId::Sense(renamed[&target].clone()))?;
+        }
+    }
+    for (source, rel) in incoming {
+        wn.add_rel(&source, rel, new_id)?;
+    }
+    change_list.mark();
+    Ok(())
+}
+
+/// Respell the entry `lemma` (with part of speech key `pos`) as `new_lemma`, e.g. to fix its
+/// hyphenation. Its senses keep their synsets and relations, but are renamed, since the lemma
+/// is part of the sense key, as are those of any satellites that take it as their head word.
+/// If there is already an entry for `new_lemma` the senses join it, as long as the two share
+/// no synset. `new_lemma` is normalised first (see `normalise_text`), as lookups are.
+pub fn rename_lemma<L : Lexicon>(wn : &mut L,
+                    lemma : &str,
+                    pos : &PosKey,
+                    new_lemma : String,
+                    change_list : &mut ChangeList) -> result::Result<(), String> {
+    let new_lemma = normalise_text(&new_lemma).into_owned();
+    if lemma == new_lemma {
+        return Ok(());
+    }
+    if new_lemma.trim().is_empty() {
+        return Err("The new lemma cannot be empty".to_owned());
+    }
+    let entries = wn.entry_by_lemma_with_pos(lemma).map_err(|e| e.to_string())?;
+    let entry = entries.iter().find(|(p, _)| p == pos)
+        .map(|(_, e)| e.clone().into_owned())
+        .ok_or_else(|| format!("No entry for {} ({})", lemma, pos.as_str()))?;
+    for (p, existing) in wn.entry_by_lemma_with_pos(&new_lemma).map_err(|e| e.to_string())? {
+        if p == *pos {
+            if let Some(sense) = existing.sense.iter()
+                .find(|s| entry.sense.iter().any(|s2| s2.synset == s.synset)) {
+                return Err(format!("{} is already a member of {}", new_lemma,
+                    sense.synset.as_str()));
+            }
+        }
+    }
+    rename_entry(wn, lemma, pos, entry, new_lemma, change_list).map_err(|e| e.to_string())
+}
+
+/// The body of `rename_lemma`, once it is known the rename can be made.
+fn rename_entry<L : Lexicon>(wn : &mut L, lemma : &str, pos : &PosKey, entry : Entry,
+                new_lemma : String, change_list : &mut ChangeList) -> Result<()> {
+    let existing = wn.entry_by_lemma_with_pos(&new_lemma)?.into_iter()
+        .find(|(p, _)| p == pos).map(|(_, e)| e.into_owned());
+    match existing {
+        Some(existing) => {
+            for form in entry.form.iter().filter(|f| !existing.form.contains(f)) {
+                wn.add_form(&new_lemma, pos, form.clone())?;
+            }
+            for pronunciation in entry.pronunciation.iter()
+                .filter(|p| !existing.pronunciation.contains(p)) {
+                wn.add_pronunciation(&new_lemma, pos, pronunciation.clone())?;
+            }
+        },
+        None => {
+            let mut new_entry = Entry::new();
+            new_entry.form = entry.form.clone();
+            new_entry.pronunciation = entry.pronunciation.clone();
+            wn.insert_entry(new_lemma.clone(), pos.clone(), new_entry)?;
+        }
+    }
+    let mut heads = Vec::new();
+    for sense in entry.sense {
+        let synset_id = sense.synset.clone();
+        let synset = match wn.synset_by_id(&synset_id)? {
+            Some(synset) => synset.into_owned(),
+            None => continue
+        };
+        // Renamed while still under the old lemma, so `update_sense_key` carries every
+        // relation to the sense across; it then moves as it is.
+        let mut new_key = get_sense_key(wn, &new_lemma, Some(&sense.id), &synset, &synset_id)?;
+        let taken = wn.entry_by_lemma_ignore_case(&new_lemma)?.iter()
+            .any(|e| e.sense.iter().any(|s| s.id == new_key && s.id != sense.id));
+        if taken {
+            new_key = get_sense_key(wn, &new_lemma, None, &synset, &synset_id)?;
+        }
+        wn.update_sense_key(&sense.id, &new_key)?;
+        let renamed = match wn.get_sense_by_id(&new_key)? {
+            Some((_, _, renamed)) => renamed.into_owned(),
+            None => continue
+        };
+        wn.remove_sense(lemma, pos, &synset_id)?;
+        wn.insert_sense(new_lemma.clone(), pos.clone(), renamed)?;
+        wn.update_synset(&synset_id, |ss| {
+            for member in ss.members.iter_mut() {
+                if member == lemma {
+                    *member = new_lemma.clone();
+                }
+            }
+        })?;
+        if synset.part_of_speech == PartOfSpeech::a {
+            heads.extend(synset.similar);
+        }
+    }
+    // Satellites name their head adjective in their sense keys
+    let mut changes = Vec::new();
+    for satellite in heads {
+        for member in wn.members_by_id(&satellite)? {
+            for sense in wn.get_sense(&member, &satellite)? {
+                if let Some(key) = get_sense_key2(wn, &member, Some(&sense.id), &satellite)? {
+                    if key != sense.id {
+                        changes.push((sense.id.clone(), key));
+                    }
+                }
+            }
+        }
+    }
+    for (old, new) in changes {
+        wn.update_sense_key(&old, &new)?;
+    }
+    change_list.mark();
+    Ok(())
+}
+
 fn new_id<L : Lexicon>(wn : &L,
     pos : &PartOfSpeech, definition : &str) -> result::Result<SynsetId, String> {
     let s = Sha256::digest(definition.as_bytes());
@@ -441,6 +1020,27 @@ pub fn add_synset<L : Lexicon>(wn : &mut L,
     }
 }
 
+/// Add a synset in `lexfile` (whose name gives its part of speech) with `lemmas` as its
+/// members, as the editor would - the fixture the tests of the editing actions and of the checks
+/// built on them share.
+#[cfg(test)]
+pub(crate) fn add_test_synset<L : Lexicon>(wn : &mut L, ssid : Option<&str>, lexfile : &str,
+    definition : &str, lemmas : &[&str], change_list : &mut ChangeList) -> SynsetId {
+    let pos = PosKey::new(match lexfile.split('.').next() {
+        Some("verb") => "v",
+        Some("adj") => "a",
+        Some("adv") => "r",
+        _ => "n"
+    }.to_owned());
+    let id = add_synset(wn, definition.to_owned(), lexfile.to_owned(), pos.clone(),
+        ssid.map(SynsetId::new), change_list).unwrap();
+    for lemma in lemmas {
+        add_entry(wn, id.clone(), lemma.to_string(), pos.clone(), Vec::new(), None,
+            change_list).unwrap();
+    }
+    id
+}
+
 fn find_rel_type<L : Lexicon>(wn : &L,
     source : &SynsetId, target : &SynsetId) 
     -> Result<Vec<SynsetRelType>> {
@@ -583,6 +1183,15 @@ pub fn update_ex<L : Lexicon>(wn : &mut L,
 /// Remove all indirect relations
 pub fn fix_indirect_relations<L : Lexicon>(wn : &mut L,
             change_list : &mut ChangeList) -> Result<()> {
+    for (source, target2) in indirect_relations(wn)? {
+        delete_rel(wn, &source, &target2, change_list);
+    }
+    Ok(())
+}
+
+/// The hypernym links `fix_indirect_relations` would remove: those from a synset to
+/// a hypernym of one of its own hypernyms
+pub fn indirect_relations<L : Lexicon>(wn : &L) -> Result<Vec<(SynsetId, SynsetId)>> {
     let mut to_delete = Vec::new();
     for synset in wn.synsets()? {
         let (synset_id, synset) = synset?;
@@ -599,10 +1208,7 @@ pub fn fix_indirect_relations<L : Lexicon>(wn : &mut L,
             }
         }
     }
-    for (source, target2) in to_delete {
-        delete_rel(wn, &source, &target2, change_list);
-    }
-    Ok(())
+    Ok(to_delete)
 }
 
 #[cfg(test)]
@@ -636,4 +1242,226 @@ mod tests {
             .filter(|sense| sense.synset == target_synset_id).next().unwrap();
         assert_eq!(sense.id, SenseId::new("test%1:17:00::".to_owned()));
     }
+
+    #[test]
+    fn test_split_synset() {
+        let mut wn = LexiconHashMapBackend::new();
+        let mut change_list = ChangeList::new();
+        let bat = add_test_synset(&mut wn, None, "noun.animal", "a flying mammal or a club",
+            &["bat", "chiropteran"], &mut change_list);
+        let animal = add_test_synset(&mut wn, None, "noun.animal", "a living organism",
+            &["animal"], &mut change_list);
+        let club = add_test_synset(&mut wn, None, "noun.animal", "a stick",
+            &["stick"], &mut change_list);
+        let vampire = add_test_synset(&mut wn, None, "noun.animal", "a blood-drinking bat",
+            &["vampire bat"], &mut change_list);
+        wn.add_rel(&bat, SynsetRelType::Hypernym, &animal).unwrap();
+        wn.add_rel(&bat, SynsetRelType::Also, &club).unwrap();
+        wn.add_rel(&vampire, SynsetRelType::Hypernym, &bat).unwrap();
+        let chiropteran = wn.get_sense_id2("chiropteran", &bat).unwrap().unwrap();
+
+        assert!(split_synset(&mut wn, &bat, vec!["bat".to_owned(), "chiropteran".to_owned()],
+            "a flying mammal".to_owned(), None, Vec::new(), &mut change_list).is_err());
+        assert!(split_synset(&mut wn, &bat, vec!["chiropteran".to_owned()],
+            "a flying mammal".to_owned(), None,
+            vec![(SynsetRelType::Hypernym, club.clone(), true)], &mut change_list).is_err());
+
+        let new_id = split_synset(&mut wn, &bat, vec!["chiropteran".to_owned()],
+            "a flying mammal".to_owned(), None,
+            vec![(SynsetRelType::Hypernym, animal.clone(), false),
+                 (SynsetRelType::Hyponym, vampire.clone(), true)],
+            &mut change_list).unwrap();
+
+        let original = wn.synset_by_id(&bat).unwrap().unwrap().into_owned();
+        assert_eq!(original.members, vec!["bat"]);
+        assert_eq!(original.links_from(), vec![
+            (SynsetRelType::Also, club.clone()), (SynsetRelType::Hypernym, animal.clone())]);
+        let split = wn.synset_by_id(&new_id).unwrap().unwrap().into_owned();
+        assert_eq!(split.members, vec!["chiropteran"]);
+        assert_eq!(split.definition, vec!["a flying mammal"]);
+        assert_eq!(split.links_from(), vec![(SynsetRelType::Hypernym, animal.clone())]);
+        assert_eq!(wn.synset_by_id(&vampire).unwrap().unwrap().hypernym, vec![new_id.clone()]);
+        assert_eq!(wn.get_sense_id2("chiropteran", &new_id).unwrap(), Some(chiropteran));
+    }
+
+    #[test]
+    fn test_change_lexfile() {
+        let mut wn = LexiconHashMapBackend::new();
+        let mut change_list = ChangeList::new();
+        let bat = add_test_synset(&mut wn, None, "noun.animal", "a club used in sports",
+            &["bat"], &mut change_list);
+        let club = add_test_synset(&mut wn, None, "noun.artifact", "a heavy stick",
+            &["bat"], &mut change_list);
+        let ball = add_test_synset(&mut wn, None, "noun.artifact", "a round object",
+            &["ball"], &mut change_list);
+        let bat_sense = wn.get_sense_id2("bat", &bat).unwrap().unwrap();
+        let ball_sense = wn.get_sense_id2("ball", &ball).unwrap().unwrap();
+        assert_eq!(bat_sense.as_str(), "bat%1:05:00::");
+        wn.add_sense_rel(&ball_sense, SenseRelType::Also, &SenseOrSynsetId::Sense(bat_sense.clone())).unwrap();
+        wn.add_sense_rel(&bat_sense, SenseRelType::Antonym, &SenseOrSynsetId::Sense(ball_sense.clone())).unwrap();
+
+        assert!(change_lexfile(&mut wn, &bat, "verb.motion".to_owned(), &mut change_list).is_err());
+        change_lexfile(&mut wn, &bat, "noun.artifact".to_owned(), &mut change_list).unwrap();
+
+        assert_eq!(wn.lex_name_for(&bat).unwrap(), Some("noun.artifact".to_owned()));
+        assert!(wn.synset_by_id(&bat).unwrap().is_some());
+        // The lex id is kept, as it doesn't clash with the other "bat" in `noun.artifact`.
+        let new_sense = wn.get_sense_id2("bat", &bat).unwrap().unwrap();
+        assert_eq!(new_sense.as_str(), "bat%1:06:00::");
+        assert!(!wn.has_sense(&bat_sense).unwrap());
+        assert_eq!(wn.get_sense_id2("bat", &club).unwrap().unwrap().as_str(), "bat%1:06:01::");
+        let new_target = UnresolvedSenseOrSynsetId::Sense(new_sense.clone());
+        assert_eq!(wn.sense_links_from_id(&ball_sense).unwrap(), vec![(SenseRelType::Also, new_target)]);
+        assert_eq!(wn.sense_links_from_id(&new_sense).unwrap(),
+            vec![(SenseRelType::Antonym, UnresolvedSenseOrSynsetId::Sense(ball_sense.clone()))]);
+        assert_eq!(wn.sense_links_to_get(&ball_sense).unwrap().unwrap().into_owned(),
+            vec![(SenseRelType::Antonym, new_sense.clone())]);
+        assert_eq!(wn.sense_links_to_get(&new_sense).unwrap().unwrap().into_owned(),
+            vec![(SenseRelType::Also, ball_sense)]);
+    }
+
+    #[test]
+    fn test_change_pos() {
+        let mut wn = LexiconHashMapBackend::new();
+        let mut change_list = ChangeList::new();
+        let hot = add_test_synset(&mut wn, None, "adj.all", "having a high temperature",
+            &["hot"], &mut change_list);
+        let warm = add_test_synset(&mut wn, None, "adj.all", "having a moderately high temperature",
+            &["warm"], &mut change_list);
+        let cold = add_test_synset(&mut wn, None, "adj.all", "having a low temperature",
+            &["cold"], &mut change_list);
+        wn.add_rel(&hot, SynsetRelType::Similar, &warm).unwrap();
+        wn.add_rel(&warm, SynsetRelType::Similar, &hot).unwrap();
+        let cold_sense = wn.get_sense_id2("cold", &cold).unwrap().unwrap();
+        let warm_sense = wn.get_sense_id2("warm", &warm).unwrap().unwrap();
+        wn.add_sense_rel(&cold_sense, SenseRelType::Antonym,
+            &SenseOrSynsetId::Sense(warm_sense.clone())).unwrap();
+        wn.add_sense_rel(&warm_sense, SenseRelType::Antonym,
+            &SenseOrSynsetId::Sense(cold_sense.clone())).unwrap();
+
+        assert!(change_pos(&mut wn, &warm, PartOfSpeech::n, None, &mut change_list).is_err());
+        let satellite = change_pos(&mut wn, &warm, PartOfSpeech::s, None, &mut change_list).unwrap();
+
+        assert_eq!(satellite.as_str(), format!("{}s", &warm.as_str()[..warm.as_str().len() - 1]));
+        assert!(wn.synset_by_id(&warm).unwrap().is_none());
+        assert_eq!(wn.deprecations_get().unwrap().len(), 1);
+        let ss = wn.synset_by_id(&satellite).unwrap().unwrap().into_owned();
+        assert_eq!(ss.part_of_speech, PartOfSpeech::s);
+        assert_eq!(ss.similar, vec![hot.clone()]);
+        assert_eq!(wn.synset_by_id(&hot).unwrap().unwrap().similar, vec![satellite.clone()]);
+        assert_eq!(wn.pos_for_entry_synset("warm", &satellite).unwrap(),
+            Some(PosKey::new("s".to_owned())));
+        let new_sense = wn.get_sense_id2("warm", &satellite).unwrap().unwrap();
+        assert_eq!(new_sense.as_str(), "warm%5:00:00:hot:00");
+        assert_eq!(wn.sense_links_from_id(&cold_sense).unwrap(),
+            vec![(SenseRelType::Antonym, UnresolvedSenseOrSynsetId::Sense(new_sense.clone()))]);
+        assert_eq!(wn.sense_links_from_id(&new_sense).unwrap(),
+            vec![(SenseRelType::Antonym, UnresolvedSenseOrSynsetId::Sense(cold_sense))]);
+        assert!(crate::validate::pos_change_errors(&wn, &satellite).unwrap().is_empty());
+
+        // `hot` has no head adjective left to be a satellite of, and a noun can't keep its
+        // `similar` relation
+        assert!(change_pos(&mut wn, &hot, PartOfSpeech::s, None, &mut change_list).is_err());
+        wn.add_lexfile("noun.attribute").unwrap();
+        let hot2 = change_pos(&mut wn, &hot, PartOfSpeech::n, Some("noun.attribute".to_owned()),
+            &mut change_list).unwrap();
+        assert!(!crate::validate::pos_change_errors(&wn, &hot2).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_rename_lemma() {
+        let mut wn = LexiconHashMapBackend::new();
+        let mut change_list = ChangeList::new();
+        let pos = PosKey::new("n".to_owned());
+        let mail = add_test_synset(&mut wn, None, "noun.communication",
+            "messages sent electronically", &["email", "e mail"], &mut change_list);
+        let system = add_test_synset(&mut wn, None, "noun.communication",
+            "a system for sending messages", &["e mail"], &mut change_list);
+        let letter = add_test_synset(&mut wn, None, "noun.communication", "a written message",
+            &["letter"], &mut change_list);
+        wn.add_form("e mail", &pos, "e mails".to_owned()).unwrap();
+        let sense = |wn : &LexiconHashMapBackend, lemma : &str, synset : &SynsetId|
+            wn.get_sense_id2(lemma, synset).unwrap().unwrap();
+        let letter_sense = sense(&wn, "letter", &letter);
+        let old_sense = sense(&wn, "e mail", &mail);
+        assert_eq!(old_sense.as_str(), "e_mail%1:10:00::");
+        wn.add_sense_rel(&letter_sense, SenseRelType::Also,
+            &SenseOrSynsetId::Sense(old_sense.clone())).unwrap();
+        wn.add_sense_rel(&old_sense, SenseRelType::Antonym,
+            &SenseOrSynsetId::Sense(letter_sense.clone())).unwrap();
+
+        assert!(rename_lemma(&mut wn, "e mail", &pos, "email".to_owned(), &mut change_list).is_err());
+        assert!(rename_lemma(&mut wn, "e mail", &PosKey::new("v".to_owned()), "e-mail".to_owned(),
+            &mut change_list).is_err());
+        rename_lemma(&mut wn, "e mail", &pos, "e-mail".to_owned(), &mut change_list).unwrap();
+
+        assert!(wn.entry_by_lemma("e mail").unwrap().is_empty());
+        assert_eq!(wn.get_forms("e-mail", &pos).unwrap(), vec!["e mails"]);
+        assert_eq!(wn.members_by_id(&mail).unwrap(), vec!["email", "e-mail"]);
+        assert_eq!(wn.members_by_id(&system).unwrap(), vec!["e-mail"]);
+        let new_sense = sense(&wn, "e-mail", &mail);
+        assert_eq!(new_sense.as_str(), "e-mail%1:10:00::");
+        assert_eq!(sense(&wn, "e-mail", &system).as_str(), "e-mail%1:10:01::");
+        assert_eq!(wn.sense_links_from_id(&letter_sense).unwrap(),
+            vec![(SenseRelType::Also, UnresolvedSenseOrSynsetId::Sense(new_sense.clone()))]);
+        assert_eq!(wn.sense_links_from_id(&new_sense).unwrap(),
+            vec![(SenseRelType::Antonym, UnresolvedSenseOrSynsetId::Sense(letter_sense.clone()))]);
+        assert_eq!(wn.sense_links_to_get(&letter_sense).unwrap().unwrap().into_owned(),
+            vec![(SenseRelType::Antonym, new_sense)]);
+    }
+
+    #[test]
+    fn test_merge_synsets() {
+        let mut wn = LexiconHashMapBackend::new();
+        let mut change_list = ChangeList::new();
+        let cat = add_test_synset(&mut wn, None, "noun.animal", "a feline mammal",
+            &["cat", "puss"], &mut change_list);
+        let feline = add_test_synset(&mut wn, None, "noun.animal", "a small feline",
+            &["cat", "kitty"], &mut change_list);
+        let animal = add_test_synset(&mut wn, None, "noun.animal", "a living organism",
+            &["animal"], &mut change_list);
+        let kitten = add_test_synset(&mut wn, None, "noun.animal", "a young cat",
+            &["kitten"], &mut change_list);
+        let dog = add_test_synset(&mut wn, None, "noun.animal", "a canine mammal",
+            &["dog"], &mut change_list);
+        wn.add_rel(&cat, SynsetRelType::Hypernym, &animal).unwrap();
+        wn.add_rel(&feline, SynsetRelType::Hypernym, &animal).unwrap();
+        wn.add_rel(&kitten, SynsetRelType::Hypernym, &feline).unwrap();
+        wn.add_rel(&cat, SynsetRelType::Also, &feline).unwrap();
+        let sense = |wn : &LexiconHashMapBackend, lemma : &str, synset : &SynsetId|
+            wn.get_sense_id2(lemma, synset).unwrap().unwrap();
+        let dog_sense = sense(&wn, "dog", &dog);
+        let feline_cat = sense(&wn, "cat", &feline);
+        wn.add_sense_rel(&dog_sense, SenseRelType::Antonym,
+            &SenseOrSynsetId::Sense(feline_cat.clone())).unwrap();
+        wn.add_sense_rel(&sense(&wn, "kitty", &feline), SenseRelType::Derivation,
+            &SenseOrSynsetId::Sense(dog_sense.clone())).unwrap();
+        wn.update_synset(&feline, |ss| {
+            ss.example.push(Example::new("the cat sat".to_owned(), None));
+            ss.wikidata.push("Q146".to_owned());
+        }).unwrap();
+
+        assert!(merge_synsets(&mut wn, &cat, &cat, None, "dup".to_owned(), &mut change_list).is_err());
+        merge_synsets(&mut wn, &cat, &feline, Some("a small feline mammal".to_owned()),
+            "Duplicate (#1)".to_owned(), &mut change_list).unwrap();
+
+        assert!(wn.synset_by_id(&feline).unwrap().is_none());
+        let merged = wn.synset_by_id(&cat).unwrap().unwrap().into_owned();
+        assert_eq!(merged.members, vec!["cat", "puss", "kitty"]);
+        assert_eq!(merged.definition, vec!["a small feline mammal"]);
+        assert_eq!(merged.example.len(), 1);
+        assert_eq!(merged.wikidata, vec!["Q146"]);
+        // The duplicate hypernym is dropped, as is the `also` link that would loop back.
+        assert_eq!(merged.links_from(), vec![(SynsetRelType::Hypernym, animal.clone())]);
+        assert_eq!(wn.links_to(&cat).unwrap(), vec![(SynsetRelType::Hypernym, kitten.clone())]);
+        assert_eq!(wn.synset_by_id(&kitten).unwrap().unwrap().hypernym, vec![cat.clone()]);
+        // The shared lemma keeps one sense, which takes over the dropped sense's relations.
+        let cat_sense = sense(&wn, "cat", &cat);
+        assert_eq!(wn.entry_by_lemma("cat").unwrap()[0].sense.len(), 1);
+        assert_eq!(wn.sense_links_from_id(&dog_sense).unwrap(),
+            vec![(SenseRelType::Antonym, UnresolvedSenseOrSynsetId::Sense(cat_sense))]);
+        assert_eq!(wn.sense_links_from_id(&sense(&wn, "kitty", &cat)).unwrap(),
+            vec![(SenseRelType::Derivation, UnresolvedSenseOrSynsetId::Sense(dog_sense))]);
+        assert_eq!(wn.deprecations_get().unwrap().len(), 1);
+    }
 }
@@ -0,0 +1,472 @@
+//! Checks on the wording of definitions and examples, over the whole lexicon: circular
+//! definitions, which use one of the synset's own members ("cat": "a small domesticated cat"),
+//! and unknown words, content words in definitions and examples that have no entry in the
+//! wordnet at all. Words are lemmatised first, using the irregular forms of entries
+//! (`Entry::form`) and then the regular English inflections, so "mice" is a form of "mouse" and
+//! "running" one of "run".
+//!
+//! These are the opt-in checks `CircularDefinition` and `UnknownWord` (see
+//! `validate::OPT_IN_CHECKS`): `validate` does not make them, and they are only reported by
+//! `opt_in_errors` if the project's validation profile gives them a severity.
+
+use crate::progress::Progress;
+use crate::validate::ValidationError;
+use crate::validation_profile::{Severity, ValidationProfile};
+use crate::wordnet::{Lexicon, Result, Synset, SynsetId};
+use std::collections::{BTreeSet, HashMap, HashSet};
+
+/// Function words, which are not expected to be in a wordnet
+const FUNCTION_WORDS: &[&str] = &[
+    "a",
+    "about",
+    "above",
+    "after",
+    "against",
+    "all",
+    "although",
+    "am",
+    "among",
+    "an",
+    "and",
+    "another",
+    "any",
+    "are",
+    "as",
+    "at",
+    "be",
+    "because",
+    "been",
+    "before",
+    "being",
+    "below",
+    "between",
+    "both",
+    "but",
+    "by",
+    "can",
+    "could",
+    "did",
+    "do",
+    "does",
+    "during",
+    "each",
+    "e.g",
+    "either",
+    "etc",
+    "every",
+    "for",
+    "from",
+    "had",
+    "has",
+    "have",
+    "he",
+    "her",
+    "hers",
+    "herself",
+    "him",
+    "himself",
+    "his",
+    "how",
+    "i",
+    "i.e",
+    "if",
+    "in",
+    "into",
+    "is",
+    "it",
+    "its",
+    "itself",
+    "may",
+    "me",
+    "might",
+    "must",
+    "my",
+    "neither",
+    "no",
+    "nor",
+    "not",
+    "of",
+    "off",
+    "on",
+    "one's",
+    "oneself",
+    "onto",
+    "or",
+    "other",
+    "our",
+    "ours",
+    "ourselves",
+    "shall",
+    "she",
+    "should",
+    "so",
+    "some",
+    "such",
+    "than",
+    "that",
+    "the",
+    "their",
+    "theirs",
+    "them",
+    "themselves",
+    "then",
+    "there",
+    "these",
+    "they",
+    "this",
+    "those",
+    "through",
+    "to",
+    "toward",
+    "towards",
+    "under",
+    "until",
+    "up",
+    "upon",
+    "us",
+    "was",
+    "we",
+    "were",
+    "what",
+    "when",
+    "where",
+    "whether",
+    "which",
+    "while",
+    "who",
+    "whom",
+    "whose",
+    "why",
+    "will",
+    "with",
+    "within",
+    "without",
+    "would",
+    "you",
+    "your",
+    "yours",
+    "yourself",
+];
+
+/// The regular inflections, as `(suffix, replacement)`: a word ending in the suffix may be a
+/// form of the word with the replacement instead (as WordNet's `morphy` detaches them)
+const INFLECTIONS: &[(&str, &str)] = &[
+    ("s", ""),
+    ("ses", "s"),
+    ("xes", "x"),
+    ("zes", "z"),
+    ("ches", "ch"),
+    ("shes", "sh"),
+    ("men", "man"),
+    ("ies", "y"),
+    ("es", "e"),
+    ("es", ""),
+    ("ed", "e"),
+    ("ed", ""),
+    ("ing", "e"),
+    ("ing", ""),
+    ("er", ""),
+    ("est", ""),
+    ("er", "e"),
+    ("est", "e"),
+];
+
+/// The words of a text, lower-cased, with any possessive `'s` dropped. Hyphens and
+/// apostrophes within a word are kept.
+fn words(text: &str) -> Vec<String> {
+    text.split(|c: char| !(c.is_alphanumeric() || c == '-' || c == '\'' || c == '.'))
+        .map(|word| word.trim_matches(|c| c == '-' || c == '\'' || c == '.'))
+        .filter(|word| !word.is_empty())
+        .map(|word| {
+            let word = word.to_lowercase();
+            match word.strip_suffix("'s") {
+                Some(stem) => stem.to_string(),
+                None => word,
+            }
+        })
+        .collect()
+}
+
+/// Finds the lemmas a word could be a form of
+pub struct Lemmatiser {
+    /// Every lemma in the wordnet, lower-cased
+    lemmas: HashSet<String>,
+    /// The lemmas of each irregular form, lower-cased
+    forms: HashMap<String, Vec<String>>,
+}
+
+impl Lemmatiser {
+    pub fn new<L: Lexicon>(wn: &L) -> Result<Lemmatiser> {
+        let mut lemmas = HashSet::new();
+        let mut forms: HashMap<String, Vec<String>> = HashMap::new();
+        for entry in wn.entries()? {
+            let (lemma, _, entry) = entry?;
+            let lemma = lemma.to_lowercase();
+            for form in entry.form.iter() {
+                forms
+                    .entry(form.to_lowercase())
+                    .or_default()
+                    .push(lemma.clone());
+            }
+            lemmas.insert(lemma);
+        }
+        Ok(Lemmatiser { lemmas, forms })
+    }
+
+    /// The lemmas (lower-cased) `word` could be a form of, itself included if it is one
+    pub fn lemmas(&self, word: &str) -> BTreeSet<String> {
+        let word = word.to_lowercase();
+        let mut lemmas = BTreeSet::new();
+        if let Some(of) = self.forms.get(&word) {
+            lemmas.extend(of.iter().cloned());
+        }
+        for (suffix, replacement) in INFLECTIONS {
+            if let Some(stem) = word.strip_suffix(suffix) {
+                let lemma = format!("{}{}", stem, replacement);
+                if !stem.is_empty() && self.lemmas.contains(&lemma) {
+                    lemmas.insert(lemma);
+                }
+            }
+        }
+        if self.lemmas.contains(&word) {
+            lemmas.insert(word);
+        }
+        lemmas
+    }
+
+    /// Whether `word` is in the wordnet, as a lemma or a form of one. A hyphenated word is also
+    /// known if each of its parts is.
+    pub fn is_known(&self, word: &str) -> bool {
+        !self.lemmas(word).is_empty()
+            || (word.contains('-')
+                && word
+                    .split('-')
+                    .all(|part| part.is_empty() || self.is_known(part)))
+    }
+}
+
+/// Whether `words` contain `member` (a lemma of one or more words), the last word possibly
+/// inflected
+fn uses_member(words: &[String], member: &str, lemmatiser: &Lemmatiser) -> bool {
+    let member: Vec<String> = member
+        .to_lowercase()
+        .split([' ', '_'])
+        .map(|word| word.to_string())
+        .collect();
+    let (last, init) = match member.split_last() {
+        Some(split) => split,
+        None => return false,
+    };
+    words.windows(member.len()).any(|window| {
+        let (word, preceding) = window.split_last().expect("Members have a word");
+        preceding == init && (word == last || lemmatiser.lemmas(word).contains(last))
+    })
+}
+
+/// The circular definitions of one synset: a `CircularDefinition` for each member (other than
+/// a single letter, like "a" for the letter A) one of its definitions uses
+pub fn circular_definitions(
+    synset_id: &SynsetId,
+    synset: &Synset,
+    lemmatiser: &Lemmatiser,
+) -> Vec<ValidationError> {
+    let definitions: Vec<Vec<String>> = synset
+        .definition
+        .iter()
+        .map(|definition| words(definition))
+        .collect();
+    synset
+        .members
+        .iter()
+        .filter(|member| member.chars().count() > 1)
+        .filter(|member| {
+            definitions
+                .iter()
+                .any(|words| uses_member(words, member, lemmatiser))
+        })
+        .map(|member| ValidationError::CircularDefinition {
+            id: synset_id.clone(),
+            member: member.clone(),
+        })
+        .collect()
+}
+
+/// The unknown words of one synset: an `UnknownWord` for each word of its definitions and
+/// examples (other than function words and numbers) that `lemmatiser` does not know, once each
+pub fn unknown_words(
+    synset_id: &SynsetId,
+    synset: &Synset,
+    lemmatiser: &Lemmatiser,
+) -> Vec<ValidationError> {
+    let texts = synset
+        .definition
+        .iter()
+        .chain(synset.example.iter().map(|example| &example.text));
+    let mut unknown = BTreeSet::new();
+    for text in texts {
+        for word in words(text) {
+            if !FUNCTION_WORDS.contains(&word.as_str())
+                && !word.chars().any(|c| c.is_numeric())
+                && !lemmatiser.is_known(&word)
+            {
+                unknown.insert(word);
+            }
+        }
+    }
+    unknown
+        .into_iter()
+        .map(|word| ValidationError::UnknownWord {
+            id: synset_id.clone(),
+            word,
+        })
+        .collect()
+}
+
+/// Every circular definition (if `circular`) and unknown word (if `unknown`) in `wn`
+pub fn check_definitions<L: Lexicon, Bar: Progress>(
+    wn: &L,
+    circular: bool,
+    unknown: bool,
+    bar: &mut Bar,
+) -> Result<Vec<ValidationError>> {
+    let mut errors = Vec::new();
+    if !circular && !unknown {
+        return Ok(errors);
+    }
+    let lemmatiser = Lemmatiser::new(wn)?;
+    bar.start(wn.n_synsets()? as u64);
+    bar.set_percent_mode(true);
+    for synset in wn.synsets()? {
+        let (synset_id, synset) = synset?;
+        bar.inc(1);
+        if circular {
+            errors.extend(circular_definitions(&synset_id, &synset, &lemmatiser));
+        }
+        if unknown {
+            errors.extend(unknown_words(&synset_id, &synset, &lemmatiser));
+        }
+    }
+    bar.finish();
+    Ok(errors)
+}
+
+/// What the opt-in checks `profile` asks for find in `wn` (nothing, for the default profile)
+pub fn opt_in_errors<L: Lexicon, Bar: Progress>(
+    wn: &L,
+    profile: &ValidationProfile,
+    bar: &mut Bar,
+) -> Result<Vec<ValidationError>> {
+    check_definitions(
+        wn,
+        profile.check_severity("CircularDefinition") != Severity::Off,
+        profile.check_severity("UnknownWord") != Severity::Off,
+        bar,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::change_manager::{add_test_synset, ChangeList};
+    use crate::progress::NullProgress;
+    use crate::wordnet::{Example, LexiconHashMapBackend, PosKey};
+
+    #[test]
+    fn test_check_definitions() {
+        let mut wn = LexiconHashMapBackend::new();
+        let mut changes = ChangeList::new();
+        let mouse = add_test_synset(
+            &mut wn,
+            Some("00000010-n"),
+            "noun.animal",
+            "a small rodent",
+            &["mouse"],
+            &mut changes,
+        );
+        let rodent = add_test_synset(
+            &mut wn,
+            Some("00000011-n"),
+            "noun.animal",
+            "a gnawing mammal",
+            &["rodent", "gnawer"],
+            &mut changes,
+        );
+        let mammal = add_test_synset(
+            &mut wn,
+            Some("00000012-n"),
+            "noun.animal",
+            "an animal that suckles its young",
+            &["mammal"],
+            &mut changes,
+        );
+        let letter = add_test_synset(
+            &mut wn,
+            Some("00000013-n"),
+            "noun.animal",
+            "the letter a, 1st of the alphabet",
+            &["a", "letter a"],
+            &mut changes,
+        );
+        wn.add_form("mouse", &PosKey::new("n".to_string()), "mice".to_string())
+            .unwrap();
+        wn.update_synset(&rodent, |synset| {
+            synset
+                .example
+                .push(Example::new("mice are rodents".to_string(), None))
+        })
+        .unwrap();
+        wn.update_synset(&mouse, |synset| {
+            synset.definition = vec!["a small rodent, like other mice".to_string()]
+        })
+        .unwrap();
+
+        let errors = check_definitions(&wn, true, false, &mut NullProgress).unwrap();
+        let found: Vec<String> = errors.iter().map(|e| e.to_string()).collect();
+        assert_eq!(
+            found,
+            vec![
+                "00000010-n is defined using its own member mouse",
+                "00000013-n is defined using its own member letter a",
+            ]
+        );
+
+        let lemmatiser = Lemmatiser::new(&wn).unwrap();
+        assert!(lemmatiser.is_known("mice"));
+        assert!(lemmatiser.is_known("rodents"));
+        assert!(lemmatiser.is_known("rodent-mammal"));
+        assert!(!lemmatiser.is_known("mouse-like"));
+        let errors = check_definitions(&wn, false, true, &mut NullProgress).unwrap();
+        let unknown: Vec<(SynsetId, String)> = errors
+            .into_iter()
+            .map(|error| match error {
+                ValidationError::UnknownWord { id, word } => (id, word),
+                _ => panic!("Not an unknown word"),
+            })
+            .collect();
+        assert!(unknown.contains(&(mouse.clone(), "small".to_string())));
+        assert!(unknown.contains(&(mouse.clone(), "like".to_string())));
+        assert!(unknown.contains(&(rodent.clone(), "gnawing".to_string())));
+        assert!(unknown.contains(&(mammal.clone(), "suckles".to_string())));
+        assert!(unknown.contains(&(letter.clone(), "alphabet".to_string())));
+        assert!(!unknown
+            .iter()
+            .any(|(_, word)| word == "mice" || word == "the" || word == "1st"));
+
+        assert!(!ValidationProfile::default().opts_in());
+        assert!(
+            opt_in_errors(&wn, &ValidationProfile::default(), &mut NullProgress)
+                .unwrap()
+                .is_empty()
+        );
+        let profile =
+            ValidationProfile::from_settings("[validation]\nCircularDefinition = \"warning\"\n")
+                .unwrap();
+        assert!(profile.opts_in());
+        assert_eq!(
+            opt_in_errors(&wn, &profile, &mut NullProgress)
+                .unwrap()
+                .len(),
+            2
+        );
+    }
+}
@@ -0,0 +1,266 @@
+//! Machine-readable validation results. A `Diagnostic` is one `ValidationError` with its stable
+//! code, its fields and the YAML source file and line of the synset, sense or entry it concerns,
+//! so CI and editor integrations can annotate the offending line rather than matching messages.
+//!
+//! ```json
+//! {"code": "EWE022", "check": "NoHypernym", "severity": "error",
+//!  "message": "No hypernym for 00001740-n", "fields": {"id": "00001740-n"},
+//!  "file": "src/yaml/noun.Tops.yaml", "line": 1}
+//! ```
+
+use crate::validate::{ErrorSubject, ValidationError};
+use crate::validation_profile::{Severity, ValidationProfile};
+use crate::wordnet::lexicon::entry_key;
+use crate::wordnet::Lexicon;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+
+/// One validation result, as reported by `ewe validate --format json`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Diagnostic {
+    /// The stable code of the check (`ValidationError::code`), e.g. `EWE022`
+    pub code: String,
+    /// The name of the check (`ValidationError::name`), e.g. `NoHypernym`
+    pub check: String,
+    pub severity: Severity,
+    /// The human-readable message, as `validate` has always printed it
+    pub message: String,
+    /// The fields of the error (`ValidationError::fields`)
+    pub fields: BTreeMap<String, String>,
+    /// The YAML source file defining the synset, sense or entry at fault, if it could be found
+    pub file: Option<String>,
+    /// The (1-based) line in `file` where it is defined, if it could be found
+    pub line: Option<usize>,
+}
+
+impl Diagnostic {
+    /// A diagnostic for `error` with no source location
+    pub fn new(error: &ValidationError, severity: Severity) -> Diagnostic {
+        Diagnostic {
+            code: error.code(),
+            check: error.name().to_string(),
+            severity,
+            message: error.to_string(),
+            fields: error
+                .fields()
+                .into_iter()
+                .map(|(name, value)| (name.to_string(), value))
+                .collect(),
+            file: None,
+            line: None,
+        }
+    }
+}
+
+/// Finds where synsets, senses and entries are defined in a YAML source directory. Each file is
+/// read and indexed once, the first time something in it is looked up.
+pub struct SourceLocator {
+    source: PathBuf,
+    files: HashMap<String, Option<SourceFile>>,
+}
+
+/// The line of every top-level key (synset id or lemma), part of speech key (within its
+/// lemma) and sense id in one YAML source file
+#[derive(Default)]
+struct SourceFile {
+    keys: HashMap<String, usize>,
+    poskeys: HashMap<(String, String), usize>,
+    sense_ids: HashMap<String, usize>,
+}
+
+impl SourceFile {
+    fn parse(text: &str) -> SourceFile {
+        let mut file = SourceFile::default();
+        let mut key = String::new();
+        for (i, line) in text.lines().enumerate() {
+            if line.starts_with(' ') {
+                let trimmed = line.trim_start();
+                let item = trimmed.strip_prefix("- ").unwrap_or(trimmed);
+                if let Some(id) = item.strip_prefix("id: ") {
+                    file.sense_ids.entry(unquote(id)).or_insert(i + 1);
+                } else if line.len() - trimmed.len() == 2 {
+                    if let Some(poskey) = trimmed.strip_suffix(':') {
+                        file.poskeys
+                            .entry((key.clone(), poskey.to_string()))
+                            .or_insert(i + 1);
+                    }
+                }
+            } else if let Some(k) = line.strip_suffix(':') {
+                key = unquote(k);
+                file.keys.entry(key.clone()).or_insert(i + 1);
+            }
+        }
+        file
+    }
+}
+
+/// The value of a YAML scalar as written (e.g. `'foo:'`), falling back to the text itself
+fn unquote(s: &str) -> String {
+    serde_yaml::from_str::<String>(s).unwrap_or_else(|_| s.to_string())
+}
+
+impl SourceLocator {
+    /// A locator for the YAML source directory `source` (as passed to `Lexicon::load`)
+    pub fn new(source: impl AsRef<Path>) -> SourceLocator {
+        SourceLocator {
+            source: source.as_ref().to_path_buf(),
+            files: HashMap::new(),
+        }
+    }
+
+    fn file(&mut self, name: &str) -> Option<&SourceFile> {
+        let source = &self.source;
+        self.files
+            .entry(name.to_string())
+            .or_insert_with(|| {
+                std::fs::read_to_string(source.join(name))
+                    .ok()
+                    .map(|text| SourceFile::parse(&text))
+            })
+            .as_ref()
+    }
+
+    /// The file (joined to the source directory) and line where `subject` is defined. The file
+    /// is given whenever it can be worked out, even if the line cannot (e.g. because the
+    /// source on disk predates unsaved edits).
+    pub fn locate<L: Lexicon>(
+        &mut self,
+        wn: &L,
+        subject: &ErrorSubject,
+    ) -> (Option<String>, Option<usize>) {
+        let (name, line) = match subject {
+            ErrorSubject::Synset(id) => match wn.lex_name_for(id).ok().flatten() {
+                Some(lexfile) => {
+                    let name = format!("{}.yaml", lexfile);
+                    let line = self
+                        .file(&name)
+                        .and_then(|file| file.keys.get(id.as_str()).copied());
+                    (name, line)
+                }
+                None => return (None, None),
+            },
+            ErrorSubject::Sense(id) => {
+                if id.as_str().is_empty() {
+                    return (None, None);
+                }
+                let name = format!("entries-{}.yaml", entry_key(id.as_str()));
+                let line = self
+                    .file(&name)
+                    .and_then(|file| file.sense_ids.get(id.as_str()).copied());
+                (name, line)
+            }
+            ErrorSubject::Entry(lemma, poskey) => {
+                if lemma.is_empty() {
+                    return (None, None);
+                }
+                let name = format!("entries-{}.yaml", entry_key(lemma));
+                let line = self.file(&name).and_then(|file| {
+                    file.poskeys
+                        .get(&(lemma.clone(), poskey.as_str().to_string()))
+                        .or_else(|| file.keys.get(lemma))
+                        .copied()
+                });
+                (name, line)
+            }
+        };
+        (Some(self.source.join(name).display().to_string()), line)
+    }
+
+    /// A diagnostic for `error`, located in the source
+    pub fn diagnostic<L: Lexicon>(
+        &mut self,
+        wn: &L,
+        error: &ValidationError,
+        severity: Severity,
+    ) -> Diagnostic {
+        let (file, line) = self.locate(wn, &error.subject());
+        Diagnostic {
+            file,
+            line,
+            ..Diagnostic::new(error, severity)
+        }
+    }
+}
+
+/// Diagnostics for everything `validate` found in the wordnet loaded from `source`, with the
+/// severity `profile` gives each (dropping anything it switches off); errors first, then
+/// warnings
+pub fn diagnostics<L: Lexicon>(
+    wn: &L,
+    source: impl AsRef<Path>,
+    profile: &ValidationProfile,
+    found: Vec<ValidationError>,
+) -> Vec<Diagnostic> {
+    let mut locator = SourceLocator::new(source);
+    let (errors, warnings) = profile.classify(found);
+    let errors = errors.iter().map(|error| (error, Severity::Error));
+    let warnings = warnings.iter().map(|warning| (warning, Severity::Warning));
+    errors
+        .chain(warnings)
+        .map(|(error, severity)| locator.diagnostic(wn, error, severity))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::progress::NullProgress;
+    use crate::validate::validate;
+    use crate::wordnet::{LexiconHashMapBackend, PosKey, SenseId, SynsetId};
+
+    #[test]
+    fn test_diagnostics_locate_source() {
+        let dir = std::env::temp_dir().join(format!("ewe_diagnostics_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(
+            dir.join("noun.animal.yaml"),
+            "00001740-n:\n  definition:\n  - a cat\n  members:\n  - cat\n  partOfSpeech: n\n\
+             00001741-n:\n  definition:\n  - a dog\n  members:\n  - dog\n  partOfSpeech: n\n",
+        )
+        .unwrap();
+        std::fs::write(
+            dir.join("entries-c.yaml"),
+            "cat:\n  n:\n    sense:\n    - id: 'cat%1:05:00::'\n      synset: 00001740-n\n",
+        )
+        .unwrap();
+        std::fs::write(
+            dir.join("entries-d.yaml"),
+            "dog:\n  n:\n    sense:\n    - id: 'dog%1:05:00::'\n      synset: 00001741-n\n",
+        )
+        .unwrap();
+        let wn = LexiconHashMapBackend::new()
+            .load(&dir, &mut NullProgress)
+            .unwrap();
+
+        let found = validate(&wn, &mut NullProgress).unwrap();
+        let diagnostics = diagnostics(&wn, &dir, &ValidationProfile::default(), found);
+        let no_hypernym = diagnostics
+            .iter()
+            .find(|d| d.check == "NoHypernym" && d.fields["id"] == "00001741-n")
+            .unwrap();
+        assert_eq!(no_hypernym.code, "EWE022");
+        assert_eq!(no_hypernym.severity, Severity::Error);
+        assert_eq!(
+            no_hypernym.file,
+            Some(dir.join("noun.animal.yaml").display().to_string())
+        );
+        assert_eq!(no_hypernym.line, Some(7));
+
+        let mut locator = SourceLocator::new(&dir);
+        let sense = ErrorSubject::Sense(SenseId::new("dog%1:05:00::".to_string()));
+        assert_eq!(
+            locator.locate(&wn, &sense),
+            (Some(dir.join("entries-d.yaml").display().to_string()), Some(4))
+        );
+        let entry = ErrorSubject::Entry("cat".to_string(), PosKey::new("n".to_string()));
+        assert_eq!(locator.locate(&wn, &entry).1, Some(2));
+        let missing = ErrorSubject::Synset(SynsetId::new("00009999-n"));
+        assert_eq!(locator.locate(&wn, &missing), (None, None));
+
+        let json = serde_json::to_string(no_hypernym).unwrap();
+        let back: Diagnostic = serde_json::from_str(&json).unwrap();
+        assert_eq!(&back, no_hypernym);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,462 @@
+//! A review of definitions against hypernyms. Definitions are meant to be written as a genus
+//! and a differentia ("ewe": "a female sheep" - a kind of sheep, differentiated by being
+//! female), and the genus should then name something the synset is a kind of. This finds the
+//! noun and verb synsets where it doesn't: the genus is not a member of any hypernym (or
+//! instance hypernym) within a given number of steps. For each, it suggests the synsets that do
+//! have the genus as a member.
+//!
+//! The genus is found by a simple heuristic, not a parser: for a noun, the head of the first
+//! noun phrase (skipping determiners and openings like "a kind of" or "any of various"); for a
+//! verb, its first word. So this is a report for a lexicographer to review, not a check that
+//! blocks anything.
+
+use crate::progress::Progress;
+use crate::wordnet::{Lexicon, PartOfSpeech, Result, Synset, SynsetId};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashSet};
+
+/// A synset whose definition's genus is not among its hypernyms
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GenusMismatch {
+    pub synset: SynsetId,
+    pub definition: String,
+    /// The genus, as found in the definition
+    pub genus: String,
+    /// The synsets of the same part of speech with the genus as a member - the hypernyms the
+    /// definition suggests
+    pub candidates: Vec<SynsetId>,
+}
+
+/// Words that may open a noun phrase without being its head
+const DETERMINERS: &[&str] = &[
+    "a", "an", "the", "any", "some", "one", "each", "every", "either", "another", "its", "his",
+    "her", "their", "all", "various", "several", "numerous", "many", "certain",
+];
+
+/// Words that name no genus of their own when followed by "of" ("a kind of dog")
+const OF_OPENINGS: &[&str] = &[
+    "kind", "type", "sort", "variety", "form", "species", "member",
+];
+
+/// Words that end the first noun phrase of a definition
+const NOUN_PHRASE_ENDS: &[&str] = &[
+    ",",
+    "of",
+    "that",
+    "which",
+    "who",
+    "whom",
+    "whose",
+    "with",
+    "without",
+    "for",
+    "in",
+    "on",
+    "by",
+    "from",
+    "to",
+    "into",
+    "at",
+    "as",
+    "about",
+    "between",
+    "like",
+    "than",
+    "under",
+    "over",
+    "or",
+    "and",
+    "where",
+    "when",
+    "used",
+    "having",
+    "consisting",
+    "containing",
+    "made",
+    "especially",
+    "usually",
+    "typically",
+    "often",
+    "characterized",
+    "resembling",
+];
+
+/// The words of a definition, lower-cased, up to its first semicolon and without anything in
+/// parentheses. Commas are kept, as words of their own.
+fn words(definition: &str) -> Vec<String> {
+    let mut words = Vec::new();
+    let mut word = String::new();
+    let mut depth = 0usize;
+    for c in definition.chars() {
+        if c == '(' {
+            depth += 1;
+        } else if c == ')' {
+            depth = depth.saturating_sub(1);
+        } else if depth > 0 {
+            continue;
+        } else if c == ';' {
+            break;
+        } else if c.is_alphanumeric() || c == '-' || c == '\'' {
+            word.extend(c.to_lowercase());
+            continue;
+        }
+        if !word.is_empty() {
+            words.push(std::mem::take(&mut word));
+        }
+        if c == ',' {
+            words.push(",".to_string());
+        }
+    }
+    if !word.is_empty() {
+        words.push(word);
+    }
+    words
+}
+
+/// The words of the genus phrase of a definition (for a noun, the whole noun phrase, whose last
+/// word is the genus itself), or `None` if there doesn't seem to be one
+pub fn genus_phrase(definition: &str, pos: &PartOfSpeech) -> Option<Vec<String>> {
+    let words = words(definition);
+    match pos {
+        PartOfSpeech::n => {
+            let mut start = 0;
+            loop {
+                while start < words.len() && DETERMINERS.contains(&words[start].as_str()) {
+                    start += 1;
+                }
+                let end = words[start..]
+                    .iter()
+                    .position(|w| NOUN_PHRASE_ENDS.contains(&w.as_str()))
+                    .map_or(words.len(), |n| start + n);
+                let phrase = &words[start..end];
+                let opening = phrase.is_empty()
+                    || (phrase.len() == 1 && OF_OPENINGS.contains(&phrase[0].as_str()));
+                if opening && words.get(end).map(|w| w.as_str()) == Some("of") {
+                    start = end + 1;
+                } else if phrase.is_empty() {
+                    return None;
+                } else {
+                    return Some(phrase.to_vec());
+                }
+            }
+        }
+        PartOfSpeech::v => {
+            let start = if words.first().map(|w| w.as_str()) == Some("to") {
+                1
+            } else {
+                0
+            };
+            let phrase: Vec<String> = words
+                .iter()
+                .skip(start)
+                .take_while(|w| w.as_str() != ",")
+                .take(2)
+                .cloned()
+                .collect();
+            if phrase.is_empty() {
+                None
+            } else {
+                Some(phrase)
+            }
+        }
+        _ => None,
+    }
+}
+
+/// The lemmas the genus phrase could stand for, most specific first: for a noun every ending
+/// of the phrase (e.g. "domestic animal", "animal"), each also singular, and for a verb the
+/// first two words as a phrasal verb, then the first
+fn genus_forms(phrase: &[String], pos: &PartOfSpeech) -> Vec<String> {
+    let mut forms = Vec::new();
+    match pos {
+        PartOfSpeech::v => {
+            if phrase.len() > 1 {
+                forms.push(phrase.join(" "));
+            }
+            forms.push(phrase[0].clone());
+        }
+        _ => {
+            for start in 0..phrase.len() {
+                let form = phrase[start..].join(" ");
+                let singulars = [("ies", "y"), ("es", ""), ("s", "")]
+                    .iter()
+                    .filter_map(|(plural, singular)| {
+                        form.strip_suffix(plural)
+                            .map(|stem| format!("{}{}", stem, singular))
+                    })
+                    .filter(|singular| !singular.ends_with(' ') && !singular.is_empty())
+                    .collect::<Vec<_>>();
+                forms.push(form);
+                forms.extend(singulars);
+            }
+        }
+    }
+    forms
+}
+
+/// The members (lower-cased) of every hypernym and instance hypernym of `synset` within
+/// `depth` steps
+fn ancestor_members<L: Lexicon>(wn: &L, synset: &Synset, depth: usize) -> Result<HashSet<String>> {
+    let mut members = HashSet::new();
+    let mut seen = HashSet::new();
+    let mut level: Vec<SynsetId> = synset
+        .hypernym
+        .iter()
+        .chain(synset.instance_hypernym.iter())
+        .cloned()
+        .collect();
+    for _ in 0..depth {
+        let mut next = Vec::new();
+        for id in level {
+            if !seen.insert(id.clone()) {
+                continue;
+            }
+            if let Some(ancestor) = wn.synset_by_id(&id)? {
+                members.extend(
+                    ancestor
+                        .members
+                        .iter()
+                        .map(|m| m.to_lowercase().replace('_', " ")),
+                );
+                next.extend(
+                    ancestor
+                        .hypernym
+                        .iter()
+                        .chain(ancestor.instance_hypernym.iter())
+                        .cloned(),
+                );
+            }
+        }
+        level = next;
+    }
+    Ok(members)
+}
+
+/// The synsets other than `synset_id` of part of speech `pos` with `lemma` as a member
+fn synsets_with_member<L: Lexicon>(
+    wn: &L,
+    lemma: &str,
+    pos: &PartOfSpeech,
+    synset_id: &SynsetId,
+) -> Result<Vec<SynsetId>> {
+    let mut found = BTreeSet::new();
+    for (poskey, entry) in wn.entry_by_lemma_with_pos(lemma)? {
+        if poskey.to_part_of_speech().as_ref() != Some(pos) {
+            continue;
+        }
+        for sense in entry.sense.iter() {
+            if sense.synset != *synset_id {
+                found.insert(sense.synset.clone());
+            }
+        }
+    }
+    Ok(found.into_iter().collect())
+}
+
+/// Checks the genus of the (first) definition of one synset against its hypernyms within
+/// `depth` steps
+pub fn check_synset_genus<L: Lexicon>(
+    wn: &L,
+    synset_id: &SynsetId,
+    synset: &Synset,
+    depth: usize,
+) -> Result<Option<GenusMismatch>> {
+    // A synset without hypernyms is a `NoHypernym` validation error already
+    if synset.hypernym.is_empty() && synset.instance_hypernym.is_empty() {
+        return Ok(None);
+    }
+    let definition = match synset.definition.first() {
+        Some(definition) => definition,
+        None => return Ok(None),
+    };
+    let pos = &synset.part_of_speech;
+    let phrase = match genus_phrase(definition, pos) {
+        Some(phrase) => phrase,
+        None => return Ok(None),
+    };
+    let forms = genus_forms(&phrase, pos);
+    let members = ancestor_members(wn, synset, depth)?;
+    if forms.iter().any(|form| members.contains(form)) {
+        return Ok(None);
+    }
+    // Report the most specific form something has as a member, or else the genus word itself
+    for form in forms.iter() {
+        let candidates = synsets_with_member(wn, form, pos, synset_id)?;
+        if !candidates.is_empty() {
+            return Ok(Some(GenusMismatch {
+                synset: synset_id.clone(),
+                definition: definition.clone(),
+                genus: form.clone(),
+                candidates,
+            }));
+        }
+    }
+    let genus = match pos {
+        PartOfSpeech::v => phrase[0].clone(),
+        _ => phrase[phrase.len() - 1].clone(),
+    };
+    Ok(Some(GenusMismatch {
+        synset: synset_id.clone(),
+        definition: definition.clone(),
+        genus,
+        candidates: Vec::new(),
+    }))
+}
+
+/// Checks every noun and verb synset in `lexfiles` (or in the whole wordnet, if that is empty)
+/// as `check_synset_genus` does
+pub fn check_genus<L: Lexicon, Bar: Progress>(
+    wn: &L,
+    lexfiles: &[String],
+    depth: usize,
+    bar: &mut Bar,
+) -> Result<Vec<GenusMismatch>> {
+    let mut mismatches = Vec::new();
+    bar.start(wn.n_synsets()? as u64);
+    bar.set_percent_mode(true);
+    for synset in wn.synsets()? {
+        let (synset_id, synset) = synset?;
+        bar.inc(1);
+        if !matches!(synset.part_of_speech, PartOfSpeech::n | PartOfSpeech::v) {
+            continue;
+        }
+        if !lexfiles.is_empty() {
+            match wn.lex_name_for(&synset_id)? {
+                Some(lexfile) if lexfiles.contains(&lexfile) => {}
+                _ => continue,
+            }
+        }
+        mismatches.extend(check_synset_genus(wn, &synset_id, &synset, depth)?);
+    }
+    bar.finish();
+    mismatches.sort_by(|a, b| a.synset.cmp(&b.synset));
+    Ok(mismatches)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::change_manager::{add_test_synset, ChangeList};
+    use crate::progress::NullProgress;
+    use crate::rels::SynsetRelType;
+    use crate::wordnet::LexiconHashMapBackend;
+
+    fn phrase(definition: &str, pos: PartOfSpeech) -> Option<String> {
+        genus_phrase(definition, &pos).map(|words| words.join(" "))
+    }
+
+    #[test]
+    fn test_genus_phrase() {
+        assert_eq!(
+            phrase("a female sheep", PartOfSpeech::n),
+            Some("female sheep".to_string())
+        );
+        assert_eq!(
+            phrase(
+                "any of various large birds (especially of Africa) that cannot fly",
+                PartOfSpeech::n
+            ),
+            Some("large birds".to_string())
+        );
+        assert_eq!(
+            phrase("a kind of dog, with short legs", PartOfSpeech::n),
+            Some("dog".to_string())
+        );
+        assert_eq!(
+            phrase("the female of sheep", PartOfSpeech::n),
+            Some("female".to_string())
+        );
+        assert_eq!(phrase("of or relating to sheep", PartOfSpeech::n), None);
+        assert_eq!(
+            phrase("to move fast; run", PartOfSpeech::v),
+            Some("move fast".to_string())
+        );
+        assert_eq!(phrase("quickly", PartOfSpeech::r), None);
+        assert_eq!(
+            genus_forms(
+                &["large".to_string(), "birds".to_string()],
+                &PartOfSpeech::n
+            ),
+            vec!["large birds", "large bird", "birds", "bird"]
+        );
+    }
+
+    #[test]
+    fn test_check_genus() {
+        let mut wn = LexiconHashMapBackend::new();
+        let mut changes = ChangeList::new();
+        let animal = add_test_synset(
+            &mut wn,
+            Some("00000010-n"),
+            "noun.animal",
+            "a living organism",
+            &["animal"],
+            &mut changes,
+        );
+        let mammal = add_test_synset(
+            &mut wn,
+            Some("00000011-n"),
+            "noun.animal",
+            "a warm-blooded animal",
+            &["mammal"],
+            &mut changes,
+        );
+        let sheep = add_test_synset(
+            &mut wn,
+            Some("00000012-n"),
+            "noun.animal",
+            "a woolly mammal",
+            &["sheep"],
+            &mut changes,
+        );
+        let ewe = add_test_synset(
+            &mut wn,
+            Some("00000013-n"),
+            "noun.animal",
+            "a female sheep",
+            &["ewe"],
+            &mut changes,
+        );
+        let dog = add_test_synset(
+            &mut wn,
+            Some("00000014-n"),
+            "noun.animal",
+            "a domestic animal that barks",
+            &["dog"],
+            &mut changes,
+        );
+        let puppy = add_test_synset(
+            &mut wn,
+            Some("00000015-n"),
+            "noun.animal",
+            "a young sheep",
+            &["puppy"],
+            &mut changes,
+        );
+        wn.add_rel(&mammal, SynsetRelType::Hypernym, &animal)
+            .unwrap();
+        wn.add_rel(&sheep, SynsetRelType::Hypernym, &mammal)
+            .unwrap();
+        wn.add_rel(&ewe, SynsetRelType::Hypernym, &sheep).unwrap();
+        wn.add_rel(&dog, SynsetRelType::Hypernym, &mammal).unwrap();
+        wn.add_rel(&puppy, SynsetRelType::Hypernym, &dog).unwrap();
+
+        // "animal" is two steps up from dog, so only found with a depth of 2
+        let mismatches = check_genus(&wn, &[], 1, &mut NullProgress).unwrap();
+        let ids: Vec<&SynsetId> = mismatches.iter().map(|m| &m.synset).collect();
+        assert_eq!(ids, vec![&dog, &puppy]);
+        assert_eq!(mismatches[1].genus, "sheep");
+        assert_eq!(mismatches[1].candidates, vec![sheep.clone()]);
+        assert_eq!(mismatches[0].genus, "animal");
+        assert_eq!(mismatches[0].candidates, vec![animal.clone()]);
+
+        let mismatches = check_genus(&wn, &[], 2, &mut NullProgress).unwrap();
+        assert_eq!(mismatches.len(), 1);
+        assert_eq!(mismatches[0].synset, puppy);
+        assert!(
+            check_genus(&wn, &["noun.person".to_string()], 2, &mut NullProgress)
+                .unwrap()
+                .is_empty()
+        );
+    }
+}
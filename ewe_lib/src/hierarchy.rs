@@ -0,0 +1,475 @@
+//! Maintenance of the hypernym graph: the synsets and their `hypernym` and `instance_hypernym`
+//! links. Validation reports the problems one synset at a time (`Transitivity`, `Loop`); this
+//! looks at the graph as a whole, to find
+//!
+//! - redundant links, from a synset to one it already reaches another way (A→C when A→B→C, or
+//!   any longer path, already holds), and
+//! - cycles - the strongly connected components of the graph - with the smallest sets of links
+//!   whose removal would break each one.
+//!
+//! `repair_batch` turns what was found into an automaton batch of `delete_relation` actions, to
+//! be reviewed (and edited) before it is applied.
+
+use crate::automaton::{Action, SynsetRef};
+use crate::progress::Progress;
+use crate::wordnet::{Lexicon, Result, SynsetId};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet, VecDeque};
+
+/// How many candidate sets of links `check_hierarchy` tries, for each cycle, before giving up
+/// on finding the smallest ones
+const MAX_CUT_CANDIDATES: usize = 100_000;
+
+/// How many of the smallest sets of links breaking a cycle are reported
+const MAX_CUTS: usize = 10;
+
+/// A `hypernym` or `instance_hypernym` link
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct Link {
+    pub source: SynsetId,
+    /// `hypernym` or `instance_hypernym`
+    pub relation: String,
+    pub target: SynsetId,
+}
+
+/// A link from a synset to one it also reaches through others
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RedundantLink {
+    pub link: Link,
+    /// The synsets on a (shortest) other path from the source to the target, in order
+    pub via: Vec<SynsetId>,
+}
+
+/// A strongly connected component of the hypernym graph: synsets that are each other's
+/// ancestors
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Cycle {
+    pub synsets: Vec<SynsetId>,
+    /// The links between them
+    pub links: Vec<Link>,
+    /// The smallest sets of `links` whose removal leaves no cycle, the one least likely to
+    /// leave a synset without any hypernym first. If there were too many links to search, just
+    /// one set, which breaks the cycle but may not be the smallest.
+    pub cuts: Vec<Vec<Link>>,
+}
+
+/// What `check_hierarchy` found
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct HierarchyReport {
+    pub redundant: Vec<RedundantLink>,
+    pub cycles: Vec<Cycle>,
+}
+
+/// The hypernym graph: the links out of each synset, to synsets that exist
+type Graph = BTreeMap<SynsetId, Vec<(String, SynsetId)>>;
+
+fn graph<L: Lexicon, Bar: Progress>(wn: &L, bar: &mut Bar) -> Result<Graph> {
+    let mut links = Vec::new();
+    let mut graph = Graph::new();
+    bar.start(wn.n_synsets()? as u64);
+    bar.set_percent_mode(true);
+    for synset in wn.synsets()? {
+        let (synset_id, synset) = synset?;
+        bar.inc(1);
+        for target in synset.hypernym.iter() {
+            links.push((synset_id.clone(), "hypernym", target.clone()));
+        }
+        for target in synset.instance_hypernym.iter() {
+            links.push((synset_id.clone(), "instance_hypernym", target.clone()));
+        }
+        graph.insert(synset_id, Vec::new());
+    }
+    bar.finish();
+    for (source, relation, target) in links {
+        if !graph.contains_key(&target) {
+            continue; // a missing target is reported by `validate`
+        }
+        let targets = graph.get_mut(&source).expect("Every source is a synset");
+        if !targets.iter().any(|(_, t)| *t == target) {
+            targets.push((relation.to_string(), target));
+        }
+    }
+    Ok(graph)
+}
+
+/// The strongly connected components of `graph` with more than one synset, or a single synset
+/// linked to itself, by Tarjan's algorithm (without recursion, as hypernym chains are long)
+fn components(graph: &Graph) -> Vec<Vec<SynsetId>> {
+    let mut index: HashMap<&SynsetId, usize> = HashMap::new();
+    let mut low: HashMap<&SynsetId, usize> = HashMap::new();
+    let mut stack: Vec<&SynsetId> = Vec::new();
+    let mut on_stack: HashSet<&SynsetId> = HashSet::new();
+    let mut components = Vec::new();
+    for root in graph.keys() {
+        if index.contains_key(root) {
+            continue;
+        }
+        // Each frame is a synset and how many of its links have been followed
+        let mut frames: Vec<(&SynsetId, usize)> = vec![(root, 0)];
+        while let Some((node, next)) = frames.pop() {
+            if next == 0 {
+                let n = index.len();
+                index.insert(node, n);
+                low.insert(node, n);
+                stack.push(node);
+                on_stack.insert(node);
+            }
+            let links = &graph[node];
+            if let Some((_, target)) = links.get(next) {
+                frames.push((node, next + 1));
+                if !index.contains_key(target) {
+                    frames.push((target, 0));
+                } else if on_stack.contains(target) {
+                    let lowest = low[node].min(index[target]);
+                    low.insert(node, lowest);
+                }
+                continue;
+            }
+            if let Some((parent, _)) = frames.last() {
+                let lowest = low[parent].min(low[node]);
+                low.insert(parent, lowest);
+            }
+            if low[node] == index[node] {
+                let mut component = Vec::new();
+                loop {
+                    let member = stack.pop().expect("The synset is on the stack");
+                    on_stack.remove(member);
+                    component.push(member.clone());
+                    if member == node {
+                        break;
+                    }
+                }
+                if component.len() > 1 || links.iter().any(|(_, t)| t == node) {
+                    component.sort();
+                    components.push(component);
+                }
+            }
+        }
+    }
+    components.sort();
+    components
+}
+
+/// A shortest path from `source` to `target` that does not use the direct link between them:
+/// the synsets in between
+fn other_path(graph: &Graph, source: &SynsetId, target: &SynsetId) -> Option<Vec<SynsetId>> {
+    let mut previous: HashMap<&SynsetId, &SynsetId> = HashMap::new();
+    let mut queue: VecDeque<&SynsetId> = VecDeque::new();
+    for (_, next) in graph[source].iter().filter(|(_, t)| t != target) {
+        if !previous.contains_key(next) {
+            previous.insert(next, source);
+            queue.push_back(next);
+        }
+    }
+    while let Some(node) = queue.pop_front() {
+        for (_, next) in graph[node].iter() {
+            if previous.contains_key(next) || next == source {
+                continue;
+            }
+            previous.insert(next, node);
+            if next == target {
+                let mut via = Vec::new();
+                let mut step = previous[next];
+                while step != source {
+                    via.push(step.clone());
+                    step = previous[step];
+                }
+                via.reverse();
+                return Some(via);
+            }
+            queue.push_back(next);
+        }
+    }
+    None
+}
+
+/// Whether the links of a cycle, less `removed`, still contain a cycle
+fn has_cycle(links: &[Link], removed: &[&Link]) -> bool {
+    let mut out: BTreeMap<&SynsetId, Vec<&SynsetId>> = BTreeMap::new();
+    let mut incoming: HashMap<&SynsetId, usize> = HashMap::new();
+    for link in links.iter().filter(|link| !removed.contains(link)) {
+        out.entry(&link.source).or_default().push(&link.target);
+        out.entry(&link.target).or_default();
+        *incoming.entry(&link.target).or_default() += 1;
+    }
+    // Kahn's algorithm: a cycle is whatever is never freed of incoming links
+    let mut free: Vec<&SynsetId> = out
+        .keys()
+        .filter(|node| !incoming.contains_key(*node))
+        .cloned()
+        .collect();
+    let mut seen = 0;
+    while let Some(node) = free.pop() {
+        seen += 1;
+        for target in out[node].iter() {
+            let n = incoming
+                .get_mut(target)
+                .expect("Each target has an incoming link");
+            *n -= 1;
+            if *n == 0 {
+                free.push(target);
+            }
+        }
+    }
+    seen < out.len()
+}
+
+/// The smallest sets of `links` (of a cycle) whose removal breaks it, trying the sets of each
+/// size in turn; or, if that takes too many tries, the links back up the path of a
+/// depth-first search, which break it but may not be the fewest
+fn cuts(links: &[Link]) -> Vec<Vec<Link>> {
+    let mut tried = 0;
+    for size in 1..=links.len() {
+        let mut found = Vec::new();
+        let mut chosen: Vec<usize> = (0..size).collect();
+        loop {
+            tried += 1;
+            if tried > MAX_CUT_CANDIDATES {
+                return vec![back_links(links)];
+            }
+            let removed: Vec<&Link> = chosen.iter().map(|i| &links[*i]).collect();
+            if !has_cycle(links, &removed) {
+                found.push(removed.into_iter().cloned().collect());
+                if found.len() == MAX_CUTS {
+                    return found;
+                }
+            }
+            // The next combination of `size` links, in lexicographic order
+            let mut i = size;
+            while i > 0 && chosen[i - 1] == links.len() - size + i - 1 {
+                i -= 1;
+            }
+            if i == 0 {
+                break;
+            }
+            chosen[i - 1] += 1;
+            for j in i..size {
+                chosen[j] = chosen[j - 1] + 1;
+            }
+        }
+        if !found.is_empty() {
+            return found;
+        }
+    }
+    Vec::new()
+}
+
+/// The links of a cycle that a depth-first search follows back to a synset still being
+/// searched from - removing them all leaves no cycle
+fn back_links(links: &[Link]) -> Vec<Link> {
+    let mut out: BTreeMap<&SynsetId, Vec<&Link>> = BTreeMap::new();
+    for link in links {
+        out.entry(&link.source).or_default().push(link);
+    }
+    let mut done: HashSet<&SynsetId> = HashSet::new();
+    let mut back = Vec::new();
+    for root in out.keys() {
+        if done.contains(root) {
+            continue;
+        }
+        let mut path: HashSet<&SynsetId> = HashSet::from([*root]);
+        let mut frames: Vec<(&SynsetId, usize)> = vec![(root, 0)];
+        while let Some((node, next)) = frames.pop() {
+            match out.get(node).and_then(|links| links.get(next)) {
+                Some(link) => {
+                    frames.push((node, next + 1));
+                    if path.contains(&link.target) {
+                        back.push((*link).clone());
+                    } else if !done.contains(&link.target) {
+                        path.insert(&link.target);
+                        frames.push((&link.target, 0));
+                    }
+                }
+                None => {
+                    path.remove(node);
+                    done.insert(node);
+                }
+            }
+        }
+    }
+    back
+}
+
+/// How many synsets removing `cut` would leave with no hypernym at all
+fn orphans(graph: &Graph, cut: &[Link]) -> usize {
+    let sources: BTreeSet<&SynsetId> = cut.iter().map(|link| &link.source).collect();
+    sources
+        .into_iter()
+        .filter(|source| {
+            graph[*source].iter().all(|(_, target)| {
+                cut.iter()
+                    .any(|link| link.source == **source && link.target == *target)
+            })
+        })
+        .count()
+}
+
+/// Finds the redundant links and the cycles of the hypernym graph of `wn`. Links out of a
+/// synset in a cycle are not checked for redundancy until the cycle is broken.
+pub fn check_hierarchy<L: Lexicon, Bar: Progress>(
+    wn: &L,
+    bar: &mut Bar,
+) -> Result<HierarchyReport> {
+    let graph = graph(wn, bar)?;
+    let link = |source: &SynsetId, relation: &str, target: &SynsetId| Link {
+        source: source.clone(),
+        relation: relation.to_string(),
+        target: target.clone(),
+    };
+
+    let mut cycles = Vec::new();
+    let mut in_cycle = HashSet::new();
+    for synsets in components(&graph) {
+        let mut links = Vec::new();
+        for source in synsets.iter() {
+            for (relation, target) in graph[source].iter() {
+                if synsets.contains(target) {
+                    links.push(link(source, relation, target));
+                }
+            }
+        }
+        let mut cuts = cuts(&links);
+        cuts.sort_by_key(|cut| orphans(&graph, cut));
+        in_cycle.extend(synsets.iter().cloned());
+        cycles.push(Cycle {
+            synsets,
+            links,
+            cuts,
+        });
+    }
+
+    let mut redundant = Vec::new();
+    for (source, targets) in graph.iter() {
+        if targets.len() < 2 || in_cycle.contains(source) {
+            continue;
+        }
+        for (relation, target) in targets.iter() {
+            if let Some(via) = other_path(&graph, source, target) {
+                redundant.push(RedundantLink {
+                    link: link(source, relation, target),
+                    via,
+                });
+            }
+        }
+    }
+    Ok(HierarchyReport { redundant, cycles })
+}
+
+/// An automaton batch removing every redundant link in `report`, and the first (preferred) set
+/// of links breaking each of its cycles - only those links, not any other relation between the
+/// same synsets
+pub fn repair_batch(report: &HierarchyReport) -> Vec<Action> {
+    let redundant = report.redundant.iter().map(|redundant| &redundant.link);
+    let cuts = report
+        .cycles
+        .iter()
+        .flat_map(|cycle| cycle.cuts.iter().take(1).flatten());
+    redundant
+        .chain(cuts)
+        .map(|link| Action::DeleteRelation {
+            source: SynsetRef::Id(link.source.clone()),
+            source_sense: None,
+            relation: Some(link.relation.clone()),
+            target: SynsetRef::Id(link.target.clone()),
+            target_sense: None,
+            source_lemma: None,
+            target_lemma: None,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::automaton::apply_automaton;
+    use crate::change_manager::{add_test_synset, ChangeList};
+    use crate::progress::NullProgress;
+    use crate::wordnet::LexiconHashMapBackend;
+
+    #[test]
+    fn test_check_hierarchy() {
+        let mut wn = LexiconHashMapBackend::new();
+        let mut changes = ChangeList::new();
+        let ids: Vec<SynsetId> = (0..7)
+            .map(|i| {
+                let id = format!("0000010{}-n", i);
+                let definition = format!("synset {}", id);
+                add_test_synset(
+                    &mut wn,
+                    Some(&id),
+                    "noun.object",
+                    &definition,
+                    &[],
+                    &mut changes,
+                )
+            })
+            .collect();
+        let hypernym = |wn: &mut LexiconHashMapBackend, source: usize, target: usize| {
+            wn.update_synset(&ids[source], |ss| ss.hypernym.push(ids[target].clone()))
+                .unwrap();
+        };
+        // 0 → 1 → 2 → 3, with 0 → 3 and 1 → 3 redundant
+        hypernym(&mut wn, 0, 1);
+        hypernym(&mut wn, 1, 2);
+        hypernym(&mut wn, 2, 3);
+        hypernym(&mut wn, 0, 3);
+        hypernym(&mut wn, 1, 3);
+        // 4 → 5 → 6 → 4, with 6 also under 3: breaking it at 6 → 4 orphans nothing
+        hypernym(&mut wn, 4, 5);
+        hypernym(&mut wn, 5, 6);
+        hypernym(&mut wn, 6, 4);
+        hypernym(&mut wn, 6, 3);
+        // A relation besides the redundant hypernym, which repairing must leave alone
+        wn.update_synset(&ids[0], |ss| ss.mero_part.push(ids[3].clone()))
+            .unwrap();
+
+        let report = check_hierarchy(&wn, &mut NullProgress).unwrap();
+        let redundant: Vec<(&SynsetId, &SynsetId)> = report
+            .redundant
+            .iter()
+            .map(|r| (&r.link.source, &r.link.target))
+            .collect();
+        assert_eq!(redundant, vec![(&ids[0], &ids[3]), (&ids[1], &ids[3])]);
+        assert_eq!(report.redundant[0].via, vec![ids[1].clone()]);
+        assert_eq!(report.redundant[1].via, vec![ids[2].clone()]);
+
+        assert_eq!(report.cycles.len(), 1);
+        let cycle = &report.cycles[0];
+        assert_eq!(
+            cycle.synsets,
+            vec![ids[4].clone(), ids[5].clone(), ids[6].clone()]
+        );
+        assert_eq!(cycle.links.len(), 3);
+        assert_eq!(cycle.cuts.len(), 3);
+        assert_eq!(cycle.cuts[0].len(), 1);
+        assert_eq!(cycle.cuts[0][0].source, ids[6]);
+
+        let batch = repair_batch(&report);
+        assert_eq!(batch.len(), 3);
+        apply_automaton(batch, &mut wn, &mut changes).unwrap();
+        let report = check_hierarchy(&wn, &mut NullProgress).unwrap();
+        assert_eq!(report, HierarchyReport::default());
+        let synset = wn.synset_by_id(&ids[0]).unwrap().unwrap();
+        assert_eq!(synset.hypernym, vec![ids[1].clone()]);
+        assert_eq!(synset.mero_part, vec![ids[3].clone()]);
+    }
+
+    #[test]
+    fn test_back_links() {
+        let link = |source: &str, target: &str| Link {
+            source: SynsetId::new(source),
+            relation: "hypernym".to_string(),
+            target: SynsetId::new(target),
+        };
+        let links = vec![
+            link("a", "b"),
+            link("b", "a"),
+            link("b", "c"),
+            link("c", "a"),
+        ];
+        let back = back_links(&links);
+        let removed: Vec<&Link> = back.iter().collect();
+        assert!(!has_cycle(&links, &removed));
+        assert!(has_cycle(&links, &[]));
+    }
+}
@@ -10,6 +10,13 @@ pub mod change_manager;
 pub mod wordnet;
 pub mod sense_keys;
 pub mod validate;
+pub mod validation_profile;
+pub mod user_rules;
+pub mod diagnostic;
+pub mod definitions;
+pub mod genus;
+pub mod hierarchy;
 pub mod automaton;
+pub mod undo;
 pub mod progress;
 pub mod source_mtime;
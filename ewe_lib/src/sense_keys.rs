@@ -135,25 +135,30 @@ fn get_head_word<L: Lexicon>(wn: &L, ss: &Synset) -> Result<(String, String)> {
     if srs.len() != 1 {
         Ok(("???".to_string(), "00".to_string()))
     } else {
-        let tss = srs.pop().unwrap();
-        match wn
-            .members_by_id(tss)?
-            .iter()
-            .flat_map(|m| sense_for_entry_synset_id(wn, tss, m).unwrap())
-            .next()
-        {
-            Some(s2) => {
-                let (entry_id, _) = s2
-                    .id
-                    .as_str()
-                    .split_at(s2.id.as_str().find("%").unwrap_or(0));
-                Ok((
-                    entry_id.to_string(),
-                    format!("{:02}", extract_lex_id(&s2.id)),
-                ))
-            }
-            None => Ok(("???".to_string(), "00".to_string())),
+        Ok(head_word(wn, srs.pop().unwrap())?.unwrap_or(("???".to_string(), "00".to_string())))
+    }
+}
+
+/// The lemma and lex id the sense keys of a satellite of `head` name it by, taken from the
+/// sense of its first member that has one - `None` if none has.
+pub(crate) fn head_word<L: Lexicon>(wn: &L, head: &SynsetId) -> Result<Option<(String, String)>> {
+    match wn
+        .members_by_id(head)?
+        .iter()
+        .flat_map(|m| sense_for_entry_synset_id(wn, head, m).unwrap())
+        .next()
+    {
+        Some(s2) => {
+            let (entry_id, _) = s2
+                .id
+                .as_str()
+                .split_at(s2.id.as_str().find("%").unwrap_or(0));
+            Ok(Some((
+                entry_id.to_string(),
+                format!("{:02}", extract_lex_id(&s2.id)),
+            )))
         }
+        None => Ok(None),
     }
 }
 
@@ -186,6 +191,28 @@ pub fn get_sense_key<L: Lexicon>(
     sense_key: Option<&SenseId>,
     synset: &Synset,
     synset_id: &SynsetId,
+) -> Result<SenseId> {
+    let (head_word, head_id) = if synset.part_of_speech == PartOfSpeech::s {
+        get_head_word(wn, synset)?
+    } else {
+        (String::new(), String::new())
+    };
+    get_sense_key_with_head(
+        wn, lemma, sense_key, synset, synset_id, &head_word, &head_id,
+    )
+}
+
+/// [`get_sense_key`], with the head word and its lex id (for a satellite, else empty) given -
+/// for a satellite whose head `get_sense_key` wouldn't find, as it only looks for it among
+/// Princeton synsets (see [`head_word`]).
+pub(crate) fn get_sense_key_with_head<L: Lexicon>(
+    wn: &L,
+    lemma: &str,
+    sense_key: Option<&SenseId>,
+    synset: &Synset,
+    synset_id: &SynsetId,
+    head_word: &str,
+    head_id: &str,
 ) -> Result<SenseId> {
     let ss_type = synset.part_of_speech.ss_type();
     let lex_filenum = wn
@@ -199,11 +226,6 @@ pub fn get_sense_key<L: Lexicon>(
     };
     // Escape literal underscores first so they can't collide with the "_" used below for spaces.
     let lemma = lemma.replace("_", "-lowbar-").replace(" ", "_").replace("&apos", "'").to_lowercase();
-    let (head_word, head_id) = if synset.part_of_speech == PartOfSpeech::s {
-        get_head_word(wn, synset)?
-    } else {
-        (String::new(), String::new())
-    };
     Ok(SenseId::new(format!(
         "{}%{}:{:02}:{:02}:{}:{}",
         lemma, ss_type, lex_filenum, lex_id, head_word, head_id
@@ -0,0 +1,1453 @@
+//! Undo and redo, built on the change log.
+//!
+//! Every batch [`apply_automaton`](crate::automaton::apply_automaton) applies is logged together
+//! with the batch that reverses it ([`ChangeLogEntry::undo`]), worked out action by action from
+//! the state each action found: a changed definition is reversed by changing it back, an added
+//! entry by deleting it, and anything that can't be put back that simply (a deleted synset, a
+//! rewritten set of relations) by a `restore_synset` of each synset it touched, as it stood
+//! beforehand. Undoing an entry applies that batch as a new one, so the undo is itself logged
+//! and can be undone in turn - which is all redo is.
+use crate::automaton::{
+    apply_batch, changelog_recent, resolve_sense, Action, ChangeLogEntry, Refs, SenseRef, SynsetRef,
+};
+use crate::change_manager::{self, ChangeList};
+use crate::progress::NullProgress;
+use crate::rels::{SenseRelType, SynsetRelType};
+use crate::validate::{fix_touches, validate, ValidationError};
+use crate::validation_profile::ValidationProfile;
+use crate::wordnet::{
+    Entry, Lexicon, PartOfSpeech, PosKey, Pronunciation, Result, Sense, SenseId, SenseOrSynsetId,
+    Synset, SynsetId, UnresolvedSenseOrSynsetId,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::ops::ControlFlow;
+
+/// How many change log entries [`undo_last`]/[`redo_last`] fetch at a time while looking for
+/// the one to reverse.
+const PAGE_SIZE: usize = 100;
+
+/// A synset as it stood when captured by [`snapshot`], with everything
+/// [`restore_synset`] needs to put it back.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SynsetSnapshot {
+    pub lexfile: String,
+    /// The synset itself, including the relations stored on it.
+    pub synset: Synset,
+    /// The senses of its members, including the relations stored on them.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub senses: Vec<MemberSense>,
+    /// Synset relations stored on other synsets that target this one.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub incoming: Vec<IncomingRelation>,
+    /// Sense relations stored on other synsets' senses that target one of this one's senses.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub incoming_sense: Vec<IncomingSenseRelation>,
+}
+
+/// One member's sense of a [`SynsetSnapshot`]. Carries the forms and pronunciations of the
+/// entry it belongs to, since removing an entry's last sense removes the entry too.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MemberSense {
+    pub lemma: String,
+    pub pos: PosKey,
+    pub sense: Sense,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub form: Vec<String>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub pronunciation: Vec<Pronunciation>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct IncomingRelation {
+    pub source: SynsetId,
+    pub relation: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct IncomingSenseRelation {
+    pub source: SenseId,
+    pub relation: String,
+    pub target: SenseId,
+}
+
+// Hand-written rather than derived: a snapshot embeds `Synset` and `Sense` as they're stored
+// in the YAML source, which don't implement `JsonSchema` - and nothing is expected to write one
+// by hand anyway, only to pass along what an undo produced.
+#[cfg(feature = "schema")]
+pub(crate) fn synset_snapshot_schema(_generator: &mut schemars::SchemaGenerator) -> schemars::Schema {
+    schemars::json_schema!({
+        "type": "object",
+        "description": "A synset as captured for undo: its lexicographer file, the synset and its members' senses as stored in the YAML source, and the relations other synsets/senses hold to it."
+    })
+}
+
+/// Captures `synset_id` as it currently stands, or `None` if there's no such synset.
+pub fn snapshot<L: Lexicon>(wn: &L, synset_id: &SynsetId) -> Result<Option<SynsetSnapshot>> {
+    snapshot_with(wn, synset_id, &[])
+}
+
+/// [`snapshot`], also capturing the senses in the synset of `unlisted` - lemmas that have one
+/// but are missing from its members, which restoring it would otherwise lose.
+fn snapshot_with<L: Lexicon>(
+    wn: &L,
+    synset_id: &SynsetId,
+    unlisted: &[String],
+) -> Result<Option<SynsetSnapshot>> {
+    let (lexfile, synset) = match (wn.lex_name_for(synset_id)?, wn.synset_by_id(synset_id)?) {
+        (Some(lexfile), Some(synset)) => (lexfile, synset.into_owned()),
+        _ => return Ok(None),
+    };
+
+    let mut senses = Vec::new();
+    for member in synset.members.iter().chain(unlisted.iter()) {
+        for (pos, entry) in wn.entry_by_lemma_with_pos(member)? {
+            for sense in entry.sense.iter().filter(|sense| sense.synset == *synset_id) {
+                senses.push(MemberSense {
+                    lemma: member.clone(),
+                    pos: pos.clone(),
+                    sense: sense.clone(),
+                    form: entry.form.clone(),
+                    pronunciation: entry.pronunciation.clone(),
+                });
+            }
+        }
+    }
+
+    // Relations between two of this synset's own senses are already part of `senses`.
+    let own_senses: HashSet<SenseId> = senses.iter().map(|s| s.sense.id.clone()).collect();
+    let mut incoming_sense = Vec::new();
+    for target in own_senses.iter() {
+        let target_id = UnresolvedSenseOrSynsetId::Sense(target.clone());
+        let mut sources = HashSet::new();
+        for (_, source) in wn.sense_links_to_get(target)?.iter().flat_map(|v| v.iter()) {
+            if own_senses.contains(source) || !sources.insert(source.clone()) {
+                continue;
+            }
+            for (rel, stored_target) in wn.sense_links_from_id(source)? {
+                if stored_target == target_id {
+                    incoming_sense.push(IncomingSenseRelation {
+                        source: source.clone(),
+                        relation: rel.value().to_string(),
+                        target: target.clone(),
+                    });
+                }
+            }
+        }
+    }
+
+    let mut incoming = Vec::new();
+    let mut sources = HashSet::new();
+    for (_, source) in wn.links_to(synset_id)? {
+        if source == *synset_id || !sources.insert(source.clone()) {
+            continue;
+        }
+        if let Some(source_synset) = wn.synset_by_id(&source)? {
+            for (rel, target) in source_synset.links_from() {
+                if target == *synset_id {
+                    incoming.push(IncomingRelation {
+                        source: source.clone(),
+                        relation: rel.value().to_string(),
+                    });
+                }
+            }
+        }
+    }
+
+    // `links_to` comes from a hash map on some backends - keep snapshots comparable.
+    incoming.sort_by(|a, b| (a.source.as_str(), &a.relation).cmp(&(b.source.as_str(), &b.relation)));
+    incoming_sense.sort_by(|a, b| {
+        (a.source.as_str(), &a.relation, a.target.as_str())
+            .cmp(&(b.source.as_str(), &b.relation, b.target.as_str()))
+    });
+
+    Ok(Some(SynsetSnapshot {
+        lexfile,
+        synset,
+        senses,
+        incoming,
+        incoming_sense,
+    }))
+}
+
+/// Puts `synset_id` back exactly as `snapshot` captured it. If the synset currently exists, it
+/// is cleared out first - every relation to or from it and its senses, and every member - so
+/// nothing added since the snapshot survives. Also removes any deprecation record for it, since
+/// a restored synset is no longer deprecated.
+pub fn restore_synset<L: Lexicon>(
+    wn: &mut L,
+    synset_id: &SynsetId,
+    snapshot: SynsetSnapshot,
+    change_list: &mut ChangeList,
+) -> Result<()> {
+    if wn.synset_by_id(synset_id)?.is_some() {
+        change_manager::update_rels(wn, synset_id, Vec::new(), change_list)?;
+        for member in wn.members_by_id(synset_id)? {
+            if let Some(pos) = wn.pos_for_entry_synset(&member, synset_id)? {
+                change_manager::delete_entry(wn, synset_id, &member, &pos, false, change_list)?;
+            }
+        }
+        wn.remove_synset(synset_id)?;
+    }
+
+    wn.insert_synset(snapshot.lexfile, synset_id.clone(), snapshot.synset)?;
+    for member in snapshot.senses {
+        let has_entry = wn
+            .entry_by_lemma_with_pos(&member.lemma)?
+            .iter()
+            .any(|(pos, _)| *pos == member.pos);
+        if !has_entry {
+            let mut entry = Entry::new();
+            entry.form = member.form;
+            entry.pronunciation = member.pronunciation;
+            wn.insert_entry(member.lemma.clone(), member.pos.clone(), entry)?;
+        }
+        wn.insert_sense(member.lemma, member.pos, member.sense)?;
+    }
+    for rel in snapshot.incoming {
+        if let Some(rel_type) = SynsetRelType::from(&rel.relation) {
+            if wn.synset_by_id(&rel.source)?.is_some() {
+                wn.add_rel(&rel.source, rel_type, synset_id)?;
+            }
+        }
+    }
+    for rel in snapshot.incoming_sense {
+        if let Some(rel_type) = SenseRelType::from(&rel.relation) {
+            if wn.has_sense(&rel.source)? {
+                wn.add_sense_rel(&rel.source, rel_type, &SenseOrSynsetId::Sense(rel.target))?;
+            }
+        }
+    }
+    wn.undeprecate(synset_id)?;
+    change_list.mark();
+    Ok(())
+}
+
+/// The actions that would reverse `action`, given the lexicon as it stands just before
+/// `action` is applied. `add_synset`, `split_synset` and `change_pos` are the exceptions: the
+/// id they create isn't known yet, so `apply_automaton` adds (that part of) their inverse
+/// itself once it is.
+pub(crate) fn inverse<L: Lexicon>(
+    wn: &L,
+    action: &Action,
+    refs: &Refs,
+) -> std::result::Result<Vec<Action>, String> {
+    let resolve = |synset: &SynsetRef| synset.clone().resolve(refs);
+    let inverse = match action {
+        Action::AddEntry { synset, lemma, .. } => {
+            let synset = resolve(synset)?;
+            if wn.members_by_id(&synset).map_err(|e| e.to_string())?.contains(lemma) {
+                Vec::new()
+            } else {
+                vec![Action::DeleteEntry {
+                    synset: SynsetRef::Id(synset),
+                    lemma: lemma.clone(),
+                }]
+            }
+        }
+        Action::DeleteEntry { synset, .. }
+        | Action::ChangeMembers { synset, .. }
+        | Action::UpdateRelations { synset, .. } => restore(wn, &[resolve(synset)?])?,
+        Action::MoveEntry {
+            synset,
+            target_synset,
+            ..
+        } => restore(wn, &[resolve(target_synset)?, resolve(synset)?])?,
+        // Every synset the entry has a sense in, and the satellites whose sense keys name it
+        // as their head word - the senses may not get their old lex ids back.
+        Action::RenameLemma { lemma, pos, .. } => {
+            let mut synsets = Vec::new();
+            for (p, entry) in wn.entry_by_lemma_with_pos(lemma).map_err(|e| e.to_string())? {
+                if p != *pos {
+                    continue;
+                }
+                for sense in entry.sense.iter() {
+                    let synset = wn.synset_by_id(&sense.synset).map_err(|e| e.to_string())?;
+                    if let Some(synset) = synset {
+                        synsets.push(sense.synset.clone());
+                        if synset.part_of_speech == PartOfSpeech::a {
+                            synsets.extend(synset.similar.iter().cloned());
+                        }
+                    }
+                }
+            }
+            restore(wn, &synsets)?
+        }
+        Action::AddForm { synset, lemma, form } | Action::DeleteForm { synset, lemma, form } => {
+            let synset = resolve(synset)?;
+            let present = match wn.pos_for_entry_synset(lemma, &synset).map_err(|e| e.to_string())? {
+                Some(pos) => wn.get_forms(lemma, &pos).map_err(|e| e.to_string())?.contains(form),
+                None => return Ok(Vec::new()),
+            };
+            match (action, present) {
+                (Action::AddForm { .. }, false) => vec![Action::DeleteForm {
+                    synset: SynsetRef::Id(synset),
+                    lemma: lemma.clone(),
+                    form: form.clone(),
+                }],
+                (Action::DeleteForm { .. }, true) => vec![Action::AddForm {
+                    synset: SynsetRef::Id(synset),
+                    lemma: lemma.clone(),
+                    form: form.clone(),
+                }],
+                _ => Vec::new(),
+            }
+        }
+        Action::AddPronunciation { synset, lemma, pronunciation, variety }
+        | Action::DeletePronunciation { synset, lemma, pronunciation, variety } => {
+            let synset = resolve(synset)?;
+            let present = match wn.pos_for_entry_synset(lemma, &synset).map_err(|e| e.to_string())? {
+                Some(pos) => wn
+                    .get_pronunciations(lemma, &pos)
+                    .map_err(|e| e.to_string())?
+                    .iter()
+                    .any(|p| p.value == *pronunciation && p.variety == *variety),
+                None => return Ok(Vec::new()),
+            };
+            match (action, present) {
+                (Action::AddPronunciation { .. }, false) => vec![Action::DeletePronunciation {
+                    synset: SynsetRef::Id(synset),
+                    lemma: lemma.clone(),
+                    pronunciation: pronunciation.clone(),
+                    variety: variety.clone(),
+                }],
+                (Action::DeletePronunciation { .. }, true) => vec![Action::AddPronunciation {
+                    synset: SynsetRef::Id(synset),
+                    lemma: lemma.clone(),
+                    pronunciation: pronunciation.clone(),
+                    variety: variety.clone(),
+                }],
+                _ => Vec::new(),
+            }
+        }
+        Action::ChangeAdjposition { synset, lemma, .. } => {
+            let synset = resolve(synset)?;
+            match wn.get_sense(lemma, &synset).map_err(|e| e.to_string())?.first() {
+                Some(sense) => vec![Action::ChangeAdjposition {
+                    synset: SynsetRef::Id(synset),
+                    lemma: lemma.clone(),
+                    adjposition: sense.adjposition.clone().unwrap_or_default(),
+                }],
+                None => Vec::new(),
+            }
+        }
+        Action::ChangeSubcat { synset, lemma, .. } => {
+            let synset = resolve(synset)?;
+            match wn.get_sense(lemma, &synset).map_err(|e| e.to_string())?.first() {
+                Some(sense) => vec![Action::ChangeSubcat {
+                    synset: SynsetRef::Id(synset),
+                    lemma: lemma.clone(),
+                    subcat: sense.subcat.clone(),
+                }],
+                None => Vec::new(),
+            }
+        }
+        // Not simply a move back: the senses may not get their old lex ids back.
+        Action::ChangeLexfile { synset, .. } => restore(wn, &[resolve(synset)?])?,
+        // `apply_automaton` adds the deletion of the new synset itself, as for `add_synset`.
+        Action::SplitSynset { synset, .. } | Action::ChangePos { synset, .. } => {
+            restore(wn, &[resolve(synset)?])?
+        }
+        Action::MergeSynsets { synset, merged, .. } => {
+            restore(wn, &[resolve(synset)?, resolve(merged)?])?
+        }
+        Action::AddSynset { .. } => Vec::new(),
+        Action::DeleteSynset {
+            synset,
+            superseded_by,
+            ..
+        } => {
+            let synset = resolve(synset)?;
+            match superseded_by {
+                Some(superseded_by) => {
+                    let superseded_by = resolve(superseded_by)?;
+                    let mut inverse = restore(wn, &[superseded_by, synset.clone()])?;
+                    inverse.extend(bridged_hypernyms(wn, &synset)?);
+                    inverse
+                }
+                None => restore(wn, &[synset])?,
+            }
+        }
+        Action::Definition { synset, .. } => {
+            let synset = resolve(synset)?;
+            match wn.synset_by_id(&synset).map_err(|e| e.to_string())? {
+                Some(ss) if ss.definition.len() == 1 => vec![Action::Definition {
+                    synset: SynsetRef::Id(synset.clone()),
+                    definition: ss.definition[0].clone(),
+                }],
+                Some(_) => restore(wn, &[synset])?,
+                None => Vec::new(),
+            }
+        }
+        Action::ChangeILI { synset, .. } => {
+            let synset = resolve(synset)?;
+            match wn.synset_by_id(&synset).map_err(|e| e.to_string())? {
+                Some(ss) => vec![Action::ChangeILI {
+                    synset: SynsetRef::Id(synset.clone()),
+                    ili: ss.ili.as_ref().map(|ili| ili.as_str().to_string()).unwrap_or_default(),
+                }],
+                None => Vec::new(),
+            }
+        }
+        Action::ChangeWikidata { synset, .. } => {
+            let synset = resolve(synset)?;
+            match wn.synset_by_id(&synset).map_err(|e| e.to_string())? {
+                Some(ss) => vec![Action::ChangeWikidata {
+                    synset: SynsetRef::Id(synset.clone()),
+                    wikidata: ss.wikidata.clone(),
+                }],
+                None => Vec::new(),
+            }
+        }
+        Action::ChangeSource { synset, .. } => {
+            let synset = resolve(synset)?;
+            match wn.synset_by_id(&synset).map_err(|e| e.to_string())? {
+                Some(ss) => vec![Action::ChangeSource {
+                    synset: SynsetRef::Id(synset.clone()),
+                    source: ss.source.clone().unwrap_or_default(),
+                }],
+                None => Vec::new(),
+            }
+        }
+        Action::AddExample { synset, .. } => {
+            let synset = resolve(synset)?;
+            match wn.synset_by_id(&synset).map_err(|e| e.to_string())? {
+                Some(ss) => vec![Action::DeleteExample {
+                    synset: SynsetRef::Id(synset.clone()),
+                    number: ss.example.len() + 1,
+                }],
+                None => Vec::new(),
+            }
+        }
+        Action::UpdateExample { synset, number, .. } => {
+            let synset = resolve(synset)?;
+            let previous = wn
+                .synset_by_id(&synset)
+                .map_err(|e| e.to_string())?
+                .and_then(|ss| ss.example.get(number.wrapping_sub(1)).cloned());
+            match previous {
+                Some(example) => vec![Action::UpdateExample {
+                    synset: SynsetRef::Id(synset),
+                    number: *number,
+                    example: example.text,
+                    source: example.source,
+                }],
+                None => Vec::new(),
+            }
+        }
+        Action::DeleteExample { synset, .. } => restore(wn, &[resolve(synset)?])?,
+        Action::AddRelation {
+            source,
+            source_sense,
+            relation,
+            target,
+            target_sense,
+            source_lemma,
+            target_lemma,
+        } => {
+            let source = resolve(source)?;
+            let source_sense =
+                resolve_sense(wn, source_sense, source_lemma, &source, refs)?;
+            let target = resolve(target)?;
+            let target_sense =
+                resolve_sense(wn, target_sense, target_lemma, &target, refs)?;
+            let existing = match &source_sense {
+                Some(source_sense) => {
+                    sense_relations_between(wn, &source, source_sense, &target, &target_sense)?
+                }
+                None => relations_between(wn, &source, &target)?,
+            };
+            // Adding a `similar` relation can also rename sense keys (see
+            // `change_manager::insert_rel`), which deleting the relation again wouldn't undo.
+            if existing.is_empty() && relation != SynsetRelType::Similar.value() {
+                vec![Action::DeleteRelation {
+                    source: SynsetRef::Id(source),
+                    source_sense: source_sense.map(SenseRef::Id),
+                    relation: None,
+                    target: SynsetRef::Id(target),
+                    target_sense: target_sense.map(SenseRef::Id),
+                    source_lemma: None,
+                    target_lemma: None,
+                }]
+            } else {
+                restore(wn, &[source, target])?
+            }
+        }
+        Action::DeleteRelation {
+            source,
+            source_sense,
+            relation,
+            target,
+            target_sense,
+            source_lemma,
+            target_lemma,
+        } => {
+            let source = resolve(source)?;
+            let source_sense =
+                resolve_sense(wn, source_sense, source_lemma, &source, refs)?;
+            let target = resolve(target)?;
+            let target_sense =
+                resolve_sense(wn, target_sense, target_lemma, &target, refs)?;
+            let mut existing = match &source_sense {
+                Some(source_sense) => {
+                    sense_relations_between(wn, &source, source_sense, &target, &target_sense)?
+                }
+                None => relations_between(wn, &source, &target)?,
+            };
+            // A delete of one relation leaves the others, and only needs that one back
+            if let Some(rel) = relation.as_deref().and_then(SynsetRelType::from) {
+                let inverse = rel.inverse();
+                existing.retain(|action| match action {
+                    Action::AddRelation { relation, .. } => {
+                        *relation == rel.value()
+                            || inverse.as_ref().is_some_and(|inverse| *relation == inverse.value())
+                    }
+                    _ => true,
+                });
+            }
+            existing
+        }
+        Action::ReverseRelation {
+            source,
+            source_sense,
+            target,
+            target_sense,
+        } => {
+            let source = resolve(source)?;
+            let target = resolve(target)?;
+            let source_sense = resolve_sense(wn, source_sense, &None, &source, refs)?;
+            let target_sense = resolve_sense(wn, target_sense, &None, &target, refs)?;
+            let existing = match &source_sense {
+                Some(source_sense) => {
+                    sense_relations_between(wn, &source, source_sense, &target, &target_sense)?
+                }
+                None => relations_between(wn, &source, &target)?,
+            };
+            let mut inverse = vec![Action::DeleteRelation {
+                source: SynsetRef::Id(source),
+                source_sense: source_sense.map(SenseRef::Id),
+                relation: None,
+                target: SynsetRef::Id(target),
+                target_sense: target_sense.map(SenseRef::Id),
+                source_lemma: None,
+                target_lemma: None,
+            }];
+            inverse.extend(existing);
+            inverse
+        }
+        Action::Validate
+        | Action::AssertSynsetExists { .. }
+        | Action::AssertDefinition { .. }
+        | Action::AssertMember { .. } => Vec::new(),
+        // `apply_automaton` strips guards before working out the inverse.
+        Action::Guarded { action, .. } => inverse(wn, action, refs)?,
+        Action::FixTransitivity => {
+            let mut inverse = Vec::new();
+            let mut seen = HashSet::new();
+            for (source, target) in
+                change_manager::indirect_relations(wn).map_err(|e| e.to_string())?
+            {
+                if seen.insert((source.clone(), target.clone())) {
+                    inverse.extend(relations_between(wn, &source, &target)?);
+                }
+            }
+            inverse
+        }
+        // Every synset one of the fixes will change. One that adds a missing member leaves
+        // that member's sense to be deleted with the rest by the restore, so its snapshot
+        // captures that sense too.
+        Action::FixValidationErrors => {
+            let mut synsets = Vec::new();
+            let mut unlisted: HashMap<SynsetId, Vec<String>> = HashMap::new();
+            for error in validate(wn, &mut NullProgress).map_err(|e| e.to_string())? {
+                synsets.extend(fix_touches(wn, &error).map_err(|e| e.to_string())?);
+                if let ValidationError::SenseNotInSynsetMembers { id, member } = error {
+                    unlisted.entry(id).or_default().push(member);
+                }
+            }
+            restore_with(wn, &synsets, &unlisted)?
+        }
+        Action::RestoreSynset { synset, .. } => {
+            let synset = resolve(synset)?;
+            match snapshot(wn, &synset).map_err(|e| e.to_string())? {
+                Some(snapshot) => vec![Action::RestoreSynset {
+                    synset: SynsetRef::Id(synset),
+                    snapshot: Box::new(snapshot),
+                }],
+                None => vec![redelete(wn, synset)?],
+            }
+        }
+    };
+    Ok(inverse)
+}
+
+/// A `restore_synset` for each of `synsets` that currently exists, in order (skipping repeats).
+fn restore<L: Lexicon>(wn: &L, synsets: &[SynsetId]) -> std::result::Result<Vec<Action>, String> {
+    restore_with(wn, synsets, &HashMap::new())
+}
+
+/// [`restore`], with the snapshot of each synset in `unlisted` also capturing the senses of
+/// the lemmas given for it (see [`snapshot_with`]).
+fn restore_with<L: Lexicon>(
+    wn: &L,
+    synsets: &[SynsetId],
+    unlisted: &HashMap<SynsetId, Vec<String>>,
+) -> std::result::Result<Vec<Action>, String> {
+    let mut actions = Vec::new();
+    let mut seen = HashSet::new();
+    for synset in synsets {
+        if !seen.insert(synset) {
+            continue;
+        }
+        let lemmas = unlisted.get(synset).map(|lemmas| lemmas.as_slice()).unwrap_or(&[]);
+        if let Some(snapshot) = snapshot_with(wn, synset, lemmas).map_err(|e| e.to_string())? {
+            actions.push(Action::RestoreSynset {
+                synset: SynsetRef::Id(synset.clone()),
+                snapshot: Box::new(snapshot),
+            });
+        }
+    }
+    Ok(actions)
+}
+
+/// The inverse of restoring a synset that doesn't currently exist: deleting it again, and if
+/// it was deleted in favour of another synset (i.e. has a deprecation record) doing so the same
+/// way, so the record comes back too.
+fn redelete<L: Lexicon>(wn: &L, synset: SynsetId) -> std::result::Result<Action, String> {
+    let id = format!("ewn-{}", synset.as_str());
+    let record = wn
+        .deprecations_get()
+        .map_err(|e| e.to_string())?
+        .iter()
+        .find(|record| record.0 == id)
+        .cloned();
+    Ok(match record {
+        Some(record) => Action::DeleteSynset {
+            synset: SynsetRef::Id(synset),
+            reason: record.4,
+            superseded_by: Some(SynsetRef::Id(SynsetId::new_owned(
+                record.2.trim_start_matches("ewn-").to_string(),
+            ))),
+        },
+        None => Action::DeleteSynset {
+            synset: SynsetRef::Id(synset),
+            reason: "Undo".to_string(),
+            superseded_by: None,
+        },
+    })
+}
+
+/// An `add_relation` for every synset relation currently stored between `a` and `b`, in
+/// either direction.
+fn relations_between<L: Lexicon>(
+    wn: &L,
+    a: &SynsetId,
+    b: &SynsetId,
+) -> std::result::Result<Vec<Action>, String> {
+    let mut actions = Vec::new();
+    for (source, target) in [(a, b), (b, a)] {
+        for (rel, stored_target) in wn.links_from(source).map_err(|e| e.to_string())? {
+            if stored_target == *target {
+                actions.push(add_relation(source, None, rel.value(), target, None));
+            }
+        }
+        if a == b {
+            break;
+        }
+    }
+    Ok(actions)
+}
+
+/// An `add_relation` for every sense relation currently stored between `source_sense` and
+/// `target_sense` (or `target` itself, if there's no target sense), in either direction.
+fn sense_relations_between<L: Lexicon>(
+    wn: &L,
+    source: &SynsetId,
+    source_sense: &SenseId,
+    target: &SynsetId,
+    target_sense: &Option<SenseId>,
+) -> std::result::Result<Vec<Action>, String> {
+    let mut actions = Vec::new();
+    let stored_target = match target_sense {
+        Some(target_sense) => UnresolvedSenseOrSynsetId::Sense(target_sense.clone()),
+        None => UnresolvedSenseOrSynsetId::Synset(target.clone()),
+    };
+    for (rel, t) in wn.sense_links_from_id(source_sense).map_err(|e| e.to_string())? {
+        if t == stored_target {
+            actions.push(add_relation(
+                source,
+                Some(source_sense),
+                rel.value(),
+                target,
+                target_sense.as_ref(),
+            ));
+        }
+    }
+    if let Some(target_sense) = target_sense {
+        let stored_source = UnresolvedSenseOrSynsetId::Sense(source_sense.clone());
+        for (rel, t) in wn.sense_links_from_id(target_sense).map_err(|e| e.to_string())? {
+            if t == stored_source {
+                actions.push(add_relation(
+                    target,
+                    Some(target_sense),
+                    rel.value(),
+                    source,
+                    Some(source_sense),
+                ));
+            }
+        }
+    }
+    Ok(actions)
+}
+
+fn add_relation(
+    source: &SynsetId,
+    source_sense: Option<&SenseId>,
+    relation: &str,
+    target: &SynsetId,
+    target_sense: Option<&SenseId>,
+) -> Action {
+    Action::AddRelation {
+        source: SynsetRef::Id(source.clone()),
+        source_sense: source_sense.map(|s| SenseRef::Id(s.clone())),
+        relation: relation.to_string(),
+        target: SynsetRef::Id(target.clone()),
+        target_sense: target_sense.map(|s| SenseRef::Id(s.clone())),
+        source_lemma: None,
+        target_lemma: None,
+    }
+}
+
+/// `delete_synset` with a `superseded_by` links each of the deleted synset's hyponyms straight
+/// to its hypernyms. A `delete_relation` for each such link that doesn't exist yet - the
+/// `restore_synset`s cover everything else it changes.
+fn bridged_hypernyms<L: Lexicon>(
+    wn: &L,
+    synset: &SynsetId,
+) -> std::result::Result<Vec<Action>, String> {
+    let hypernyms = match wn.synset_by_id(synset).map_err(|e| e.to_string())? {
+        Some(ss) => ss.hypernym.clone(),
+        None => return Ok(Vec::new()),
+    };
+    let mut actions = Vec::new();
+    for (_, source) in wn.links_to(synset).map_err(|e| e.to_string())? {
+        let is_hyponym = wn
+            .synset_by_id(&source)
+            .map_err(|e| e.to_string())?
+            .is_some_and(|ss| ss.hypernym.contains(synset));
+        if !is_hyponym {
+            continue;
+        }
+        for hypernym in hypernyms.iter() {
+            let action = Action::DeleteRelation {
+                source: SynsetRef::Id(source.clone()),
+                source_sense: None,
+                relation: None,
+                target: SynsetRef::Id(hypernym.clone()),
+                target_sense: None,
+                source_lemma: None,
+                target_lemma: None,
+            };
+            if relations_between(wn, &source, hypernym)?.is_empty() && !actions.contains(&action) {
+                actions.push(action);
+            }
+        }
+    }
+    Ok(actions)
+}
+
+/// Undoes change log entry `id`, by applying the batch logged with it as a new batch (recorded
+/// as undoing `id`). Fails if the entry has nothing to undo, or has already been undone - or if
+/// a newer change still in effect touched any of the same synsets or senses, as the undo batch
+/// puts those back as they were before `id` and so would silently revert that change too.
+pub fn undo<L: Lexicon>(
+    wn: &mut L,
+    id: u64,
+    changes: &mut ChangeList,
+) -> std::result::Result<(), String> {
+    let entry = changelog_entry(wn, id)?;
+    if !entry.is_undoable() {
+        return Err(format!("Change {} has nothing to undo", id));
+    }
+    // `walk_log` skips entries that have been undone, so this one is still in effect only if
+    // the walk actually reaches it.
+    let in_effect = walk_log(wn, |entry_id, _| {
+        if entry_id <= id {
+            ControlFlow::Break(entry_id == id)
+        } else {
+            ControlFlow::Continue(())
+        }
+    })?;
+    if in_effect != Some(true) {
+        return Err(format!("Change {} has already been undone", id));
+    }
+    let (synsets, senses) = entry.touched();
+    let synsets: HashSet<SynsetId> = synsets.into_iter().collect();
+    let senses: HashSet<SenseId> = senses.into_iter().collect();
+    // An undo of an entry newer than this one just cancels that entry out (which the walk then
+    // skips), so doesn't count as a change of its own.
+    let newer = walk_log(wn, |entry_id, newer| {
+        if entry_id <= id {
+            ControlFlow::Break(None)
+        } else if newer.undo_of.is_some_and(|target| target > id) {
+            ControlFlow::Continue(())
+        } else {
+            let (newer_synsets, newer_senses) = newer.touched();
+            if newer_synsets.iter().any(|synset| synsets.contains(synset))
+                || newer_senses.iter().any(|sense| senses.contains(sense))
+            {
+                ControlFlow::Break(Some(entry_id))
+            } else {
+                ControlFlow::Continue(())
+            }
+        }
+    })?;
+    if let Some(Some(newer)) = newer {
+        return Err(format!(
+            "Change {} can't be undone while change {} is in effect: it touched the same \
+             synsets or senses, and undoing {} would revert it too",
+            id, newer, id
+        ));
+    }
+    apply_batch(entry.undo, wn, changes, &ValidationProfile::default(), Some(id), None)?;
+    Ok(())
+}
+
+/// Undoes the most recent change that hasn't already been undone, returning its id - `None`
+/// if there's nothing left to undo.
+pub fn undo_last<L: Lexicon>(
+    wn: &mut L,
+    changes: &mut ChangeList,
+) -> std::result::Result<Option<u64>, String> {
+    let last = walk_log(wn, |id, entry| {
+        if entry.undo_of.is_none() && entry.is_undoable() {
+            ControlFlow::Break(id)
+        } else {
+            ControlFlow::Continue(())
+        }
+    })?;
+    match last {
+        Some(id) => {
+            undo(wn, id, changes)?;
+            Ok(Some(id))
+        }
+        None => Ok(None),
+    }
+}
+
+/// Reverses the most recent undo, as long as no other change has been made since, returning
+/// the id of the change it re-applied - `None` if there's nothing to redo.
+pub fn redo_last<L: Lexicon>(
+    wn: &mut L,
+    changes: &mut ChangeList,
+) -> std::result::Result<Option<u64>, String> {
+    let last = walk_log(wn, |id, entry| {
+        if entry.undo_of.is_some() {
+            ControlFlow::Break(Some((id, entry.clone())))
+        } else if entry.redo_of.is_none() && entry.is_undoable() {
+            ControlFlow::Break(None)
+        } else {
+            ControlFlow::Continue(())
+        }
+    })?;
+    match last.flatten() {
+        Some((id, entry)) => {
+            apply_batch(entry.undo, wn, changes, &ValidationProfile::default(), None, Some(id))?;
+            Ok(entry.undo_of)
+        }
+        None => Ok(None),
+    }
+}
+
+/// The change log entry stored under `id`.
+fn changelog_entry<L: Lexicon>(wn: &L, id: u64) -> std::result::Result<ChangeLogEntry, String> {
+    changelog_recent(wn, 1, Some(id + 1))?
+        .into_iter()
+        .find(|(found, _)| *found == id)
+        .map(|(_, entry)| entry)
+        .ok_or(format!("No change {} in the change log", id))
+}
+
+/// Walks the change log newest first, calling `f` on each entry that is still in effect -
+/// i.e. hasn't been undone by a newer entry that is itself still in effect - until it breaks.
+fn walk_log<L: Lexicon, T>(
+    wn: &L,
+    mut f: impl FnMut(u64, &ChangeLogEntry) -> ControlFlow<T>,
+) -> std::result::Result<Option<T>, String> {
+    let mut undone = HashSet::new();
+    let mut before = None;
+    loop {
+        let page = changelog_recent(wn, PAGE_SIZE, before)?;
+        for (id, entry) in page.iter() {
+            if undone.contains(id) {
+                continue;
+            }
+            if let ControlFlow::Break(result) = f(*id, entry) {
+                return Ok(Some(result));
+            }
+            if let Some(target) = entry.undo_of.or(entry.redo_of) {
+                undone.insert(target);
+            }
+        }
+        match page.last() {
+            Some((id, _)) if page.len() == PAGE_SIZE => before = Some(*id),
+            _ => return Ok(None),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::automaton::{apply_automaton, apply_automaton_with_profile, changelog_recent};
+    use crate::validation_profile::Severity;
+    use crate::wordnet::LexiconHashMapBackend;
+
+    fn noun() -> PosKey {
+        PosKey::new("n".to_string())
+    }
+
+    fn add_synset(lexicon: &mut LexiconHashMapBackend, definition: &str, lemma: &str) -> SynsetId {
+        let actions = vec![Action::AddSynset {
+            definition: definition.to_string(),
+            lexfile: "noun.animal".to_string(),
+            pos: Some(noun()),
+            lemmas: vec![lemma.to_string()],
+            subcats: Vec::new(),
+            name: None,
+        }];
+        apply_automaton(actions, lexicon, &mut ChangeList::new())
+            .unwrap()
+            .0
+            .unwrap()
+    }
+
+    fn hypernym(source: &SynsetId, target: &SynsetId) -> Action {
+        add_relation(source, None, "hypernym", target, None)
+    }
+
+    #[test]
+    fn test_undo_entry_and_sense_attribute_actions() {
+        let mut lexicon = LexiconHashMapBackend::new();
+        lexicon.add_lexfile("noun.animal").unwrap();
+        let mut changes = ChangeList::new();
+        let ssid = add_synset(&mut lexicon, "a domestic feline", "cat");
+        lexicon
+            .add_pronunciation("cat", &noun(), Pronunciation { value: "kat".to_string(), variety: None })
+            .unwrap();
+        let synset = || SynsetRef::Id(ssid.clone());
+        let actions = vec![
+            Action::AddForm { synset: synset(), lemma: "cat".to_string(), form: "cats".to_string() },
+            Action::DeletePronunciation {
+                synset: synset(),
+                lemma: "cat".to_string(),
+                pronunciation: "kat".to_string(),
+                variety: None,
+            },
+            Action::ChangeAdjposition { synset: synset(), lemma: "cat".to_string(), adjposition: "a".to_string() },
+            Action::ChangeSubcat { synset: synset(), lemma: "cat".to_string(), subcat: vec!["via".to_string()] },
+        ];
+        let before = snapshot(&lexicon, &ssid).unwrap();
+        apply_automaton(actions, &mut lexicon, &mut changes).unwrap();
+        assert_ne!(snapshot(&lexicon, &ssid).unwrap(), before);
+
+        undo_last(&mut lexicon, &mut changes).unwrap();
+        assert_eq!(snapshot(&lexicon, &ssid).unwrap(), before);
+    }
+
+    #[test]
+    fn test_undo_fix_validation_errors() {
+        let mut lexicon = LexiconHashMapBackend::new();
+        lexicon.add_lexfile("noun.animal").unwrap();
+        let mut changes = ChangeList::new();
+        let cat = add_synset(&mut lexicon, "a domestic feline", "cat");
+        let dog = add_synset(&mut lexicon, "a domestic canine", "dog");
+        // A sense whose lemma is missing from its synset's members, a member with no sense, an
+        // invalid Wikidata id and a repeated subcat frame - all fixable
+        lexicon.insert_entry("feline".to_string(), noun(), Entry::new()).unwrap();
+        let feline = SenseId::new("feline%1:05:00::");
+        lexicon
+            .insert_sense("feline".to_string(), noun(), Sense::new(feline.clone(), cat.clone()))
+            .unwrap();
+        lexicon
+            .update_synset(&dog, |synset| {
+                synset.members.push("hound".to_string());
+                synset.wikidata.push("not a qid".to_string());
+            })
+            .unwrap();
+        let cat_sense = lexicon.get_sense_id2("cat", &cat).unwrap().unwrap();
+        lexicon
+            .update_sense(&cat_sense, |sense| {
+                sense.subcat = vec!["via".to_string(), "via".to_string()]
+            })
+            .unwrap();
+        let unlisted = ["feline".to_string()];
+        let state = |lexicon: &LexiconHashMapBackend| {
+            (
+                snapshot_with(lexicon, &cat, &unlisted).unwrap(),
+                snapshot(lexicon, &dog).unwrap(),
+            )
+        };
+        let before = state(&lexicon);
+
+        let (_, report) =
+            apply_automaton(vec![Action::FixValidationErrors], &mut lexicon, &mut changes).unwrap();
+        let report = report.expect("fix_validation_errors should report what is left");
+        assert!(report.fixed >= 4, "fixed {}", report.fixed);
+        assert!(!report.errors.iter().any(|e| matches!(
+            e,
+            ValidationError::SenseNotInSynsetMembers { .. }
+                | ValidationError::SynsetMemberNotInEntries { .. }
+                | ValidationError::InvalidWikidataId { .. }
+                | ValidationError::DuplicateSyntacticBehaviour { .. }
+        )));
+        assert!(lexicon.members_by_id(&cat).unwrap().contains(&"feline".to_string()));
+
+        undo_last(&mut lexicon, &mut changes).unwrap();
+        assert_eq!(state(&lexicon), before);
+        assert!(!lexicon.members_by_id(&cat).unwrap().contains(&"feline".to_string()));
+    }
+
+    #[test]
+    fn test_fix_validation_errors_skips_checks_switched_off() {
+        let mut lexicon = LexiconHashMapBackend::new();
+        lexicon.add_lexfile("noun.animal").unwrap();
+        let mut changes = ChangeList::new();
+        let cat = add_synset(&mut lexicon, "a domestic feline", "cat");
+        lexicon
+            .update_synset(&cat, |synset| {
+                synset.members.push("moggy".to_string());
+                synset.wikidata.push("not a qid".to_string());
+            })
+            .unwrap();
+        let profile = ValidationProfile(
+            [("InvalidWikidataId".to_string(), Severity::Off)].into_iter().collect(),
+        );
+
+        let (_, report) = apply_automaton_with_profile(
+            vec![Action::FixValidationErrors],
+            &mut lexicon,
+            &mut changes,
+            &profile,
+        )
+        .unwrap();
+        let report = report.expect("fix_validation_errors should report what is left");
+        assert_eq!(report.fixed, 1);
+        let synset = lexicon.synset_by_id(&cat).unwrap().unwrap();
+        assert!(!synset.members.contains(&"moggy".to_string()));
+        assert_eq!(synset.wikidata, vec!["not a qid".to_string()]);
+    }
+
+    #[test]
+    fn test_undo_and_redo_add_synset() {
+        let mut lexicon = LexiconHashMapBackend::new();
+        lexicon.add_lexfile("noun.animal").unwrap();
+        let mut changes = ChangeList::new();
+        let ssid = add_synset(&mut lexicon, "a small cat", "kitten");
+
+        assert_eq!(undo_last(&mut lexicon, &mut changes).unwrap(), Some(0));
+        assert!(lexicon.synset_by_id(&ssid).unwrap().is_none());
+        assert!(lexicon.entry_by_lemma("kitten").unwrap().is_empty());
+        assert_eq!(undo_last(&mut lexicon, &mut changes).unwrap(), None,
+            "an undone change shouldn't be offered for undo again");
+        assert!(undo(&mut lexicon, 0, &mut changes).is_err());
+
+        assert_eq!(redo_last(&mut lexicon, &mut changes).unwrap(), Some(0));
+        let synset = lexicon.synset_by_id(&ssid).unwrap().expect("redo should bring the synset back");
+        assert_eq!(synset.definition[0], "a small cat");
+        assert_eq!(lexicon.entry_by_lemma("kitten").unwrap().len(), 1);
+        assert_eq!(redo_last(&mut lexicon, &mut changes).unwrap(), None);
+
+        // The redo can itself be undone.
+        assert!(undo_last(&mut lexicon, &mut changes).unwrap().is_some());
+        assert!(lexicon.synset_by_id(&ssid).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_redo_unavailable_after_new_change() {
+        let mut lexicon = LexiconHashMapBackend::new();
+        lexicon.add_lexfile("noun.animal").unwrap();
+        let mut changes = ChangeList::new();
+        add_synset(&mut lexicon, "a small cat", "kitten");
+        undo_last(&mut lexicon, &mut changes).unwrap();
+        add_synset(&mut lexicon, "a small dog", "puppy");
+        assert_eq!(redo_last(&mut lexicon, &mut changes).unwrap(), None);
+    }
+
+    #[test]
+    fn test_undo_definition_and_example() {
+        let mut lexicon = LexiconHashMapBackend::new();
+        lexicon.add_lexfile("noun.animal").unwrap();
+        let mut changes = ChangeList::new();
+        let ssid = add_synset(&mut lexicon, "a small cat", "kitten");
+        let actions = vec![
+            Action::Definition {
+                synset: SynsetRef::Id(ssid.clone()),
+                definition: "a young cat".to_string(),
+            },
+            Action::AddExample {
+                synset: SynsetRef::Id(ssid.clone()),
+                example: "the kitten purred".to_string(),
+                source: None,
+            },
+            Action::ChangeSource {
+                synset: SynsetRef::Id(ssid.clone()),
+                source: "Collins".to_string(),
+            },
+        ];
+        apply_automaton(actions, &mut lexicon, &mut changes).unwrap();
+
+        let (id, entry) = changelog_recent(&lexicon, 1, None).unwrap().remove(0);
+        assert_eq!(entry.undo.len(), 3, "the logged undo batch should survive the YAML round trip");
+        undo(&mut lexicon, id, &mut changes).unwrap();
+
+        let synset = lexicon.synset_by_id(&ssid).unwrap().unwrap();
+        assert_eq!(synset.definition, vec!["a small cat".to_string()]);
+        assert!(synset.example.is_empty());
+        assert!(synset.source.is_none());
+    }
+
+    #[test]
+    fn test_undo_refused_under_newer_change() {
+        let mut lexicon = LexiconHashMapBackend::new();
+        lexicon.add_lexfile("noun.animal").unwrap();
+        let mut changes = ChangeList::new();
+        let kitten = add_synset(&mut lexicon, "a small cat", "kitten");
+        let puppy = add_synset(&mut lexicon, "a small dog", "puppy");
+        let define = |synset: &SynsetId, definition: &str| {
+            vec![Action::Definition {
+                synset: SynsetRef::Id(synset.clone()),
+                definition: definition.to_string(),
+            }]
+        };
+        let definition = |lexicon: &LexiconHashMapBackend| {
+            lexicon.synset_by_id(&kitten).unwrap().unwrap().definition[0].clone()
+        };
+        apply_automaton(define(&kitten, "a young cat"), &mut lexicon, &mut changes).unwrap();
+        let (first, _) = changelog_recent(&lexicon, 1, None).unwrap().remove(0);
+        apply_automaton(define(&kitten, "a baby cat"), &mut lexicon, &mut changes).unwrap();
+        let (second, _) = changelog_recent(&lexicon, 1, None).unwrap().remove(0);
+        apply_automaton(define(&puppy, "a young dog"), &mut lexicon, &mut changes).unwrap();
+
+        let err = undo(&mut lexicon, first, &mut changes).unwrap_err();
+        assert!(err.contains(&second.to_string()), "{}", err);
+        assert_eq!(definition(&lexicon), "a baby cat",
+            "a refused undo shouldn't revert the newer change");
+
+        // A newer change to another synset doesn't stand in the way, and once the change to
+        // the same synset is undone the older one can be too.
+        undo(&mut lexicon, second, &mut changes).unwrap();
+        assert_eq!(definition(&lexicon), "a young cat");
+        undo(&mut lexicon, first, &mut changes).unwrap();
+        assert_eq!(definition(&lexicon), "a small cat");
+    }
+
+    #[test]
+    fn test_undo_delete_entry_restores_forms() {
+        let mut lexicon = LexiconHashMapBackend::new();
+        lexicon.add_lexfile("noun.animal").unwrap();
+        let mut changes = ChangeList::new();
+        let ssid = add_synset(&mut lexicon, "a small cat", "kitten");
+        lexicon.add_form("kitten", &noun(), "kittens".to_string()).unwrap();
+        let sense = lexicon.entry_by_lemma("kitten").unwrap()[0].sense[0].clone();
+
+        let actions = vec![Action::DeleteEntry {
+            synset: SynsetRef::Id(ssid.clone()),
+            lemma: "kitten".to_string(),
+        }];
+        apply_automaton(actions, &mut lexicon, &mut changes).unwrap();
+        assert!(lexicon.entry_by_lemma("kitten").unwrap().is_empty());
+
+        undo_last(&mut lexicon, &mut changes).unwrap();
+        let entries = lexicon.entry_by_lemma("kitten").unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].form, vec!["kittens".to_string()]);
+        assert_eq!(entries[0].sense, vec![sense]);
+        assert_eq!(lexicon.members_by_id(&ssid).unwrap(), vec!["kitten".to_string()]);
+    }
+
+    #[test]
+    fn test_undo_superseding_delete_synset() {
+        let mut lexicon = LexiconHashMapBackend::new();
+        lexicon.add_lexfile("noun.animal").unwrap();
+        let mut changes = ChangeList::new();
+        let animal = add_synset(&mut lexicon, "a living organism", "animal");
+        let cat = add_synset(&mut lexicon, "a feline mammal", "cat");
+        let feline = add_synset(&mut lexicon, "a cat", "feline");
+        let kitten = add_synset(&mut lexicon, "a young cat", "kitten");
+        apply_automaton(
+            vec![hypernym(&cat, &animal), hypernym(&feline, &animal), hypernym(&kitten, &cat)],
+            &mut lexicon,
+            &mut changes,
+        )
+        .unwrap();
+        let before: Vec<_> = [&animal, &cat, &feline, &kitten]
+            .into_iter()
+            .map(|id| snapshot(&lexicon, id).unwrap().unwrap())
+            .collect();
+
+        let actions = vec![Action::DeleteSynset {
+            synset: SynsetRef::Id(cat.clone()),
+            reason: "duplicate".to_string(),
+            superseded_by: Some(SynsetRef::Id(feline.clone())),
+        }];
+        apply_automaton(actions, &mut lexicon, &mut changes).unwrap();
+        assert!(lexicon.synset_by_id(&cat).unwrap().is_none());
+        assert_eq!(lexicon.deprecations_get().unwrap().len(), 1);
+
+        undo_last(&mut lexicon, &mut changes).unwrap();
+        let after: Vec<_> = [&animal, &cat, &feline, &kitten]
+            .into_iter()
+            .map(|id| snapshot(&lexicon, id).unwrap().unwrap())
+            .collect();
+        assert_eq!(before, after);
+        assert!(lexicon.deprecations_get().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_undo_merge_synsets() {
+        let mut lexicon = LexiconHashMapBackend::new();
+        lexicon.add_lexfile("noun.animal").unwrap();
+        let mut changes = ChangeList::new();
+        let animal = add_synset(&mut lexicon, "a living organism", "animal");
+        let cat = add_synset(&mut lexicon, "a feline mammal", "cat");
+        let feline = add_synset(&mut lexicon, "a cat", "cat");
+        let kitten = add_synset(&mut lexicon, "a young cat", "kitten");
+        apply_automaton(
+            vec![
+                hypernym(&cat, &animal),
+                hypernym(&feline, &animal),
+                hypernym(&kitten, &feline),
+                Action::AddEntry {
+                    synset: SynsetRef::Id(feline.clone()),
+                    lemma: "feline".to_string(),
+                    pos: noun(),
+                    subcat: Vec::new(),
+                    name: None,
+                },
+            ],
+            &mut lexicon,
+            &mut changes,
+        )
+        .unwrap();
+        let before: Vec<_> = [&animal, &cat, &feline, &kitten]
+            .into_iter()
+            .map(|id| snapshot(&lexicon, id).unwrap().unwrap())
+            .collect();
+
+        let actions = vec![Action::MergeSynsets {
+            synset: SynsetRef::Id(cat.clone()),
+            merged: SynsetRef::Id(feline.clone()),
+            definition: None,
+            reason: "duplicate".to_string(),
+        }];
+        apply_automaton(actions, &mut lexicon, &mut changes).unwrap();
+        assert!(lexicon.synset_by_id(&feline).unwrap().is_none());
+
+        undo_last(&mut lexicon, &mut changes).unwrap();
+        let after: Vec<_> = [&animal, &cat, &feline, &kitten]
+            .into_iter()
+            .map(|id| snapshot(&lexicon, id).unwrap().unwrap())
+            .collect();
+        assert_eq!(before, after);
+        assert!(lexicon.deprecations_get().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_undo_split_synset() {
+        let mut lexicon = LexiconHashMapBackend::new();
+        lexicon.add_lexfile("noun.animal").unwrap();
+        let mut changes = ChangeList::new();
+        let animal = add_synset(&mut lexicon, "a living organism", "animal");
+        let bat = add_synset(&mut lexicon, "a flying mammal or a club", "bat");
+        let vampire = add_synset(&mut lexicon, "a blood-drinking bat", "vampire bat");
+        apply_automaton(
+            vec![
+                hypernym(&bat, &animal),
+                hypernym(&vampire, &bat),
+                Action::AddEntry {
+                    synset: SynsetRef::Id(bat.clone()),
+                    lemma: "chiropteran".to_string(),
+                    pos: noun(),
+                    subcat: Vec::new(),
+                    name: None,
+                },
+            ],
+            &mut lexicon,
+            &mut changes,
+        )
+        .unwrap();
+        let before: Vec<_> = [&animal, &bat, &vampire]
+            .into_iter()
+            .map(|id| snapshot(&lexicon, id).unwrap().unwrap())
+            .collect();
+
+        let script = format!(
+            "- split_synset:\n    synset: {}\n    members: [chiropteran]\n    definition: a flying mammal\n    relations:\n    - relation: hypernym\n      target: {}\n      mode: copy\n    - relation: hyponym\n      target: {}\n      mode: move\n    as: $mammal\n- add_example:\n    synset: $mammal\n    example: bats hang upside down\n",
+            bat.as_str(),
+            animal.as_str(),
+            vampire.as_str()
+        );
+        let actions: Vec<Action> = serde_yaml::from_str::<Vec<crate::automaton::ActionWrapper>>(&script)
+            .unwrap()
+            .into_iter()
+            .map(|a| a.0)
+            .collect();
+        let (new_id, _) = apply_automaton(actions, &mut lexicon, &mut changes).unwrap();
+        let new_id = new_id.unwrap();
+        assert_eq!(lexicon.synset_by_id(&new_id).unwrap().unwrap().example.len(), 1);
+        assert_eq!(lexicon.synset_by_id(&vampire).unwrap().unwrap().hypernym, vec![new_id.clone()]);
+
+        undo_last(&mut lexicon, &mut changes).unwrap();
+        assert!(lexicon.synset_by_id(&new_id).unwrap().is_none());
+        let after: Vec<_> = [&animal, &bat, &vampire]
+            .into_iter()
+            .map(|id| snapshot(&lexicon, id).unwrap().unwrap())
+            .collect();
+        assert_eq!(before, after);
+    }
+
+    #[test]
+    fn test_undo_change_lexfile() {
+        let mut lexicon = LexiconHashMapBackend::new();
+        lexicon.add_lexfile("noun.animal").unwrap();
+        lexicon.add_lexfile("noun.artifact").unwrap();
+        let mut changes = ChangeList::new();
+        let animal = add_synset(&mut lexicon, "a living organism", "animal");
+        let bat = add_synset(&mut lexicon, "a club used in sports", "bat");
+        apply_automaton(vec![hypernym(&bat, &animal)], &mut lexicon, &mut changes).unwrap();
+        let before = snapshot(&lexicon, &bat).unwrap();
+
+        let actions = vec![Action::ChangeLexfile {
+            synset: SynsetRef::Id(bat.clone()),
+            lexfile: "noun.artifact".to_string(),
+        }];
+        apply_automaton(actions, &mut lexicon, &mut changes).unwrap();
+        assert_eq!(lexicon.get_sense_id2("bat", &bat).unwrap().unwrap().as_str(), "bat%1:06:00::");
+
+        undo_last(&mut lexicon, &mut changes).unwrap();
+        assert_eq!(snapshot(&lexicon, &bat).unwrap(), before);
+        assert_eq!(lexicon.lex_name_for(&bat).unwrap(), Some("noun.animal".to_string()));
+    }
+
+    #[test]
+    fn test_undo_rename_lemma() {
+        let mut lexicon = LexiconHashMapBackend::new();
+        lexicon.add_lexfile("noun.animal").unwrap();
+        let mut changes = ChangeList::new();
+        let cat = add_synset(&mut lexicon, "a feline mammal", "cat");
+        let kitten = add_synset(&mut lexicon, "a young cat", "kitten");
+        let cat_sense = lexicon.get_sense_id2("cat", &cat).unwrap().unwrap();
+        let kitten_sense = lexicon.get_sense_id2("kitten", &kitten).unwrap().unwrap();
+        lexicon
+            .add_sense_rel(&kitten_sense, SenseRelType::Also, &SenseOrSynsetId::Sense(cat_sense))
+            .unwrap();
+        let before = snapshot(&lexicon, &cat).unwrap();
+
+        let actions = vec![Action::RenameLemma {
+            lemma: "cat".to_string(),
+            pos: noun(),
+            new_lemma: "Cat".to_string(),
+        }];
+        apply_automaton(actions, &mut lexicon, &mut changes).unwrap();
+        assert_eq!(lexicon.members_by_id(&cat).unwrap(), vec!["Cat"]);
+
+        undo_last(&mut lexicon, &mut changes).unwrap();
+        assert_eq!(snapshot(&lexicon, &cat).unwrap(), before);
+        assert!(lexicon.entry_by_lemma("Cat").unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_undo_change_pos() {
+        let mut lexicon = LexiconHashMapBackend::new();
+        lexicon.add_lexfile("noun.animal").unwrap();
+        lexicon.add_lexfile("verb.motion").unwrap();
+        let mut changes = ChangeList::new();
+        let animal = add_synset(&mut lexicon, "a living organism", "animal");
+        let bat = add_synset(&mut lexicon, "to strike with a club", "bat");
+        apply_automaton(vec![hypernym(&bat, &animal)], &mut lexicon, &mut changes).unwrap();
+        let before = snapshot(&lexicon, &bat).unwrap();
+
+        let actions = vec![Action::ChangePos {
+            synset: SynsetRef::Id(bat.clone()),
+            pos: PosKey::new("v"),
+            lexfile: Some("verb.motion".to_string()),
+        }];
+        let (new_id, report) = apply_automaton(actions, &mut lexicon, &mut changes).unwrap();
+        let new_id = new_id.unwrap();
+        assert!(new_id.as_str().ends_with("-v"));
+        assert_eq!(lexicon.get_sense_id2("bat", &new_id).unwrap().unwrap().as_str(), "bat%2:38:00::");
+        // The hypernym to a noun is kept, but reported
+        assert!(report.unwrap().errors.iter().any(|e| matches!(e, ValidationError::CrossPOSHyper { .. })));
+
+        undo_last(&mut lexicon, &mut changes).unwrap();
+        assert_eq!(snapshot(&lexicon, &bat).unwrap(), before);
+        assert!(lexicon.synset_by_id(&new_id).unwrap().is_none());
+        assert!(lexicon.deprecations_get().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_undo_delete_relation() {
+        let mut lexicon = LexiconHashMapBackend::new();
+        lexicon.add_lexfile("noun.animal").unwrap();
+        let mut changes = ChangeList::new();
+        let animal = add_synset(&mut lexicon, "a living organism", "animal");
+        let cat = add_synset(&mut lexicon, "a feline mammal", "cat");
+        apply_automaton(vec![hypernym(&cat, &animal)], &mut lexicon, &mut changes).unwrap();
+
+        let actions = vec![Action::DeleteRelation {
+            source: SynsetRef::Id(animal.clone()),
+            source_sense: None,
+            relation: None,
+            target: SynsetRef::Id(cat.clone()),
+            target_sense: None,
+            source_lemma: None,
+            target_lemma: None,
+        }];
+        apply_automaton(actions, &mut lexicon, &mut changes).unwrap();
+        assert!(lexicon.synset_by_id(&cat).unwrap().unwrap().hypernym.is_empty());
+
+        undo_last(&mut lexicon, &mut changes).unwrap();
+        assert_eq!(lexicon.synset_by_id(&cat).unwrap().unwrap().hypernym, vec![animal]);
+    }
+
+    #[test]
+    fn test_undo_delete_one_relation() {
+        let mut lexicon = LexiconHashMapBackend::new();
+        lexicon.add_lexfile("noun.animal").unwrap();
+        let mut changes = ChangeList::new();
+        let animal = add_synset(&mut lexicon, "a living organism", "animal");
+        let cat = add_synset(&mut lexicon, "a feline mammal", "cat");
+        let actions = vec![
+            hypernym(&cat, &animal),
+            add_relation(&cat, None, "mero_part", &animal, None),
+        ];
+        apply_automaton(actions, &mut lexicon, &mut changes).unwrap();
+
+        let actions = vec![Action::DeleteRelation {
+            source: SynsetRef::Id(cat.clone()),
+            source_sense: None,
+            relation: Some("hypernym".to_string()),
+            target: SynsetRef::Id(animal.clone()),
+            target_sense: None,
+            source_lemma: None,
+            target_lemma: None,
+        }];
+        apply_automaton(actions, &mut lexicon, &mut changes).unwrap();
+        let synset = lexicon.synset_by_id(&cat).unwrap().unwrap();
+        assert!(synset.hypernym.is_empty());
+        assert_eq!(synset.mero_part, vec![animal.clone()]);
+        assert_eq!(lexicon.links_to(&animal).unwrap().len(), 1);
+
+        undo_last(&mut lexicon, &mut changes).unwrap();
+        let synset = lexicon.synset_by_id(&cat).unwrap().unwrap();
+        assert_eq!(synset.hypernym, vec![animal.clone()]);
+        assert_eq!(synset.mero_part, vec![animal.clone()]);
+        assert_eq!(lexicon.links_to(&animal).unwrap().len(), 2);
+    }
+}
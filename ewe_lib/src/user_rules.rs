@@ -0,0 +1,590 @@
+//! Project-specific validation rules - the house rules of one wordnet, which don't belong among
+//! `validate`'s built-in checks. They are read from `validation-rules.yaml` in the project
+//! directory (see `UserRules::for_source`), as a list of rules, each selecting the synsets,
+//! senses or entries it applies to and asserting conditions that must hold of them:
+//!
+//! ```yaml
+//! - name: definition-final-period
+//!   select: synset
+//!   assert:
+//!     definition_not_matches: '\.$'
+//! - name: person-hypernym
+//!   select: synset
+//!   where:
+//!     lexfile: noun.person
+//!   assert:
+//!     descends_from: 00007846-n
+//!   message: noun.person synsets must be under person.n.01
+//! - name: example-contains-member
+//!   select: synset
+//!   assert:
+//!     example_contains_member: true
+//! ```
+//!
+//! A rule is broken by every subject `where` holds of (every one of the selected kind, if there
+//! is no `where`) but `assert` does not. Each is reported as a `ValidationError::UserRule`,
+//! with the rule's `message` or, if it has none, a description of what failed. A project's
+//! validation profile sets the severity of them all under the name `UserRule`.
+
+use crate::rels::{SenseRelType, SynsetRelType};
+use crate::validate::{ErrorSubject, ValidationError};
+use crate::wordnet::{Entry, Lexicon, PartOfSpeech, PosKey, Result, Synset, SynsetId};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+
+/// The name of the file rules are read from
+pub const RULES_FILE: &str = "validation-rules.yaml";
+
+/// The kind of thing a rule applies to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Select {
+    Synset,
+    Sense,
+    Entry,
+}
+
+/// One rule. See the module documentation.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct UserRule {
+    /// The name the rule is reported under
+    pub name: String,
+    pub select: Select,
+    /// Which of the selected synsets, senses or entries the rule applies to
+    #[serde(default, rename = "where")]
+    pub filter: Condition,
+    /// What must hold of each of them
+    #[serde(rename = "assert")]
+    pub assertion: Condition,
+    /// Reported when the rule is broken, instead of what failed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+}
+
+/// Conditions on a synset, sense or entry, all of which must hold. Those on a synset's
+/// lexicographer file, part of speech, hypernyms, definitions and examples are, for a sense,
+/// about its synset and, for an entry, about the synset of every one of its senses. `relation`
+/// and `no_relation` name a synset relation for a synset and a sense relation otherwise.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Condition {
+    /// In this lexicographer file, e.g. `noun.person`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub lexfile: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pos: Option<PartOfSpeech>,
+    /// Has at least one relation of this type
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub relation: Option<String>,
+    /// Has no relation of this type
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub no_relation: Option<String>,
+    /// Is this synset, or has it among its hypernyms, following `hypernym` and
+    /// `instance_hypernym` relations as far as they go
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub descends_from: Option<SynsetId>,
+    /// Every definition matches this regular expression
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub definition_matches: Option<String>,
+    /// No definition matches this regular expression
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub definition_not_matches: Option<String>,
+    /// Every example matches this regular expression
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub example_matches: Option<String>,
+    /// No example matches this regular expression
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub example_not_matches: Option<String>,
+    /// Whether every example contains (ignoring case) one of the synset's members - or, for a
+    /// sense or entry, its lemma
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub example_contains_member: Option<bool>,
+    /// Every member (for a sense or entry, its lemma) matches this regular expression
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub lemma_matches: Option<String>,
+    /// No member (for a sense or entry, its lemma) matches this regular expression
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub lemma_not_matches: Option<String>,
+}
+
+/// What a condition is tested against
+struct Target<'a> {
+    /// The synset, the sense's synset or the synsets of the entry's senses, each with its
+    /// lexicographer file
+    synsets: Vec<(SynsetId, Cow<'a, Synset>, Option<String>)>,
+    /// The lemma of a sense or entry
+    lemma: Option<&'a str>,
+    /// The relations of a sense, or of all an entry's senses
+    sense_relations: Option<Vec<SenseRelType>>,
+}
+
+impl Target<'_> {
+    fn lemmas(&self) -> Vec<&str> {
+        match self.lemma {
+            Some(lemma) => vec![lemma],
+            None => self
+                .synsets
+                .iter()
+                .flat_map(|(_, synset, _)| synset.members.iter().map(|m| m.as_str()))
+                .collect(),
+        }
+    }
+
+    fn has_relation(&self, rel: &str) -> bool {
+        match &self.sense_relations {
+            Some(rels) => rels.iter().any(|r| r.value() == rel),
+            None => self.synsets.iter().any(|(_, synset, _)| {
+                synset.links_from().iter().any(|(r, _)| r.value() == rel)
+            }),
+        }
+    }
+}
+
+/// Whether `synset` (with id `id`) is `ancestor` or `ancestor` is reachable from it by
+/// `hypernym` and `instance_hypernym` relations
+fn descends_from<L: Lexicon>(
+    wn: &L,
+    id: &SynsetId,
+    synset: &Synset,
+    ancestor: &SynsetId,
+) -> Result<bool> {
+    if id == ancestor {
+        return Ok(true);
+    }
+    let mut seen = HashSet::new();
+    let mut stack: Vec<SynsetId> =
+        synset.hypernym.iter().chain(synset.instance_hypernym.iter()).cloned().collect();
+    while let Some(next) = stack.pop() {
+        if next == *ancestor {
+            return Ok(true);
+        }
+        if seen.insert(next.clone()) {
+            if let Some(synset) = wn.synset_by_id(&next)? {
+                stack.extend(synset.hypernym.iter().chain(synset.instance_hypernym.iter()).cloned());
+            }
+        }
+    }
+    Ok(false)
+}
+
+impl Condition {
+    /// The regular expressions this condition uses
+    fn patterns(&self) -> impl Iterator<Item = &String> {
+        [
+            &self.definition_matches,
+            &self.definition_not_matches,
+            &self.example_matches,
+            &self.example_not_matches,
+            &self.lemma_matches,
+            &self.lemma_not_matches,
+        ]
+        .into_iter()
+        .flatten()
+    }
+
+    /// Why this condition does not hold of `target`, or `None` if it does
+    fn failure<L: Lexicon>(
+        &self,
+        wn: &L,
+        target: &Target,
+        patterns: &HashMap<String, Regex>,
+    ) -> Result<Option<String>> {
+        for (id, synset, lexfile) in target.synsets.iter() {
+            if let Some(expected) = &self.lexfile {
+                if lexfile.as_ref() != Some(expected) {
+                    return Ok(Some(format!("{} is not in {}", id.as_str(), expected)));
+                }
+            }
+            if let Some(pos) = &self.pos {
+                if synset.part_of_speech != *pos {
+                    return Ok(Some(format!("{} is not of part of speech {}", id.as_str(), pos.value())));
+                }
+            }
+            if let Some(ancestor) = &self.descends_from {
+                if !descends_from(wn, id, synset, ancestor)? {
+                    return Ok(Some(format!("{} is not under {}", id.as_str(), ancestor.as_str())));
+                }
+            }
+            for definition in synset.definition.iter() {
+                if let Some(failure) = text_failure(
+                    "definition",
+                    definition,
+                    &self.definition_matches,
+                    &self.definition_not_matches,
+                    patterns,
+                ) {
+                    return Ok(Some(failure));
+                }
+            }
+            for example in synset.example.iter() {
+                if let Some(failure) = text_failure(
+                    "example",
+                    &example.text,
+                    &self.example_matches,
+                    &self.example_not_matches,
+                    patterns,
+                ) {
+                    return Ok(Some(failure));
+                }
+                if let Some(expected) = self.example_contains_member {
+                    let text = example.text.to_lowercase();
+                    let lemmas = match target.lemma {
+                        Some(lemma) => vec![lemma],
+                        None => synset.members.iter().map(|m| m.as_str()).collect(),
+                    };
+                    let contains = lemmas.iter().any(|lemma| text.contains(&lemma.to_lowercase()));
+                    if contains != expected {
+                        return Ok(Some(format!(
+                            "example \"{}\" {} {}",
+                            example.text,
+                            if expected { "does not contain" } else { "contains" },
+                            lemmas.join(" or ")
+                        )));
+                    }
+                }
+            }
+        }
+        if let Some(rel) = &self.relation {
+            if !target.has_relation(rel) {
+                return Ok(Some(format!("no {} relation", rel)));
+            }
+        }
+        if let Some(rel) = &self.no_relation {
+            if target.has_relation(rel) {
+                return Ok(Some(format!("has a {} relation", rel)));
+            }
+        }
+        for lemma in target.lemmas() {
+            if let Some(failure) =
+                text_failure("lemma", lemma, &self.lemma_matches, &self.lemma_not_matches, patterns)
+            {
+                return Ok(Some(failure));
+            }
+        }
+        Ok(None)
+    }
+}
+
+/// Why `text` fails to match the pattern `matches` or does match `not_matches`, if it does
+fn text_failure(
+    what: &str,
+    text: &str,
+    matches: &Option<String>,
+    not_matches: &Option<String>,
+    patterns: &HashMap<String, Regex>,
+) -> Option<String> {
+    if let Some(pattern) = matches {
+        if !patterns[pattern].is_match(text) {
+            return Some(format!("{} \"{}\" does not match {}", what, text, pattern));
+        }
+    }
+    if let Some(pattern) = not_matches {
+        if patterns[pattern].is_match(text) {
+            return Some(format!("{} \"{}\" matches {}", what, text, pattern));
+        }
+    }
+    None
+}
+
+/// A project's rules, checked and with their regular expressions compiled
+#[derive(Debug, Clone, Default)]
+pub struct UserRules {
+    rules: Vec<UserRule>,
+    patterns: HashMap<String, Regex>,
+}
+
+impl UserRules {
+    /// Checks `rules` are well-formed: every name distinct, every relation a known one (of
+    /// synsets for a synset rule, of senses otherwise) and every pattern a valid regular
+    /// expression
+    pub fn new(rules: Vec<UserRule>) -> std::result::Result<UserRules, String> {
+        let mut names = HashSet::new();
+        let mut patterns = HashMap::new();
+        for rule in rules.iter() {
+            if !names.insert(rule.name.as_str()) {
+                return Err(format!("Duplicate rule name {}", rule.name));
+            }
+            for condition in [&rule.filter, &rule.assertion] {
+                for rel in condition.relation.iter().chain(condition.no_relation.iter()) {
+                    let known = match rule.select {
+                        Select::Synset => SynsetRelType::from(rel).is_some(),
+                        Select::Sense | Select::Entry => SenseRelType::from(rel).is_some(),
+                    };
+                    if !known {
+                        return Err(format!("Rule {}: unknown relation {}", rule.name, rel));
+                    }
+                }
+                for pattern in condition.patterns() {
+                    let regex = Regex::new(pattern)
+                        .map_err(|e| format!("Rule {}: {}", rule.name, e))?;
+                    patterns.insert(pattern.clone(), regex);
+                }
+            }
+        }
+        Ok(UserRules { rules, patterns })
+    }
+
+    /// Reads rules from the text of a rules file
+    pub fn from_yaml(text: &str) -> std::result::Result<UserRules, String> {
+        let rules: Option<Vec<UserRule>> = serde_yaml::from_str(text).map_err(|e| e.to_string())?;
+        UserRules::new(rules.unwrap_or_default())
+    }
+
+    /// The rules for the YAML source at `source`, read from the first `validation-rules.yaml`
+    /// found in the directory above it or the one above that (the project directory in the
+    /// usual `<project>/src/yaml/` layout). Not `source` itself, where every `.yaml` file is
+    /// loaded as a lexicographer file. No rules if there is none.
+    pub fn for_source(source: impl AsRef<Path>) -> std::result::Result<UserRules, String> {
+        let source = source.as_ref();
+        for dir in [source.join(".."), source.join("../..")] {
+            let path = dir.join(RULES_FILE);
+            if path.is_file() {
+                let text = std::fs::read_to_string(&path)
+                    .map_err(|e| format!("Could not read {}: {}", path.display(), e))?;
+                return UserRules::from_yaml(&text)
+                    .map_err(|e| format!("Could not read {}: {}", path.display(), e));
+            }
+        }
+        Ok(UserRules::default())
+    }
+
+    pub fn rules(&self) -> &[UserRule] {
+        &self.rules
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    /// Every breach of a rule in `wn`
+    pub fn check<L: Lexicon>(&self, wn: &L) -> Result<Vec<ValidationError>> {
+        let mut errors = Vec::new();
+        if self.selects(Select::Synset) {
+            for synset in wn.synsets()? {
+                let (id, synset) = synset?;
+                self.check_synset(wn, id, synset, &mut errors)?;
+            }
+        }
+        if self.selects(Select::Sense) || self.selects(Select::Entry) {
+            for entry in wn.entries()? {
+                let (lemma, poskey, entry) = entry?;
+                self.check_entry(wn, &lemma, &poskey, &entry, &mut errors)?;
+            }
+        }
+        Ok(errors)
+    }
+
+    /// Whether any rule selects `select`
+    pub(crate) fn selects(&self, select: Select) -> bool {
+        self.rules.iter().any(|rule| rule.select == select)
+    }
+
+    /// Whether any rule uses `descends_from`, which an edit to one synset can break (or mend)
+    /// for every synset below it
+    pub(crate) fn follows_hypernyms(&self) -> bool {
+        self.rules.iter().any(|rule| {
+            rule.filter.descends_from.is_some() || rule.assertion.descends_from.is_some()
+        })
+    }
+
+    /// Every breach of a synset rule by synset `id`
+    pub(crate) fn check_synset<L: Lexicon>(
+        &self,
+        wn: &L,
+        id: SynsetId,
+        synset: Cow<'_, Synset>,
+        errors: &mut Vec<ValidationError>,
+    ) -> Result<()> {
+        let lexfile = wn.lex_name_for(&id)?;
+        let target = Target {
+            synsets: vec![(id.clone(), synset, lexfile)],
+            lemma: None,
+            sense_relations: None,
+        };
+        self.check_target(wn, Select::Synset, &target, ErrorSubject::Synset(id), errors)
+    }
+
+    /// Every breach of a sense rule by one of `entry`'s senses, or of an entry rule by `entry`
+    pub(crate) fn check_entry<L: Lexicon>(
+        &self,
+        wn: &L,
+        lemma: &str,
+        poskey: &PosKey,
+        entry: &Entry,
+        errors: &mut Vec<ValidationError>,
+    ) -> Result<()> {
+        let mut synsets = Vec::new();
+        let mut relations = Vec::new();
+        for sense in entry.sense.iter() {
+            // A sense whose synset is missing is already a `SenseSynsetNotExists`
+            let synset = match wn.synset_by_id(&sense.synset)? {
+                Some(synset) => synset,
+                None => continue,
+            };
+            let lexfile = wn.lex_name_for(&sense.synset)?;
+            let sense_relations: Vec<SenseRelType> =
+                sense.sense_links_from().into_iter().map(|(rel, _)| rel).collect();
+            if self.selects(Select::Sense) {
+                let target = Target {
+                    synsets: vec![(sense.synset.clone(), synset.clone(), lexfile.clone())],
+                    lemma: Some(lemma),
+                    sense_relations: Some(sense_relations.clone()),
+                };
+                let subject = ErrorSubject::Sense(sense.id.clone());
+                self.check_target(wn, Select::Sense, &target, subject, errors)?;
+            }
+            synsets.push((sense.synset.clone(), synset, lexfile));
+            relations.extend(sense_relations);
+        }
+        if self.selects(Select::Entry) {
+            let target = Target {
+                synsets,
+                lemma: Some(lemma),
+                sense_relations: Some(relations),
+            };
+            let subject = ErrorSubject::Entry(lemma.to_string(), poskey.clone());
+            self.check_target(wn, Select::Entry, &target, subject, errors)?;
+        }
+        Ok(())
+    }
+
+    fn check_target<L: Lexicon>(
+        &self,
+        wn: &L,
+        select: Select,
+        target: &Target,
+        subject: ErrorSubject,
+        errors: &mut Vec<ValidationError>,
+    ) -> Result<()> {
+        for rule in self.rules.iter().filter(|rule| rule.select == select) {
+            if rule.filter.failure(wn, target, &self.patterns)?.is_some() {
+                continue;
+            }
+            if let Some(failure) = rule.assertion.failure(wn, target, &self.patterns)? {
+                errors.push(ValidationError::UserRule {
+                    rule: rule.name.clone(),
+                    subject: subject.clone(),
+                    message: rule.message.clone().unwrap_or(failure),
+                });
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::progress::NullProgress;
+    use crate::wordnet::{LexiconHashMapBackend, SenseId};
+
+    #[test]
+    fn test_user_rules() {
+        let project = std::env::temp_dir().join(format!("ewe_user_rules_{}", std::process::id()));
+        let dir = project.join("src/yaml");
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(
+            dir.join("noun.Tops.yaml"),
+            "00001740-n:\n  definition:\n  - an entity\n  members:\n  - entity\n  partOfSpeech: n\n",
+        )
+        .unwrap();
+        std::fs::write(
+            dir.join("noun.person.yaml"),
+            "00007846-n:\n  definition:\n  - a human being\n  example:\n  - a person of note\n  \
+             hypernym:\n  - 00001740-n\n  members:\n  - person\n  partOfSpeech: n\n\
+             00007847-n:\n  definition:\n  - a cook.\n  example:\n  - she is a fine chef\n  \
+             hypernym:\n  - 00007846-n\n  members:\n  - cook\n  partOfSpeech: n\n\
+             00007848-n:\n  definition:\n  - a ghost\n  hypernym:\n  - 00001740-n\n  members:\n  \
+             - ghost\n  partOfSpeech: n\n",
+        )
+        .unwrap();
+        std::fs::write(
+            dir.join("entries-e.yaml"),
+            "entity:\n  n:\n    sense:\n    - id: 'entity%1:03:00::'\n      synset: 00001740-n\n",
+        )
+        .unwrap();
+        std::fs::write(
+            dir.join("entries-p.yaml"),
+            "person:\n  n:\n    sense:\n    - id: 'person%1:18:00::'\n      synset: 00007846-n\n",
+        )
+        .unwrap();
+        std::fs::write(
+            dir.join("entries-c.yaml"),
+            "cook:\n  n:\n    sense:\n    - id: 'cook%1:18:00::'\n      synset: 00007847-n\n",
+        )
+        .unwrap();
+        std::fs::write(
+            dir.join("entries-g.yaml"),
+            "ghost:\n  n:\n    sense:\n    - id: 'ghost%1:18:00::'\n      synset: 00007848-n\n",
+        )
+        .unwrap();
+        std::fs::write(
+            project.join(RULES_FILE),
+            "- name: final-period\n  select: synset\n  assert:\n    definition_not_matches: '\\.$'\n\
+             - name: person-hypernym\n  select: synset\n  where:\n    lexfile: noun.person\n    \
+             no_relation: similar\n  assert:\n    descends_from: 00007846-n\n  \
+             message: must be a kind of person\n\
+             - name: example-member\n  select: synset\n  assert:\n    example_contains_member: true\n\
+             - name: lowercase\n  select: entry\n  where:\n    pos: n\n  assert:\n    \
+             lemma_not_matches: '^g'\n",
+        )
+        .unwrap();
+        let wn = LexiconHashMapBackend::new().load(&dir, &mut NullProgress).unwrap();
+        let rules = UserRules::for_source(&dir).unwrap();
+        assert_eq!(rules.rules().len(), 4);
+
+        let found: Vec<(String, ErrorSubject, String)> = rules
+            .check(&wn)
+            .unwrap()
+            .into_iter()
+            .map(|error| match error {
+                ValidationError::UserRule { rule, subject, message } => (rule, subject, message),
+                _ => panic!("Not a user rule"),
+            })
+            .collect();
+        let cook = ErrorSubject::Synset(SynsetId::new("00007847-n"));
+        assert!(found.contains(&(
+            "final-period".to_string(),
+            cook.clone(),
+            "definition \"a cook.\" matches \\.$".to_string()
+        )));
+        assert!(found.contains(&(
+            "example-member".to_string(),
+            cook,
+            "example \"she is a fine chef\" does not contain cook".to_string()
+        )));
+        // person itself satisfies the rule, but ghost is only under entity
+        assert!(!found.iter().any(|(_, subject, _)| {
+            *subject == ErrorSubject::Synset(SynsetId::new("00007846-n"))
+        }));
+        assert!(found.contains(&(
+            "person-hypernym".to_string(),
+            ErrorSubject::Synset(SynsetId::new("00007848-n")),
+            "must be a kind of person".to_string()
+        )));
+        assert!(found.contains(&(
+            "lowercase".to_string(),
+            ErrorSubject::Entry("ghost".to_string(), PosKey::new("n".to_string())),
+            "lemma \"ghost\" matches ^g".to_string()
+        )));
+        assert_eq!(found.len(), 4);
+        assert!(!found
+            .iter()
+            .any(|(_, subject, _)| *subject == ErrorSubject::Sense(SenseId::new("ghost%1:18:00::"))));
+
+        let error = rules.check(&wn).unwrap().remove(0);
+        assert_eq!(error.name(), "UserRule");
+        assert_eq!(error.code(), "EWE040");
+
+        assert!(UserRules::from_yaml("- name: x\n  select: sense\n  assert:\n    relation: hypernym\n").is_err());
+        assert!(UserRules::from_yaml("- name: x\n  select: synset\n  assert:\n    lemma_matches: '('\n").is_err());
+        assert!(UserRules::from_yaml("- name: x\n  select: synset\n  assert:\n    definitions: x\n").is_err());
+        assert!(UserRules::from_yaml("").unwrap().is_empty());
+        assert!(UserRules::for_source(&project).unwrap().is_empty());
+        std::fs::remove_dir_all(&project).unwrap();
+    }
+}
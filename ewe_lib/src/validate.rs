@@ -2,11 +2,14 @@ use crate::wordnet::*;
 use crate::rels::*;
 use crate::sense_keys::get_sense_key2;
 use crate::progress::Progress;
+use std::borrow::Cow;
 use std::fmt;
-use std::collections::{HashSet,HashMap};
+use std::collections::{BTreeMap,BTreeSet,HashSet,HashMap};
 use lazy_static::lazy_static;
 use regex::Regex;
 use crate::change_manager;
+use crate::automaton::changelog_recent;
+use crate::user_rules::{Select, UserRules};
 
 pub fn validate<L : Lexicon, Bar : Progress>(wn : &L, bar : &mut Bar) -> Result<Vec<ValidationError>> {
     let mut errors = Vec::new();
@@ -19,101 +22,9 @@ pub fn validate<L : Lexicon, Bar : Progress>(wn : &L, bar : &mut Bar) -> Result<
     for entry in wn.entries()? {
         let (lemma, poskey, entry) = entry?;
         bar.inc(1);
+        check_entry(&mut errors, &lemma, &poskey, &entry);
         for sense in entry.sense.iter() {
-           match get_sense_key2(wn, &lemma, Some(&sense.id), &sense.synset)? {
-               Some(sense_key) => {
-                   if sense_key != sense.id {
-                       errors.push(ValidationError::InvalidSenseId {
-                           id: sense.id.clone(),
-                           expected: sense_key.clone()
-                       });
-                   }
-               },
-               None => {} // No synset error will be checked next.
-           }
-           match wn.synset_by_id(&sense.synset)? {
-               Some(synset) => {
-                   if poskey.to_part_of_speech() == None ||
-                       synset.part_of_speech != poskey.to_part_of_speech().unwrap() && 
-                       !(synset.part_of_speech == PartOfSpeech::s &&
-                         poskey.to_part_of_speech().unwrap() == PartOfSpeech::a) {
-                        errors.push(ValidationError::EntryPartOfSpeech {
-                            id: sense.id.clone(),
-                            pos: poskey.clone(),
-                            synset_pos: synset.part_of_speech.clone()
-                        });
-                   }
-
-                   if !synset.members.iter().any(|member| *member == lemma) {
-                       errors.push(ValidationError::SenseNotInSynsetMembers {
-                           id: sense.synset.clone(),
-                           member: lemma.clone()
-                       });
-                   }
-               }, None => {
-                   errors.push(ValidationError::SenseSynsetNotExists {
-                       id: sense.id.clone(),
-                       synset: sense.synset.clone()
-                   });
-               }
-           }
-           let mut sr_items = HashSet::new();
-           for (rel, raw_target) in sense.sense_links_from() {
-               let target = match raw_target.resolve(wn) {
-                   Ok(target) => target,
-                   Err(_) => {
-                       errors.push(ValidationError::SenseRelTargetMissing {
-                           id: sense.id.clone(),
-                           rel: rel.clone(),
-                           target: raw_target.clone()
-                       });
-                       continue;
-                   }
-               };
-
-               match poskey.to_part_of_speech() {
-                   Some(pos) => {
-                       if !rel.pos().iter().any(|p| **p == pos) {
-                           errors.push(ValidationError::SenseRelationPOS {
-                               id: sense.id.clone(),
-                               pos: pos,
-                               rel: rel.clone()
-                           });
-                      }
-                   },
-                   None => {}
-               }
-               // No relation type marked `is_symmetric` is sense-synset, so a
-               // `Synset` target here would mean the data is already broken
-               // in a way `SenseRelationPOS` above will have caught.
-               if rel.is_symmetric() {
-                   if let SenseOrSynsetId::Sense(target_sense) = &target {
-                       if !wn.sense_links_from_id(target_sense)?.iter().any(|(r2, t2)| {
-                           *r2 == rel && *t2 == UnresolvedSenseOrSynsetId::Sense(sense.id.clone()) }) {
-                           errors.push(ValidationError::SenseRelationSymmetry {
-                               source: sense.id.clone(),
-                               rel: rel.clone(),
-                               target: target.clone()
-                           });
-                       }
-                   }
-               }
-               if let SenseOrSynsetId::Sense(target_sense) = &target {
-                   if sense.id == *target_sense {
-                       errors.push(ValidationError::SelfReferencingSenseRelation {
-                           source: sense.id.clone(),
-                           rel: rel.clone(),
-                           target: target.clone() });
-                   }
-               }
-               if sr_items.contains(&(rel.clone(), target.clone())) {
-                   errors.push(ValidationError::DuplicateSenseRelation {
-                       source: sense.id.clone(),
-                       rel, target });
-               } else {
-                   sr_items.insert((rel, target));
-               }
-           }
+           check_sense(wn, &mut errors, &lemma, &poskey, &entry, sense)?;
            if sense_keys.contains(&sense.id) {
                errors.push(ValidationError::DuplicateSenseKey {
                    id: sense.id.clone()
@@ -121,23 +32,6 @@ pub fn validate<L : Lexicon, Bar : Progress>(wn : &L, bar : &mut Bar) -> Result<
            } else {
                sense_keys.insert(sense.id.clone());
            }
-           let mut subcat = sense.subcat.clone();
-           subcat.sort_unstable();
-           subcat.dedup();
-           if subcat.len() != sense.subcat.len() {
-                errors.push(ValidationError::DuplicateSyntacticBehaviour {
-                    id: sense.id.clone()
-                });
-           }
-
-           for sense2 in entry.sense.iter() {
-               if sense.id != sense2.id && sense.synset == sense2.synset {
-                   errors.push(ValidationError::DuplicateSense { 
-                       id1: sense.id.clone(), id2: sense2.id.clone(), 
-                       synset: sense.synset.clone() 
-                   });
-               }
-           }
         }
         if entry.sense.is_empty() {
             errors.push(ValidationError::NoSenses {
@@ -149,58 +43,28 @@ pub fn validate<L : Lexicon, Bar : Progress>(wn : &L, bar : &mut Bar) -> Result<
     for synset in wn.synsets()? {
         let (synset_id, synset) = synset?;
         bar.inc(1);
-        let ssid = synset_id.as_str();
-        if ssid[(ssid.len() - 1)..ssid.len()] != *synset.part_of_speech.value() {
-            errors.push(ValidationError::SynsetIdPos {
-                id: synset_id.clone(),
-                pos: synset.part_of_speech.clone()
-            });
-        }
-        if !is_valid_synset_id(&synset_id) {
-            errors.push(ValidationError::InvalidSynsetId {
-                id: synset_id.clone()
-            });
-        }
-
-        if synset.members.is_empty() {
-            errors.push(ValidationError::EmptySynset {
-                id: synset_id.clone()
-            });
-        }
+        check_synset(wn, &mut errors, &synset_id, &synset)?;
 
         match synset.ili {
-            Some(ref ili) => {
-                if !is_valid_ili(&ili) {
-                    errors.push(ValidationError::InvalidILIId {
-                        id: synset_id.clone(),
-                        ili: ili.clone()
-                    });
-                }
-                if ili.as_str() != "in" {
-                    match ili_index.get(ili.as_str()) {
-                        Some(prev) => {
-                            errors.push(ValidationError::DuplicateILI {
-                                id1: prev.clone(),
-                                id2: synset_id.clone(),
-                                ili: ili.clone()
-                            });
-                        },
-                        None => {
-                            ili_index.insert(ili.as_str().to_string(), synset_id.clone());
-                        }
+            Some(ref ili) if ili.as_str() != "in" => {
+                match ili_index.get(ili.as_str()) {
+                    Some(prev) => {
+                        errors.push(ValidationError::DuplicateILI {
+                            id1: prev.clone(),
+                            id2: synset_id.clone(),
+                            ili: ili.clone()
+                        });
+                    },
+                    None => {
+                        ili_index.insert(ili.as_str().to_string(), synset_id.clone());
                     }
                 }
             },
-            None => {}
+            _ => {}
         }
 
         for qid in synset.wikidata.iter() {
-            if !is_valid_wikidata_id(qid) {
-                errors.push(ValidationError::InvalidWikidataId {
-                    id: synset_id.clone(),
-                    qid: qid.clone()
-                });
-            } else {
+            if is_valid_wikidata_id(qid) {
                 match wikidata_index.get(qid) {
                     Some(prev) => {
                         errors.push(ValidationError::DuplicateWikidataId {
@@ -231,164 +95,430 @@ pub fn validate<L : Lexicon, Bar : Progress>(wn : &L, bar : &mut Bar) -> Result<
                 }
             }
         }
+    }
+    check_no_loops(wn, &mut errors, bar)?;
+    check_deprecations(wn, &mut errors)?;
+    bar.finish();
+    Ok(errors)
+}
 
-        let mut sr_items = HashSet::new();
-        for (rel, target) in synset.links_from() {
-            if !rel.pos().iter().any(|p| **p == synset.part_of_speech) {
-                errors.push(ValidationError::SynsetRelationPOS {
-                    id: synset_id.clone(),
-                    pos: synset.part_of_speech.clone(),
-                    rel: rel.clone()
+/// The checks `validate` makes on an entry itself, rather than on its senses: that its forms
+/// and lemma are normalised (see `normalise_text`). The lemma comes last, so that fixing the
+/// forms first leaves them normalised when the entry is renamed.
+fn check_entry(errors : &mut Vec<ValidationError>, lemma : &str, poskey : &PosKey,
+               entry : &Entry) {
+    for text in entry.form.iter().map(|form| form.as_str()).chain(std::iter::once(lemma)) {
+        if let Cow::Owned(normalised) = normalise_text(text) {
+            errors.push(ValidationError::UnnormalisedEntry {
+                lemma: lemma.to_string(),
+                poskey: poskey.clone(),
+                text: text.to_string(),
+                normalised
+            });
+        }
+    }
+}
+
+/// The checks `validate` makes on a single sense of an entry - everything but whether its
+/// sense key is used elsewhere too, which needs the whole wordnet.
+fn check_sense<L : Lexicon>(wn : &L, errors : &mut Vec<ValidationError>,
+                            lemma : &str, poskey : &PosKey,
+                            entry : &Entry, sense : &Sense) -> Result<()> {
+    match get_sense_key2(wn, lemma, Some(&sense.id), &sense.synset)? {
+        Some(sense_key) => {
+            if sense_key != sense.id {
+                errors.push(ValidationError::InvalidSenseId {
+                    id: sense.id.clone(),
+                    expected: sense_key.clone()
                 });
             }
-            if rel == SynsetRelType::Hypernym ||
-                rel == SynsetRelType::InstanceHypernym {
-                match wn.synset_by_id(&target)? {
-                    Some(target_synset) => {
-                        if synset.part_of_speech != target_synset.part_of_speech {
-                            errors.push(ValidationError::CrossPOSHyper {
-                                source: synset_id.clone(),
-                                target: target.clone()
-                            });
-                        }
-                        if rel == SynsetRelType::Hypernym &&
-                            !target_synset.instance_hypernym.is_empty() {
-                            errors.push(ValidationError::HypernymTargetIsInstance {
-                                source: synset_id.clone(),
-                                target: target.clone()
-                            });
-                        }
-                    },
-                    None => {
-                        errors.push(ValidationError::SynsetRelTargetMissing {
-                            id: synset_id.clone(),
-                            rel: rel.clone(),
+        },
+        None => {} // No synset error will be checked next.
+    }
+    match wn.synset_by_id(&sense.synset)? {
+        Some(synset) => {
+            if poskey.to_part_of_speech() == None ||
+                synset.part_of_speech != poskey.to_part_of_speech().unwrap() && 
+                !(synset.part_of_speech == PartOfSpeech::s &&
+                  poskey.to_part_of_speech().unwrap() == PartOfSpeech::a) {
+                 errors.push(ValidationError::EntryPartOfSpeech {
+                     id: sense.id.clone(),
+                     pos: poskey.clone(),
+                     synset_pos: synset.part_of_speech.clone()
+                 });
+            }
+
+            if !synset.members.iter().any(|member| *member == lemma) {
+                errors.push(ValidationError::SenseNotInSynsetMembers {
+                    id: sense.synset.clone(),
+                    member: lemma.to_string()
+                });
+            }
+        }, None => {
+            errors.push(ValidationError::SenseSynsetNotExists {
+                id: sense.id.clone(),
+                synset: sense.synset.clone()
+            });
+        }
+    }
+    let mut sr_items = HashSet::new();
+    for (rel, raw_target) in sense.sense_links_from() {
+        let target = match raw_target.resolve(wn) {
+            Ok(target) => target,
+            Err(_) => {
+                errors.push(ValidationError::SenseRelTargetMissing {
+                    id: sense.id.clone(),
+                    rel: rel.clone(),
+                    target: raw_target.clone()
+                });
+                continue;
+            }
+        };
+
+        match poskey.to_part_of_speech() {
+            Some(pos) => {
+                if !rel.pos().iter().any(|p| **p == pos) {
+                    errors.push(ValidationError::SenseRelationPOS {
+                        id: sense.id.clone(),
+                        pos: pos,
+                        rel: rel.clone()
+                    });
+               }
+            },
+            None => {}
+        }
+        // No relation type marked `is_symmetric` is sense-synset, so a
+        // `Synset` target here would mean the data is already broken
+        // in a way `SenseRelationPOS` above will have caught.
+        if rel.is_symmetric() {
+            if let SenseOrSynsetId::Sense(target_sense) = &target {
+                if !wn.sense_links_from_id(target_sense)?.iter().any(|(r2, t2)| {
+                    *r2 == rel && *t2 == UnresolvedSenseOrSynsetId::Sense(sense.id.clone()) }) {
+                    errors.push(ValidationError::SenseRelationSymmetry {
+                        source: sense.id.clone(),
+                        rel: rel.clone(),
+                        target: target.clone()
+                    });
+                }
+            }
+        }
+        if let SenseOrSynsetId::Sense(target_sense) = &target {
+            if sense.id == *target_sense {
+                errors.push(ValidationError::SelfReferencingSenseRelation {
+                    source: sense.id.clone(),
+                    rel: rel.clone(),
+                    target: target.clone() });
+            }
+        }
+        if sr_items.contains(&(rel.clone(), target.clone())) {
+            errors.push(ValidationError::DuplicateSenseRelation {
+                source: sense.id.clone(),
+                rel, target });
+        } else {
+            sr_items.insert((rel, target));
+        }
+    }
+    let mut subcat = sense.subcat.clone();
+    subcat.sort_unstable();
+    subcat.dedup();
+    if subcat.len() != sense.subcat.len() {
+         errors.push(ValidationError::DuplicateSyntacticBehaviour {
+             id: sense.id.clone()
+         });
+    }
+
+    for sense2 in entry.sense.iter() {
+        if sense.id != sense2.id && sense.synset == sense2.synset {
+            errors.push(ValidationError::DuplicateSense { 
+                id1: sense.id.clone(), id2: sense2.id.clone(), 
+                synset: sense.synset.clone() 
+            });
+        }
+    }
+    Ok(())
+}
+
+/// The checks `validate` makes on a single synset - everything but whether its definition,
+/// ILI or Wikidata ids are used elsewhere too, and whether it is part of a loop, which need
+/// the whole wordnet.
+fn check_synset<L : Lexicon>(wn : &L, errors : &mut Vec<ValidationError>,
+                             synset_id : &SynsetId, synset : &Synset) -> Result<()> {
+    let ssid = synset_id.as_str();
+    if ssid[(ssid.len() - 1)..ssid.len()] != *synset.part_of_speech.value() {
+        errors.push(ValidationError::SynsetIdPos {
+            id: synset_id.clone(),
+            pos: synset.part_of_speech.clone()
+        });
+    }
+    if !is_valid_synset_id(synset_id) {
+        errors.push(ValidationError::InvalidSynsetId {
+            id: synset_id.clone()
+        });
+    }
+
+    if synset.members.is_empty() {
+        errors.push(ValidationError::EmptySynset {
+            id: synset_id.clone()
+        });
+    }
+
+    if let Some(ili) = synset.ili.as_ref().filter(|ili| !is_valid_ili(ili)) {
+        errors.push(ValidationError::InvalidILIId {
+            id: synset_id.clone(),
+            ili: ili.clone()
+        });
+    }
+
+    for qid in synset.wikidata.iter() {
+        if !is_valid_wikidata_id(qid) {
+            errors.push(ValidationError::InvalidWikidataId {
+                id: synset_id.clone(),
+                qid: qid.clone()
+            });
+        }
+    }
+
+    let mut sr_items = HashSet::new();
+    for (rel, target) in synset.links_from() {
+        if !rel.pos().iter().any(|p| **p == synset.part_of_speech) {
+            errors.push(ValidationError::SynsetRelationPOS {
+                id: synset_id.clone(),
+                pos: synset.part_of_speech.clone(),
+                rel: rel.clone()
+            });
+        }
+        if rel == SynsetRelType::Hypernym ||
+            rel == SynsetRelType::InstanceHypernym {
+            match wn.synset_by_id(&target)? {
+                Some(target_synset) => {
+                    if synset.part_of_speech != target_synset.part_of_speech {
+                        errors.push(ValidationError::CrossPOSHyper {
+                            source: synset_id.clone(),
                             target: target.clone()
                         });
                     }
-                }
-            }
-            if rel == SynsetRelType::Similar {
-                if let Some(target_synset) = wn.synset_by_id(&target)? {
-                    let expected = match synset.part_of_speech {
-                        PartOfSpeech::a => Some(PartOfSpeech::s),
-                        PartOfSpeech::s => Some(PartOfSpeech::a),
-                        _ => None
-                    };
-                    if let Some(expected) = expected {
-                        if target_synset.part_of_speech != expected {
-                            errors.push(ValidationError::SimilarTargetPOS {
-                                id: synset_id.clone(),
-                                target: target.clone()
-                            });
-                        }
+                    if rel == SynsetRelType::Hypernym &&
+                        !target_synset.instance_hypernym.is_empty() {
+                        errors.push(ValidationError::HypernymTargetIsInstance {
+                            source: synset_id.clone(),
+                            target: target.clone()
+                        });
                     }
-                }
-            }
-            if rel.is_symmetric() {
-                if !wn.links_from(&target)?.iter().any(|(r2, t2)| {
-                    *r2 == rel && *t2 == synset_id }) {
-                    errors.push(ValidationError::SynsetRelationSymmetry {
-                        source: synset_id.clone(),
+                },
+                None => {
+                    errors.push(ValidationError::SynsetRelTargetMissing {
+                        id: synset_id.clone(),
                         rel: rel.clone(),
                         target: target.clone()
                     });
                 }
             }
-            if synset_id == target {
-                errors.push(ValidationError::SelfReferencingSynsetRelation {
+        }
+        if rel == SynsetRelType::Similar {
+            if let Some(target_synset) = wn.synset_by_id(&target)? {
+                let expected = match synset.part_of_speech {
+                    PartOfSpeech::a => Some(PartOfSpeech::s),
+                    PartOfSpeech::s => Some(PartOfSpeech::a),
+                    _ => None
+                };
+                if let Some(expected) = expected {
+                    if target_synset.part_of_speech != expected {
+                        errors.push(ValidationError::SimilarTargetPOS {
+                            id: synset_id.clone(),
+                            target: target.clone()
+                        });
+                    }
+                }
+            }
+        }
+        if rel.is_symmetric() {
+            if !wn.links_from(&target)?.iter().any(|(r2, t2)| {
+                *r2 == rel && t2 == synset_id }) {
+                errors.push(ValidationError::SynsetRelationSymmetry {
                     source: synset_id.clone(),
                     rel: rel.clone(),
                     target: target.clone()
                 });
             }
-             if sr_items.contains(&(rel.clone(), target.clone())) {
-                errors.push(ValidationError::DuplicateSynsetRelation {
-                    source: synset_id.clone(),
-                    rel, target });
-            } else {
-                sr_items.insert((rel, target));
-            }
-         }
-
-        if synset.part_of_speech == PartOfSpeech::s &&
-            synset.similar.len() != 1 {
-                errors.push(ValidationError::SatelliteSimilar {
-                    id: synset_id.clone(),
-                    n: synset.similar.len()
-                });
         }
-
-        if synset.part_of_speech == PartOfSpeech::n &&
-            !synset_id.as_str().starts_with("00001740") &&
-            synset.hypernym.is_empty() &&
-            synset.instance_hypernym.is_empty() {
-            errors.push(ValidationError::NoHypernym {
-                id: synset_id.clone()
+        if *synset_id == target {
+            errors.push(ValidationError::SelfReferencingSynsetRelation {
+                source: synset_id.clone(),
+                rel: rel.clone(),
+                target: target.clone()
             });
         }
+         if sr_items.contains(&(rel.clone(), target.clone())) {
+            errors.push(ValidationError::DuplicateSynsetRelation {
+                source: synset_id.clone(),
+                rel, target });
+        } else {
+            sr_items.insert((rel, target));
+        }
+     }
 
-        if !synset.hypernym.is_empty() && !synset.instance_hypernym.is_empty() {
-            errors.push(ValidationError::HypernymInstanceConflict {
-                id: synset_id.clone()
+    if synset.part_of_speech == PartOfSpeech::s &&
+        synset.similar.len() != 1 {
+            errors.push(ValidationError::SatelliteSimilar {
+                id: synset_id.clone(),
+                n: synset.similar.len()
             });
-        }
+    }
+
+    if synset.part_of_speech == PartOfSpeech::n &&
+        !synset_id.as_str().starts_with("00001740") &&
+        synset.hypernym.is_empty() &&
+        synset.instance_hypernym.is_empty() {
+        errors.push(ValidationError::NoHypernym {
+            id: synset_id.clone()
+        });
+    }
+
+    if !synset.hypernym.is_empty() && !synset.instance_hypernym.is_empty() {
+        errors.push(ValidationError::HypernymInstanceConflict {
+            id: synset_id.clone()
+        });
+    }
+
+    if synset.definition.is_empty() ||
+        synset.definition.iter().any(|def| def == "") {
+        errors.push(ValidationError::Definition {
+            id : synset_id.clone()
+        });
+    }
 
-        if synset.definition.is_empty() ||
-            synset.definition.iter().any(|def| def == "") {
-            errors.push(ValidationError::Definition {
-                id : synset_id.clone()
+    let texts = synset.definition.iter().chain(synset.example.iter().map(|ex| &ex.text));
+    for text in texts {
+        if let Cow::Owned(normalised) = normalise_text(text) {
+            errors.push(ValidationError::UnnormalisedText {
+                id: synset_id.clone(),
+                text: text.clone(),
+                normalised
             });
         }
+    }
 
-        match wn.lex_name_for(&synset_id)? {
-            Some(lex_name) => {
-                if !wn.pos_for_lexfile(&lex_name)?.iter().any(|pos| {
-                    *pos == synset.part_of_speech }) {
-                    errors.push(ValidationError::Lexfile {
-                        id: synset_id.clone(),
-                        lexfile: lex_name.clone()
-                    });
-                }
-            },
-            None => { // should never happen
+    match wn.lex_name_for(synset_id)? {
+        Some(lex_name) => {
+            if !wn.pos_for_lexfile(&lex_name)?.iter().any(|pos| {
+                *pos == synset.part_of_speech }) {
+                errors.push(ValidationError::Lexfile {
+                    id: synset_id.clone(),
+                    lexfile: lex_name.clone()
+                });
             }
+        },
+        None => { // should never happen
+        }
+    }
+
+    for member in synset.members.iter() {
+        if !wn.entry_by_lemma(member)?.iter().
+            any(|entry| {
+                entry.sense.iter().any(
+                    |sense| {
+                        sense.synset == *synset_id
+                    })
+            }) {
+            errors.push(ValidationError::SynsetMemberNotInEntries {
+                id: synset_id.clone(), 
+                member: member.to_string()
+            });
         }
+    }
 
-        for member in synset.members.iter() {
-            if !wn.entry_by_lemma(member)?.iter().
-                any(|entry| {
-                    entry.sense.iter().any(
-                        |sense| {
-                            sense.synset == synset_id
-                        })
-                }) {
-                errors.push(ValidationError::SynsetMemberNotInEntries {
-                    id: synset_id.clone(), 
-                    member: member.to_string()
+    for (i, mem1) in synset.members.iter().enumerate() {
+        for (j, mem2) in synset.members.iter().enumerate() {
+            if i > j && mem1 == mem2 {
+                errors.push(ValidationError::DuplicateMember {
+                    id: synset_id.clone(),
+                    member: mem1.clone()
                 });
             }
         }
+    }
 
-        for (i, mem1) in synset.members.iter().enumerate() {
-            for (j, mem2) in synset.members.iter().enumerate() {
-                if i > j && mem1 == mem2 {
-                    errors.push(ValidationError::DuplicateMember {
-                        id: synset_id.clone(),
-                        member: mem1.clone()
+    check_transitive(wn, errors, synset_id, synset)
+}
+
+/// The part-of-speech constraints broken by the relations to and from a single synset and its
+/// senses: what `validate` would report about them, without checking the whole wordnet. Used
+/// after `change_manager::change_pos` to report the relations the new part of speech does not
+/// allow.
+pub fn pos_change_errors<L : Lexicon>(wn : &L, synset_id : &SynsetId) -> Result<Vec<ValidationError>> {
+    let mut errors = Vec::new();
+    let synset = match wn.synset_by_id(synset_id)? {
+        Some(synset) => synset,
+        None => return Ok(errors)
+    };
+    let pos = synset.part_of_speech.clone();
+    for (rel, target) in synset.links_from() {
+        if !rel.pos().iter().any(|p| **p == pos) {
+            errors.push(ValidationError::SynsetRelationPOS {
+                id: synset_id.clone(),
+                pos: pos.clone(),
+                rel: rel.clone()
+            });
+        }
+        if let Some(target_synset) = wn.synset_by_id(&target)? {
+            check_pos_pair(&mut errors, &rel, synset_id, &pos, &target,
+                &target_synset.part_of_speech);
+        }
+    }
+    for (rel, source) in wn.links_to(synset_id)? {
+        if let Some(source_synset) = wn.synset_by_id(&source)? {
+            check_pos_pair(&mut errors, &rel, &source, &source_synset.part_of_speech,
+                synset_id, &pos);
+        }
+    }
+    if pos == PartOfSpeech::s && synset.similar.len() != 1 {
+        errors.push(ValidationError::SatelliteSimilar {
+            id: synset_id.clone(),
+            n: synset.similar.len()
+        });
+    }
+    for member in synset.members.iter() {
+        for sense in wn.get_sense(member, synset_id)? {
+            for (rel, _) in sense.sense_links_from() {
+                if !rel.pos().iter().any(|p| **p == pos) {
+                    errors.push(ValidationError::SenseRelationPOS {
+                        id: sense.id.clone(),
+                        pos: pos.clone(),
+                        rel
                     });
                 }
             }
         }
-
-        check_transitive(wn, &mut errors, &synset_id, &synset)?;
-
     }
-    check_no_loops(wn, &mut errors, bar)?;
-    bar.finish();
     Ok(errors)
 }
 
+/// The checks `validate` makes on the parts of speech at either end of a synset relation.
+fn check_pos_pair(errors : &mut Vec<ValidationError>, rel : &SynsetRelType,
+                  source : &SynsetId, source_pos : &PartOfSpeech,
+                  target : &SynsetId, target_pos : &PartOfSpeech) {
+    if (*rel == SynsetRelType::Hypernym || *rel == SynsetRelType::InstanceHypernym) &&
+        source_pos != target_pos {
+        errors.push(ValidationError::CrossPOSHyper {
+            source: source.clone(),
+            target: target.clone()
+        });
+    }
+    if *rel == SynsetRelType::Similar {
+        let expected = match source_pos {
+            PartOfSpeech::a => Some(PartOfSpeech::s),
+            PartOfSpeech::s => Some(PartOfSpeech::a),
+            _ => None
+        };
+        if expected.is_some_and(|expected| *target_pos != expected) {
+            errors.push(ValidationError::SimilarTargetPOS {
+                id: source.clone(),
+                target: target.clone()
+            });
+        }
+    }
+}
+
 fn check_transitive<L : Lexicon>(wn : &L,
                    errors : &mut Vec<ValidationError>,
                    synset_id : &SynsetId, synset : &Synset) -> Result<()> {
@@ -494,6 +624,546 @@ fn check_no_loops<L : Lexicon, ProgressBar : Progress>(wn : &L,
     Ok(())
 }
 
+/// The synset id a deprecation record refers to, without the `ewn-` prefix it is written with
+fn deprecated_id(id : &str) -> SynsetId {
+    SynsetId::new_owned(id.trim_start_matches("ewn-").to_string())
+}
+
+/// The checks on the deprecation records (see `Lexicon::deprecate`), from which a release
+/// redirects the ids of deleted synsets: that a deprecated id is not a synset again, and that
+/// following what supersedes it - through any synsets deprecated in turn - ends at a synset,
+/// rather than at an id that is gone or back where it started. A record that names nothing
+/// superseding it is only checked for the first.
+fn check_deprecations<L : Lexicon>(wn : &L, errors : &mut Vec<ValidationError>) -> Result<()> {
+    let mut deprecated = BTreeSet::new();
+    let mut superseded_by : BTreeMap<SynsetId, SynsetId> = BTreeMap::new();
+    for record in wn.deprecations_get()?.iter() {
+        let id = deprecated_id(&record.0);
+        if !record.2.is_empty() && !superseded_by.contains_key(&id) {
+            superseded_by.insert(id.clone(), deprecated_id(&record.2));
+        }
+        deprecated.insert(id);
+    }
+    for id in deprecated.iter() {
+        if wn.synset_by_id(id)?.is_some() {
+            errors.push(ValidationError::DeprecatedSynsetExists { id: id.clone() });
+        }
+    }
+    for (id, target) in superseded_by.iter() {
+        let mut next = target;
+        let mut seen = BTreeSet::new();
+        while wn.synset_by_id(next)?.is_none() {
+            if next == id {
+                errors.push(ValidationError::DeprecationLoop { id: id.clone() });
+                break;
+            }
+            match superseded_by.get(next) {
+                Some(after) if seen.insert(next) => next = after,
+                // A loop further on, reported for the ids in it
+                Some(_) => break,
+                None => {
+                    errors.push(ValidationError::DeprecationTargetMissing {
+                        id: id.clone(),
+                        superseded_by: target.clone()
+                    });
+                    break;
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Validation that keeps up with a wordnet as it is edited, rechecking only what a change can
+/// have affected instead of the whole wordnet: the synsets and senses a batch touched and
+/// their direct neighbours (every synset related to them, in either direction). The checks
+/// that need the whole wordnet are kept up to date between runs instead - indexes of the sense
+/// keys, definitions, ILIs and Wikidata ids in use, and the synsets in hypernym or domain loops,
+/// which are only followed up from the synsets rechecked. Building one is a full `validate`;
+/// after that, catching up with an edit is near-instant.
+///
+/// `errors` reports what `validate` would, except that a duplicate definition, ILI or Wikidata
+/// id is reported against the lowest synset id sharing it, and a loop once for each synset in
+/// it - followed by the breaches of a project's own rules, if given them (see `set_rules`).
+#[derive(Clone, Default)]
+pub struct IncrementalValidator {
+    /// What the per-sense and per-synset checks found, by synset (a sense's being the synset
+    /// it is in)
+    synset_errors : BTreeMap<SynsetId, Vec<ValidationError>>,
+    /// Entries with no senses
+    no_senses : BTreeSet<(String, PosKey)>,
+    /// What the per-entry checks found, by entry
+    entry_errors : BTreeMap<(String, PosKey), Vec<ValidationError>>,
+    /// The senses in each synset when it was last checked, as `(lemma, pos, id)`
+    synset_senses : HashMap<SynsetId, Vec<(String, PosKey, SenseId)>>,
+    /// How many senses use each sense key
+    sense_keys : HashMap<SenseId, usize>,
+    /// The synsets with each definition, ILI and Wikidata id
+    definitions : HashMap<String, BTreeSet<SynsetId>>,
+    ilis : HashMap<String, BTreeSet<SynsetId>>,
+    wikidata : HashMap<String, BTreeSet<SynsetId>>,
+    /// What each synset was last entered under in those three indexes
+    indexed : HashMap<SynsetId, IndexKeys>,
+    loops : BTreeSet<SynsetId>,
+    domain_loops : BTreeSet<SynsetId>,
+    /// What the checks on the deprecation records found, which are all made again on every
+    /// update (there are few records, and any edit may delete or restore a synset they name)
+    deprecation_errors : Vec<ValidationError>,
+    /// The project's own rules, checked alongside
+    rules : UserRules,
+    /// What `rules` found about each synset
+    synset_rule_errors : BTreeMap<SynsetId, Vec<ValidationError>>,
+    /// What `rules` found about each entry and its senses
+    entry_rule_errors : BTreeMap<(String, PosKey), Vec<ValidationError>>,
+    /// The newest change log entry taken into account
+    changelog_id : Option<u64>
+}
+
+#[derive(Clone, Default)]
+struct IndexKeys {
+    definitions : Vec<String>,
+    ili : Option<String>,
+    wikidata : Vec<String>
+}
+
+/// How many change log entries `IncrementalValidator::catch_up` reads at a time
+const CHANGELOG_PAGE_SIZE : usize = 10;
+
+impl IncrementalValidator {
+    /// Validates the whole of `wn`, as `validate` does, keeping what is needed to recheck it
+    /// piece by piece afterwards
+    pub fn new<L : Lexicon, Bar : Progress>(wn : &L, bar : &mut Bar) -> Result<IncrementalValidator> {
+        let mut validator = IncrementalValidator {
+            changelog_id: wn.changelog_recent(1, None)?.first().map(|(id, _)| *id),
+            ..IncrementalValidator::default()
+        };
+        bar.start((wn.n_entries()? + 2 * wn.n_synsets()?) as u64);
+        bar.set_percent_mode(true);
+        for entry in wn.entries()? {
+            let (lemma, poskey, entry) = entry?;
+            bar.inc(1);
+            validator.add_entry(&lemma, &poskey, &entry);
+            for sense in entry.sense.iter() {
+                validator.add_sense(wn, &lemma, &poskey, &entry, sense)?;
+            }
+            if entry.sense.is_empty() {
+                validator.no_senses.insert((lemma.clone(), poskey.clone()));
+            }
+        }
+        for synset in wn.synsets()? {
+            let (synset_id, synset) = synset?;
+            bar.inc(1);
+            validator.add_synset(wn, &synset_id, &synset)?;
+        }
+        let mut loops = Vec::new();
+        check_no_loops(wn, &mut loops, bar)?;
+        for error in loops {
+            match error {
+                ValidationError::Loop { id } => { validator.loops.insert(id); },
+                ValidationError::DomainLoop { id } => { validator.domain_loops.insert(id); },
+                _ => {}
+            }
+        }
+        check_deprecations(wn, &mut validator.deprecation_errors)?;
+        bar.finish();
+        Ok(validator)
+    }
+
+    /// Every error currently found in the wordnet
+    pub fn errors(&self) -> Vec<ValidationError> {
+        let mut errors : Vec<ValidationError> = self.synset_errors.values()
+            .flat_map(|errors| errors.iter().cloned()).collect();
+        for (lemma, poskey) in self.no_senses.iter() {
+            errors.push(ValidationError::NoSenses {
+                lemma: lemma.clone(),
+                poskey: poskey.clone()
+            });
+        }
+        for errors_of_entry in self.entry_errors.values() {
+            errors.extend(errors_of_entry.iter().cloned());
+        }
+        let mut sense_keys : Vec<(&SenseId, &usize)> = self.sense_keys.iter()
+            .filter(|(_, n)| **n > 1).collect();
+        sense_keys.sort_by(|a, b| a.0.as_str().cmp(b.0.as_str()));
+        for (id, n) in sense_keys {
+            for _ in 1..*n {
+                errors.push(ValidationError::DuplicateSenseKey { id: id.clone() });
+            }
+        }
+        for (ili, id1, id2) in duplicates(&self.ilis) {
+            errors.push(ValidationError::DuplicateILI { id1, id2, ili: ILIID::new(ili) });
+        }
+        for (qid, id1, id2) in duplicates(&self.wikidata) {
+            errors.push(ValidationError::DuplicateWikidataId { id1, id2, qid: qid.to_string() });
+        }
+        for (_, id1, id2) in duplicates(&self.definitions) {
+            errors.push(ValidationError::DuplicateDefinition { id1, id2 });
+        }
+        for id in self.loops.iter() {
+            errors.push(ValidationError::Loop { id: id.clone() });
+        }
+        for id in self.domain_loops.iter() {
+            errors.push(ValidationError::DomainLoop { id: id.clone() });
+        }
+        errors.extend(self.deprecation_errors.iter().cloned());
+        errors.extend(self.synset_rule_errors.values().flatten().cloned());
+        errors.extend(self.entry_rule_errors.values().flatten().cloned());
+        errors
+    }
+
+    /// The project rules checked alongside `validate`'s own checks
+    pub fn rules(&self) -> &UserRules {
+        &self.rules
+    }
+
+    /// Checks the whole of `wn` against a project's own `rules` (in place of any given before),
+    /// whose breaches are then kept up to date along with everything else
+    pub fn set_rules<L : Lexicon>(&mut self, wn : &L, rules : UserRules) -> Result<()> {
+        self.rules = rules;
+        self.synset_rule_errors.clear();
+        self.entry_rule_errors.clear();
+        if self.rules.selects(Select::Synset) {
+            for synset in wn.synsets()? {
+                let (synset_id, synset) = synset?;
+                let mut errors = Vec::new();
+                self.rules.check_synset(wn, synset_id.clone(), synset, &mut errors)?;
+                if !errors.is_empty() {
+                    self.synset_rule_errors.insert(synset_id, errors);
+                }
+            }
+        }
+        if self.rules.selects(Select::Sense) || self.rules.selects(Select::Entry) {
+            for entry in wn.entries()? {
+                let (lemma, poskey, entry) = entry?;
+                let mut errors = Vec::new();
+                self.rules.check_entry(wn, &lemma, &poskey, &entry, &mut errors)?;
+                if !errors.is_empty() {
+                    self.entry_rule_errors.insert((lemma, poskey), errors);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Rechecks `synsets`, the synsets of `senses`, and every synset directly related to one
+    /// of them, after an edit that changed them. A synset or sense that no longer exists is
+    /// fine to name - what was found about it before is dropped.
+    pub fn update<L : Lexicon>(&mut self, wn : &L, synsets : &[SynsetId],
+                               senses : &[SenseId]) -> Result<()> {
+        let mut touched : BTreeSet<SynsetId> = synsets.iter().cloned().collect();
+        for sense_id in senses {
+            touched.extend(synset_of_sense(wn, sense_id)?);
+        }
+        let mut recheck = touched.clone();
+        for synset_id in touched.iter() {
+            for (_, target) in wn.links_from(synset_id)? {
+                recheck.insert(target);
+            }
+            for (_, source) in wn.links_to(synset_id)? {
+                recheck.insert(source);
+            }
+            for (source, _, target) in wn.all_sense_links(synset_id)? {
+                recheck.extend(synset_of_sense(wn, &source)?);
+                match target {
+                    UnresolvedSenseOrSynsetId::Sense(target) => {
+                        recheck.extend(synset_of_sense(wn, &target)?);
+                    },
+                    UnresolvedSenseOrSynsetId::Synset(target) => {
+                        recheck.insert(target);
+                    },
+                    UnresolvedSenseOrSynsetId::Unresolved(_) => {}
+                }
+            }
+        }
+        // The rules are rechecked for the same synsets - and everything below them, if a rule
+        // looks up the hierarchy - and for every entry with a sense in one, before or after
+        let mut rule_region = recheck.clone();
+        if self.rules.follows_hypernyms() {
+            rule_region.extend(descendants(wn, &recheck)?);
+        }
+        let mut rule_entries : BTreeSet<(String, PosKey)> = BTreeSet::new();
+        let mut senses_of = |validator : &IncrementalValidator| {
+            for synset_id in rule_region.iter() {
+                for (lemma, poskey, _) in validator.synset_senses.get(synset_id).into_iter().flatten() {
+                    rule_entries.insert((lemma.clone(), poskey.clone()));
+                }
+            }
+        };
+        senses_of(self);
+        for synset_id in recheck.iter() {
+            self.recheck_synset(wn, synset_id)?;
+        }
+        senses_of(self);
+        self.recheck_loops(wn, &recheck, false)?;
+        self.recheck_loops(wn, &recheck, true)?;
+        for synset_id in rule_region.iter() {
+            self.recheck_synset_rules(wn, synset_id)?;
+        }
+        for (lemma, poskey) in rule_entries.iter() {
+            self.recheck_entry_rules(wn, lemma, poskey)?;
+        }
+        self.deprecation_errors.clear();
+        check_deprecations(wn, &mut self.deprecation_errors)
+    }
+
+    /// Rechecks whatever the change log entries added since this validator was built (or
+    /// last caught up) touched - see `ChangeLogEntry::touched`. A lexicon rebuilt from its
+    /// source needs a new validator instead, since it has a new change log.
+    pub fn catch_up<L : Lexicon>(&mut self, wn : &L) -> std::result::Result<(), String> {
+        let mut synsets = Vec::new();
+        let mut senses = Vec::new();
+        let mut newest = None;
+        let mut before = None;
+        'pages: loop {
+            let page = changelog_recent(wn, CHANGELOG_PAGE_SIZE, before)?;
+            if page.is_empty() {
+                break;
+            }
+            for (id, entry) in page {
+                if self.changelog_id.is_some_and(|seen| id <= seen) {
+                    break 'pages;
+                }
+                newest = newest.or(Some(id));
+                before = Some(id);
+                let (touched_synsets, touched_senses) = entry.touched();
+                synsets.extend(touched_synsets);
+                senses.extend(touched_senses);
+            }
+        }
+        if newest.is_some() {
+            self.update(wn, &synsets, &senses).map_err(|e| e.to_string())?;
+            self.changelog_id = newest;
+        }
+        Ok(())
+    }
+
+    fn add_entry(&mut self, lemma : &str, poskey : &PosKey, entry : &Entry) {
+        let mut errors = Vec::new();
+        check_entry(&mut errors, lemma, poskey, entry);
+        if !errors.is_empty() {
+            self.entry_errors.insert((lemma.to_string(), poskey.clone()), errors);
+        }
+    }
+
+    fn add_sense<L : Lexicon>(&mut self, wn : &L, lemma : &str, poskey : &PosKey,
+                              entry : &Entry, sense : &Sense) -> Result<()> {
+        check_sense(wn, self.synset_errors.entry(sense.synset.clone()).or_default(),
+            lemma, poskey, entry, sense)?;
+        self.synset_senses.entry(sense.synset.clone()).or_default()
+            .push((lemma.to_string(), poskey.clone(), sense.id.clone()));
+        *self.sense_keys.entry(sense.id.clone()).or_default() += 1;
+        Ok(())
+    }
+
+    fn add_synset<L : Lexicon>(&mut self, wn : &L, synset_id : &SynsetId,
+                               synset : &Synset) -> Result<()> {
+        check_synset(wn, self.synset_errors.entry(synset_id.clone()).or_default(),
+            synset_id, synset)?;
+        let keys = IndexKeys {
+            definitions: synset.definition.iter().filter(|d| !d.is_empty()).cloned().collect(),
+            ili: synset.ili.as_ref().map(|ili| ili.as_str().to_string())
+                .filter(|ili| ili != "in"),
+            wikidata: synset.wikidata.iter().filter(|qid| is_valid_wikidata_id(qid))
+                .cloned().collect()
+        };
+        for defn in keys.definitions.iter() {
+            self.definitions.entry(defn.clone()).or_default().insert(synset_id.clone());
+        }
+        for ili in keys.ili.iter() {
+            self.ilis.entry(ili.clone()).or_default().insert(synset_id.clone());
+        }
+        for qid in keys.wikidata.iter() {
+            self.wikidata.entry(qid.clone()).or_default().insert(synset_id.clone());
+        }
+        self.indexed.insert(synset_id.clone(), keys);
+        Ok(())
+    }
+
+    /// Forgets everything found about `synset_id` and its senses, then checks them again as
+    /// they now stand
+    fn recheck_synset<L : Lexicon>(&mut self, wn : &L, synset_id : &SynsetId) -> Result<()> {
+        self.synset_errors.remove(synset_id);
+        if let Some(keys) = self.indexed.remove(synset_id) {
+            unindex(&mut self.definitions, &keys.definitions, synset_id);
+            unindex(&mut self.ilis, &keys.ili.into_iter().collect::<Vec<_>>(), synset_id);
+            unindex(&mut self.wikidata, &keys.wikidata, synset_id);
+        }
+        let mut entries : BTreeSet<(String, PosKey)> = BTreeSet::new();
+        for (lemma, poskey, sense_id) in self.synset_senses.remove(synset_id).unwrap_or_default() {
+            if let Some(n) = self.sense_keys.get_mut(&sense_id) {
+                *n -= 1;
+                if *n == 0 {
+                    self.sense_keys.remove(&sense_id);
+                }
+            }
+            entries.insert((lemma, poskey));
+        }
+        let synset = wn.synset_by_id(synset_id)?;
+        if let Some(ref synset) = synset {
+            for member in synset.members.iter() {
+                for (poskey, _) in wn.entry_by_lemma_with_pos(member)? {
+                    entries.insert((member.clone(), poskey));
+                }
+            }
+        }
+        for (lemma, poskey) in entries {
+            self.no_senses.remove(&(lemma.clone(), poskey.clone()));
+            self.entry_errors.remove(&(lemma.clone(), poskey.clone()));
+            for (poskey2, entry) in wn.entry_by_lemma_with_pos(&lemma)? {
+                if poskey2 != poskey {
+                    continue;
+                }
+                self.add_entry(&lemma, &poskey, &entry);
+                for sense in entry.sense.iter().filter(|sense| sense.synset == *synset_id) {
+                    self.add_sense(wn, &lemma, &poskey, &entry, sense)?;
+                }
+                if entry.sense.is_empty() {
+                    self.no_senses.insert((lemma.clone(), poskey.clone()));
+                }
+            }
+        }
+        if let Some(synset) = synset {
+            self.add_synset(wn, synset_id, &synset)?;
+        }
+        Ok(())
+    }
+
+    /// Checks synset `synset_id` against the rules again, as it now stands
+    fn recheck_synset_rules<L : Lexicon>(&mut self, wn : &L, synset_id : &SynsetId) -> Result<()> {
+        self.synset_rule_errors.remove(synset_id);
+        if !self.rules.selects(Select::Synset) {
+            return Ok(());
+        }
+        if let Some(synset) = wn.synset_by_id(synset_id)? {
+            let mut errors = Vec::new();
+            self.rules.check_synset(wn, synset_id.clone(), synset, &mut errors)?;
+            if !errors.is_empty() {
+                self.synset_rule_errors.insert(synset_id.clone(), errors);
+            }
+        }
+        Ok(())
+    }
+
+    /// Checks the entry for `lemma` and `poskey`, and its senses, against the rules again, as
+    /// they now stand
+    fn recheck_entry_rules<L : Lexicon>(&mut self, wn : &L, lemma : &str,
+                                        poskey : &PosKey) -> Result<()> {
+        let key = (lemma.to_string(), poskey.clone());
+        self.entry_rule_errors.remove(&key);
+        if !self.rules.selects(Select::Sense) && !self.rules.selects(Select::Entry) {
+            return Ok(());
+        }
+        let mut errors = Vec::new();
+        for (poskey2, entry) in wn.entry_by_lemma_with_pos(lemma)? {
+            if poskey2 == *poskey {
+                self.rules.check_entry(wn, lemma, poskey, &entry, &mut errors)?;
+            }
+        }
+        if !errors.is_empty() {
+            self.entry_rule_errors.insert(key, errors);
+        }
+        Ok(())
+    }
+
+    /// Works out again which of `region` and the synsets already known to be in a loop are in
+    /// one, following hypernyms (or, if `domain`, domain relations). A loop made by an edit
+    /// runs through a synset it touched, and every synset in it is found from there.
+    fn recheck_loops<L : Lexicon>(&mut self, wn : &L, region : &BTreeSet<SynsetId>,
+                                  domain : bool) -> Result<()> {
+        let loops = if domain { &mut self.domain_loops } else { &mut self.loops };
+        let mut candidates = std::mem::take(loops);
+        candidates.extend(region.iter().cloned());
+        for synset_id in candidates {
+            if loops.contains(&synset_id) {
+                continue;
+            }
+            let ancestors = reachable(wn, &synset_id, domain)?;
+            if ancestors.contains(&synset_id) {
+                for other in ancestors.iter() {
+                    if !loops.contains(other) &&
+                        reachable(wn, other, domain)?.contains(&synset_id) {
+                        loops.insert(other.clone());
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Every synset below one of `synsets` by `hypernym` and `instance_hypernym` relations
+fn descendants<L : Lexicon>(wn : &L, synsets : &BTreeSet<SynsetId>) -> Result<BTreeSet<SynsetId>> {
+    let mut found = BTreeSet::new();
+    let mut stack : Vec<SynsetId> = synsets.iter().cloned().collect();
+    while let Some(synset_id) = stack.pop() {
+        for (rel, source) in wn.links_to(&synset_id)? {
+            if (rel == SynsetRelType::Hypernym || rel == SynsetRelType::InstanceHypernym)
+                && found.insert(source.clone()) {
+                stack.push(source);
+            }
+        }
+    }
+    Ok(found)
+}
+
+/// The synset `sense_id` is in, if there is such a sense
+fn synset_of_sense<L : Lexicon>(wn : &L, sense_id : &SenseId) -> Result<Option<SynsetId>> {
+    Ok(wn.get_sense_by_id(sense_id)?.map(|(_, _, sense)| sense.synset.clone()))
+}
+
+/// Removes `synset_id` from `index` under each of `keys`
+fn unindex(index : &mut HashMap<String, BTreeSet<SynsetId>>, keys : &[String],
+           synset_id : &SynsetId) {
+    for key in keys {
+        if let Some(ids) = index.get_mut(key) {
+            ids.remove(synset_id);
+            if ids.is_empty() {
+                index.remove(key);
+            }
+        }
+    }
+}
+
+/// `(key, first, other)` for every other synset sharing a key in `index` with the first
+fn duplicates(index : &HashMap<String, BTreeSet<SynsetId>>) -> Vec<(&str, SynsetId, SynsetId)> {
+    let mut shared : Vec<(&String, &BTreeSet<SynsetId>)> = index.iter()
+        .filter(|(_, ids)| ids.len() > 1).collect();
+    shared.sort();
+    let mut duplicates = Vec::new();
+    for (key, ids) in shared {
+        let mut ids = ids.iter();
+        if let Some(first) = ids.next() {
+            for other in ids {
+                duplicates.push((key.as_str(), first.clone(), other.clone()));
+            }
+        }
+    }
+    duplicates
+}
+
+/// Every synset reachable from `synset_id` by one or more hypernym (or, if `domain`, domain)
+/// relations
+fn reachable<L : Lexicon>(wn : &L, synset_id : &SynsetId, domain : bool) -> Result<BTreeSet<SynsetId>> {
+    let mut reached = BTreeSet::new();
+    let mut stack = vec![synset_id.clone()];
+    while let Some(next) = stack.pop() {
+        if let Some(synset) = wn.synset_by_id(&next)? {
+            let targets : Vec<&SynsetId> = if domain {
+                synset.domain_region.iter().chain(synset.domain_topic.iter())
+                    .chain(synset.exemplifies.iter()).collect()
+            } else {
+                synset.hypernym.iter().collect()
+            };
+            for target in targets {
+                if reached.insert(target.clone()) {
+                    stack.push(target.clone());
+                }
+            }
+        }
+    }
+    Ok(reached)
+}
+
 lazy_static! {
    static ref VALID_SYNSET_ID : Regex = Regex::new("^[0-9]{8}-[nvars]$").unwrap();
    static ref VALID_ILI : Regex = Regex::new("^i\\d+$").unwrap();
@@ -512,6 +1182,7 @@ fn is_valid_wikidata_id(qid : &str) -> bool {
     VALID_WIKIDATA_ID.is_match(qid)
 }
 
+#[derive(Clone)]
 pub enum ValidationError {
     InvalidSenseId { id : SenseId, expected : SenseId },
     SenseSynsetNotExists { id : SenseId, synset : SynsetId },
@@ -551,7 +1222,337 @@ pub enum ValidationError {
     DuplicateDefinition { id1: SynsetId, id2: SynsetId },
     DuplicateILI { id1: SynsetId, id2: SynsetId, ili: ILIID },
     InvalidWikidataId { id: SynsetId, qid: String },
-    DuplicateWikidataId { id1: SynsetId, id2: SynsetId, qid: String }
+    DuplicateWikidataId { id1: SynsetId, id2: SynsetId, qid: String },
+    /// A project's own rule (see `crate::user_rules`) does not hold
+    UserRule { rule: String, subject: ErrorSubject, message: String },
+    /// A definition uses one of the synset's own members (see `crate::definitions`)
+    CircularDefinition { id: SynsetId, member: String },
+    /// A definition or example uses a word not in the wordnet (see `crate::definitions`)
+    UnknownWord { id: SynsetId, word: String },
+    /// The lemma or a form of an entry is not normalised (see `normalise_text`)
+    UnnormalisedEntry { lemma: String, poskey: PosKey, text: String, normalised: String },
+    /// A definition or example of a synset is not normalised (see `normalise_text`)
+    UnnormalisedText { id: SynsetId, text: String, normalised: String },
+    /// A deprecated synset id is in use as a synset again
+    DeprecatedSynsetExists { id: SynsetId },
+    /// What supersedes a deprecated id neither exists nor is itself superseded by a synset
+    /// that does
+    DeprecationTargetMissing { id: SynsetId, superseded_by: SynsetId },
+    /// Following what supersedes a deprecated id leads back to it
+    DeprecationLoop { id: SynsetId }
+}
+
+/// What a `ValidationError` is reported against (see `ValidationError::subject`)
+#[derive(Debug, Clone, PartialEq)]
+pub enum ErrorSubject {
+    Synset(SynsetId),
+    Sense(SenseId),
+    /// An entry, by lemma and part of speech key
+    Entry(String, PosKey),
+}
+
+impl fmt::Display for ErrorSubject {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ErrorSubject::Synset(id) => write!(f, "{}", id.as_str()),
+            ErrorSubject::Sense(id) => write!(f, "{}", id.as_str()),
+            ErrorSubject::Entry(lemma, poskey) => write!(f, "Entry for {} ({})", lemma, poskey.as_str())
+        }
+    }
+}
+
+/// The name of every kind of `ValidationError`, as `ValidationError::name` gives it. Only ever
+/// append to this list: an error's position in it is its `ValidationError::code`.
+pub const CHECKS : &[&str] = &[
+    "InvalidSenseId",
+    "SenseSynsetNotExists",
+    "EntryPartOfSpeech",
+    "SenseRelationPOS",
+    "SynsetRelationPOS",
+    "DuplicateSenseRelation",
+    "SelfReferencingSenseRelation",
+    "SelfReferencingSynsetRelation",
+    "DuplicateSynsetRelation",
+    "DuplicateSenseKey",
+    "DuplicateSyntacticBehaviour",
+    "DuplicateSense",
+    "SynsetIdPos",
+    "InvalidSynsetId",
+    "EmptySynset",
+    "InvalidILIId",
+    "NoSenses",
+    "CrossPOSHyper",
+    "SenseRelTargetMissing",
+    "SynsetRelTargetMissing",
+    "SatelliteSimilar",
+    "NoHypernym",
+    "Definition",
+    "Lexfile",
+    "SenseRelationSymmetry",
+    "SynsetRelationSymmetry",
+    "Transitivity",
+    "Loop",
+    "DomainLoop",
+    "SynsetMemberNotInEntries",
+    "DuplicateMember",
+    "SenseNotInSynsetMembers",
+    "SimilarTargetPOS",
+    "HypernymInstanceConflict",
+    "HypernymTargetIsInstance",
+    "DuplicateDefinition",
+    "DuplicateILI",
+    "InvalidWikidataId",
+    "DuplicateWikidataId",
+    "UserRule",
+    "CircularDefinition",
+    "UnknownWord",
+    "UnnormalisedEntry",
+    "UnnormalisedText",
+    "DeprecatedSynsetExists",
+    "DeprecationTargetMissing",
+    "DeprecationLoop",
+];
+
+/// The checks `validate` itself does not make, which are only reported if a
+/// `ValidationProfile` asks for them (see `crate::definitions::opt_in_errors`)
+pub const OPT_IN_CHECKS : &[&str] = &[
+    "CircularDefinition",
+    "UnknownWord",
+];
+
+/// The checks a `ValidationProfile` reports as warnings unless it names them: text entered
+/// before they existed breaks them all over a wordnet, without being wrong as such, and
+/// `fix` can put all of it right in one go
+pub const WARNING_CHECKS : &[&str] = &[
+    "UnnormalisedEntry",
+    "UnnormalisedText",
+];
+
+impl ValidationError {
+    /// The name of this kind of error (its variant name, e.g. `NoHypernym`), by which a
+    /// `ValidationProfile` refers to it.
+    pub fn name(&self) -> &'static str {
+        match self {
+            ValidationError::InvalidSenseId { .. } => "InvalidSenseId",
+            ValidationError::SenseSynsetNotExists { .. } => "SenseSynsetNotExists",
+            ValidationError::EntryPartOfSpeech { .. } => "EntryPartOfSpeech",
+            ValidationError::SenseRelationPOS { .. } => "SenseRelationPOS",
+            ValidationError::SynsetRelationPOS { .. } => "SynsetRelationPOS",
+            ValidationError::DuplicateSenseRelation { .. } => "DuplicateSenseRelation",
+            ValidationError::SelfReferencingSenseRelation { .. } => "SelfReferencingSenseRelation",
+            ValidationError::SelfReferencingSynsetRelation { .. } => "SelfReferencingSynsetRelation",
+            ValidationError::DuplicateSynsetRelation { .. } => "DuplicateSynsetRelation",
+            ValidationError::DuplicateSenseKey { .. } => "DuplicateSenseKey",
+            ValidationError::DuplicateSyntacticBehaviour { .. } => "DuplicateSyntacticBehaviour",
+            ValidationError::DuplicateSense { .. } => "DuplicateSense",
+            ValidationError::SynsetIdPos { .. } => "SynsetIdPos",
+            ValidationError::InvalidSynsetId { .. } => "InvalidSynsetId",
+            ValidationError::EmptySynset { .. } => "EmptySynset",
+            ValidationError::InvalidILIId { .. } => "InvalidILIId",
+            ValidationError::NoSenses { .. } => "NoSenses",
+            ValidationError::CrossPOSHyper { .. } => "CrossPOSHyper",
+            ValidationError::SenseRelTargetMissing { .. } => "SenseRelTargetMissing",
+            ValidationError::SynsetRelTargetMissing { .. } => "SynsetRelTargetMissing",
+            ValidationError::SatelliteSimilar { .. } => "SatelliteSimilar",
+            ValidationError::NoHypernym { .. } => "NoHypernym",
+            ValidationError::Definition { .. } => "Definition",
+            ValidationError::Lexfile { .. } => "Lexfile",
+            ValidationError::SenseRelationSymmetry { .. } => "SenseRelationSymmetry",
+            ValidationError::SynsetRelationSymmetry { .. } => "SynsetRelationSymmetry",
+            ValidationError::Transitivity { .. } => "Transitivity",
+            ValidationError::Loop { .. } => "Loop",
+            ValidationError::DomainLoop { .. } => "DomainLoop",
+            ValidationError::SynsetMemberNotInEntries { .. } => "SynsetMemberNotInEntries",
+            ValidationError::DuplicateMember { .. } => "DuplicateMember",
+            ValidationError::SenseNotInSynsetMembers { .. } => "SenseNotInSynsetMembers",
+            ValidationError::SimilarTargetPOS { .. } => "SimilarTargetPOS",
+            ValidationError::HypernymInstanceConflict { .. } => "HypernymInstanceConflict",
+            ValidationError::HypernymTargetIsInstance { .. } => "HypernymTargetIsInstance",
+            ValidationError::DuplicateDefinition { .. } => "DuplicateDefinition",
+            ValidationError::DuplicateILI { .. } => "DuplicateILI",
+            ValidationError::InvalidWikidataId { .. } => "InvalidWikidataId",
+            ValidationError::DuplicateWikidataId { .. } => "DuplicateWikidataId",
+            ValidationError::UserRule { .. } => "UserRule",
+            ValidationError::CircularDefinition { .. } => "CircularDefinition",
+            ValidationError::UnknownWord { .. } => "UnknownWord",
+            ValidationError::UnnormalisedEntry { .. } => "UnnormalisedEntry",
+            ValidationError::UnnormalisedText { .. } => "UnnormalisedText",
+            ValidationError::DeprecatedSynsetExists { .. } => "DeprecatedSynsetExists",
+            ValidationError::DeprecationTargetMissing { .. } => "DeprecationTargetMissing",
+            ValidationError::DeprecationLoop { .. } => "DeprecationLoop",
+        }
+    }
+
+    /// A stable code for this kind of error, `EWE` followed by its (1-based) position in
+    /// `CHECKS` - e.g. `EWE022` for `NoHypernym`. `CHECKS` is only ever appended to, so a code
+    /// keeps its meaning across releases.
+    pub fn code(&self) -> String {
+        let index = CHECKS.iter().position(|name| *name == self.name())
+            .expect("Every check is listed in CHECKS");
+        format!("EWE{:03}", index + 1)
+    }
+
+    /// The fields of this error by name, each as it would be written in the YAML source
+    pub fn fields(&self) -> Vec<(&'static str, String)> {
+        match self {
+            ValidationError::InvalidSenseId { id, expected } =>
+                vec![("id", id.as_str().to_string()), ("expected", expected.as_str().to_string())],
+            ValidationError::SenseSynsetNotExists { id, synset } =>
+                vec![("id", id.as_str().to_string()), ("synset", synset.as_str().to_string())],
+            ValidationError::EntryPartOfSpeech { id, pos, synset_pos } =>
+                vec![("id", id.as_str().to_string()), ("pos", pos.as_str().to_string()),
+                     ("synset_pos", synset_pos.value().to_string())],
+            ValidationError::SenseRelationPOS { id, pos, rel } =>
+                vec![("id", id.as_str().to_string()), ("pos", pos.value().to_string()),
+                     ("rel", rel.value().to_string())],
+            ValidationError::SynsetRelationPOS { id, pos, rel } =>
+                vec![("id", id.as_str().to_string()), ("pos", pos.value().to_string()),
+                     ("rel", rel.value().to_string())],
+            ValidationError::DuplicateSenseRelation { source, rel, target } |
+            ValidationError::SelfReferencingSenseRelation { source, rel, target } |
+            ValidationError::SenseRelationSymmetry { source, rel, target } =>
+                vec![("source", source.as_str().to_string()), ("rel", rel.value().to_string()),
+                     ("target", target.as_str().to_string())],
+            ValidationError::SelfReferencingSynsetRelation { source, rel, target } |
+            ValidationError::DuplicateSynsetRelation { source, rel, target } |
+            ValidationError::SynsetRelationSymmetry { source, rel, target } =>
+                vec![("source", source.as_str().to_string()), ("rel", rel.value().to_string()),
+                     ("target", target.as_str().to_string())],
+            ValidationError::DuplicateSenseKey { id } |
+            ValidationError::DuplicateSyntacticBehaviour { id } =>
+                vec![("id", id.as_str().to_string())],
+            ValidationError::DuplicateSense { id1, id2, synset } =>
+                vec![("id1", id1.as_str().to_string()), ("id2", id2.as_str().to_string()),
+                     ("synset", synset.as_str().to_string())],
+            ValidationError::SynsetIdPos { id, pos } =>
+                vec![("id", id.as_str().to_string()), ("pos", pos.value().to_string())],
+            ValidationError::InvalidSynsetId { id } |
+            ValidationError::EmptySynset { id } |
+            ValidationError::NoHypernym { id } |
+            ValidationError::Definition { id } |
+            ValidationError::Loop { id } |
+            ValidationError::DomainLoop { id } |
+            ValidationError::HypernymInstanceConflict { id } =>
+                vec![("id", id.as_str().to_string())],
+            ValidationError::InvalidILIId { id, ili } =>
+                vec![("id", id.as_str().to_string()), ("ili", ili.as_str().to_string())],
+            ValidationError::NoSenses { lemma, poskey } =>
+                vec![("lemma", lemma.clone()), ("poskey", poskey.as_str().to_string())],
+            ValidationError::CrossPOSHyper { source, target } |
+            ValidationError::HypernymTargetIsInstance { source, target } =>
+                vec![("source", source.as_str().to_string()), ("target", target.as_str().to_string())],
+            ValidationError::SenseRelTargetMissing { id, rel, target } =>
+                vec![("id", id.as_str().to_string()), ("rel", rel.value().to_string()),
+                     ("target", target.as_str().to_string())],
+            ValidationError::SynsetRelTargetMissing { id, rel, target } =>
+                vec![("id", id.as_str().to_string()), ("rel", rel.value().to_string()),
+                     ("target", target.as_str().to_string())],
+            ValidationError::SatelliteSimilar { id, n } =>
+                vec![("id", id.as_str().to_string()), ("n", n.to_string())],
+            ValidationError::Lexfile { id, lexfile } =>
+                vec![("id", id.as_str().to_string()), ("lexfile", lexfile.clone())],
+            ValidationError::Transitivity { id1, id2, id3 } =>
+                vec![("id1", id1.as_str().to_string()), ("id2", id2.as_str().to_string()),
+                     ("id3", id3.as_str().to_string())],
+            ValidationError::SynsetMemberNotInEntries { id, member } |
+            ValidationError::DuplicateMember { id, member } |
+            ValidationError::SenseNotInSynsetMembers { id, member } =>
+                vec![("id", id.as_str().to_string()), ("member", member.clone())],
+            ValidationError::SimilarTargetPOS { id, target } =>
+                vec![("id", id.as_str().to_string()), ("target", target.as_str().to_string())],
+            ValidationError::DuplicateDefinition { id1, id2 } =>
+                vec![("id1", id1.as_str().to_string()), ("id2", id2.as_str().to_string())],
+            ValidationError::DuplicateILI { id1, id2, ili } =>
+                vec![("id1", id1.as_str().to_string()), ("id2", id2.as_str().to_string()),
+                     ("ili", ili.as_str().to_string())],
+            ValidationError::InvalidWikidataId { id, qid } =>
+                vec![("id", id.as_str().to_string()), ("qid", qid.clone())],
+            ValidationError::DuplicateWikidataId { id1, id2, qid } =>
+                vec![("id1", id1.as_str().to_string()), ("id2", id2.as_str().to_string()),
+                     ("qid", qid.clone())],
+            ValidationError::UserRule { rule, subject, message } =>
+                vec![("rule", rule.clone()), ("subject", subject.to_string()),
+                     ("message", message.clone())],
+            ValidationError::CircularDefinition { id, member } =>
+                vec![("id", id.as_str().to_string()), ("member", member.clone())],
+            ValidationError::UnknownWord { id, word } =>
+                vec![("id", id.as_str().to_string()), ("word", word.clone())],
+            ValidationError::UnnormalisedEntry { lemma, poskey, text, normalised } =>
+                vec![("lemma", lemma.clone()), ("poskey", poskey.as_str().to_string()),
+                     ("text", text.clone()), ("normalised", normalised.clone())],
+            ValidationError::UnnormalisedText { id, text, normalised } =>
+                vec![("id", id.as_str().to_string()), ("text", text.clone()),
+                     ("normalised", normalised.clone())],
+            ValidationError::DeprecatedSynsetExists { id } |
+            ValidationError::DeprecationLoop { id } =>
+                vec![("id", id.as_str().to_string())],
+            ValidationError::DeprecationTargetMissing { id, superseded_by } =>
+                vec![("id", id.as_str().to_string()),
+                     ("superseded_by", superseded_by.as_str().to_string())],
+        }
+    }
+
+    /// The synset, sense or entry this error is reported against - the one to edit to fix it.
+    /// For a duplicate that is the second of the pair (the first is assumed to be the original).
+    pub fn subject(&self) -> ErrorSubject {
+        match self {
+            ValidationError::InvalidSenseId { id, .. } |
+            ValidationError::SenseSynsetNotExists { id, .. } |
+            ValidationError::EntryPartOfSpeech { id, .. } |
+            ValidationError::SenseRelationPOS { id, .. } |
+            ValidationError::DuplicateSenseKey { id } |
+            ValidationError::DuplicateSyntacticBehaviour { id } |
+            ValidationError::SenseRelTargetMissing { id, .. } =>
+                ErrorSubject::Sense(id.clone()),
+            ValidationError::DuplicateSenseRelation { source, .. } |
+            ValidationError::SelfReferencingSenseRelation { source, .. } |
+            ValidationError::SenseRelationSymmetry { source, .. } =>
+                ErrorSubject::Sense(source.clone()),
+            ValidationError::DuplicateSense { id2, .. } =>
+                ErrorSubject::Sense(id2.clone()),
+            ValidationError::SynsetRelationPOS { id, .. } |
+            ValidationError::SynsetIdPos { id, .. } |
+            ValidationError::InvalidSynsetId { id } |
+            ValidationError::EmptySynset { id } |
+            ValidationError::InvalidILIId { id, .. } |
+            ValidationError::SynsetRelTargetMissing { id, .. } |
+            ValidationError::SatelliteSimilar { id, .. } |
+            ValidationError::NoHypernym { id } |
+            ValidationError::Definition { id } |
+            ValidationError::Lexfile { id, .. } |
+            ValidationError::Loop { id } |
+            ValidationError::DomainLoop { id } |
+            ValidationError::SynsetMemberNotInEntries { id, .. } |
+            ValidationError::DuplicateMember { id, .. } |
+            ValidationError::SenseNotInSynsetMembers { id, .. } |
+            ValidationError::SimilarTargetPOS { id, .. } |
+            ValidationError::HypernymInstanceConflict { id } |
+            ValidationError::InvalidWikidataId { id, .. } |
+            ValidationError::CircularDefinition { id, .. } |
+            ValidationError::UnknownWord { id, .. } |
+            ValidationError::UnnormalisedText { id, .. } |
+            ValidationError::DeprecatedSynsetExists { id } |
+            ValidationError::DeprecationTargetMissing { id, .. } |
+            ValidationError::DeprecationLoop { id } =>
+                ErrorSubject::Synset(id.clone()),
+            ValidationError::SelfReferencingSynsetRelation { source, .. } |
+            ValidationError::DuplicateSynsetRelation { source, .. } |
+            ValidationError::SynsetRelationSymmetry { source, .. } |
+            ValidationError::CrossPOSHyper { source, .. } |
+            ValidationError::HypernymTargetIsInstance { source, .. } =>
+                ErrorSubject::Synset(source.clone()),
+            ValidationError::Transitivity { id1, .. } =>
+                ErrorSubject::Synset(id1.clone()),
+            ValidationError::DuplicateDefinition { id2, .. } |
+            ValidationError::DuplicateILI { id2, .. } |
+            ValidationError::DuplicateWikidataId { id2, .. } =>
+                ErrorSubject::Synset(id2.clone()),
+            ValidationError::NoSenses { lemma, poskey } |
+            ValidationError::UnnormalisedEntry { lemma, poskey, .. } =>
+                ErrorSubject::Entry(lemma.clone(), poskey.clone()),
+            ValidationError::UserRule { subject, .. } => subject.clone(),
+        }
+    }
 }
 
 impl fmt::Display for ValidationError {
@@ -656,7 +1657,26 @@ impl fmt::Display for ValidationError {
             ValidationError::InvalidWikidataId { id, qid } =>
                 write!(f, "{} has an invalid Wikidata id {}", id.as_str(), qid),
             ValidationError::DuplicateWikidataId { id1, id2, qid } =>
-                write!(f, "{} and {} both use Wikidata id {}", id1.as_str(), id2.as_str(), qid)
+                write!(f, "{} and {} both use Wikidata id {}", id1.as_str(), id2.as_str(), qid),
+            ValidationError::UserRule { rule, subject, message } =>
+                write!(f, "{} breaks rule {}: {}", subject, rule, message),
+            ValidationError::CircularDefinition { id, member } =>
+                write!(f, "{} is defined using its own member {}", id.as_str(), member),
+            ValidationError::UnknownWord { id, word } =>
+                write!(f, "{} uses {}, which is not in the wordnet", id.as_str(), word),
+            ValidationError::UnnormalisedEntry { lemma, poskey, text, normalised } =>
+                write!(f, "Entry for {} ({}) has {:?}, which should be normalised to {:?}",
+                       lemma, poskey.as_str(), text, normalised),
+            ValidationError::UnnormalisedText { id, text, normalised } =>
+                write!(f, "{} has {:?}, which should be normalised to {:?}",
+                       id.as_str(), text, normalised),
+            ValidationError::DeprecatedSynsetExists { id } =>
+                write!(f, "{} is deprecated but is a synset again", id.as_str()),
+            ValidationError::DeprecationTargetMissing { id, superseded_by } =>
+                write!(f, "{} is superseded by {}, which is not a synset nor superseded by one",
+                       id.as_str(), superseded_by.as_str()),
+            ValidationError::DeprecationLoop { id } =>
+                write!(f, "Following what supersedes {} leads back to it", id.as_str())
         }
     }
 }
@@ -702,7 +1722,14 @@ pub fn fix<L : Lexicon>(wn : &mut L,
             true
         },
         ValidationError::DuplicateSenseKey { .. } => false,
-        ValidationError::DuplicateSyntacticBehaviour { .. } => false,
+        ValidationError::DuplicateSyntacticBehaviour { id } => {
+            wn.update_sense(id, |sense| {
+                let mut seen = HashSet::new();
+                sense.subcat.retain(|subcat| seen.insert(subcat.clone()));
+            })?;
+            change_list.mark();
+            true
+        },
         ValidationError::DuplicateSense { .. } =>  false,
         ValidationError::SynsetIdPos { .. } => false,
         ValidationError::InvalidSynsetId { .. } => false,
@@ -710,8 +1737,23 @@ pub fn fix<L : Lexicon>(wn : &mut L,
         ValidationError::InvalidILIId { .. } => false,
         ValidationError::NoSenses { .. } => false,
         ValidationError::CrossPOSHyper { .. } => false,
-        ValidationError::SenseRelTargetMissing { .. } => false,
-        ValidationError::SynsetRelTargetMissing { .. } => false,
+        ValidationError::SenseRelTargetMissing { id, target, .. } => {
+            // Only the relations that may target either a sense or a synset are stored
+            // unresolved, so only they can dangle
+            wn.update_sense(id, |sense| {
+                sense.domain_topic.retain(|t| t.as_str() != target.as_str());
+                sense.domain_region.retain(|t| t.as_str() != target.as_str());
+                sense.exemplifies.retain(|t| t.as_str() != target.as_str());
+                sense.other.retain(|t| t.as_str() != target.as_str());
+            })?;
+            change_list.mark();
+            true
+        },
+        ValidationError::SynsetRelTargetMissing { id, target, .. } => {
+            wn.remove_rel(id, target)?;
+            change_list.mark();
+            true
+        },
         ValidationError::SatelliteSimilar { .. } =>  false,
         ValidationError::NoHypernym { .. } => false,
         ValidationError::Definition { .. } => false,
@@ -732,7 +1774,12 @@ pub fn fix<L : Lexicon>(wn : &mut L,
         },
         ValidationError::Loop { .. } =>  false,
         ValidationError::DomainLoop { .. } =>  false,
-        ValidationError::SynsetMemberNotInEntries { .. } => false,
+        ValidationError::SynsetMemberNotInEntries { id, member } => {
+            // Not `change_members`, which would delete the (missing) entry too
+            wn.update_synset(id, |synset| synset.members.retain(|m| m != member))?;
+            change_list.mark();
+            true
+        },
         ValidationError::DuplicateMember { id, .. } => {
             match wn.synset_by_id(id)? {
                 Some(synset) => {
@@ -746,14 +1793,122 @@ pub fn fix<L : Lexicon>(wn : &mut L,
                 None => false
             }
         },
-        ValidationError::SenseNotInSynsetMembers { .. } => false,
+        ValidationError::SenseNotInSynsetMembers { id, member } => {
+            // Not `change_members`, which would add another sense for the member
+            wn.update_synset(id, |synset| synset.members.push(member.clone()))?;
+            change_list.mark();
+            true
+        },
         ValidationError::SimilarTargetPOS { .. } => false,
         ValidationError::HypernymInstanceConflict { .. } => false,
         ValidationError::HypernymTargetIsInstance { .. } => false,
         ValidationError::DuplicateDefinition { .. } => false,
         ValidationError::DuplicateILI { .. } => false,
-        ValidationError::InvalidWikidataId { .. } => false,
+        ValidationError::InvalidWikidataId { id, qid } => {
+            wn.update_synset(id, |synset| synset.wikidata.retain(|q| q != qid))?;
+            change_list.mark();
+            true
+        },
         ValidationError::DuplicateWikidataId { .. } => false,
+        ValidationError::UserRule { .. } => false,
+        ValidationError::CircularDefinition { .. } => false,
+        ValidationError::UnknownWord { .. } => false,
+        // Refused if the normalised lemma is already a member of one of the entry's synsets:
+        // that is a duplicate to merge by hand
+        ValidationError::UnnormalisedEntry { lemma, poskey, text, normalised } if text == lemma =>
+            change_manager::rename_lemma(wn, lemma, poskey, normalised.clone(), change_list).is_ok(),
+        ValidationError::UnnormalisedEntry { lemma, poskey, text, normalised } => {
+            if wn.get_forms(lemma, poskey)?.contains(text) {
+                wn.remove_form(lemma, poskey, text)?;
+                wn.add_form(lemma, poskey, normalised.clone())?;
+                change_list.mark();
+                true
+            } else {
+                false
+            }
+        },
+        ValidationError::UnnormalisedText { id, text, normalised } => {
+            let mut changed = false;
+            wn.update_synset(id, |synset| {
+                for definition in synset.definition.iter_mut().filter(|d| *d == text) {
+                    *definition = normalised.clone();
+                    changed = true;
+                }
+                for example in synset.example.iter_mut().filter(|ex| ex.text == *text) {
+                    example.text = normalised.clone();
+                    changed = true;
+                }
+            })?;
+            if changed {
+                change_list.mark();
+            }
+            changed
+        },
+        // Either the record or the synset is wrong, which only the editor knows
+        ValidationError::DeprecatedSynsetExists { .. } => false,
+        ValidationError::DeprecationTargetMissing { .. } => false,
+        ValidationError::DeprecationLoop { .. } => false,
+    })
+}
+
+/// The synsets `fix` would change to fix `error` (empty if it has no fix), so they can be
+/// snapshotted beforehand. A sense's synset stands in for the sense.
+pub(crate) fn fix_touches<L : Lexicon>(wn : &L, error : &ValidationError) -> Result<Vec<SynsetId>> {
+    let senses = |ids : &[&SenseId]| -> Result<Vec<SynsetId>> {
+        let mut synsets = Vec::new();
+        for id in ids {
+            synsets.extend(synset_of_sense(wn, id)?);
+        }
+        Ok(synsets)
+    };
+    let sense_or_synset = |source : &SenseId, target : &SenseOrSynsetId| -> Result<Vec<SynsetId>> {
+        let mut synsets = senses(&[source])?;
+        match target {
+            SenseOrSynsetId::Sense(target) => synsets.extend(senses(&[target])?),
+            SenseOrSynsetId::Synset(target) => synsets.push(target.clone()),
+        }
+        Ok(synsets)
+    };
+    Ok(match error {
+        ValidationError::InvalidSenseId { id, .. } |
+        ValidationError::DuplicateSyntacticBehaviour { id } |
+        ValidationError::SenseRelTargetMissing { id, .. } => senses(&[id])?,
+        ValidationError::SelfReferencingSenseRelation { source, target, .. } |
+        ValidationError::DuplicateSenseRelation { source, target, .. } |
+        ValidationError::SenseRelationSymmetry { source, target, .. } =>
+            sense_or_synset(source, target)?,
+        ValidationError::SelfReferencingSynsetRelation { source, target, .. } |
+        ValidationError::DuplicateSynsetRelation { source, target, .. } |
+        ValidationError::SynsetRelationSymmetry { source, target, .. } =>
+            vec![source.clone(), target.clone()],
+        ValidationError::Transitivity { id1, id2, id3 } =>
+            vec![id1.clone(), id2.clone(), id3.clone()],
+        ValidationError::SynsetRelTargetMissing { id, .. } |
+        ValidationError::SynsetMemberNotInEntries { id, .. } |
+        ValidationError::DuplicateMember { id, .. } |
+        ValidationError::SenseNotInSynsetMembers { id, .. } |
+        ValidationError::InvalidWikidataId { id, .. } |
+        ValidationError::UnnormalisedText { id, .. } => vec![id.clone()],
+        // A respelt lemma renames the senses, and with them those of the satellites that
+        // take it as their head word
+        ValidationError::UnnormalisedEntry { lemma, poskey, .. } => {
+            let mut synsets = Vec::new();
+            for (pos, entry) in wn.entry_by_lemma_with_pos(lemma)? {
+                if pos != *poskey {
+                    continue;
+                }
+                for sense in entry.sense.iter() {
+                    if let Some(synset) = wn.synset_by_id(&sense.synset)? {
+                        synsets.push(sense.synset.clone());
+                        if synset.part_of_speech == PartOfSpeech::a {
+                            synsets.extend(synset.similar.iter().cloned());
+                        }
+                    }
+                }
+            }
+            synsets
+        },
+        _ => Vec::new()
     })
 }
 
@@ -882,6 +2037,49 @@ mod tests {
                 ((*id1 == a && *id2 == b) || (*id1 == b && *id2 == a)))));
     }
 
+    #[test]
+    fn test_deprecations() {
+        let mut wn = LexiconHashMapBackend::new();
+        let mut change_list = change_manager::ChangeList::new();
+        let a = add_noun(&mut wn, "00000065-n", "surviving synset", 'n', &mut change_list);
+        let b = add_noun(&mut wn, "00000066-n", "restored synset", 'n', &mut change_list);
+        let record = |id : &str, superseded_by : &str| DeprecationRecord(
+            format!("ewn-{}", id), String::new(), format!("ewn-{}", superseded_by),
+            String::new(), "test".to_owned());
+        for (id, superseded_by) in [
+            ("00000067-n", "00000065-n"), // fine
+            ("00000068-n", "00000067-n"), // fine, through 00000067-n
+            ("00000069-n", "00000070-n"), // 00000070-n is gone
+            ("00000071-n", "00000072-n"), // a loop
+            ("00000072-n", "00000071-n"),
+            ("00000073-n", "00000071-n"), // into the loop, reported there
+            ("00000066-n", "00000065-n"), // but 00000066-n is a synset again
+        ] {
+            wn.deprecations_push(record(id, superseded_by)).unwrap();
+        }
+
+        let errors : Vec<(&str, String)> = validate_errors(&wn).iter()
+            .filter(|e| e.name().starts_with("Deprecat"))
+            .map(|e| (e.name(), e.subject().to_string())).collect();
+        assert_eq!(errors, vec![
+            ("DeprecatedSynsetExists", b.as_str().to_owned()),
+            ("DeprecationTargetMissing", "00000069-n".to_owned()),
+            ("DeprecationLoop", "00000071-n".to_owned()),
+            ("DeprecationLoop", "00000072-n".to_owned()),
+        ]);
+
+        let mut validator = IncrementalValidator::new(&wn, &mut NullProgress).unwrap();
+        assert_eq!(validator.errors().iter().filter(|e| e.name().starts_with("Deprecat"))
+            .count(), 4);
+        // Deleting the synset everything else resolves to breaks those chains too
+        change_manager::delete_synset(&mut wn, &a, None, "test".to_owned(), &mut change_list)
+            .unwrap();
+        validator.update(&wn, std::slice::from_ref(&a), &[]).unwrap();
+        let missing = validator.errors().iter()
+            .filter(|e| e.name() == "DeprecationTargetMissing").count();
+        assert_eq!(missing, 4);
+    }
+
     #[test]
     fn test_fix_duplicate_synset_relation() {
         let mut wn = LexiconHashMapBackend::new();
@@ -949,4 +2147,224 @@ mod tests {
         assert_eq!(links.iter().filter(|(r, t)|
             *r == SenseRelType::Antonym && *t == UnresolvedSenseOrSynsetId::Sense(sense2.clone())).count(), 1);
     }
+
+    #[test]
+    fn test_fix_dangling_relations() {
+        let mut wn = LexiconHashMapBackend::new();
+        let mut change_list = change_manager::ChangeList::new();
+        let a = add_noun(&mut wn, "00000095-n", "dangling synset", 'n', &mut change_list);
+        let missing = SynsetId::new("00000096-n");
+        let sense = change_manager::add_entry(&mut wn, a.clone(), "danglingword".to_owned(),
+            PosKey::new("n".to_owned()), Vec::new(), None, &mut change_list).unwrap().unwrap();
+        wn.update_synset(&a, |ss| { ss.hypernym.push(missing.clone()); }).unwrap();
+        wn.update_sense(&sense, |s| {
+            s.domain_topic.push(UnresolvedSenseOrSynsetId::Unresolved("00000097-n".to_owned()));
+        }).unwrap();
+
+        let errors = validate_errors(&wn);
+        let synset_rel = errors.iter().find(|e| matches!(e,
+            ValidationError::SynsetRelTargetMissing { id, target, .. } if *id == a && *target == missing))
+            .expect("expected a SynsetRelTargetMissing error");
+        let sense_rel = errors.iter().find(|e| matches!(e,
+            ValidationError::SenseRelTargetMissing { id, .. } if *id == sense))
+            .expect("expected a SenseRelTargetMissing error");
+        assert_eq!(fix_touches(&wn, sense_rel).unwrap(), vec![a.clone()]);
+
+        assert!(fix(&mut wn, synset_rel, &mut change_list).unwrap());
+        assert!(fix(&mut wn, sense_rel, &mut change_list).unwrap());
+
+        assert!(wn.synset_by_id(&a).unwrap().unwrap().hypernym.is_empty());
+        assert!(wn.sense_links_from_id(&sense).unwrap().is_empty());
+        assert!(!validate_errors(&wn).iter().any(|e| matches!(e,
+            ValidationError::SynsetRelTargetMissing { .. } | ValidationError::SenseRelTargetMissing { .. })));
+    }
+
+    #[test]
+    fn test_incremental_validator() {
+        use crate::automaton::{apply_automaton, Action, SynsetRef};
+        let mut wn = LexiconHashMapBackend::new();
+        let mut change_list = change_manager::ChangeList::new();
+        let a = add_noun(&mut wn, "00000050-n", "synset a", 'n', &mut change_list);
+        let b = add_noun(&mut wn, "00000051-n", "synset b", 'n', &mut change_list);
+        let c = add_noun(&mut wn, "00000052-n", "synset c", 'n', &mut change_list);
+        let hypernym = |source : &SynsetId, target : &SynsetId| Action::AddRelation {
+            source: SynsetRef::Id(source.clone()),
+            source_sense: None,
+            relation: "hypernym".to_string(),
+            target: SynsetRef::Id(target.clone()),
+            target_sense: None,
+            source_lemma: None,
+            target_lemma: None
+        };
+        fn messages(errors : Vec<ValidationError>) -> Vec<String> {
+            let mut messages : Vec<String> = errors.iter().map(|e| e.to_string()).collect();
+            messages.sort();
+            messages.dedup();
+            messages
+        }
+        let mut validator = IncrementalValidator::new(&wn, &mut NullProgress).unwrap();
+        assert_eq!(messages(validator.errors()), messages(validate_errors(&wn)));
+
+        let batches = vec![
+            vec![
+                Action::AddEntry {
+                    synset: SynsetRef::Id(a.clone()),
+                    lemma: "cat".to_string(),
+                    pos: PosKey::new("n".to_string()),
+                    subcat: Vec::new(),
+                    name: None
+                },
+                Action::Definition {
+                    synset: SynsetRef::Id(b.clone()),
+                    definition: "synset a".to_string()
+                }
+            ],
+            vec![hypernym(&a, &b), hypernym(&b, &c), hypernym(&c, &a)],
+            vec![
+                Action::DeleteRelation {
+                    source: SynsetRef::Id(c.clone()),
+                    source_sense: None,
+                    relation: None,
+                    target: SynsetRef::Id(a.clone()),
+                    target_sense: None,
+                    source_lemma: None,
+                    target_lemma: None
+                },
+                Action::Definition {
+                    synset: SynsetRef::Id(b.clone()),
+                    definition: "synset b".to_string()
+                }
+            ],
+            vec![Action::DeleteSynset {
+                synset: SynsetRef::Id(b.clone()),
+                reason: "test".to_string(),
+                superseded_by: None
+            }]
+        ];
+        for batch in batches {
+            apply_automaton(batch, &mut wn, &mut change_list).unwrap();
+            validator.catch_up(&wn).unwrap();
+            let errors = messages(validate_errors(&wn));
+            assert!(!errors.is_empty());
+            assert_eq!(messages(validator.errors()), errors);
+        }
+    }
+
+    #[test]
+    fn test_incremental_validator_rules() {
+        use crate::automaton::{apply_automaton, Action, SynsetRef};
+        let mut wn = LexiconHashMapBackend::new();
+        let mut change_list = change_manager::ChangeList::new();
+        let a = add_noun(&mut wn, "00000050-n", "synset a", 'n', &mut change_list);
+        let b = add_noun(&mut wn, "00000051-n", "synset b", 'n', &mut change_list);
+        let c = add_noun(&mut wn, "00000052-n", "synset c", 'n', &mut change_list);
+        let d = add_noun(&mut wn, "00000053-n", "synset d", 'n', &mut change_list);
+        let hypernyms = [(&b, &a), (&c, &b), (&d, &c)].into_iter()
+            .map(|(source, target)| Action::AddRelation {
+                source: SynsetRef::Id(source.clone()),
+                source_sense: None,
+                relation: "hypernym".to_string(),
+                target: SynsetRef::Id(target.clone()),
+                target_sense: None,
+                source_lemma: None,
+                target_lemma: None
+            }).collect();
+        apply_automaton(hypernyms, &mut wn, &mut change_list).unwrap();
+        let rules = UserRules::from_yaml(
+            "- name: under-a\n  select: synset\n  assert:\n    descends_from: 00000050-n\n\
+             - name: no-x\n  select: entry\n  assert:\n    lemma_not_matches: '^x'\n").unwrap();
+        fn messages(errors : Vec<ValidationError>) -> Vec<String> {
+            let mut messages : Vec<String> = errors.iter().map(|e| e.to_string()).collect();
+            messages.sort();
+            messages
+        }
+        let expected = |wn : &LexiconHashMapBackend| {
+            let mut errors = validate_errors(wn);
+            errors.extend(rules.check(wn).unwrap());
+            messages(errors)
+        };
+        let mut validator = IncrementalValidator::new(&wn, &mut NullProgress).unwrap();
+        validator.set_rules(&wn, rules.clone()).unwrap();
+        assert_eq!(messages(validator.errors()), expected(&wn));
+
+        let batches = vec![
+            // d is two synsets below the one edited, but no longer under a either
+            vec![Action::DeleteRelation {
+                source: SynsetRef::Id(b.clone()),
+                source_sense: None,
+                relation: None,
+                target: SynsetRef::Id(a.clone()),
+                target_sense: None,
+                source_lemma: None,
+                target_lemma: None
+            }],
+            vec![Action::AddEntry {
+                synset: SynsetRef::Id(d.clone()),
+                lemma: "xylophone".to_string(),
+                pos: PosKey::new("n".to_string()),
+                subcat: Vec::new(),
+                name: None
+            }],
+            vec![Action::DeleteSynset {
+                synset: SynsetRef::Id(d.clone()),
+                reason: "test".to_string(),
+                superseded_by: None
+            }]
+        ];
+        for batch in batches {
+            apply_automaton(batch, &mut wn, &mut change_list).unwrap();
+            validator.catch_up(&wn).unwrap();
+            let errors = expected(&wn);
+            assert!(errors.iter().any(|e| e.contains("under-a")), "{:?}", errors);
+            assert_eq!(messages(validator.errors()), errors);
+        }
+        assert!(validator.errors().iter().any(|e| e.to_string().contains("00000052-n")));
+    }
+
+    #[test]
+    fn test_unnormalised_text() {
+        let mut wn = LexiconHashMapBackend::new();
+        let mut change_list = change_manager::ChangeList::new();
+        let a = add_noun(&mut wn, "00000080-n", "a small caf\u{e9}\u{a0}or bar", 'n',
+            &mut change_list);
+        change_manager::add_ex(&mut wn, &a, "it\u{2019}s open".to_owned(), None,
+            &mut change_list);
+        // An entry added directly, as `add_entry` would normalise it
+        let decomposed = "cafe\u{301}";
+        let mut entry = Entry::new();
+        entry.sense.push(Sense::new(SenseId::new("cafe%1:06:00::".to_owned()), a.clone()));
+        entry.form.push("cafe\u{301}s".to_owned());
+        wn.insert_entry(decomposed.to_owned(), PosKey::new("n".to_owned()), entry).unwrap();
+        wn.update_synset(&a, |ss| { ss.members.push(decomposed.to_owned()); }).unwrap();
+
+        let errors = validate_errors(&wn);
+        let unnormalised : Vec<&ValidationError> = errors.iter()
+            .filter(|e| e.name().starts_with("Unnormalised")).collect();
+        assert_eq!(unnormalised.len(), 4);
+        assert!(unnormalised.iter().any(|e| matches!(e,
+            ValidationError::UnnormalisedText { text, normalised, .. }
+                if text == "it\u{2019}s open" && normalised == "it's open")));
+        assert!(unnormalised.iter().any(|e| matches!(e,
+            ValidationError::UnnormalisedEntry { text, normalised, .. }
+                if text == decomposed && normalised == "caf\u{e9}")));
+        let validator = IncrementalValidator::new(&wn, &mut NullProgress).unwrap();
+        assert_eq!(validator.errors().iter().filter(|e| e.name().starts_with("Unnormalised"))
+            .count(), 4);
+
+        for error in unnormalised.into_iter().cloned().collect::<Vec<_>>() {
+            assert!(fix(&mut wn, &error, &mut change_list).unwrap());
+        }
+        assert!(!validate_errors(&wn).iter().any(|e| e.name().starts_with("Unnormalised")));
+        let synset = wn.synset_by_id(&a).unwrap().unwrap();
+        assert_eq!(synset.definition, vec!["a small caf\u{e9} or bar".to_owned()]);
+        assert_eq!(synset.members, vec!["caf\u{e9}".to_owned()]);
+
+        // Lookups find the entry under either form, and with a curly apostrophe
+        assert_eq!(wn.entry_by_lemma(decomposed).unwrap().len(), 1);
+        assert_eq!(wn.entry_by_lemma("caf\u{e9}").unwrap()[0].form, vec!["caf\u{e9}s".to_owned()]);
+        change_manager::add_entry(&mut wn, a.clone(), "rock \u{2019}n\u{2019} roll".to_owned(),
+            PosKey::new("n".to_owned()), Vec::new(), None, &mut change_list).unwrap();
+        assert_eq!(wn.entry_by_lemma("rock 'n' roll").unwrap().len(), 1);
+        assert_eq!(wn.entry_by_lemma("rock \u{2019}n\u{2019} roll").unwrap().len(), 1);
+    }
 }
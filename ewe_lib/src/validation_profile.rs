@@ -0,0 +1,180 @@
+//! Per-project validation profiles - which of `validate`'s checks block a save, which are only
+//! worth a warning, and which a project has opted out of altogether (e.g. `NoHypernym` or
+//! `DuplicateDefinition` for a young wordnet that can't satisfy them yet). A profile is the
+//! `[validation]` table of the project's `settings.toml`, keyed by check name
+//! (`ValidationError::name`):
+//!
+//! ```toml
+//! [validation]
+//! NoHypernym = "warning"
+//! DuplicateDefinition = "off"
+//! ```
+//!
+//! Any check not named there is an error, so an empty profile behaves exactly as validation
+//! always has - except the opt-in checks (`OPT_IN_CHECKS`, e.g. `UnknownWord`), which are off
+//! unless named, and the normalisation checks (`WARNING_CHECKS`), which are warnings.
+
+use crate::validate::{ValidationError, CHECKS, OPT_IN_CHECKS, WARNING_CHECKS};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::Path;
+
+/// How a profile treats one kind of `ValidationError`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    /// Reported, and blocks saving (unless forced)
+    Error,
+    /// Reported, but does not block saving
+    Warning,
+    /// Not reported at all
+    Off,
+}
+
+/// The severity a project gives each check, by check name. See the module documentation.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct ValidationProfile(pub BTreeMap<String, Severity>);
+
+/// The part of `settings.toml` a profile is read from; every other setting is ignored.
+#[derive(Deserialize)]
+struct SettingsFile {
+    #[serde(default)]
+    validation: ValidationProfile,
+}
+
+impl ValidationProfile {
+    /// The severity of `error` under this profile
+    pub fn severity(&self, error: &ValidationError) -> Severity {
+        self.check_severity(error.name())
+    }
+
+    /// The severity of the check named `check` under this profile
+    pub fn check_severity(&self, check: &str) -> Severity {
+        match self.0.get(check) {
+            Some(severity) => *severity,
+            None if OPT_IN_CHECKS.contains(&check) => Severity::Off,
+            None if WARNING_CHECKS.contains(&check) => Severity::Warning,
+            None => Severity::Error,
+        }
+    }
+
+    /// Whether this profile turns on any of the opt-in checks - if not, there is nothing for
+    /// `crate::definitions::opt_in_errors` to look for, and no need to call it
+    pub fn opts_in(&self) -> bool {
+        OPT_IN_CHECKS
+            .iter()
+            .any(|check| self.check_severity(check) != Severity::Off)
+    }
+
+    /// Splits what `validate` found into `(errors, warnings)`, dropping anything switched off
+    pub fn classify(
+        &self,
+        found: Vec<ValidationError>,
+    ) -> (Vec<ValidationError>, Vec<ValidationError>) {
+        let mut errors = Vec::new();
+        let mut warnings = Vec::new();
+        for error in found {
+            match self.severity(&error) {
+                Severity::Error => errors.push(error),
+                Severity::Warning => warnings.push(error),
+                Severity::Off => {}
+            }
+        }
+        (errors, warnings)
+    }
+
+    /// Checks every name in the profile is a real check, so a typo doesn't silently leave a
+    /// check at its default
+    pub fn check(&self) -> Result<(), String> {
+        match self.0.keys().find(|name| !CHECKS.contains(&name.as_str())) {
+            Some(name) => Err(format!(
+                "Unknown validation check {} (expected one of {})",
+                name,
+                CHECKS.join(", ")
+            )),
+            None => Ok(()),
+        }
+    }
+
+    /// Reads the `[validation]` table from the text of a `settings.toml`
+    pub fn from_settings(settings: &str) -> Result<ValidationProfile, String> {
+        let settings: SettingsFile = toml::from_str(settings).map_err(|e| e.to_string())?;
+        settings.validation.check()?;
+        Ok(settings.validation)
+    }
+
+    /// The profile for the YAML source at `source`, read from the project's `settings.toml`:
+    /// the first found in `source` itself or the two directories above it (so both a bare
+    /// source directory and the usual `<project>/src/yaml/` layout work). The default (see the
+    /// module documentation) if there is none.
+    pub fn for_source(source: impl AsRef<Path>) -> Result<ValidationProfile, String> {
+        let source = source.as_ref();
+        for dir in [source.to_path_buf(), source.join(".."), source.join("../..")] {
+            let path = dir.join("settings.toml");
+            if path.is_file() {
+                let settings = std::fs::read_to_string(&path)
+                    .map_err(|e| format!("Could not read {}: {}", path.display(), e))?;
+                return ValidationProfile::from_settings(&settings)
+                    .map_err(|e| format!("Could not read {}: {}", path.display(), e));
+            }
+        }
+        Ok(ValidationProfile::default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::wordnet::SynsetId;
+
+    #[test]
+    fn test_profile_from_settings() {
+        let profile = ValidationProfile::from_settings(
+            "database = \"wordnet.db\"\n\n[validation]\nNoHypernym = \"warning\"\nDuplicateDefinition = \"off\"\n",
+        )
+        .unwrap();
+        let no_hypernym = ValidationError::NoHypernym {
+            id: SynsetId::new("00001740-n"),
+        };
+        let duplicate = ValidationError::DuplicateDefinition {
+            id1: SynsetId::new("00001740-n"),
+            id2: SynsetId::new("00001741-n"),
+        };
+        let empty = ValidationError::EmptySynset {
+            id: SynsetId::new("00001740-n"),
+        };
+        assert_eq!(profile.severity(&no_hypernym), Severity::Warning);
+        assert_eq!(profile.severity(&empty), Severity::Error);
+        let unknown = ValidationError::UnknownWord {
+            id: SynsetId::new("00001740-n"),
+            word: "zyzzyva".to_string(),
+        };
+        assert_eq!(profile.severity(&unknown), Severity::Off);
+        let opted_in =
+            ValidationProfile::from_settings("[validation]\nUnknownWord = \"warning\"\n").unwrap();
+        assert_eq!(opted_in.severity(&unknown), Severity::Warning);
+        let unnormalised = ValidationError::UnnormalisedText {
+            id: SynsetId::new("00001740-n"),
+            text: "it\u{2019}s".to_string(),
+            normalised: "it's".to_string(),
+        };
+        assert_eq!(profile.severity(&unnormalised), Severity::Warning);
+        let strict =
+            ValidationProfile::from_settings("[validation]\nUnnormalisedText = \"error\"\n")
+                .unwrap();
+        assert_eq!(strict.severity(&unnormalised), Severity::Error);
+        let (errors, warnings) = profile.classify(vec![no_hypernym, duplicate, empty]);
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].name(), "EmptySynset");
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].name(), "NoHypernym");
+
+        assert_eq!(
+            ValidationProfile::from_settings("database = \"wordnet.db\"\n").unwrap(),
+            ValidationProfile::default()
+        );
+        assert!(ValidationProfile::from_settings("[validation]\nNoHypernyms = \"off\"\n").is_err());
+        assert!(ValidationProfile::from_settings("[validation]\nNoHypernym = \"never\"\n").is_err());
+    }
+}
@@ -141,6 +141,14 @@ pub trait Entries : Sized {
         })
     }
 
+    fn remove_form(&mut self, lemma : &str, pos : &PosKey, form : &str) -> Result<bool> {
+        self.update_entry(lemma, pos, |e| {
+            let len = e.form.len();
+            e.form.retain(|f| f != form);
+            e.form.len() != len
+        })
+    }
+
     fn get_pronunciations(&self, lemma : &str, pos : &PosKey) -> Result<Vec<Pronunciation>> {
         if let Some(e) = self.entry(lemma, pos)? {
             Ok(e.pronunciation.iter().map(|x| x.clone()).collect())
@@ -157,6 +165,14 @@ pub trait Entries : Sized {
         })
     }
 
+    fn remove_pronunciation(&mut self, lemma : &str, pos : &PosKey, pronunciation : &Pronunciation) -> Result<bool> {
+        self.update_entry(lemma, pos, |e| {
+            let len = e.pronunciation.len();
+            e.pronunciation.retain(|x| x != pronunciation);
+            e.pronunciation.len() != len
+        })
+    }
+
     fn get_sense<'a>(&'a self, lemma : &str, 
                          synset_id : &SynsetId) -> Result<Vec<Cow<'a, Sense>>> {
         let mut senses = Vec::new();
@@ -192,6 +208,15 @@ pub trait Entries : Sized {
         })
     }
 
+    fn update_sense(&mut self, lemma : &str, key : &PosKey,
+                    sense_id : &SenseId, f : impl FnOnce(&mut Sense)) -> Result<()> {
+        self.update_entry(lemma, key, |entry| {
+            if let Some(sense) = entry.sense.iter_mut().find(|s| s.id == *sense_id) {
+                f(sense);
+            }
+        })
+    }
+
     fn entries<'a>(&'a self) -> Result<impl Iterator<Item=Result<(String, PosKey, Cow<'a, Entry>)>> + 'a>;
 
     fn into_entries(self) -> Result<impl Iterator<Item=Result<(String, PosKey, Entry)>>>;
@@ -0,0 +1,90 @@
+//! A small lexicon for the export and import tests: each importer's round-trip test writes it
+//! out in its format, reads it back and checks what came through.
+
+use crate::wordnet::lexicon::finalize_bulk_load;
+use crate::wordnet::{
+    Entry, Example, Lexicon, LexiconHashMapBackend, LexiconMetadata, PartOfSpeech, PosKey,
+    Pronunciation, Sense, SenseId, Synset, SynsetId, ILIID,
+};
+
+/// "ewe" (`00000002-n`, with an example) has the hypernym "sheep" (`00000001-n`, with an ILI,
+/// a Wikidata id, a form and a pronunciation), from which the verb "bleat" (`00000003-v`, in
+/// `verb.communication`, with a subcat frame and a sentence) derives.
+pub(crate) fn round_trip_fixture() -> LexiconHashMapBackend {
+    let mut wn = LexiconHashMapBackend::new();
+    let mut sheep = Synset::new(PartOfSpeech::n);
+    sheep.definition.push("woolly ruminant".to_string());
+    sheep.members.push("sheep".to_string());
+    sheep.ili = Some(ILIID::new("i1"));
+    sheep.wikidata.push("Q7368".to_string());
+    wn.insert_synset(
+        "noun.animal".to_string(),
+        SynsetId::new("00000001-n"),
+        sheep,
+    )
+    .unwrap();
+    let mut ewe = Synset::new(PartOfSpeech::n);
+    ewe.definition.push("a female sheep".to_string());
+    ewe.example.push(Example::new(
+        "the ewe and her lamb".to_string(),
+        Some("Anon".to_string()),
+    ));
+    ewe.members.push("ewe".to_string());
+    ewe.hypernym.push(SynsetId::new("00000001-n"));
+    wn.insert_synset("noun.animal".to_string(), SynsetId::new("00000002-n"), ewe)
+        .unwrap();
+    let mut bleat = Synset::new(PartOfSpeech::v);
+    bleat.definition.push("cry like a sheep".to_string());
+    bleat.members.push("bleat".to_string());
+    wn.insert_synset(
+        "verb.communication".to_string(),
+        SynsetId::new("00000003-v"),
+        bleat,
+    )
+    .unwrap();
+    wn.frames_set(vec![("via".to_string(), "Somebody ----s".to_string())])
+        .unwrap();
+
+    let mut entry = Entry::new();
+    entry.sense.push(Sense::new(
+        SenseId::new("sheep%1:05:00::"),
+        SynsetId::new("00000001-n"),
+    ));
+    entry.form.push("sheeps".to_string());
+    entry.pronunciation.push(Pronunciation {
+        value: "ʃiːp".to_string(),
+        variety: Some("GB".to_string()),
+    });
+    wn.insert_entry("sheep".to_string(), PosKey::new("n"), entry)
+        .unwrap();
+    let mut entry = Entry::new();
+    entry.sense.push(Sense::new(
+        SenseId::new("ewe%1:05:00::"),
+        SynsetId::new("00000002-n"),
+    ));
+    wn.insert_entry("ewe".to_string(), PosKey::new("n"), entry)
+        .unwrap();
+    let mut entry = Entry::new();
+    let mut sense = Sense::new(SenseId::new("bleat%2:32:00::"), SynsetId::new("00000003-v"));
+    sense.subcat.push("via".to_string());
+    sense.set_sent(vec!["The sheep %s".to_string()]);
+    sense.derivation.push(SenseId::new("sheep%1:05:00::"));
+    entry.sense.push(sense);
+    wn.insert_entry("bleat".to_string(), PosKey::new("v"), entry)
+        .unwrap();
+    finalize_bulk_load(&mut wn).unwrap();
+    wn
+}
+
+/// The metadata the round-trip tests write the fixture with, for the formats that carry it
+pub(crate) fn round_trip_metadata() -> LexiconMetadata {
+    LexiconMetadata {
+        id_prefix: "oewn".to_string(),
+        label: "Test Wordnet".to_string(),
+        language: "en".to_string(),
+        email: Some("test@example.com".to_string()),
+        license: "https://creativecommons.org/licenses/by/4.0".to_string(),
+        version: "2025".to_string(),
+        url: None,
+    }
+}
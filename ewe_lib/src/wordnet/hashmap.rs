@@ -1,4 +1,5 @@
 use std::collections::{BTreeMap, HashMap};
+use std::hash::Hash;
 use crate::rels::{SenseRelType,SynsetRelType};
 use crate::wordnet::*;
 use crate::wordnet::entry::BTEntries;
@@ -22,9 +23,57 @@ pub struct LexiconHashMapBackend {
     deprecations : Vec<DeprecationRecord>,
     frames : Vec<(String, String)>,
     changelog : Vec<(u64, String)>,
+    last_saved_changelog_id : Option<u64>,
+    checkpoint : Option<Box<Checkpoint>>
+}
+
+/// What `Lexicon::checkpoint` captured. Rather than cloning the whole lexicon up front, each
+/// keyed map records a key's prior value (`None` meaning it was absent) the first time it's
+/// written after the checkpoint, so taking and rolling back a checkpoint costs in proportion
+/// to what the batch touched rather than to the size of the lexicon. The append-only change
+/// log only needs its length; the deprecation list is copied the first time it's written.
+#[derive(Clone)]
+struct Checkpoint {
+    entries : HashMap<char, Option<BTEntries>>,
+    synsets : HashMap<String, Option<BTSynsets>>,
+    synset_id_to_lexfile : HashMap<SynsetId, Option<String>>,
+    sense_links_to : HashMap<SenseId, Option<Vec<(SenseRelType, SenseId)>>>,
+    links_to : HashMap<SynsetId, Option<Vec<(SynsetRelType, SynsetId)>>>,
+    sense_id_to_lemma_pos : HashMap<SenseId, Option<(String, PosKey)>>,
+    deprecations : Option<Vec<DeprecationRecord>>,
+    frames : Vec<(String, String)>,
+    changelog_len : usize,
     last_saved_changelog_id : Option<u64>
 }
 
+/// Records `current` as `key`'s pre-checkpoint value, unless an earlier write already did.
+fn record_prior<K : Hash + Eq + Clone, V : Clone>(journal : &mut HashMap<K, Option<V>>,
+    key : &K, current : Option<&V>) {
+    if !journal.contains_key(key) {
+        journal.insert(key.clone(), current.cloned());
+    }
+}
+
+/// Puts every journalled key of `live` back to its pre-checkpoint value.
+fn restore_prior<K : Hash + Eq, V>(journal : HashMap<K, Option<V>>, live : &mut HashMap<K, V>) {
+    for (key, prior) in journal {
+        match prior {
+            Some(v) => { live.insert(key, v); },
+            None => { live.remove(&key); }
+        }
+    }
+}
+
+/// `restore_prior` for the `BTreeMap`-backed buckets.
+fn restore_prior_bt<K : Ord, V>(journal : HashMap<K, Option<V>>, live : &mut BTreeMap<K, V>) {
+    for (key, prior) in journal {
+        match prior {
+            Some(v) => { live.insert(key, v); },
+            None => { live.remove(&key); }
+        }
+    }
+}
+
 impl LexiconHashMapBackend {
     pub fn new() -> LexiconHashMapBackend {
         LexiconHashMapBackend {
@@ -37,7 +86,8 @@ impl LexiconHashMapBackend {
             deprecations : Vec::new(),
             frames : Vec::new(),
             changelog : Vec::new(),
-            last_saved_changelog_id : None
+            last_saved_changelog_id : None,
+            checkpoint : None
         }
     }
     #[cfg(test)]
@@ -55,6 +105,9 @@ impl Lexicon for LexiconHashMapBackend {
         Ok(self.entries.get(&key).map(|x| Cow::Borrowed(x)))
     }
     fn entries_insert(&mut self, key : char, entries : BTEntries) -> Result<()> {
+        if let Some(cp) = self.checkpoint.as_mut() {
+            record_prior(&mut cp.entries, &key, self.entries.get(&key));
+        }
         self.entries.insert(key, entries);
         Ok(())
     }
@@ -62,6 +115,9 @@ impl Lexicon for LexiconHashMapBackend {
         Ok(self.entries.iter().map(|(k, v)| Ok((*k, Cow::Borrowed(v)))))
     }
     fn entries_update<X>(&mut self, key : char, f : impl FnOnce(&mut BTEntries) -> X) -> Result<X> {
+        if let Some(cp) = self.checkpoint.as_mut() {
+            record_prior(&mut cp.entries, &key, self.entries.get(&key));
+        }
         if let Some(e) = self.entries.get_mut(&key) {
             Ok(f(e))
         } else {
@@ -78,6 +134,12 @@ impl Lexicon for LexiconHashMapBackend {
         // `load` may call this once per batch for a single lexfile (see
         // `load_synsets_streaming`), so this must merge into any synsets
         // already registered under `lexname` rather than replacing them.
+        if let Some(cp) = self.checkpoint.as_mut() {
+            record_prior(&mut cp.synsets, &lexname, self.synsets.get(&lexname));
+            for id in synsets.0.keys() {
+                record_prior(&mut cp.synset_id_to_lexfile, id, self.synset_id_to_lexfile.get(id));
+            }
+        }
         for id in synsets.0.keys() {
             self.synset_id_to_lexfile.insert(id.clone(), lexname.clone());
         }
@@ -88,11 +150,17 @@ impl Lexicon for LexiconHashMapBackend {
         Ok(self.synsets.iter().map(|(k, v)| Ok((k, Cow::Borrowed(v)))))
     }
     fn synsets_insert_synset(&mut self, lexname : &str, synset_id : SynsetId, synset : Synset) -> Result<()> {
+        if let Some(cp) = self.checkpoint.as_mut() {
+            record_prior(&mut cp.synsets, &lexname.to_owned(), self.synsets.get(lexname));
+        }
         self.synsets.entry(lexname.to_owned()).or_insert_with(BTSynsets::new)
             .insert(synset_id.clone(), synset.clone())?;
         Ok(())
     }
     fn synsets_remove_synset(&mut self, lexname : &str,  synset_id : &SynsetId) -> Result<Option<(SynsetId, Synset)>> {
+        if let Some(cp) = self.checkpoint.as_mut() {
+            record_prior(&mut cp.synsets, &lexname.to_owned(), self.synsets.get(lexname));
+        }
         if let Some(synsets) = self.synsets.get_mut(lexname) {
             if let Some(synset) = synsets.remove_entry(synset_id)? {
                 return Ok(Some(synset))
@@ -117,6 +185,12 @@ impl Lexicon for LexiconHashMapBackend {
     //}
 
     fn update_synset(&mut self, synset_id : &SynsetId, f : impl FnOnce(&mut Synset)) -> Result<()> {
+        if let Some(cp) = self.checkpoint.as_mut() {
+            if let Some((lexname, synsets)) = self.synsets.iter()
+                .find(|(_, synsets)| synsets.0.contains_key(synset_id)) {
+                record_prior(&mut cp.synsets, lexname, Some(synsets));
+            }
+        }
         for synsets in self.synsets.values_mut() {
             if let Some(synset) = synsets.get_mut(synset_id) {
                 f(synset);
@@ -130,6 +204,9 @@ impl Lexicon for LexiconHashMapBackend {
         Ok(self.synset_id_to_lexfile.get(synset_id).map(Cow::Borrowed))
     }
     fn synset_id_to_lexfile_insert(&mut self, synset_id : SynsetId, lexfile : String) -> Result<()> {
+        if let Some(cp) = self.checkpoint.as_mut() {
+            record_prior(&mut cp.synset_id_to_lexfile, &synset_id, self.synset_id_to_lexfile.get(&synset_id));
+        }
         self.synset_id_to_lexfile.insert(synset_id, lexfile);
         Ok(())
     }
@@ -138,9 +215,15 @@ impl Lexicon for LexiconHashMapBackend {
     }
     fn sense_links_to_get_or(&mut self, sense_id : SenseId, f : impl FnOnce() -> Vec<(SenseRelType, SenseId)>) 
         -> Result<Vec<(SenseRelType, SenseId)>> {
+        if let Some(cp) = self.checkpoint.as_mut() {
+            record_prior(&mut cp.sense_links_to, &sense_id, self.sense_links_to.get(&sense_id));
+        }
         Ok(self.sense_links_to.entry(sense_id).or_insert_with(f).clone())
     }
     fn sense_links_to_update(&mut self, sense_id : &SenseId, f : impl FnOnce(&mut Vec<(SenseRelType, SenseId)>)) -> Result<()> {
+        if let Some(cp) = self.checkpoint.as_mut() {
+            record_prior(&mut cp.sense_links_to, sense_id, self.sense_links_to.get(sense_id));
+        }
         if let Some(v) = self.sense_links_to.get_mut(sense_id) {
             f(v);
         } else {
@@ -151,11 +234,19 @@ impl Lexicon for LexiconHashMapBackend {
         Ok(())
     }
     fn sense_links_to_push(&mut self, sense_id : SenseId, rel : SenseRelType, target : SenseId) -> Result<()> {
+        if let Some(cp) = self.checkpoint.as_mut() {
+            record_prior(&mut cp.sense_links_to, &sense_id, self.sense_links_to.get(&sense_id));
+        }
         self.sense_links_to.entry(sense_id).or_insert_with(Vec::new)
             .push((rel, target));
         Ok(())
     }
     fn set_sense_links_to(&mut self, links_to : HashMap<SenseId, Vec<(SenseRelType, SenseId)>>) -> Result<()> {
+        if let Some(cp) = self.checkpoint.as_mut() {
+            for key in self.sense_links_to.keys().chain(links_to.keys()) {
+                record_prior(&mut cp.sense_links_to, key, self.sense_links_to.get(key));
+            }
+        }
         self.sense_links_to = links_to;
         Ok(())
     }
@@ -163,9 +254,15 @@ impl Lexicon for LexiconHashMapBackend {
         Ok(self.links_to.get(synset_id).map(Cow::Borrowed))
     }
     fn links_to_get_or(&mut self, synset_id : SynsetId, f : impl FnOnce() -> Vec<(SynsetRelType, SynsetId)>) -> Result<Vec<(SynsetRelType, SynsetId)>> {
+        if let Some(cp) = self.checkpoint.as_mut() {
+            record_prior(&mut cp.links_to, &synset_id, self.links_to.get(&synset_id));
+        }
         Ok(self.links_to.entry(synset_id).or_insert_with(f).clone())
     }
     fn links_to_update(&mut self, synset_id : &SynsetId, f : impl FnOnce(&mut Vec<(SynsetRelType, SynsetId)>)) -> Result<()> {
+        if let Some(cp) = self.checkpoint.as_mut() {
+            record_prior(&mut cp.links_to, synset_id, self.links_to.get(synset_id));
+        }
         if let Some(v) = self.links_to.get_mut(synset_id) {
             f(v);
         } else {
@@ -176,11 +273,19 @@ impl Lexicon for LexiconHashMapBackend {
         Ok(())
     }
     fn links_to_push(&mut self, synset_id : SynsetId, rel : SynsetRelType, target : SynsetId) -> Result<()> {
+        if let Some(cp) = self.checkpoint.as_mut() {
+            record_prior(&mut cp.links_to, &synset_id, self.links_to.get(&synset_id));
+        }
         self.links_to.entry(synset_id).or_insert_with(Vec::new)
             .push((rel, target));
         Ok(())
     }
     fn set_links_to(&mut self, links_to : HashMap<SynsetId, Vec<(SynsetRelType, SynsetId)>>) -> Result<()> {
+        if let Some(cp) = self.checkpoint.as_mut() {
+            for key in self.links_to.keys().chain(links_to.keys()) {
+                record_prior(&mut cp.links_to, key, self.links_to.get(key));
+            }
+        }
         self.links_to = links_to;
         Ok(())
     }
@@ -188,16 +293,36 @@ impl Lexicon for LexiconHashMapBackend {
         Ok(self.sense_id_to_lemma_pos.get(sense_id).cloned())
     }
     fn sense_id_to_lemma_pos_insert(&mut self, sense_id : SenseId, lemma_pos : (String, PosKey)) -> Result<()> {
+        if let Some(cp) = self.checkpoint.as_mut() {
+            record_prior(&mut cp.sense_id_to_lemma_pos, &sense_id, self.sense_id_to_lemma_pos.get(&sense_id));
+        }
         self.sense_id_to_lemma_pos.insert(sense_id, lemma_pos);
         Ok(())
     }
+    fn sense_id_to_lemma_pos_remove(&mut self, sense_id : &SenseId) -> Result<()> {
+        if let Some(cp) = self.checkpoint.as_mut() {
+            record_prior(&mut cp.sense_id_to_lemma_pos, sense_id, self.sense_id_to_lemma_pos.get(sense_id));
+        }
+        self.sense_id_to_lemma_pos.remove(sense_id);
+        Ok(())
+    }
     fn deprecations_get<'a>(&'a self) -> Result<Cow<'a, Vec<DeprecationRecord>>> {
         Ok(Cow::Borrowed(&self.deprecations))
     }
     fn deprecations_push(&mut self, record : DeprecationRecord) -> Result<()> {
+        if let Some(cp) = self.checkpoint.as_mut() {
+            cp.deprecations.get_or_insert_with(|| self.deprecations.clone());
+        }
         self.deprecations.push(record);
         Ok(())
     }
+    fn deprecations_set(&mut self, records : Vec<DeprecationRecord>) -> Result<()> {
+        if let Some(cp) = self.checkpoint.as_mut() {
+            cp.deprecations.get_or_insert_with(|| self.deprecations.clone());
+        }
+        self.deprecations = records;
+        Ok(())
+    }
     fn frames_get<'a>(&'a self) -> Result<Cow<'a, Vec<(String, String)>>> {
         Ok(Cow::Borrowed(&self.frames))
     }
@@ -225,6 +350,43 @@ impl Lexicon for LexiconHashMapBackend {
         self.last_saved_changelog_id = Some(id);
         Ok(())
     }
+    fn checkpoint(&mut self) -> Result<()> {
+        self.checkpoint = Some(Box::new(Checkpoint {
+            entries : HashMap::new(),
+            synsets : HashMap::new(),
+            synset_id_to_lexfile : HashMap::new(),
+            sense_links_to : HashMap::new(),
+            links_to : HashMap::new(),
+            sense_id_to_lemma_pos : HashMap::new(),
+            deprecations : None,
+            frames : self.frames.clone(),
+            changelog_len : self.changelog.len(),
+            last_saved_changelog_id : self.last_saved_changelog_id
+        }));
+        Ok(())
+    }
+    fn rollback_to_checkpoint(&mut self) -> Result<()> {
+        if let Some(cp) = self.checkpoint.take() {
+            let cp = *cp;
+            restore_prior_bt(cp.entries, &mut self.entries);
+            restore_prior_bt(cp.synsets, &mut self.synsets);
+            restore_prior(cp.synset_id_to_lexfile, &mut self.synset_id_to_lexfile);
+            restore_prior(cp.sense_links_to, &mut self.sense_links_to);
+            restore_prior(cp.links_to, &mut self.links_to);
+            restore_prior(cp.sense_id_to_lemma_pos, &mut self.sense_id_to_lemma_pos);
+            if let Some(deprecations) = cp.deprecations {
+                self.deprecations = deprecations;
+            }
+            self.frames = cp.frames;
+            self.changelog.truncate(cp.changelog_len);
+            self.last_saved_changelog_id = cp.last_saved_changelog_id;
+        }
+        Ok(())
+    }
+    fn release_checkpoint(&mut self) -> Result<()> {
+        self.checkpoint = None;
+        Ok(())
+    }
 }
 
 
@@ -0,0 +1,130 @@
+//! The shape of a WN-LMF JSON document, shared by the reader and writer. A field the writer has
+//! nothing for is left out rather than written empty, and the reader takes it missing as empty.
+
+use crate::wordnet::Pronunciation;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize)]
+pub(super) struct Document {
+    #[serde(rename = "@context", default)]
+    pub context: String,
+    #[serde(rename = "@id")]
+    pub id: String,
+    #[serde(default)]
+    pub label: String,
+    #[serde(default)]
+    pub language: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub email: Option<String>,
+    #[serde(default)]
+    pub license: String,
+    #[serde(default)]
+    pub version: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+    #[serde(default)]
+    pub entry: Vec<JsonEntry>,
+    #[serde(default)]
+    pub synset: Vec<JsonSynset>,
+    #[serde(
+        rename = "syntacticBehaviour",
+        default,
+        skip_serializing_if = "Vec::is_empty"
+    )]
+    pub syntactic_behaviour: Vec<JsonSyntacticBehaviour>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub(super) struct JsonEntry {
+    #[serde(rename = "@id")]
+    pub id: String,
+    pub lemma: JsonLemma,
+    #[serde(rename = "partOfSpeech")]
+    pub part_of_speech: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub form: Vec<JsonForm>,
+    #[serde(default)]
+    pub sense: Vec<JsonSense>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub(super) struct JsonLemma {
+    #[serde(rename = "writtenForm")]
+    pub written_form: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub pronunciation: Vec<Pronunciation>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub(super) struct JsonForm {
+    #[serde(rename = "writtenForm")]
+    pub written_form: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub(super) struct JsonSense {
+    #[serde(rename = "@id")]
+    pub id: String,
+    pub synset: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub subcat: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub adjposition: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub relations: Vec<JsonRelation>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub(super) struct JsonRelation {
+    #[serde(rename = "relType")]
+    pub rel_type: String,
+    pub target: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub(super) struct JsonSynset {
+    #[serde(rename = "@id")]
+    pub id: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ili: Option<String>,
+    #[serde(rename = "partOfSpeech")]
+    pub part_of_speech: String,
+    #[serde(default)]
+    pub lexfile: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub members: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub definition: Vec<JsonDefinition>,
+    #[serde(
+        rename = "iliDefinition",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub ili_definition: Option<JsonDefinition>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub example: Vec<JsonExample>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub relations: Vec<JsonRelation>,
+    #[serde(rename = "dc:source", default, skip_serializing_if = "Option::is_none")]
+    pub source: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub(super) struct JsonDefinition {
+    pub gloss: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub(super) struct JsonExample {
+    pub value: String,
+    #[serde(rename = "dc:source", default, skip_serializing_if = "Option::is_none")]
+    pub source: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub(super) struct JsonSyntacticBehaviour {
+    #[serde(rename = "@id")]
+    pub id: String,
+    #[serde(rename = "subcategorizationFrame")]
+    pub subcategorization_frame: String,
+}
@@ -0,0 +1,45 @@
+//! GWA JSON-LD import/export - the JSON form of WN-LMF, see
+//! <https://globalwordnet.github.io/schemas/#json>.
+//!
+//! The document is one lexicon object, in the context
+//! `https://globalwordnet.github.io/schemas/wn-json-context-1.0.json` that maps its keys to the
+//! same `wn:`/`ontolex:` vocabulary as the RDF export. Its keys follow the XML elements and
+//! attributes (`entry`, `sense`, `synset`, `partOfSpeech`, `relType`, ...), and ids are the same
+//! as in WN-LMF XML (built by [`super::xml::ids`]), so a document converts between the two
+//! without renaming anything. Unlike the XML, a part of speech is written out in full (`noun`,
+//! `adjective_satellite`, ...).
+//!
+//! [`write_lexicon_json`] and [`read_lexicon_json`] mirror `write_lexicon_xml` and
+//! `read_lexicon_xml`, and [`write_lexicon_json_subset`] `write_lexicon_xml_subset` (with the same
+//! caveat about relations leaving the subset).
+
+use thiserror::Error;
+
+mod document;
+pub mod reader;
+pub mod writer;
+pub use reader::read_lexicon_json;
+pub use writer::{write_lexicon_json, write_lexicon_json_subset};
+
+pub const WN_JSON_CONTEXT: &str =
+    "https://globalwordnet.github.io/schemas/wn-json-context-1.0.json";
+
+#[derive(Error, Debug)]
+pub enum JsonExportError {
+    #[error("Could not write WN-LMF JSON: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("Could not write WN-LMF JSON: {0}")]
+    Lexicon(#[from] crate::wordnet::LexiconError),
+}
+
+#[derive(Error, Debug)]
+pub enum JsonImportError {
+    #[error("Could not read WN-LMF JSON: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("Could not read WN-LMF JSON: {0}")]
+    Lexicon(#[from] crate::wordnet::LexiconError),
+    #[error("Could not read WN-LMF JSON: {0}")]
+    Load(#[from] crate::wordnet::WordNetYAMLIOError),
+    #[error("Malformed WN-LMF JSON document: {0}")]
+    Malformed(String),
+}
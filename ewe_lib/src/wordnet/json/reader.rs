@@ -0,0 +1,311 @@
+//! WN-LMF JSON import.
+//!
+//! Unlike the XML, the document is deserialized whole with `serde` - JSON has no streaming
+//! equivalent of the XML pull parser that `serde_json` supports, and the deserialized document
+//! is no larger than the lexicon it is loaded into. From there it is loaded as `read_lexicon_xml`
+//! loads the XML: relations are stored in their canonical direction only (an inverse such as
+//! `hyponym` on one synset becomes `hypernym` on its target, once every synset is known),
+//! homograph entries get numbered part-of-speech keys, and everything is bulk-inserted and
+//! finished with `finalize_bulk_load`. Ids are decoded with [`crate::wordnet::xml::ids`].
+
+use super::document::{Document, JsonEntry, JsonSynset};
+use super::JsonImportError;
+use crate::rels::{SenseRelType, SynsetRelType, YamlSynsetRelType};
+use crate::wordnet::lexicon::finalize_bulk_load;
+use crate::wordnet::util::Warnings;
+use crate::wordnet::xml::ids;
+use crate::wordnet::{
+    Entry, Example, Lexicon, LexiconMetadata, PartOfSpeech, PosKey, Sense, SenseId,
+    SenseOrSynsetId, Synset, SynsetId, UnresolvedSenseOrSynsetId, ILIID,
+};
+use std::collections::HashMap;
+use std::io::Read;
+
+type Result<T> = std::result::Result<T, JsonImportError>;
+
+/// The entries and synsets read so far, and the relations that are to be stored on one not
+/// necessarily read yet (as `(on, relation, target)`)
+#[derive(Default)]
+struct Accumulator {
+    entries: Vec<(String, PosKey, Entry)>,
+    entry_index: HashMap<(String, PosKey), usize>,
+    sense_owner: HashMap<SenseId, (String, PosKey)>,
+    entry_id_lookup: HashMap<String, String>,
+    homograph_counts: HashMap<(String, char), u32>,
+
+    synsets: Vec<(String, SynsetId, Synset)>,
+    synset_index: HashMap<SynsetId, usize>,
+
+    pending_synset_rels: Vec<(SynsetId, YamlSynsetRelType, SynsetId)>,
+    pending_sense_rels: Vec<(SenseId, SenseRelType, SenseId)>,
+
+    warned_rel_types: Warnings,
+}
+
+/// Parses a WN-LMF JSON document and bulk-populates `lexicon` from it, returning it with the
+/// document's lexicon metadata - as `read_lexicon_xml` does for the XML.
+pub fn read_lexicon_json<L: Lexicon, R: Read>(
+    mut lexicon: L,
+    reader: R,
+) -> Result<(L, LexiconMetadata)> {
+    let document: Document = serde_json::from_reader(reader)?;
+    let prefix = document.id.as_str();
+    let mut acc = Accumulator::default();
+
+    for entry in &document.entry {
+        read_entry(entry, prefix, &mut acc)?;
+    }
+    for synset in &document.synset {
+        read_synset(synset, prefix, &mut acc)?;
+    }
+
+    for (apply_to, rel, target) in acc.pending_synset_rels {
+        if let Some(&idx) = acc.synset_index.get(&apply_to) {
+            acc.synsets[idx].2.insert_rel(&rel, &target);
+        }
+    }
+    for (apply_to, rel, target) in acc.pending_sense_rels {
+        let Some(owner) = acc.sense_owner.get(&apply_to) else {
+            continue;
+        };
+        if let Some(&idx) = acc.entry_index.get(owner) {
+            if let Some(sense) = acc.entries[idx]
+                .2
+                .sense
+                .iter_mut()
+                .find(|s| s.id == apply_to)
+            {
+                sense.add_rel(rel, SenseOrSynsetId::Sense(target));
+            }
+        }
+    }
+
+    for (lemma, pos, entry) in acc.entries {
+        lexicon.insert_entry(lemma, pos, entry)?;
+    }
+    for (lexname, id, synset) in acc.synsets {
+        lexicon.insert_synset(lexname, id, synset)?;
+    }
+    if !document.syntactic_behaviour.is_empty() {
+        lexicon.frames_set(
+            document
+                .syntactic_behaviour
+                .iter()
+                .map(|sb| (sb.id.clone(), sb.subcategorization_frame.clone()))
+                .collect(),
+        )?;
+    }
+
+    finalize_bulk_load(&mut lexicon)?;
+    acc.warned_rel_types.report();
+
+    let metadata = LexiconMetadata {
+        id_prefix: document.id.clone(),
+        label: document.label,
+        language: document.language,
+        email: document.email.filter(|s| !s.is_empty()),
+        license: document.license,
+        version: document.version,
+        url: document.url.filter(|s| !s.is_empty()),
+    };
+    Ok((lexicon, metadata))
+}
+
+/// A part of speech as written in full (`noun`), or as the XML's letter (`n`)
+fn part_of_speech_from_str(s: &str) -> Option<PartOfSpeech> {
+    match s {
+        "noun" | "n" => Some(PartOfSpeech::n),
+        "verb" | "v" => Some(PartOfSpeech::v),
+        "adjective" | "a" => Some(PartOfSpeech::a),
+        "adverb" | "r" => Some(PartOfSpeech::r),
+        "adjective_satellite" | "s" => Some(PartOfSpeech::s),
+        _ => None,
+    }
+}
+
+fn read_entry(json: &JsonEntry, prefix: &str, acc: &mut Accumulator) -> Result<()> {
+    let lemma = json.lemma.written_form.clone();
+    let pos = part_of_speech_from_str(&json.part_of_speech).ok_or_else(|| {
+        JsonImportError::Malformed(format!(
+            "entry {}: unrecognized partOfSpeech {:?}",
+            json.id, json.part_of_speech
+        ))
+    })?;
+    let pos_letter = pos.value().chars().next().unwrap_or('n');
+
+    let mut senses = Vec::new();
+    for json_sense in &json.sense {
+        let sense_id = SenseId::new(ids::unmap_sense_key(&json_sense.id, prefix));
+        let synset_id = SynsetId::new_owned(ids::strip_prefix_id(prefix, &json_sense.synset));
+        let mut sense = Sense::new(sense_id.clone(), synset_id);
+        sense.subcat = json_sense.subcat.clone();
+        sense.adjposition = json_sense.adjposition.clone();
+        for relation in &json_sense.relations {
+            let Some(rel) = SenseRelType::from(&relation.rel_type) else {
+                acc.warned_rel_types.warn_once(format!(
+                    "Unrecognized sense relType {:?}",
+                    relation.rel_type
+                ));
+                continue;
+            };
+            let target_raw = ids::unmap_sense_key(&relation.target, prefix);
+            let allows_synset_target = rel.allows_synset_target();
+            let (canonical_direction, canonical_rel) = rel.to_canonical();
+            if !canonical_direction {
+                acc.pending_sense_rels.push((
+                    SenseId::new(target_raw),
+                    canonical_rel,
+                    sense_id.clone(),
+                ));
+            } else if allows_synset_target {
+                // Resolved against the whole lexicon by `finalize_bulk_load`, as in the XML
+                // reader
+                let target = UnresolvedSenseOrSynsetId::Unresolved(target_raw);
+                let targets = match canonical_rel {
+                    SenseRelType::DomainTopic => &mut sense.domain_topic,
+                    SenseRelType::DomainRegion => &mut sense.domain_region,
+                    SenseRelType::Exemplifies => &mut sense.exemplifies,
+                    _ => &mut sense.other,
+                };
+                if !targets.contains(&target) {
+                    targets.push(target);
+                }
+            } else {
+                sense.add_rel(
+                    canonical_rel,
+                    SenseOrSynsetId::Sense(SenseId::new(target_raw)),
+                );
+            }
+        }
+        senses.push(sense);
+    }
+
+    // Homographs are numbered "n", "n2", "n3", ... as in the XML reader
+    let count = acc
+        .homograph_counts
+        .entry((lemma.clone(), pos_letter))
+        .or_insert(0);
+    let poskey = if *count == 0 {
+        PosKey::new(pos_letter.to_string())
+    } else {
+        PosKey::new(format!("{}{}", pos_letter, *count + 1))
+    };
+    *count += 1;
+
+    acc.entry_id_lookup.insert(json.id.clone(), lemma.clone());
+    for sense in &senses {
+        acc.sense_owner
+            .insert(sense.id.clone(), (lemma.clone(), poskey.clone()));
+    }
+    let entry = Entry {
+        sense: senses,
+        form: json.form.iter().map(|f| f.written_form.clone()).collect(),
+        pronunciation: json.lemma.pronunciation.clone(),
+    };
+    acc.entry_index
+        .insert((lemma.clone(), poskey.clone()), acc.entries.len());
+    acc.entries.push((lemma, poskey, entry));
+    Ok(())
+}
+
+fn read_synset(json: &JsonSynset, prefix: &str, acc: &mut Accumulator) -> Result<()> {
+    let id = SynsetId::new_owned(ids::strip_prefix_id(prefix, &json.id));
+    let pos = part_of_speech_from_str(&json.part_of_speech).ok_or_else(|| {
+        JsonImportError::Malformed(format!(
+            "synset {}: unrecognized partOfSpeech {:?}",
+            json.id, json.part_of_speech
+        ))
+    })?;
+
+    let mut synset = Synset::new(pos);
+    if let Some(ili) = json.ili.as_deref().filter(|ili| *ili != "in") {
+        synset.ili = Some(ILIID::new(ili));
+    }
+    for member_id in &json.members {
+        match acc.entry_id_lookup.get(member_id) {
+            Some(lemma) => synset.members.push(lemma.clone()),
+            None => eprintln!(
+                "Synset {}: member {member_id:?} doesn't match any entry - dropped",
+                json.id
+            ),
+        }
+    }
+    synset.definition = json.definition.iter().map(|d| d.gloss.clone()).collect();
+    synset.example = json
+        .example
+        .iter()
+        .map(|e| Example::new(e.value.clone(), e.source.clone()))
+        .collect();
+    synset.source = json.source.clone();
+
+    for relation in &json.relations {
+        let Some(rel) = SynsetRelType::from(&relation.rel_type) else {
+            acc.warned_rel_types.warn_once(format!(
+                "Unrecognized synset relType {:?}",
+                relation.rel_type
+            ));
+            continue;
+        };
+        let target = SynsetId::new_owned(ids::strip_prefix_id(prefix, &relation.target));
+        let (canonical_direction, canonical_rel) = rel.to_yaml();
+        if canonical_direction {
+            synset.insert_rel(&canonical_rel, &target);
+        } else {
+            acc.pending_synset_rels
+                .push((target, canonical_rel, id.clone()));
+        }
+    }
+
+    acc.synset_index.insert(id.clone(), acc.synsets.len());
+    acc.synsets.push((json.lexfile.clone(), id, synset));
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::write_lexicon_json;
+    use super::*;
+    use crate::wordnet::fixtures::{round_trip_fixture, round_trip_metadata};
+    use crate::wordnet::LexiconHashMapBackend;
+
+    #[test]
+    fn test_read_lexicon_json_round_trip() {
+        let json = write_lexicon_json(&round_trip_fixture(), &round_trip_metadata()).unwrap();
+        let (wn, read_metadata) =
+            read_lexicon_json(LexiconHashMapBackend::new(), json.as_slice()).unwrap();
+        assert_eq!(read_metadata, round_trip_metadata());
+        assert_eq!(wn.n_synsets().unwrap(), 3);
+        assert_eq!(wn.n_entries().unwrap(), 3);
+
+        let ewe = wn
+            .synset_by_id(&SynsetId::new("00000002-n"))
+            .unwrap()
+            .unwrap();
+        assert_eq!(ewe.members, vec!["ewe".to_string()]);
+        assert_eq!(ewe.hypernym, vec![SynsetId::new("00000001-n")]);
+        assert_eq!(ewe.ili, None);
+        assert_eq!(ewe.example[0].source.as_deref(), Some("Anon"));
+        // Written as hyponym on the other end too, and not stored twice
+        let sheep = wn
+            .synset_by_id(&SynsetId::new("00000001-n"))
+            .unwrap()
+            .unwrap();
+        assert!(sheep.hypernym.is_empty());
+        assert_eq!(sheep.ili, Some(ILIID::new("i1")));
+        assert_eq!(
+            wn.lex_name_for(&SynsetId::new("00000003-v")).unwrap(),
+            Some("verb.communication".to_string())
+        );
+
+        let entries = wn.entry_by_lemma("sheep").unwrap();
+        assert_eq!(entries[0].form, vec!["sheeps".to_string()]);
+        assert_eq!(entries[0].pronunciation[0].variety.as_deref(), Some("GB"));
+        let bleat = wn
+            .get_sense_by_id(&SenseId::new("bleat%2:32:00::"))
+            .unwrap()
+            .unwrap();
+        assert_eq!(bleat.2.derivation, vec![SenseId::new("sheep%1:05:00::")]);
+        assert_eq!(bleat.2.subcat, vec!["via".to_string()]);
+        assert_eq!(wn.frames_get().unwrap().len(), 1);
+    }
+}
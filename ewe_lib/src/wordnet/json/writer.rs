@@ -0,0 +1,251 @@
+//! WN-LMF JSON export.
+//!
+//! Laid out as [`crate::wordnet::xml::writer`] lays out the XML, which it mirrors: entries and
+//! synsets sorted by id, each entry's senses in their rank order, the frame table last, and the
+//! same relations (from [`sense_relations_xml`] and [`synset_relations_xml`]). A synset with no
+//! ILI gets `"ili": "in"` and an `iliDefinition` only if the lexicon is English, as the ILI
+//! definition must be; otherwise its `ili` is left out (where the XML writer refuses to go on).
+
+use super::document::{
+    Document, JsonDefinition, JsonEntry, JsonExample, JsonForm, JsonLemma, JsonRelation, JsonSense,
+    JsonSynset, JsonSyntacticBehaviour,
+};
+use super::{JsonExportError, WN_JSON_CONTEXT};
+use crate::wordnet::entry::Entries;
+use crate::wordnet::synset_members::Member;
+use crate::wordnet::xml::ids;
+use crate::wordnet::xml::writer::{sense_relations_xml, synset_relations_xml, SenseKeyLookup};
+use crate::wordnet::{Lexicon, LexiconMetadata, MemberSynset, PosKey, SenseId, Synsets};
+use std::collections::{BTreeMap, HashMap};
+
+type Result<T> = std::result::Result<T, JsonExportError>;
+
+/// Export every synset in `wn` as a single WN-LMF JSON document.
+pub fn write_lexicon_json<L: Lexicon>(wn: &L, metadata: &LexiconMetadata) -> Result<Vec<u8>> {
+    let mut synsets = Vec::new();
+    for lexfile in wn.synsets_iter()? {
+        let (_, syns) = lexfile?;
+        for entry in syns.iter()? {
+            let (id, synset) = entry?;
+            synsets.push(MemberSynset::from_synset(&id, synset.into_owned(), wn)?);
+        }
+    }
+    let frames = wn.frames_get()?;
+
+    // Each sense's rank within its own entry - see `write_lexicon_xml`
+    let mut sense_rank: HashMap<SenseId, usize> = HashMap::new();
+    for bucket in wn.entries_iter()? {
+        let (_, entries) = bucket?;
+        for entry in entries.entries()? {
+            let (_, _, entry) = entry?;
+            for (rank, sense) in entry.sense.iter().enumerate() {
+                sense_rank.insert(sense.id.clone(), rank);
+            }
+        }
+    }
+
+    write_lexicon_json_subset(&synsets, metadata, &frames, &sense_rank)
+}
+
+/// Export exactly the given synsets, with the entries of their members. `frames` and
+/// `sense_rank` are as for `write_lexicon_xml_subset` - `&[]` and `&HashMap::new()` will do for
+/// a fragment such as a single synset or lemma.
+pub fn write_lexicon_json_subset(
+    synsets: &[MemberSynset],
+    metadata: &LexiconMetadata,
+    frames: &[(String, String)],
+    sense_rank: &HashMap<SenseId, usize>,
+) -> Result<Vec<u8>> {
+    let prefix = metadata.id_prefix.as_str();
+
+    let mut entries: BTreeMap<(String, PosKey), Vec<(&MemberSynset, &Member)>> = BTreeMap::new();
+    let mut sense_id_lookup: SenseKeyLookup = HashMap::new();
+    for synset in synsets {
+        for member in &synset.members {
+            entries
+                .entry((member.lemma.clone(), member.poskey.clone()))
+                .or_default()
+                .push((synset, member));
+            sense_id_lookup.insert(
+                (
+                    member.lemma.clone(),
+                    member.poskey.clone(),
+                    synset.id.clone(),
+                ),
+                member.sense.id.clone(),
+            );
+        }
+    }
+
+    let mut json_entries: Vec<JsonEntry> = entries
+        .into_iter()
+        .map(|((lemma, poskey), mut senses)| {
+            senses.sort_by_key(|(_, member)| {
+                sense_rank
+                    .get(&member.sense.id)
+                    .copied()
+                    .unwrap_or(usize::MAX)
+            });
+            json_entry(prefix, &lemma, &poskey, &senses, &sense_id_lookup)
+        })
+        .collect();
+    json_entries.sort_by(|a, b| a.id.cmp(&b.id));
+
+    let mut synsets_sorted: Vec<&MemberSynset> = synsets.iter().collect();
+    synsets_sorted.sort_by(|a, b| a.id.cmp(&b.id));
+    let json_synsets = synsets_sorted
+        .into_iter()
+        .map(|synset| json_synset(prefix, synset, &metadata.language))
+        .collect();
+
+    let document = Document {
+        context: WN_JSON_CONTEXT.to_string(),
+        id: prefix.to_string(),
+        label: metadata.label.clone(),
+        language: metadata.language.clone(),
+        email: metadata.email.clone(),
+        license: metadata.license.clone(),
+        version: metadata.version.clone(),
+        url: metadata.url.clone(),
+        entry: json_entries,
+        synset: json_synsets,
+        syntactic_behaviour: frames
+            .iter()
+            .map(|(id, frame)| JsonSyntacticBehaviour {
+                id: id.clone(),
+                subcategorization_frame: frame.clone(),
+            })
+            .collect(),
+    };
+    Ok(serde_json::to_vec_pretty(&document)?)
+}
+
+fn json_entry(
+    prefix: &str,
+    lemma: &str,
+    poskey: &PosKey,
+    senses: &[(&MemberSynset, &Member)],
+    sense_id_lookup: &SenseKeyLookup,
+) -> JsonEntry {
+    // Every member of the entry carries its forms and pronunciations; any one will do
+    let representative = senses[0].1;
+    let part_of_speech = poskey
+        .to_part_of_speech()
+        .map(|p| p.long_pos())
+        .unwrap_or("noun");
+    JsonEntry {
+        id: ids::entry_xml_id(prefix, lemma, poskey),
+        lemma: JsonLemma {
+            written_form: lemma.to_string(),
+            pronunciation: representative.pronunciation.clone(),
+        },
+        part_of_speech: part_of_speech.to_string(),
+        form: representative
+            .form
+            .iter()
+            .map(|form| JsonForm {
+                written_form: form.clone(),
+            })
+            .collect(),
+        sense: senses
+            .iter()
+            .map(|(synset, member)| JsonSense {
+                id: ids::sense_xml_id(prefix, &member.sense.id),
+                synset: ids::synset_xml_id(prefix, &synset.id),
+                subcat: member.sense.subcat.clone(),
+                adjposition: member.sense.adjposition.clone(),
+                relations: relations(sense_relations_xml(
+                    prefix,
+                    synset,
+                    &member.lemma,
+                    sense_id_lookup,
+                )),
+            })
+            .collect(),
+    }
+}
+
+fn json_synset(prefix: &str, synset: &MemberSynset, language: &str) -> JsonSynset {
+    let (ili, ili_definition) = match &synset.ili {
+        Some(ili) => (Some(ili.as_str().to_string()), None),
+        None if language == "en" => (
+            Some("in".to_string()),
+            synset.definition.first().map(|gloss| JsonDefinition {
+                gloss: gloss.clone(),
+            }),
+        ),
+        None => (None, None),
+    };
+    JsonSynset {
+        id: ids::synset_xml_id(prefix, &synset.id),
+        ili,
+        part_of_speech: synset.part_of_speech.long_pos().to_string(),
+        lexfile: synset.lexname.clone(),
+        members: synset
+            .members
+            .iter()
+            .map(|m| ids::entry_xml_id(prefix, &m.lemma, &m.poskey))
+            .collect(),
+        definition: synset
+            .definition
+            .iter()
+            .map(|gloss| JsonDefinition {
+                gloss: gloss.clone(),
+            })
+            .collect(),
+        ili_definition,
+        example: synset
+            .example
+            .iter()
+            .map(|example| JsonExample {
+                value: example.text.clone(),
+                source: example.source.clone(),
+            })
+            .collect(),
+        relations: relations(synset_relations_xml(prefix, synset)),
+        source: synset.source.clone(),
+    }
+}
+
+fn relations(relations: Vec<(&'static str, String)>) -> Vec<JsonRelation> {
+    relations
+        .into_iter()
+        .map(|(rel_type, target)| JsonRelation {
+            rel_type: rel_type.to_string(),
+            target,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::WN_JSON_CONTEXT;
+    use super::*;
+    use crate::wordnet::fixtures::{round_trip_fixture, round_trip_metadata};
+
+    #[test]
+    fn test_write_lexicon_json_layout() {
+        let json = write_lexicon_json(&round_trip_fixture(), &round_trip_metadata()).unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&json).unwrap();
+        assert_eq!(json["@context"], WN_JSON_CONTEXT);
+        assert_eq!(json["@id"], "oewn");
+        let entry = &json["entry"][0];
+        assert_eq!(entry["@id"], "oewn-bleat-v");
+        assert_eq!(entry["partOfSpeech"], "verb");
+        assert_eq!(entry["sense"][0]["@id"], "oewn-bleat__2.32.00..");
+        assert_eq!(entry["sense"][0]["synset"], "oewn-00000003-v");
+        assert_eq!(entry["sense"][0]["subcat"][0], "via");
+        let ewe = &json["synset"][1];
+        assert_eq!(ewe["@id"], "oewn-00000002-n");
+        assert_eq!(ewe["ili"], "in");
+        assert_eq!(ewe["iliDefinition"]["gloss"], "a female sheep");
+        assert_eq!(ewe["members"][0], "oewn-ewe-n");
+        assert_eq!(ewe["example"][0]["dc:source"], "Anon");
+        assert_eq!(ewe["relations"][0]["relType"], "hypernym");
+        assert_eq!(ewe["relations"][0]["target"], "oewn-00000001-n");
+        assert_eq!(
+            json["syntacticBehaviour"][0]["subcategorizationFrame"],
+            "Somebody ----s"
+        );
+    }
+}
@@ -189,8 +189,11 @@ pub trait Lexicon: Sized {
         sense_id: SenseId,
         lemma_pos: (String, PosKey),
     ) -> Result<()>;
+    fn sense_id_to_lemma_pos_remove(&mut self, sense_id: &SenseId) -> Result<()>;
     fn deprecations_get<'a>(&'a self) -> Result<Cow<'a, Vec<DeprecationRecord>>>;
     fn deprecations_push(&mut self, record: DeprecationRecord) -> Result<()>;
+    /// Replaces the whole deprecation list - see `undeprecate`.
+    fn deprecations_set(&mut self, records: Vec<DeprecationRecord>) -> Result<()>;
 
     /// The subcategorization frames available to verbs (key, human-readable description),
     /// loaded from `frames.yaml` - see `Lexicon::load`. Frame keys are what `Sense::subcat`
@@ -212,6 +215,16 @@ pub trait Lexicon: Sized {
     fn last_saved_changelog_id_get(&self) -> Result<Option<u64>>;
     fn last_saved_changelog_id_set(&mut self, id: u64) -> Result<()>;
 
+    /// Snapshots the lexicon's current state, so that a later `rollback_to_checkpoint` can put
+    /// it back exactly as it is now - see `automaton::apply_automaton`, which wraps each batch in
+    /// one so that a batch failing partway through leaves nothing half-applied. Checkpoints don't
+    /// nest: taking a new one replaces any still outstanding.
+    fn checkpoint(&mut self) -> Result<()>;
+    /// Restores the state captured by the outstanding `checkpoint` (if any) and discards it.
+    fn rollback_to_checkpoint(&mut self) -> Result<()>;
+    /// Discards the outstanding `checkpoint` (if any), keeping everything written since.
+    fn release_checkpoint(&mut self) -> Result<()>;
+
     /// Load a lexicon from a folder of YAML files
     fn load<P: AsRef<Path>, Pr: Progress>(
         mut self,
@@ -373,12 +386,13 @@ pub trait Lexicon: Sized {
             .map(|x| x.into_owned()))
     }
 
-    /// Get the entry data for a lemma
+    /// Get the entry data for a lemma - or, if there is none and the lemma is not normalised
+    /// (see `normalise_text`), for its normalised form
     fn entry_by_lemma<'a>(&'a self, lemma: &str) -> Result<Vec<Cow<'a, Entry>>> {
         if lemma.is_empty() {
             return Ok(Vec::new());
         }
-        Ok(match self.entries_get(entry_key(lemma))? {
+        let entries = match self.entries_get(entry_key(lemma))? {
             Some(Cow::Borrowed(v)) => v.entry_by_lemma(lemma)?,
             Some(Cow::Owned(v)) => v
                 .entry_by_lemma(lemma)?
@@ -386,12 +400,17 @@ pub trait Lexicon: Sized {
                 .map(|e| Cow::Owned(e.into_owned()))
                 .collect(),
             _ => Vec::new(),
-        })
+        };
+        match normalise_text(lemma) {
+            Cow::Owned(normalised) if entries.is_empty() => self.entry_by_lemma(&normalised),
+            _ => Ok(entries),
+        }
     }
 
-    /// Get the entry data for a lemma, ignoring case
+    /// Get the entry data for a lemma, ignoring case (falling back to its normalised form, as
+    /// `entry_by_lemma` does)
     fn entry_by_lemma_ignore_case<'a>(&'a self, lemma: &str) -> Result<Vec<Cow<'a, Entry>>> {
-        Ok(self
+        let entries: Vec<Cow<'a, Entry>> = self
             .entries_iter()?
             .map(|v| match v {
                 Ok((_, Cow::Borrowed(v))) => Ok(v.entry_by_lemma_ignore_case(lemma)?),
@@ -405,15 +424,22 @@ pub trait Lexicon: Sized {
             .collect::<Result<Vec<Vec<Cow<'a, Entry>>>>>()?
             .into_iter()
             .flatten()
-            .collect())
+            .collect();
+        match normalise_text(lemma) {
+            Cow::Owned(normalised) if entries.is_empty() => {
+                self.entry_by_lemma_ignore_case(&normalised)
+            }
+            _ => Ok(entries),
+        }
     }
 
-    /// Get the entry data (with the part of speech key) for a lemma
+    /// Get the entry data (with the part of speech key) for a lemma (falling back to its
+    /// normalised form, as `entry_by_lemma` does)
     fn entry_by_lemma_with_pos<'a>(&'a self, lemma: &str) -> Result<Vec<(PosKey, Cow<'a, Entry>)>> {
-        match lemma.chars().nth(0) {
+        let entries = match lemma.chars().nth(0) {
             Some(c) if c.to_ascii_lowercase() >= 'a' && c.to_ascii_lowercase() <= 'z' => {
                 let key = c.to_ascii_lowercase();
-                Ok(match self.entries_get(key)? {
+                match self.entries_get(key)? {
                     Some(Cow::Borrowed(v)) => v.entry_by_lemma_with_pos(lemma)?,
                     Some(Cow::Owned(v)) => v
                         .entry_by_lemma_with_pos(lemma)?
@@ -421,9 +447,9 @@ pub trait Lexicon: Sized {
                         .map(|(p, e)| (p, Cow::Owned(e.into_owned())))
                         .collect(),
                     _ => Vec::new(),
-                })
+                }
             }
-            Some(_) => Ok(match self.entries_get('0')? {
+            Some(_) => match self.entries_get('0')? {
                 Some(Cow::Borrowed(v)) => v.entry_by_lemma_with_pos(lemma)?,
                 Some(Cow::Owned(v)) => v
                     .entry_by_lemma_with_pos(lemma)?
@@ -431,8 +457,14 @@ pub trait Lexicon: Sized {
                     .map(|(p, e)| (p, Cow::Owned(e.into_owned())))
                     .collect(),
                 _ => Vec::new(),
-            }),
-            None => Ok(Vec::new()),
+            },
+            None => Vec::new(),
+        };
+        match normalise_text(lemma) {
+            Cow::Owned(normalised) if entries.is_empty() => {
+                self.entry_by_lemma_with_pos(&normalised)
+            }
+            _ => Ok(entries),
         }
     }
 
@@ -605,7 +637,7 @@ pub trait Lexicon: Sized {
             for (rel, target) in v.iter() {
                 if let UnresolvedSenseOrSynsetId::Sense(target) = target {
                     self.sense_links_to_update(target, |key| {
-                        key.retain(|x| x.0 != *rel && x.1 != *source);
+                        key.retain(|x| x.0 != *rel || x.1 != *source);
                     })?;
                 }
             }
@@ -835,6 +867,29 @@ pub trait Lexicon: Sized {
         Ok(())
     }
 
+    /// Remove the link of type `rel` from `source` to `target`, leaving any others between them
+    fn remove_rel_type(
+        &mut self,
+        source: &SynsetId,
+        rel: SynsetRelType,
+        target: &SynsetId,
+    ) -> Result<()> {
+        self.links_to_update(target, |v| {
+            v.retain(|x| x.0 != rel || x.1 != *source);
+        })?;
+        let (s2t, rel) = rel.to_yaml();
+        if s2t {
+            self.update_synset(source, |ss| {
+                ss.remove_rel_type(&rel, target);
+            })?;
+        } else {
+            self.update_synset(target, |ss| {
+                ss.remove_rel_type(&rel, source);
+            })?;
+        }
+        Ok(())
+    }
+
     /// Remove all links between two synsets
     fn remove_rel(&mut self, source: &SynsetId, target: &SynsetId) -> Result<()> {
         self.links_to_update(target, |v| {
@@ -860,6 +915,11 @@ pub trait Lexicon: Sized {
         Ok(())
     }
 
+    /// Remove a variant form from an entry, returning whether it was there
+    fn remove_form(&mut self, lemma: &str, pos: &PosKey, form: &str) -> Result<bool> {
+        self.entries_update(entry_key(lemma), |e| e.remove_form(lemma, pos, form))?
+    }
+
     /// Get the list of pronunications of an entry
     fn get_pronunciations(&self, lemma: &str, pos: &PosKey) -> Result<Vec<Pronunciation>> {
         Ok(match self.entries_get(entry_key(&lemma))? {
@@ -881,6 +941,18 @@ pub trait Lexicon: Sized {
         Ok(())
     }
 
+    /// Remove a pronunciation from an entry, returning whether it was there
+    fn remove_pronunciation(
+        &mut self,
+        lemma: &str,
+        pos: &PosKey,
+        pronunciation: &Pronunciation,
+    ) -> Result<bool> {
+        self.entries_update(entry_key(lemma), |e| {
+            e.remove_pronunciation(lemma, pos, pronunciation)
+        })?
+    }
+
     /// Add a deprecation note
     fn deprecate(&mut self, synset: &SynsetId, supersede: &SynsetId, reason: String) -> Result<()> {
         let ili = match self.synset_by_id(synset)? {
@@ -907,25 +979,66 @@ pub trait Lexicon: Sized {
         Ok(())
     }
 
-    fn update_sense_key(&mut self, old_key: &SenseId, new_key: &SenseId) -> Result<()> {
-        let mut lemma_pos = None;
-        match self.sense_id_to_lemma_pos_get(old_key)? {
+    /// Remove any deprecation note for a synset, e.g. when it is restored
+    fn undeprecate(&mut self, synset: &SynsetId) -> Result<()> {
+        let id = format!("ewn-{}", synset.as_str());
+        let mut records = self.deprecations_get()?.into_owned();
+        let len = records.len();
+        records.retain(|record| record.0 != id);
+        if records.len() != len {
+            self.deprecations_set(records)?;
+        }
+        Ok(())
+    }
+
+    /// Update the attributes of a sense (its adjposition, subcat frames, etc.) in place. Not
+    /// for its id or relations, which other indexes depend on - see `update_sense_key` and
+    /// `add_sense_rel`/`remove_sense_rel` for those.
+    fn update_sense(&mut self, sense_id: &SenseId, f: impl FnOnce(&mut Sense)) -> Result<()> {
+        match self.sense_id_to_lemma_pos_get(sense_id)? {
             Some((lemma, pos)) => {
-                lemma_pos = Some((lemma.clone(), pos.clone()));
+                self.entries_update(entry_key(&lemma), |e| {
+                    e.update_sense(&lemma, &pos, sense_id, f)
+                })??;
+                Ok(())
             }
-            None => {}
-        };
-        if let Some((lemma, pos)) = lemma_pos {
+            None => Err(LexiconError::SenseIdNotFound(sense_id.clone())),
+        }
+    }
+
+    /// Rename a sense, keeping everything that refers to it by id in step: its entry, the
+    /// sense id to lemma/POS lookup, the backlinks held for the targets of its own relations,
+    /// and the relations other senses have to it.
+    fn update_sense_key(&mut self, old_key: &SenseId, new_key: &SenseId) -> Result<()> {
+        if old_key == new_key {
+            return Ok(());
+        }
+        let links_from = self.sense_links_from_id(old_key)?;
+        if let Some((lemma, pos)) = self.sense_id_to_lemma_pos_get(old_key)? {
             self.entries_update(entry_key(&lemma), |e| {
                 e.update_sense_key(&lemma, &pos, old_key, new_key)
             })??;
+            self.sense_id_to_lemma_pos_insert(new_key.clone(), (lemma, pos))?;
+            self.sense_id_to_lemma_pos_remove(old_key)?;
+        }
+        for (_, target) in links_from {
+            if let UnresolvedSenseOrSynsetId::Sense(target) = target {
+                self.sense_links_to_update(&target, |links_to| {
+                    for (_, source) in links_to.iter_mut() {
+                        if source == old_key {
+                            *source = new_key.clone();
+                        }
+                    }
+                })?;
+            }
         }
-        match self.sense_links_to_get(old_key)?.map(|x| x.clone()) {
+        match self.sense_links_to_get(old_key)?.map(|x| x.into_owned()) {
             Some(links_to) => {
                 let old_key_target = SenseOrSynsetId::Sense(old_key.clone());
-                for (rel, source) in links_to.into_owned() {
+                let new_key_target = SenseOrSynsetId::Sense(new_key.clone());
+                for (rel, source) in links_to {
                     self.remove_sense_rel(&source, &old_key_target)?;
-                    self.add_sense_rel(&source, rel.clone(), &old_key_target)?;
+                    self.add_sense_rel(&source, rel, &new_key_target)?;
                 }
             }
             None => {}
@@ -1059,9 +1172,11 @@ pub trait Lexicon: Sized {
         }
     }
 
-    /// Get the list of entries starting with a prefix
+    /// Get the list of entries starting with a prefix, once normalised (see `normalise_text`)
     fn lemma_by_prefix(&self, prefix: &str, max_results: Option<usize>) -> Result<Vec<String>> {
         let limit = max_results.unwrap_or(usize::MAX);
+        let prefix = normalise_text(prefix);
+        let prefix = prefix.as_ref();
         Ok(match prefix.chars().nth(0) {
             Some(c) if c.to_ascii_lowercase() >= 'a' && c.to_ascii_lowercase() <= 'z' => {
                 let key = c.to_ascii_lowercase();
@@ -20,7 +20,7 @@ pub mod pos;
 pub use pos::{PartOfSpeech, PosKey};
 
 mod util;
-pub use util::{WordNetYAMLIOError, LexiconError, LexiconSaveError, string_or_vec};
+pub use util::{WordNetYAMLIOError, LexiconError, LexiconSaveError, string_or_vec, normalise_text};
 
 pub mod deprecation;
 pub use deprecation::DeprecationRecord;
@@ -34,13 +34,19 @@ pub use synset_members::{MemberSynset, SenseRelation};
 pub mod xml;
 pub use xml::{LexiconMetadata, XmlExportError, XmlImportError};
 
+pub mod json;
+pub use json::{JsonExportError, JsonImportError};
+
 pub mod wndb;
-pub use wndb::{write_wndb, WndbExportError, WndbExportOptions};
+pub use wndb::{read_wndb, write_wndb, WndbExportError, WndbExportOptions, WndbImportError};
+
+pub mod omw;
+pub use omw::{read_omw_tab, write_omw_tab, OmwExportError, OmwImportError};
 
 #[cfg(feature = "rdf")]
 pub mod rdf;
 #[cfg(feature = "rdf")]
-pub use rdf::{RdfExportError, RdfExportOptions, RdfFormat};
+pub use rdf::{RdfExportError, RdfExportOptions, RdfFormat, RdfImportError};
 
 #[cfg(feature = "redb")]
 pub mod redb_lexicon;
@@ -50,6 +56,9 @@ pub use redb_lexicon::ReDBLexicon;
 #[cfg(feature = "redb")]
 pub mod transaction_manager;
 
+#[cfg(test)]
+mod fixtures;
+
 #[cfg(test)]
 #[allow(unused_variables)]
 mod tests {
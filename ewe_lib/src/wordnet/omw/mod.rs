@@ -0,0 +1,40 @@
+//! Open Multilingual Wordnet tab files (`wn-data-{lang}.tab`), in which OMW distributes many
+//! wordnets: an optional `# {label}\t{language}\t{url}\t{license}` header, then one row per
+//! lemma, definition or example of a synset, with its fields separated by tabs:
+//!
+//! ```text
+//! 02084071-n    fra:lemma    chien
+//! 02084071-n    fra:def    0    mammifère domestique
+//! 02084071-n    fra:exe    0    le chien aboie
+//! ```
+//!
+//! with the synset given by its PWN offset and part of speech. A tab file has no structure of
+//! its own, so [`read_omw_tab`] attaches what it lists to the synsets of a base wordnet (such as
+//! OEWN, whose ids are the PWN 3.0 offsets), found by their id or by their ILI (`i46360`).
+//! [`write_omw_tab`] writes the `lemma`, `def` and `exe` rows of every synset.
+
+use thiserror::Error;
+
+mod reader;
+mod writer;
+
+pub use reader::read_omw_tab;
+pub use writer::write_omw_tab;
+
+#[derive(Error, Debug)]
+pub enum OmwExportError {
+    #[error("Could not write OMW tab file: {0}")]
+    Lexicon(#[from] crate::wordnet::LexiconError),
+}
+
+#[derive(Error, Debug)]
+pub enum OmwImportError {
+    #[error("Could not read OMW tab file: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Could not read OMW tab file: {0}")]
+    Lexicon(#[from] crate::wordnet::LexiconError),
+    #[error("Could not read OMW tab file: {0}")]
+    Load(#[from] crate::wordnet::WordNetYAMLIOError),
+    #[error("Malformed OMW tab file, line {line}: {message}")]
+    Malformed { line: usize, message: String },
+}
@@ -0,0 +1,380 @@
+//! OMW tab file import. Each row's synset is looked up in the base wordnet - by id for an offset
+//! (`02084071-n`, where an adjective's `a` and `s` are taken as the same, as OMW files often
+//! don't tell them apart), by ILI for an `i`-number - and the imported synset takes its id,
+//! part of speech, lexicographer file and ILI from there, and the tab file's definitions and
+//! examples (in order of their index, the rows sharing an index joined into one). The base
+//! wordnet's synset relations are kept between the synsets imported, so the new wordnet has the
+//! same hierarchy as far as it goes. Lemmas are added as `change_manager::add_entry` adds them in
+//! the editor, which makes up their sense keys; rows for a synset the base wordnet doesn't have,
+//! or of kinds other than `lemma`, `def` and `exe` (OMW's `lemma:root`, `var`, ...), are skipped
+//! with a warning.
+
+use super::OmwImportError;
+use crate::change_manager::{add_entry, ChangeList};
+use crate::wordnet::lexicon::finalize_bulk_load;
+use crate::wordnet::util::Warnings;
+use crate::wordnet::{Example, Lexicon, LexiconMetadata, PartOfSpeech, Synset, SynsetId, Synsets};
+use std::collections::{BTreeMap, HashMap};
+use std::io::{BufRead, BufReader, Read};
+
+type Result<T> = std::result::Result<T, OmwImportError>;
+
+/// What the tab file says about one synset: its lemmas in file order, and its definitions and
+/// examples by their index, each as the rows given that index (which make up one definition or
+/// example between them)
+#[derive(Default)]
+struct Rows {
+    lemmas: Vec<String>,
+    definitions: BTreeMap<usize, Vec<String>>,
+    examples: BTreeMap<usize, Vec<String>>,
+}
+
+impl Rows {
+    /// The definitions or examples of `numbered`, in order of their index
+    fn joined(numbered: &BTreeMap<usize, Vec<String>>) -> Vec<String> {
+        numbered.values().map(|parts| parts.join(" ")).collect()
+    }
+}
+
+/// Reads an OMW tab file into `lexicon`, attaching its rows to the synsets of `base` (see the
+/// module doc comment). Returns the lexicon with the metadata of the file's header (with no
+/// `id_prefix`, `email` or `version`, which the header doesn't give), its language taken from
+/// the rows' `fra:lemma`-style tags if the header doesn't give it - or with just that language,
+/// if there is no header; `None` only if there's neither.
+pub fn read_omw_tab<L: Lexicon, B: Lexicon, R: Read>(
+    mut lexicon: L,
+    reader: R,
+    base: &B,
+) -> Result<(L, Option<LexiconMetadata>)> {
+    let mut metadata = None;
+    let mut language = None;
+    let mut rows: BTreeMap<SynsetId, Rows> = BTreeMap::new();
+    let mut ili_index: Option<HashMap<String, SynsetId>> = None;
+    let mut warnings = Warnings::default();
+
+    for (n, line) in BufReader::new(reader).lines().enumerate() {
+        let line = line?;
+        if let Some(header) = line.strip_prefix('#') {
+            if n == 0 {
+                metadata = Some(read_header(header));
+            }
+            continue;
+        }
+        if line.trim().is_empty() {
+            continue;
+        }
+        let fields: Vec<&str> = line.split('\t').collect();
+        let (key, kind, index, value) = match fields[..] {
+            [key, kind, value] => (key, kind, None, value),
+            [key, kind, index, value] => (key, kind, Some(index), value),
+            _ => {
+                return Err(OmwImportError::Malformed {
+                    line: n + 1,
+                    message: format!(
+                        "expected 3 or 4 tab-separated fields, found {}",
+                        fields.len()
+                    ),
+                })
+            }
+        };
+        let (lang, kind) = kind.split_once(':').unwrap_or(("", kind));
+        if language.is_none() && !lang.is_empty() {
+            language = Some(lang.to_string());
+        }
+        if !matches!(kind, "lemma" | "def" | "exe") {
+            warnings.warn_once(format!("Skipping OMW {kind:?} rows"));
+            continue;
+        }
+        let Some(synset_id) = find_synset(base, key.trim(), &mut ili_index)? else {
+            warnings.warn_once_for(
+                "Rows for a synset not in the base wordnet are skipped",
+                format!("{key:?}"),
+            );
+            continue;
+        };
+        let value = value.trim().to_string();
+        let synset_rows = rows.entry(synset_id).or_default();
+        let numbered = match kind {
+            "lemma" => {
+                synset_rows.lemmas.push(value);
+                continue;
+            }
+            "def" => &mut synset_rows.definitions,
+            _ => &mut synset_rows.examples,
+        };
+        // A row with no index is one more definition or example, after those so far
+        let index = match index {
+            Some(index) => {
+                index
+                    .trim()
+                    .parse::<usize>()
+                    .map_err(|_| OmwImportError::Malformed {
+                        line: n + 1,
+                        message: format!("{kind} index {index:?} is not a number"),
+                    })?
+            }
+            None => numbered.keys().next_back().map_or(0, |i| i + 1),
+        };
+        numbered.entry(index).or_default().push(value);
+    }
+
+    // Synsets first, so each lemma has a synset to be added to and a satellite's head (the
+    // adjective it is `similar` to) is in place when its sense key is made up
+    let mut satellites = Vec::new();
+    let mut others = Vec::new();
+    for (id, synset_rows) in rows.iter() {
+        let Some(base_synset) = base.synset_by_id(id)? else {
+            continue;
+        };
+        let lexname = base.lex_name_for(id)?.unwrap_or_default();
+        let mut synset = Synset::new(base_synset.part_of_speech.clone());
+        synset.ili = base_synset.ili.clone();
+        synset.definition = Rows::joined(&synset_rows.definitions);
+        synset.example = Rows::joined(&synset_rows.examples)
+            .into_iter()
+            .map(|example| Example::new(example, None))
+            .collect();
+        for (rel, target) in base_synset.links_from() {
+            if rows.contains_key(&target) {
+                synset.insert_rel(&rel.to_yaml().1, &target);
+            }
+        }
+        if synset.part_of_speech == PartOfSpeech::s {
+            satellites.push(id);
+        } else {
+            others.push(id);
+        }
+        lexicon.insert_synset(lexname, id.clone(), synset)?;
+    }
+    let mut change_list = ChangeList::new();
+    for id in others.into_iter().chain(satellites) {
+        let pos = lexicon
+            .synset_by_id(id)?
+            .map(|synset| synset.part_of_speech.to_pos_key());
+        let Some(pos) = pos else {
+            continue;
+        };
+        for lemma in &rows[id].lemmas {
+            add_entry(
+                &mut lexicon,
+                id.clone(),
+                lemma.clone(),
+                pos.clone(),
+                Vec::new(),
+                None,
+                &mut change_list,
+            )?;
+        }
+    }
+    finalize_bulk_load(&mut lexicon)?;
+    warnings.report();
+
+    if let Some(language) = language {
+        let metadata = metadata.get_or_insert_with(|| read_header(""));
+        if metadata.language.is_empty() {
+            metadata.language = language;
+        }
+    }
+    Ok((lexicon, metadata))
+}
+
+/// `{label}\t{language}\t{url}\t{license}`, any of which may be missing
+fn read_header(header: &str) -> LexiconMetadata {
+    let mut fields = header.split('\t').map(|field| field.trim().to_string());
+    let mut next = || fields.next().unwrap_or_default();
+    let label = next();
+    let language = next();
+    let url = Some(next()).filter(|url| !url.is_empty());
+    let license = next();
+    LexiconMetadata {
+        id_prefix: String::new(),
+        label,
+        language,
+        email: None,
+        license,
+        version: String::new(),
+        url,
+    }
+}
+
+/// The id in `base` of the synset an OMW key names - an `i`-number is looked up by ILI (indexing
+/// `base` by ILI the first time one is), anything else as a synset id
+fn find_synset<B: Lexicon>(
+    base: &B,
+    key: &str,
+    ili_index: &mut Option<HashMap<String, SynsetId>>,
+) -> Result<Option<SynsetId>> {
+    if key.starts_with('i') && key[1..].chars().all(|c| c.is_ascii_digit()) {
+        if ili_index.is_none() {
+            let mut index = HashMap::new();
+            for lexfile in base.synsets_iter()? {
+                let (_, synsets) = lexfile?;
+                for synset in synsets.iter()? {
+                    let (id, synset) = synset?;
+                    if let Some(ili) = &synset.ili {
+                        index.insert(ili.as_str().to_string(), id);
+                    }
+                }
+            }
+            *ili_index = Some(index);
+        }
+        return Ok(ili_index.as_ref().and_then(|index| index.get(key).cloned()));
+    }
+    let id = SynsetId::new(key);
+    if base.synset_by_id(&id)?.is_some() {
+        return Ok(Some(id));
+    }
+    let other = match key.rsplit_once('-') {
+        Some((offset, "a")) => SynsetId::new_owned(format!("{offset}-s")),
+        Some((offset, "s")) => SynsetId::new_owned(format!("{offset}-a")),
+        _ => return Ok(None),
+    };
+    Ok(base.synset_by_id(&other)?.is_some().then_some(other))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::write_omw_tab;
+    use super::*;
+    use crate::wordnet::{Entry, LexiconHashMapBackend, PosKey, Sense, SenseId, ILIID};
+
+    fn base() -> LexiconHashMapBackend {
+        let mut wn = LexiconHashMapBackend::new();
+        let mut animal = Synset::new(PartOfSpeech::n);
+        animal.definition.push("a living organism".to_string());
+        animal.ili = Some(ILIID::new("i35563"));
+        wn.insert_synset(
+            "noun.animal".to_string(),
+            SynsetId::new("00015388-n"),
+            animal,
+        )
+        .unwrap();
+        let mut dog = Synset::new(PartOfSpeech::n);
+        dog.definition.push("a domestic canine".to_string());
+        dog.hypernym.push(SynsetId::new("00015388-n"));
+        dog.ili = Some(ILIID::new("i46360"));
+        wn.insert_synset("noun.animal".to_string(), SynsetId::new("02084071-n"), dog)
+            .unwrap();
+        let mut big = Synset::new(PartOfSpeech::s);
+        big.definition.push("large in size".to_string());
+        wn.insert_synset("adj.all".to_string(), SynsetId::new("01382086-s"), big)
+            .unwrap();
+        let mut entry = Entry::new();
+        entry.sense.push(Sense::new(
+            SenseId::new("dog%1:05:00::"),
+            SynsetId::new("02084071-n"),
+        ));
+        wn.insert_entry("dog".to_string(), PosKey::new("n"), entry)
+            .unwrap();
+        wn
+    }
+
+    const TAB: &str =
+        "# Wordnet Libre du Français\tfra\thttp://alpage.inria.fr/~sagot/wolf.html\tCeCILL-C\n\
+        02084071-n\tfra:lemma\tchien\n\
+        02084071-n\tfra:def\t1\tsouvent élevé pour la compagnie\n\
+        02084071-n\tfra:def\t0\tmammifère domestique\n\
+        02084071-n\tfra:exe\t0\tle chien\n\
+        02084071-n\tfra:exe\t0\taboie\n\
+        i35563\tfra:lemma\tanimal\n\
+        01382086-a\tfra:lemma\tgrand\n\
+        02084071-n\tfra:lemma:root\tchien\n\
+        99999999-n\tfra:lemma\tinconnu\n";
+
+    #[test]
+    fn test_read_omw_tab() {
+        let (wn, metadata) =
+            read_omw_tab(LexiconHashMapBackend::new(), TAB.as_bytes(), &base()).unwrap();
+        let metadata = metadata.unwrap();
+        assert_eq!(metadata.label, "Wordnet Libre du Français");
+        assert_eq!(metadata.language, "fra");
+        assert_eq!(metadata.license, "CeCILL-C");
+
+        assert_eq!(wn.n_synsets().unwrap(), 3);
+        let dog = wn
+            .synset_by_id(&SynsetId::new("02084071-n"))
+            .unwrap()
+            .unwrap();
+        assert_eq!(dog.members, vec!["chien".to_string()]);
+        // In order of their index, not the file's
+        assert_eq!(
+            dog.definition,
+            vec![
+                "mammifère domestique".to_string(),
+                "souvent élevé pour la compagnie".to_string()
+            ]
+        );
+        // Two rows with the same index make one example
+        assert_eq!(dog.example.len(), 1);
+        assert_eq!(dog.example[0].text, "le chien aboie");
+        assert_eq!(dog.ili, Some(ILIID::new("i46360")));
+        // Both ends were imported, so the hypernym link is kept
+        assert_eq!(dog.hypernym, vec![SynsetId::new("00015388-n")]);
+        assert_eq!(
+            wn.lex_name_for(&SynsetId::new("02084071-n")).unwrap(),
+            Some("noun.animal".to_string())
+        );
+
+        // Found by its ILI
+        let animal = wn
+            .synset_by_id(&SynsetId::new("00015388-n"))
+            .unwrap()
+            .unwrap();
+        assert_eq!(animal.members, vec!["animal".to_string()]);
+        // `-a` in the file, a satellite in the base wordnet
+        let big = wn
+            .synset_by_id(&SynsetId::new("01382086-s"))
+            .unwrap()
+            .unwrap();
+        assert_eq!(big.members, vec!["grand".to_string()]);
+
+        let chien = wn.entry_by_lemma("chien").unwrap();
+        assert_eq!(chien.len(), 1);
+        assert_eq!(chien[0].sense[0].id, SenseId::new("chien%1:05:00::"));
+        assert!(wn.entry_by_lemma("inconnu").unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_read_omw_tab_without_header() {
+        let tab = TAB.split_once('\n').unwrap().1;
+        let (wn, metadata) =
+            read_omw_tab(LexiconHashMapBackend::new(), tab.as_bytes(), &base()).unwrap();
+        let metadata = metadata.unwrap();
+        assert_eq!(metadata.language, "fra");
+        assert!(metadata.label.is_empty());
+        assert_eq!(wn.n_synsets().unwrap(), 3);
+    }
+
+    #[test]
+    fn test_omw_tab_round_trip() {
+        let (wn, metadata) =
+            read_omw_tab(LexiconHashMapBackend::new(), TAB.as_bytes(), &base()).unwrap();
+        let tab = write_omw_tab(&wn, &metadata.unwrap()).unwrap();
+        let tab = String::from_utf8(tab).unwrap();
+        assert!(tab.starts_with("# Wordnet Libre du Français\tfra\t"));
+        assert!(tab.contains("02084071-n\tfra:lemma\tchien\n"));
+        assert!(tab.contains("02084071-n\tfra:def\t0\tmammifère domestique\n"));
+        assert!(tab.contains("02084071-n\tfra:exe\t0\tle chien aboie\n"));
+
+        let (again, _) =
+            read_omw_tab(LexiconHashMapBackend::new(), tab.as_bytes(), &base()).unwrap();
+        assert_eq!(again.n_synsets().unwrap(), 3);
+        assert_eq!(again.n_entries().unwrap(), 3);
+        assert_eq!(
+            write_omw_tab(
+                &again,
+                &LexiconMetadata {
+                    id_prefix: String::new(),
+                    label: "Wordnet Libre du Français".to_string(),
+                    language: "fra".to_string(),
+                    email: None,
+                    license: "CeCILL-C".to_string(),
+                    version: String::new(),
+                    url: Some("http://alpage.inria.fr/~sagot/wolf.html".to_string()),
+                }
+            )
+            .unwrap(),
+            tab.into_bytes()
+        );
+    }
+}
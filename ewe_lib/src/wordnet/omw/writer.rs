@@ -0,0 +1,53 @@
+//! OMW tab file export: the header, then for each synset (by id) a `lemma` row per member, and a
+//! `def`/`exe` row per definition and example, numbered from 0. A tab or line break in the text
+//! would end its field, so is written as a space.
+
+use super::OmwExportError;
+use crate::wordnet::{Lexicon, LexiconMetadata, Synset, SynsetId, Synsets};
+
+type Result<T> = std::result::Result<T, OmwExportError>;
+
+/// Export every synset in `wn` as an OMW tab file, whose rows are tagged with
+/// `metadata.language`
+pub fn write_omw_tab<L: Lexicon>(wn: &L, metadata: &LexiconMetadata) -> Result<Vec<u8>> {
+    let mut synsets: Vec<(SynsetId, Synset)> = Vec::new();
+    for lexfile in wn.synsets_iter()? {
+        let (_, syns) = lexfile?;
+        for entry in syns.iter()? {
+            let (id, synset) = entry?;
+            synsets.push((id, synset.into_owned()));
+        }
+    }
+    synsets.sort_by(|a, b| a.0.cmp(&b.0));
+
+    let language = metadata.language.as_str();
+    let mut out = format!(
+        "# {}\t{}\t{}\t{}\n",
+        field(&metadata.label),
+        field(language),
+        field(metadata.url.as_deref().unwrap_or("")),
+        field(&metadata.license)
+    );
+    for (id, synset) in synsets.iter() {
+        for member in synset.members.iter() {
+            out.push_str(&format!("{id}\t{language}:lemma\t{}\n", field(member)));
+        }
+        for (i, definition) in synset.definition.iter().enumerate() {
+            out.push_str(&format!(
+                "{id}\t{language}:def\t{i}\t{}\n",
+                field(definition)
+            ));
+        }
+        for (i, example) in synset.example.iter().enumerate() {
+            out.push_str(&format!(
+                "{id}\t{language}:exe\t{i}\t{}\n",
+                field(&example.text)
+            ));
+        }
+    }
+    Ok(out.into_bytes())
+}
+
+fn field(text: &str) -> String {
+    text.replace(['\t', '\n', '\r'], " ")
+}
@@ -1,4 +1,4 @@
-//! RDF export - see <https://globalwordnet.github.io/schemas/> for the `wn:` vocabulary this
+//! RDF export and import - see <https://globalwordnet.github.io/schemas/> for the `wn:` vocabulary this
 //! targets, on top of `ontolex`/`lime`/`skos`/`dc`.
 //!
 //! [`write_lexicon_rdf`] produces a whole-lexicon export: every `LexicalEntry` is declared
@@ -11,9 +11,14 @@
 //! intentionally export less than the whole lexicon - it applies the same entry-dedup within
 //! whatever synsets it's given, so a multi-sense lemma still gets one `LexicalEntry` even when
 //! all its senses are passed in together (e.g. the per-lemma route).
+//!
+//! [`read_lexicon_rdf`] reads such a document (or any other OntoLex/`wn:` RDF) back into a
+//! lexicon - see [`reader`].
 
 use thiserror::Error;
 
+pub mod reader;
+pub use reader::read_lexicon_rdf;
 pub mod writer;
 pub use writer::{write_lexicon_rdf, write_lexicon_rdf_subset};
 
@@ -45,3 +50,15 @@ pub enum RdfExportError {
     #[error("Could not write RDF: {0}")]
     Lexicon(#[from] crate::wordnet::LexiconError),
 }
+
+#[derive(Error, Debug)]
+pub enum RdfImportError {
+    #[error("Could not read RDF: {0}")]
+    Parse(#[from] oxrdfio::RdfParseError),
+    #[error("Could not read RDF: {0}")]
+    Lexicon(#[from] crate::wordnet::LexiconError),
+    #[error("Could not read RDF: {0}")]
+    Load(#[from] crate::wordnet::WordNetYAMLIOError),
+    #[error("Malformed RDF document: {0}")]
+    Malformed(String),
+}
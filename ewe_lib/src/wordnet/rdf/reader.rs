@@ -0,0 +1,703 @@
+//! RDF import - the inverse of [`super::writer`], for OntoLex/`wn:` RDF in any syntax `oxrdfio`
+//! reads (Turtle, RDF/XML, N-Triples, ...).
+//!
+//! The whole document is read into memory as triples grouped by subject, since RDF puts no
+//! constraint on triple order: an entry's senses may come long before (or after) the concepts
+//! they lexicalize. Then:
+//! - every `ontolex:LexicalConcept` becomes a synset, with its `wn:definition`s and
+//!   `wn:example`s (each an `rdf:value`, plus `dc:source` for an example), `wn:ili`,
+//!   `owl:sameAs` Wikidata links, `dc:source`, `wn:lexfile` and `wn:partOfSpeech`;
+//! - every `ontolex:LexicalEntry` becomes an entry, its lemma the `ontolex:writtenRep` of its
+//!   `ontolex:canonicalForm` (and its forms those of any `ontolex:otherForm`s), with one sense
+//!   per `ontolex:sense` whose `ontolex:isLexicalizedSenseOf` (or `ontolex:reference`) is a
+//!   concept. The entry's `synsem:synBehavior` frames are given to each of its verb senses, and
+//!   their `rdfs:label`s make up the frame table;
+//! - `wn:` relations between concepts, or from a sense to a sense or concept, are stored in
+//!   their canonical direction only (`SynsetRelType::to_yaml`/`SenseRelType::to_canonical`), as
+//!   the XML reader does - our own writer emits both ends (`hypernym` and `hyponym`), which
+//!   collapse back into one. The reified `vartrans` form of a relation is ignored, as it only
+//!   repeats the direct triple.
+//!
+//! Synset ids are the last path segment of a concept's IRI (`{site}synset/00001740-n`). RDF has
+//! no sense keys, so a sense's is its `dc:identifier` if it has one (our writer adds it) and is
+//! otherwise made up the way `sense_keys::get_sense_key` would for a new sense.
+
+use super::RdfImportError;
+use crate::rels::{SenseRelType, SynsetRelType};
+use crate::sense_keys::LEX_FILENUMS;
+use crate::wordnet::lexicon::finalize_bulk_load;
+use crate::wordnet::util::Warnings;
+use crate::wordnet::{
+    Entry, Example, Lexicon, LexiconMetadata, PartOfSpeech, PosKey, Sense, SenseId,
+    SenseOrSynsetId, Synset, SynsetId, ILIID,
+};
+use oxrdf::{NamedOrBlankNode, Term};
+use oxrdfio::{RdfFormat, RdfParser};
+use percent_encoding::percent_decode_str;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::io::Read;
+
+type Result<T> = std::result::Result<T, RdfImportError>;
+
+const RDF: &str = "http://www.w3.org/1999/02/22-rdf-syntax-ns#";
+const RDFS: &str = "http://www.w3.org/2000/01/rdf-schema#";
+const ONTOLEX: &str = "http://www.w3.org/ns/lemon/ontolex#";
+const LIME: &str = "http://www.w3.org/ns/lemon/lime#";
+const SYNSEM: &str = "http://www.w3.org/ns/lemon/synsem#";
+const WN: &str = "https://globalwordnet.github.io/schemas/wn#";
+const DC: &str = "http://purl.org/dc/terms/";
+const OWL: &str = "http://www.w3.org/2002/07/owl#";
+const CC: &str = "http://creativecommons.org/ns#";
+const SCHEMA: &str = "http://schema.org/";
+const ILI: &str = "http://ili.globalwordnet.org/ili/";
+const WIKIDATA: &str = "http://www.wikidata.org/entity/";
+
+/// The `wn:` properties of a concept that are not relations
+const SYNSET_PROPERTIES: [&str; 5] = ["definition", "example", "ili", "lexfile", "partOfSpeech"];
+
+/// The inverse sense relations our writer adds, which are never stored (their forward
+/// direction, on the other sense, is)
+const DERIVED_SENSE_RELATIONS: [&str; 15] = [
+    "isParticipleOf",
+    "isAgentOf",
+    "isMaterialOf",
+    "isEventOf",
+    "isInstrumentOf",
+    "isLocationOf",
+    "isByMeansOf",
+    "isUndergoerOf",
+    "isPropertyOf",
+    "isResultOf",
+    "isStateOf",
+    "isUsedBy",
+    "isDestinationOf",
+    "isBodyPartOf",
+    "isVehicleOf",
+];
+
+/// A resource - an IRI, or `_:` and a blank node's id - and what is said about it, in
+/// document order
+#[derive(Default)]
+struct Triples {
+    by_subject: HashMap<String, Vec<(String, Term)>>,
+    /// Subjects in the order first met, so the output doesn't depend on `HashMap` order
+    subjects: Vec<String>,
+}
+
+impl Triples {
+    fn objects<'a>(&'a self, subject: &str, predicate: &'a str) -> impl Iterator<Item = &'a Term> {
+        self.by_subject
+            .get(subject)
+            .into_iter()
+            .flatten()
+            .filter(move |(p, _)| p == predicate)
+            .map(|(_, o)| o)
+    }
+
+    fn resources<'a>(
+        &'a self,
+        subject: &str,
+        predicate: &'a str,
+    ) -> impl Iterator<Item = String> + 'a {
+        self.objects(subject, predicate).filter_map(resource)
+    }
+
+    fn literal(&self, subject: &str, predicate: &str) -> Option<String> {
+        self.objects(subject, predicate).find_map(literal)
+    }
+
+    fn has_type(&self, subject: &str, class: &str) -> bool {
+        self.objects(subject, &format!("{RDF}type"))
+            .any(|o| matches!(o, Term::NamedNode(n) if n.as_str() == class))
+    }
+
+    fn of_type<'a>(&'a self, class: &'a str) -> impl Iterator<Item = &'a String> + 'a {
+        self.subjects
+            .iter()
+            .filter(move |s| self.has_type(s, class))
+    }
+}
+
+fn resource(term: &Term) -> Option<String> {
+    match term {
+        Term::NamedNode(n) => Some(n.as_str().to_string()),
+        Term::BlankNode(b) => Some(format!("_:{}", b.as_str())),
+        _ => None,
+    }
+}
+
+fn literal(term: &Term) -> Option<String> {
+    match term {
+        Term::Literal(l) => Some(l.value().to_string()),
+        _ => None,
+    }
+}
+
+/// A literal, or an IRI as a string - `dc:source` may be either
+fn literal_or_iri(term: &Term) -> Option<String> {
+    match term {
+        Term::NamedNode(n) => Some(n.as_str().to_string()),
+        _ => literal(term),
+    }
+}
+
+/// The last path segment of an IRI, percent-decoded - a synset's or frame's id
+fn local_id(iri: &str) -> String {
+    let path = iri.split('#').next().unwrap_or(iri);
+    let segment = path.rsplit('/').next().unwrap_or(path);
+    percent_decode_str(segment).decode_utf8_lossy().into_owned()
+}
+
+fn part_of_speech(iri: &str) -> Option<PartOfSpeech> {
+    let name = iri.strip_prefix(WN)?;
+    [
+        PartOfSpeech::n,
+        PartOfSpeech::v,
+        PartOfSpeech::a,
+        PartOfSpeech::r,
+        PartOfSpeech::s,
+    ]
+    .into_iter()
+    .find(|pos| pos.long_pos() == name)
+}
+
+/// `domainTopic` -> `domain_topic`, the names `SynsetRelType::from`/`SenseRelType::from` take
+fn snake_case(name: &str) -> String {
+    let mut out = String::new();
+    for c in name.chars() {
+        if c.is_ascii_uppercase() {
+            out.push('_');
+            out.push(c.to_ascii_lowercase());
+        } else {
+            out.push(c);
+        }
+    }
+    out
+}
+
+/// One sense, before its sense key (which for a satellite depends on its head's) is known
+struct PendingSense {
+    iri: String,
+    lemma: String,
+    poskey: PosKey,
+    synset: SynsetId,
+    key: Option<SenseId>,
+    subcat: Vec<String>,
+}
+
+/// Parses an RDF document in `format` and bulk-populates `lexicon` from it, the same way
+/// `Lexicon::load` bulk-populates one from a YAML source tree. Returns the lexicon together with
+/// the metadata of the document's `lime:Lexicon`, if it has one (with an empty `id_prefix`,
+/// which RDF has no equivalent of).
+pub fn read_lexicon_rdf<L: Lexicon, R: Read>(
+    mut lexicon: L,
+    reader: R,
+    format: RdfFormat,
+) -> Result<(L, Option<LexiconMetadata>)> {
+    let mut triples = Triples::default();
+    for quad in RdfParser::from_format(format).for_reader(reader) {
+        let quad = quad?;
+        let subject = match quad.subject {
+            NamedOrBlankNode::NamedNode(n) => n.into_string(),
+            NamedOrBlankNode::BlankNode(b) => format!("_:{}", b.as_str()),
+        };
+        let statements = triples.by_subject.entry(subject.clone()).or_default();
+        if statements.is_empty() {
+            triples.subjects.push(subject);
+        }
+        statements.push((quad.predicate.into_string(), quad.object));
+    }
+    let mut warnings = Warnings::default();
+
+    // Concepts
+    let mut synsets: BTreeMap<SynsetId, (String, Synset)> = BTreeMap::new();
+    let mut synset_iris: HashMap<String, SynsetId> = HashMap::new();
+    for iri in triples.of_type(&format!("{ONTOLEX}LexicalConcept")) {
+        let id = SynsetId::new_owned(local_id(iri));
+        let Some(pos) = triples
+            .resources(iri, &format!("{WN}partOfSpeech"))
+            .find_map(|pos| part_of_speech(&pos))
+        else {
+            return Err(RdfImportError::Malformed(format!(
+                "concept {iri} has no wn:partOfSpeech"
+            )));
+        };
+        let lexname = triples
+            .literal(iri, &format!("{WN}lexfile"))
+            .unwrap_or_else(|| {
+                let lexname = format!("{}.other", pos.long_pos().split('_').next().unwrap_or(""));
+                warnings.warn_once_for(
+                    &format!("Concepts with no wn:lexfile are put in {lexname}"),
+                    iri,
+                );
+                lexname
+            });
+        let mut synset = Synset::new(pos);
+        for definition in triples.resources(iri, &format!("{WN}definition")) {
+            if let Some(text) = triples.literal(&definition, &format!("{RDF}value")) {
+                synset.definition.push(text);
+            }
+        }
+        for example in triples.resources(iri, &format!("{WN}example")) {
+            if let Some(text) = triples.literal(&example, &format!("{RDF}value")) {
+                let source = triples
+                    .objects(&example, &format!("{DC}source"))
+                    .find_map(literal_or_iri);
+                synset.example.push(Example::new(text, source));
+            }
+        }
+        synset.ili = triples
+            .resources(iri, &format!("{WN}ili"))
+            .find_map(|ili| ili.strip_prefix(ILI).map(ILIID::new));
+        synset.wikidata = triples
+            .resources(iri, &format!("{OWL}sameAs"))
+            .filter_map(|wd| wd.strip_prefix(WIKIDATA).map(|id| id.to_string()))
+            .collect();
+        synset.source = triples
+            .objects(iri, &format!("{DC}source"))
+            .find_map(literal_or_iri);
+        synset_iris.insert(iri.clone(), id.clone());
+        synsets.insert(id, (lexname, synset));
+    }
+
+    // Entries and their senses
+    let mut senses: Vec<PendingSense> = Vec::new();
+    let mut entries: BTreeMap<(String, PosKey), Entry> = BTreeMap::new();
+    let mut frames: BTreeMap<String, String> = BTreeMap::new();
+    for iri in triples.of_type(&format!("{ONTOLEX}LexicalEntry")) {
+        let lemma = triples
+            .resources(iri, &format!("{ONTOLEX}canonicalForm"))
+            .find_map(|form| triples.literal(&form, &format!("{ONTOLEX}writtenRep")))
+            .ok_or_else(|| {
+                RdfImportError::Malformed(format!("entry {iri} has no canonical written form"))
+            })?;
+        let mut subcat = Vec::new();
+        for frame in triples.resources(iri, &format!("{SYNSEM}synBehavior")) {
+            let id = local_id(&frame);
+            if let Some(label) = triples.literal(&frame, &format!("{RDFS}label")) {
+                frames.insert(id.clone(), label);
+            }
+            subcat.push(id);
+        }
+        let entry_pos = triples
+            .resources(iri, &format!("{WN}partOfSpeech"))
+            .find_map(|pos| part_of_speech(&pos));
+        let mut poskey = None;
+        for sense in triples.resources(iri, &format!("{ONTOLEX}sense")) {
+            let synset = triples
+                .resources(&sense, &format!("{ONTOLEX}isLexicalizedSenseOf"))
+                .chain(triples.resources(&sense, &format!("{ONTOLEX}reference")))
+                .find_map(|concept| synset_iris.get(&concept).cloned());
+            let Some(synset) = synset else {
+                warnings.warn_once_for("Senses not of any concept are skipped", &sense);
+                continue;
+            };
+            let pos = entry_pos
+                .clone()
+                .unwrap_or_else(|| synsets[&synset].1.part_of_speech.clone());
+            let key = poskey
+                .get_or_insert_with(|| PosKey::new(pos.value()))
+                .clone();
+            senses.push(PendingSense {
+                key: triples
+                    .literal(&sense, &format!("{DC}identifier"))
+                    .map(SenseId::new),
+                iri: sense,
+                lemma: lemma.clone(),
+                poskey: key,
+                synset,
+                subcat: if pos == PartOfSpeech::v {
+                    subcat.clone()
+                } else {
+                    Vec::new()
+                },
+            });
+        }
+        let Some(poskey) = poskey.or_else(|| entry_pos.map(|pos| PosKey::new(pos.value()))) else {
+            warnings.warn_once_for("Entries with no senses or part of speech are skipped", iri);
+            continue;
+        };
+        let entry = entries.entry((lemma, poskey)).or_default();
+        for form in triples.resources(iri, &format!("{ONTOLEX}otherForm")) {
+            if let Some(form) = triples.literal(&form, &format!("{ONTOLEX}writtenRep")) {
+                if !entry.form.contains(&form) {
+                    entry.form.push(form);
+                }
+            }
+        }
+    }
+    assign_sense_keys(&mut senses, &synsets, &synset_iris, &triples);
+
+    // Relations
+    let sense_ids: HashMap<&str, SenseId> = senses
+        .iter()
+        .map(|sense| {
+            let key = sense.key.clone().expect("Every sense has a key by now");
+            (sense.iri.as_str(), key)
+        })
+        .collect();
+    // In document order, not `synset_iris`' hash order, so that relations are added (and
+    // warned of) in the same order on every import
+    for iri in triples.of_type(&format!("{ONTOLEX}LexicalConcept")) {
+        let id = &synset_iris[iri];
+        for (predicate, object) in triples.by_subject[iri].iter() {
+            let Some(name) = predicate.strip_prefix(WN) else {
+                continue;
+            };
+            let Some(target) = resource(object).and_then(|t| synset_iris.get(&t)) else {
+                continue;
+            };
+            if SYNSET_PROPERTIES.contains(&name) {
+                continue;
+            }
+            match SynsetRelType::from(&snake_case(name)) {
+                Some(rel) => {
+                    let (forward, rel) = rel.to_yaml();
+                    let (on, to) = if forward { (id, target) } else { (target, id) };
+                    if let Some((_, synset)) = synsets.get_mut(on) {
+                        synset.insert_rel(&rel, to);
+                    }
+                }
+                None => warnings.warn_once(format!("Unsupported synset relation wn:{name}")),
+            }
+        }
+    }
+    let mut sense_rels: HashMap<SenseId, Vec<(SenseRelType, SenseOrSynsetId)>> = HashMap::new();
+    for sense in senses.iter() {
+        let source = &sense_ids[sense.iri.as_str()];
+        for (predicate, object) in triples.by_subject[&sense.iri].iter() {
+            let Some(name) = predicate.strip_prefix(WN) else {
+                continue;
+            };
+            if DERIVED_SENSE_RELATIONS.contains(&name) {
+                continue;
+            }
+            let Some(target) = resource(object) else {
+                continue;
+            };
+            let target = match (sense_ids.get(target.as_str()), synset_iris.get(&target)) {
+                (Some(sense), _) => SenseOrSynsetId::Sense(sense.clone()),
+                (None, Some(synset)) => SenseOrSynsetId::Synset(synset.clone()),
+                (None, None) => {
+                    warnings.warn_once_for(
+                        &format!(
+                            "Relations wn:{name} to a resource not in the document are skipped"
+                        ),
+                        &target,
+                    );
+                    continue;
+                }
+            };
+            let Some(rel) = SenseRelType::from(&snake_case(name)) else {
+                warnings.warn_once(format!("Unsupported sense relation wn:{name}"));
+                continue;
+            };
+            let (forward, rel) = rel.to_canonical();
+            match (forward, target) {
+                (true, target) => {
+                    sense_rels
+                        .entry(source.clone())
+                        .or_default()
+                        .push((rel, target));
+                }
+                (false, SenseOrSynsetId::Sense(target)) => {
+                    let source = SenseOrSynsetId::Sense(source.clone());
+                    sense_rels.entry(target).or_default().push((rel, source));
+                }
+                // A `has_domain_topic` (etc.) to a whole concept can't be turned around, as
+                // there's no sense on that end to store it on
+                (false, SenseOrSynsetId::Synset(_)) => warnings.warn_once(format!(
+                    "Sense relation wn:{name} to a concept can't be stored"
+                )),
+            }
+        }
+    }
+
+    for pending in senses {
+        let id = pending.key.expect("Every sense has a key by now");
+        let mut sense = Sense::new(id.clone(), pending.synset.clone());
+        sense.subcat = pending.subcat;
+        for (rel, target) in sense_rels.remove(&id).unwrap_or_default() {
+            sense.add_rel(rel, target);
+        }
+        if let Some((_, synset)) = synsets.get_mut(&pending.synset) {
+            if !synset.members.contains(&pending.lemma) {
+                synset.members.push(pending.lemma.clone());
+            }
+        }
+        entries
+            .entry((pending.lemma, pending.poskey))
+            .or_default()
+            .sense
+            .push(sense);
+    }
+
+    for ((lemma, poskey), entry) in entries {
+        lexicon.insert_entry(lemma, poskey, entry)?;
+    }
+    for (id, (lexname, synset)) in synsets {
+        lexicon.insert_synset(lexname, id, synset)?;
+    }
+    if !frames.is_empty() {
+        lexicon.frames_set(frames.into_iter().collect())?;
+    }
+    finalize_bulk_load(&mut lexicon)?;
+    warnings.report();
+
+    let metadata = triples
+        .of_type(&format!("{LIME}Lexicon"))
+        .next()
+        .map(|iri| read_metadata(&triples, iri));
+    Ok((lexicon, metadata))
+}
+
+/// Makes up the sense keys of the senses without one: `lemma%ss_type:lex_filenum:lex_id::`,
+/// with the head word and its lex id at the end for a satellite, and lex ids counting up from
+/// `0` for each lemma in each lexicographer file (skipping any the given keys use).
+fn assign_sense_keys(
+    senses: &mut [PendingSense],
+    synsets: &BTreeMap<SynsetId, (String, Synset)>,
+    synset_iris: &HashMap<String, SynsetId>,
+    triples: &Triples,
+) {
+    let key_lemma = |lemma: &str| {
+        lemma
+            .replace('_', "-lowbar-")
+            .replace(' ', "_")
+            .to_lowercase()
+    };
+    let lex_filenum = |synset: &SynsetId| {
+        synsets
+            .get(synset)
+            .and_then(|(lexname, _)| LEX_FILENUMS.get(lexname.as_str()))
+            .copied()
+            .unwrap_or(99)
+    };
+    let mut used: HashSet<(String, usize, u32)> = HashSet::new();
+    for sense in senses.iter() {
+        if let Some(key) = &sense.key {
+            let lex_id = crate::sense_keys::extract_lex_id(key);
+            if lex_id >= 0 {
+                used.insert((
+                    key_lemma(&sense.lemma),
+                    lex_filenum(&sense.synset),
+                    lex_id as u32,
+                ));
+            }
+        }
+    }
+    let mut lex_ids: HashMap<(String, SynsetId), u32> = HashMap::new();
+    let mut next_lex_id = |lemma: &str, synset: &SynsetId| {
+        let (lemma, filenum) = (key_lemma(lemma), lex_filenum(synset));
+        let lex_id = (0..)
+            .find(|n| !used.contains(&(lemma.clone(), filenum, *n)))
+            .unwrap_or(0);
+        used.insert((lemma, filenum, lex_id));
+        lex_id
+    };
+
+    // Satellites last, as each needs its head's key
+    let is_satellite = |synset: &SynsetId| {
+        synsets
+            .get(synset)
+            .is_some_and(|(_, s)| s.part_of_speech == PartOfSpeech::s)
+    };
+    for satellites in [false, true] {
+        for i in 0..senses.len() {
+            if senses[i].key.is_some() || is_satellite(&senses[i].synset) != satellites {
+                continue;
+            }
+            let lex_id = next_lex_id(&senses[i].lemma, &senses[i].synset);
+            lex_ids.insert((senses[i].lemma.clone(), senses[i].synset.clone()), lex_id);
+            let (head_word, head_id) = if satellites {
+                head(
+                    senses,
+                    synsets,
+                    synset_iris,
+                    triples,
+                    &senses[i].synset,
+                    &lex_ids,
+                )
+                .unwrap_or_default()
+            } else {
+                (String::new(), String::new())
+            };
+            let sense = &mut senses[i];
+            let ss_type = synsets[&sense.synset].1.part_of_speech.ss_type();
+            sense.key = Some(SenseId::new(format!(
+                "{}%{}:{:02}:{:02}:{}:{}",
+                key_lemma(&sense.lemma),
+                ss_type,
+                lex_filenum(&sense.synset),
+                lex_id,
+                head_word,
+                head_id
+            )));
+        }
+    }
+}
+
+/// The head word of a satellite - the first member of the adjective concept it is `wn:similar`
+/// to - and that member's lex id, as a satellite's sense key ends with them
+fn head(
+    senses: &[PendingSense],
+    synsets: &BTreeMap<SynsetId, (String, Synset)>,
+    synset_iris: &HashMap<String, SynsetId>,
+    triples: &Triples,
+    satellite: &SynsetId,
+    lex_ids: &HashMap<(String, SynsetId), u32>,
+) -> Option<(String, String)> {
+    let (iri, _) = synset_iris.iter().find(|(_, id)| *id == satellite)?;
+    let head = triples
+        .resources(iri, &format!("{WN}similar"))
+        .filter_map(|target| synset_iris.get(&target))
+        .find(|id| {
+            synsets
+                .get(*id)
+                .is_some_and(|(_, s)| s.part_of_speech == PartOfSpeech::a)
+        })?;
+    let sense = senses.iter().find(|sense| sense.synset == *head)?;
+    let lex_id = match &sense.key {
+        Some(key) => crate::sense_keys::extract_lex_id(key).max(0) as u32,
+        None => *lex_ids.get(&(sense.lemma.clone(), head.clone()))?,
+    };
+    let word = sense.lemma.replace(' ', "_").to_lowercase();
+    Some((word, format!("{lex_id:02}")))
+}
+
+fn read_metadata(triples: &Triples, iri: &str) -> LexiconMetadata {
+    let label = triples
+        .objects(iri, &format!("{RDFS}label"))
+        .find_map(|o| match o {
+            Term::Literal(l) => Some((l.value().to_string(), l.language().map(|l| l.to_string()))),
+            _ => None,
+        });
+    let language = triples
+        .literal(iri, &format!("{DC}language"))
+        .or_else(|| label.as_ref().and_then(|(_, language)| language.clone()))
+        .unwrap_or_default();
+    LexiconMetadata {
+        id_prefix: String::new(),
+        label: label.map(|(label, _)| label).unwrap_or_default(),
+        language,
+        email: triples.literal(iri, &format!("{SCHEMA}email")),
+        license: triples
+            .resources(iri, &format!("{CC}license"))
+            .next()
+            .unwrap_or_default(),
+        version: triples
+            .literal(iri, &format!("{OWL}versionInfo"))
+            .unwrap_or_default(),
+        url: triples
+            .objects(iri, &format!("{SCHEMA}url"))
+            .find_map(literal_or_iri),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{write_lexicon_rdf, RdfExportOptions};
+    use super::*;
+    use crate::wordnet::fixtures::round_trip_fixture;
+    use crate::wordnet::LexiconHashMapBackend;
+
+    fn options(format: RdfFormat) -> RdfExportOptions {
+        RdfExportOptions {
+            format,
+            site: "https://example.com/".to_owned(),
+            metadata: LexiconMetadata {
+                id_prefix: String::new(),
+                label: "Example Wordnet".to_owned(),
+                language: "en".to_owned(),
+                email: None,
+                license: "https://creativecommons.org/licenses/by/4.0/".to_owned(),
+                version: "1".to_owned(),
+                url: None,
+            },
+        }
+    }
+
+    #[test]
+    fn test_read_lexicon_rdf_round_trip() {
+        let wn = round_trip_fixture();
+        for format in [RdfFormat::Turtle, RdfFormat::RdfXml] {
+            let rdf = write_lexicon_rdf(&wn, &options(format)).unwrap();
+            let (read, metadata) =
+                read_lexicon_rdf(LexiconHashMapBackend::new(), rdf.as_slice(), format).unwrap();
+            let metadata = metadata.unwrap();
+            assert_eq!(metadata.label, "Example Wordnet");
+            assert_eq!(metadata.language, "en");
+
+            assert_eq!(read.n_synsets().unwrap(), 3);
+            assert_eq!(read.n_entries().unwrap(), 3);
+            let ewe = read
+                .synset_by_id(&SynsetId::new("00000002-n"))
+                .unwrap()
+                .unwrap();
+            assert_eq!(ewe.definition, vec!["a female sheep".to_string()]);
+            assert_eq!(ewe.example[0].text, "the ewe and her lamb");
+            assert_eq!(ewe.example[0].source, Some("Anon".to_string()));
+            assert_eq!(ewe.members, vec!["ewe".to_string()]);
+            // `hypernym` and the `hyponym` written back from the other end are one relation
+            assert_eq!(ewe.hypernym, vec![SynsetId::new("00000001-n")]);
+            let sheep = read
+                .synset_by_id(&SynsetId::new("00000001-n"))
+                .unwrap()
+                .unwrap();
+            assert_eq!(sheep.ili, Some(ILIID::new("i1")));
+            assert_eq!(sheep.wikidata, vec!["Q7368".to_string()]);
+            assert_eq!(
+                read.lex_name_for(&SynsetId::new("00000003-v")).unwrap(),
+                Some("verb.communication".to_string())
+            );
+
+            let (_, _, bleat) = read
+                .get_sense_by_id(&SenseId::new("bleat%2:32:00::"))
+                .unwrap()
+                .unwrap();
+            assert_eq!(bleat.subcat, vec!["via".to_string()]);
+            assert_eq!(bleat.derivation, vec![SenseId::new("sheep%1:05:00::")]);
+            assert_eq!(
+                read.frames_get().unwrap().to_vec(),
+                vec![("via".to_string(), "Somebody ----s".to_string())]
+            );
+        }
+    }
+
+    #[test]
+    fn test_read_lexicon_rdf_without_sense_keys() {
+        let turtle = r#"
+@prefix ontolex: <http://www.w3.org/ns/lemon/ontolex#> .
+@prefix wn: <https://globalwordnet.github.io/schemas/wn#> .
+@prefix rdf: <http://www.w3.org/1999/02/22-rdf-syntax-ns#> .
+<https://example.org/synset/1-n> a ontolex:LexicalConcept ;
+    wn:partOfSpeech wn:noun ;
+    wn:lexfile "noun.animal" ;
+    wn:definition [ rdf:value "a domestic cat"@en ] .
+<https://example.org/synset/2-n> a ontolex:LexicalConcept ;
+    wn:partOfSpeech wn:noun ;
+    wn:lexfile "noun.animal" ;
+    wn:hypernym <https://example.org/synset/1-n> .
+<https://example.org/lemma/cat-n> a ontolex:LexicalEntry ;
+    ontolex:canonicalForm [ ontolex:writtenRep "cat"@en ] ;
+    ontolex:otherForm [ ontolex:writtenRep "cats"@en ] ;
+    ontolex:sense [ ontolex:isLexicalizedSenseOf <https://example.org/synset/1-n> ] ,
+        [ ontolex:isLexicalizedSenseOf <https://example.org/synset/2-n> ] .
+"#;
+        let (read, metadata) = read_lexicon_rdf(
+            LexiconHashMapBackend::new(),
+            turtle.as_bytes(),
+            RdfFormat::Turtle,
+        )
+        .unwrap();
+        assert!(metadata.is_none());
+        let entries = read.entry_by_lemma("cat").unwrap();
+        let entry = &entries[0];
+        let mut keys: Vec<&str> = entry.sense.iter().map(|s| s.id.as_str()).collect();
+        keys.sort();
+        assert_eq!(keys, vec!["cat%1:05:00::", "cat%1:05:01::"]);
+        assert_eq!(entry.form, vec!["cats".to_string()]);
+        let synset = read.synset_by_id(&SynsetId::new("2-n")).unwrap().unwrap();
+        assert_eq!(synset.hypernym, vec![SynsetId::new("1-n")]);
+    }
+}
@@ -333,6 +333,9 @@ fn write_synset_triples<W: std::io::Write>(
         )?;
         triple!(&sense, rdf::TYPE, &ontolex("LexicalSense")?);
         triple!(&sense, &ontolex("isLexicalizedSenseOf")?, &ss);
+        // The sense key, which the URI doesn't carry - `read_lexicon_rdf` would otherwise have
+        // to make up a new one
+        triple!(&sense, &dc("identifier")?, lit!(member.sense.id.as_str()));
         macro_rules! sense_rel {
             ($rel_type:ident, $rel_name:expr) => {
                 // `MemberSynset::from_synset` builds these lists (particularly the
@@ -9,7 +9,7 @@ use crate::wordnet::transaction_manager::TransactionManager;
 use std::sync::{Arc, Mutex};
 use crate::wordnet::entry::BTEntries;
 use crate::wordnet::synset::BTSynsets;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use crate::rels::{SenseRelType, SynsetRelType};
 use std::borrow::Cow;
 use std::path::Path;
@@ -53,6 +53,10 @@ pub struct ReDBLexicon {
     txn_manager: Arc<Mutex<TransactionManager>>,
     entries: HashMap<char, ReDBEntries>,
     synsets: HashMap<String, ReDBSynsets>,
+    /// The lexnames known when the outstanding checkpoint was taken; the
+    /// table contents themselves are covered by the transaction manager's
+    /// savepoint, but `synsets` is only an in-memory view over them.
+    checkpoint_lexnames: Option<HashSet<String>>,
 }
 
 impl ReDBLexicon {
@@ -97,6 +101,7 @@ impl ReDBLexicon {
             txn_manager,
             entries,
             synsets,
+            checkpoint_lexnames: None,
         })
     }
 
@@ -141,6 +146,7 @@ impl ReDBLexicon {
             txn_manager,
             entries,
             synsets: HashMap::new(),
+            checkpoint_lexnames: None,
         })
     }
 }
@@ -461,6 +467,13 @@ impl Lexicon for ReDBLexicon {
         )?;
         Ok(())
     }
+    fn sense_id_to_lemma_pos_remove(&mut self, sense_id : &SenseId) -> Result<()> {
+        let mut manager = self.txn_manager.lock().unwrap();
+        let txn = manager.begin_write()?;
+        let mut table = txn.open_table(SENSE_ID_TO_LEMMA_POS)?;
+        table.remove(sense_id.to_string())?;
+        Ok(())
+    }
     fn deprecations_get<'a>(&'a self) -> Result<Cow<'a, Vec<DeprecationRecord>>> {
         let mut manager = self.txn_manager.lock().unwrap();
         let txn = manager.begin_read()?;
@@ -481,6 +494,13 @@ impl Lexicon for ReDBLexicon {
         table.insert(DEPRECATION_KEY, serialize_deprecations(deprecations)?)?;
         Ok(())
     }
+    fn deprecations_set(&mut self, records : Vec<DeprecationRecord>) -> Result<()> {
+        let mut manager = self.txn_manager.lock().unwrap();
+        let txn = manager.begin_write()?;
+        let mut table = txn.open_table(DEPRECATIONS)?;
+        table.insert(DEPRECATION_KEY, serialize_deprecations(records)?)?;
+        Ok(())
+    }
     fn frames_get<'a>(&'a self) -> Result<Cow<'a, Vec<(String, String)>>> {
         let mut manager = self.txn_manager.lock().unwrap();
         let txn = manager.begin_read()?;
@@ -562,6 +582,23 @@ impl Lexicon for ReDBLexicon {
         table.insert(LAST_SAVED_CHANGELOG_ID_KEY, id)?;
         Ok(())
     }
+    fn checkpoint(&mut self) -> Result<()> {
+        self.txn_manager.lock().unwrap().savepoint()?;
+        self.checkpoint_lexnames = Some(self.synsets.keys().cloned().collect());
+        Ok(())
+    }
+    fn rollback_to_checkpoint(&mut self) -> Result<()> {
+        self.txn_manager.lock().unwrap().restore_savepoint()?;
+        if let Some(lexnames) = self.checkpoint_lexnames.take() {
+            self.synsets.retain(|lexname, _| lexnames.contains(lexname));
+        }
+        Ok(())
+    }
+    fn release_checkpoint(&mut self) -> Result<()> {
+        self.txn_manager.lock().unwrap().release_savepoint();
+        self.checkpoint_lexnames = None;
+        Ok(())
+    }
 
 
     /// Number of entries in the dictionary
@@ -160,6 +160,16 @@ impl Sense {
         self.other.retain(|x| *x != target);
     }
 
+    /// The example sentences of a verb sense, with `%s` where the verb goes - what WNDB's
+    /// `sents.vrb` holds
+    pub fn sent(&self) -> &[String] {
+        &self.sent
+    }
+
+    pub(crate) fn set_sent(&mut self, sent: Vec<String>) {
+        self.sent = sent;
+    }
+
     pub(crate) fn save<W: Write>(&self, w: &mut W) -> std::io::Result<()> {
         write!(w, "\n    - ")?;
         let mut first = true;
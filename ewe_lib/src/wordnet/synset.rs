@@ -188,6 +188,33 @@ impl Synset {
         self.other.retain(|x| x != target);
     }
 
+    /// Remove the relation of type `rel_type` to `target_id`, leaving any others to it
+    pub(crate) fn remove_rel_type(&mut self, rel_type : &YamlSynsetRelType,
+                      target_id : &SynsetId) {
+        let targets = match rel_type {
+            YamlSynsetRelType::Also => &mut self.also,
+            YamlSynsetRelType::Attribute => &mut self.attribute,
+            YamlSynsetRelType::Causes => &mut self.causes,
+            YamlSynsetRelType::DomainRegion => &mut self.domain_region,
+            YamlSynsetRelType::DomainTopic => &mut self.domain_topic,
+            YamlSynsetRelType::Exemplifies => &mut self.exemplifies,
+            YamlSynsetRelType::Entails => &mut self.entails,
+            YamlSynsetRelType::Hypernym => &mut self.hypernym,
+            YamlSynsetRelType::InstanceHypernym => &mut self.instance_hypernym,
+            YamlSynsetRelType::MeroLocation => &mut self.mero_location,
+            YamlSynsetRelType::MeroMember => &mut self.mero_member,
+            YamlSynsetRelType::MeroPart => &mut self.mero_part,
+            YamlSynsetRelType::MeroPortion => &mut self.mero_portion,
+            YamlSynsetRelType::MeroSubstance => &mut self.mero_substance,
+            YamlSynsetRelType::Meronym => &mut self.meronym,
+            YamlSynsetRelType::Similar => &mut self.similar,
+            YamlSynsetRelType::Feminine => &mut self.feminine,
+            YamlSynsetRelType::Masculine => &mut self.masculine,
+            YamlSynsetRelType::Other => &mut self.other
+        };
+        targets.retain(|id| id != target_id);
+    }
+
     pub(crate) fn insert_rel(&mut self, rel_type : &YamlSynsetRelType,
                       target_id : &SynsetId) {
         match rel_type {
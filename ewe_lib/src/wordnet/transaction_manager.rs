@@ -1,6 +1,6 @@
 use std::sync::Arc;
 
-use redb::{Database, ReadTransaction, WriteTransaction, ReadableDatabase};
+use redb::{Database, ReadTransaction, ReadableDatabase, Savepoint, WriteTransaction};
 
 use crate::wordnet::Result;
 
@@ -10,9 +10,12 @@ use crate::wordnet::Result;
 /// - Whenever a read is requested, any open write transaction is committed first,
 ///   then a fresh `ReadTransaction` is created.
 /// - When the manager is dropped, any in-flight write transaction is committed.
+/// - At most one savepoint can be outstanding, to which the database can be
+///   restored even after the writes made since have been committed.
 pub struct TransactionManager {
     db: Arc<Database>,
     write_txn: Option<WriteTransaction>,
+    savepoint: Option<Savepoint>,
 }
 
 impl TransactionManager {
@@ -21,6 +24,7 @@ impl TransactionManager {
         Self {
             db,
             write_txn: None,
+            savepoint: None,
         }
     }
 
@@ -46,6 +50,41 @@ impl TransactionManager {
         }
         Ok(self.write_txn.as_mut().unwrap())
     }
+
+    /// Take a savepoint of the database as it is now, replacing any
+    /// outstanding one.
+    ///
+    /// Pending writes are committed first, since redb can only take a
+    /// savepoint from a write transaction that has not written anything yet.
+    pub fn savepoint(&mut self) -> Result<()> {
+        self.savepoint = None;
+        if let Some(txn) = self.write_txn.take() {
+            txn.commit()?;
+        }
+        let txn = self.db.begin_write()?;
+        self.savepoint = Some(txn.ephemeral_savepoint()?);
+        self.write_txn = Some(txn);
+        Ok(())
+    }
+
+    /// Restore the database to the outstanding savepoint (if any), discarding
+    /// every write made since, committed or not.
+    pub fn restore_savepoint(&mut self) -> Result<()> {
+        if let Some(savepoint) = self.savepoint.take() {
+            if let Some(txn) = self.write_txn.take() {
+                txn.abort()?;
+            }
+            let mut txn = self.db.begin_write()?;
+            txn.restore_savepoint(&savepoint)?;
+            txn.commit()?;
+        }
+        Ok(())
+    }
+
+    /// Drop the outstanding savepoint (if any), keeping every write made since.
+    pub fn release_savepoint(&mut self) {
+        self.savepoint = None;
+    }
 }
 
 impl Drop for TransactionManager {
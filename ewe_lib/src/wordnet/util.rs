@@ -6,7 +6,9 @@ use serde::de::{self, Visitor};
 use lazy_static::lazy_static;
 use regex::Regex;
 use crate::wordnet::*;
+use std::borrow::Cow;
 use std::result;
+use unicode_normalization::{is_nfc, UnicodeNormalization};
 
 static YAML_LINE_LENGTH : usize = 80;
 lazy_static! {
@@ -149,6 +151,8 @@ pub enum WordNetYAMLIOError {
 pub enum LexiconError {
     #[error("Synset Identifier not found: {0}")]
     SynsetIdNotFound(SynsetId),
+    #[error("Sense Identifier not found: {0}")]
+    SenseIdNotFound(SenseId),
     #[error("No such entry: ({0}, {1})")]
     EntryNotFound(String, PosKey),
     #[error("Identifier is neither a sense nor a synset: {0}")]
@@ -174,6 +178,9 @@ pub enum LexiconError {
     #[error("DB error: {0}")]
     DBDatabaseError(#[from] redb::DatabaseError),
     #[cfg(feature="redb")]
+    #[error("DB error: {0}")]
+    DBSavepointError(#[from] redb::SavepointError),
+    #[cfg(feature="redb")]
     #[error("Speedy error: {0}")]
     SpeedyError(#[from] speedy::Error),
 }
@@ -188,6 +195,85 @@ pub enum LexiconSaveError {
     Csv(#[from] csv::Error)
 }
 
+/// Typographic variants of ASCII punctuation and spaces, with what they are normalised to
+const TYPOGRAPHIC : &[(char, &str)] = &[
+    ('\u{2018}', "'"),  // left single quotation mark
+    ('\u{2019}', "'"),  // right single quotation mark (the curly apostrophe)
+    ('\u{02BC}', "'"),  // modifier letter apostrophe
+    ('\u{201C}', "\""), // left double quotation mark
+    ('\u{201D}', "\""), // right double quotation mark
+    ('\u{00A0}', " "),  // no-break space
+    ('\u{2007}', " "),  // figure space
+    ('\u{202F}', " "),  // narrow no-break space
+    ('\u{2009}', " "),  // thin space
+    ('\u{200B}', ""),   // zero width space
+    ('\u{FEFF}', ""),   // zero width no-break space (a stray byte order mark)
+];
+
+/// `text` as it should be stored: in Unicode Normalization Form C, with curly apostrophes and
+/// quotes straightened and non-breaking or zero-width spaces replaced by plain ones (or
+/// dropped). Borrowed if `text` is already normalised.
+pub fn normalise_text(text : &str) -> Cow<'_, str> {
+    if is_nfc(text) && !text.chars().any(|c| TYPOGRAPHIC.iter().any(|(t, _)| *t == c)) {
+        return Cow::Borrowed(text);
+    }
+    // Replaced before composing, as dropping a zero width space can leave a combining mark
+    // next to the letter it belongs with
+    let mut replaced = String::with_capacity(text.len());
+    for c in text.chars() {
+        match TYPOGRAPHIC.iter().find(|(t, _)| *t == c) {
+            Some((_, replacement)) => replaced.push_str(replacement),
+            None => replaced.push(c)
+        }
+    }
+    Cow::Owned(replaced.nfc().collect())
+}
+
+/// The problems an importer skips over, each printed the first time it is met and only counted
+/// after that, so that a file with the same problem throughout doesn't flood stderr. A message
+/// names the kind of problem, not the item it was met on, so is the same for every occurrence.
+#[derive(Default)]
+pub(crate) struct Warnings {
+    counts : Vec<(String, usize)>
+}
+
+impl Warnings {
+    /// Warn of `message`, if not already warned of
+    pub(crate) fn warn_once(&mut self, message : impl Into<String>) {
+        let message = message.into();
+        if self.count(&message) == 1 {
+            eprintln!("{message} (further occurrences are counted, not printed)");
+        }
+    }
+
+    /// Warn of `message`, met on `example`, if not already warned of
+    pub(crate) fn warn_once_for(&mut self, message : &str, example : impl fmt::Display) {
+        if self.count(message) == 1 {
+            eprintln!("{message}, e.g. {example} (further occurrences are counted, not printed)");
+        }
+    }
+
+    fn count(&mut self, message : &str) -> usize {
+        match self.counts.iter_mut().find(|(m, _)| m == message) {
+            Some((_, count)) => {
+                *count += 1;
+                *count
+            },
+            None => {
+                self.counts.push((message.to_string(), 1));
+                1
+            }
+        }
+    }
+
+    /// Print how often each message warned of more than once was met
+    pub(crate) fn report(&self) {
+        for (message, count) in self.counts.iter().filter(|(_, count)| *count > 1) {
+            eprintln!("{message}: {count} occurrences");
+        }
+    }
+}
+
 /// Deserialize a string or a vector of strings
 pub fn string_or_vec<'de, D>(deserializer: D) -> result::Result<Vec<String>, D::Error>
 where
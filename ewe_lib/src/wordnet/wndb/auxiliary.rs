@@ -0,0 +1,148 @@
+//! The rest of a WNDB directory, which `gwn-scala-api`'s `WNDB.write` never produced but the
+//! original `wn` binary, NLTK and `WordNet::QueryData` all expect to find in `WNHOME`:
+//! `lexnames`, `cntlist`/`cntlist.rev`, `sentidx.vrb`/`sents.vrb` and `verb.Framestext`. None of
+//! these has a header, as in Princeton's own release.
+
+use super::tables::PRINCETON_FRAMES;
+use super::WndbExportError;
+use crate::sense_keys::LEX_FILENUMS;
+use crate::wordnet::{Lexicon, PartOfSpeech, Sense};
+use std::collections::{BTreeMap, HashMap};
+use std::path::Path;
+
+type Result<T> = std::result::Result<T, WndbExportError>;
+
+/// `lexnames`: `{number}\t{name}\t{syntactic category}`, for each lexicographer file the
+/// lexicon's synsets are in (numbered as `lex_name_number` numbered them in `data.*`), the
+/// category being that of the file's synsets
+pub(super) fn write_lexnames<L: Lexicon>(
+    wn: &L,
+    extra_lexnames: &HashMap<String, usize>,
+    out_dir: &Path,
+) -> Result<()> {
+    let mut lexnames: BTreeMap<usize, (String, u8)> = BTreeMap::new();
+    for entry in wn.synsets()? {
+        let (id, synset) = entry?;
+        let name = wn.lex_name_for(&id)?.unwrap_or_default();
+        let Some(n) = LEX_FILENUMS.get(name.as_str()).or_else(|| extra_lexnames.get(&name)) else {
+            continue;
+        };
+        let category = match synset.part_of_speech {
+            PartOfSpeech::n => 1,
+            PartOfSpeech::v => 2,
+            PartOfSpeech::a | PartOfSpeech::s => 3,
+            PartOfSpeech::r => 4,
+        };
+        lexnames.entry(*n).or_insert((name, category));
+    }
+    let mut out = String::new();
+    for (n, (name, category)) in lexnames {
+        out.push_str(&format!("{n:02}\t{name}\t{category}\n"));
+    }
+    std::fs::write(out_dir.join("lexnames"), out)?;
+    Ok(())
+}
+
+/// Reads tag counts in the format of `cntlist.rev` (`sense_key sense_number tag_cnt`, one sense
+/// per line) - the lexicon itself has no corpus counts, so any there are come from here
+pub(super) fn load_counts(path: Option<&Path>) -> Result<HashMap<String, u32>> {
+    let mut counts = HashMap::new();
+    let Some(path) = path else {
+        return Ok(counts);
+    };
+    for line in std::fs::read_to_string(path)?.lines() {
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        if let [key, _, count] = fields[..] {
+            if let Ok(count) = count.parse() {
+                counts.insert(key.to_string(), count);
+            }
+        }
+    }
+    Ok(counts)
+}
+
+/// `cntlist.rev` (`sense_key sense_number tag_cnt`, by sense key) and `cntlist` (`tag_cnt
+/// sense_key sense_number`, most frequent first), for the senses with a count.
+/// `senses` are the sense keys as written to `index.sense`, with their sense numbers.
+pub(super) fn write_counts(
+    senses: &[(String, usize)],
+    counts: &HashMap<String, u32>,
+    out_dir: &Path,
+) -> Result<()> {
+    let mut counted: Vec<(&str, usize, u32)> = senses
+        .iter()
+        .filter_map(|(key, n)| Some((key.as_str(), *n, *counts.get(key).filter(|c| **c > 0)?)))
+        .collect();
+    counted.sort();
+    let mut out = String::new();
+    for (key, n, count) in counted.iter() {
+        out.push_str(&format!("{key} {n} {count}\n"));
+    }
+    std::fs::write(out_dir.join("cntlist.rev"), out)?;
+
+    counted.sort_by(|a, b| b.2.cmp(&a.2).then(a.0.cmp(b.0)));
+    let mut out = String::new();
+    for (key, n, count) in counted.iter() {
+        out.push_str(&format!("{count} {key} {n}\n"));
+    }
+    std::fs::write(out_dir.join("cntlist"), out)?;
+    Ok(())
+}
+
+/// `sents.vrb` (`{number} {sentence}`) and `sentidx.vrb` (`{sense_key} {number},{number}...`)
+/// from the verb senses' `sent`s, each distinct sentence numbered in the order first met going
+/// through the senses by key. `senses` are the verb senses, with their keys as written to
+/// `index.sense`.
+pub(super) fn write_verb_sentences<'a>(
+    senses: impl Iterator<Item = (String, &'a Sense)>,
+    out_dir: &Path,
+) -> Result<()> {
+    let mut senses: Vec<(String, &Sense)> = senses
+        .filter(|(_, sense)| !sense.sent().is_empty())
+        .collect();
+    senses.sort_by(|a, b| a.0.cmp(&b.0));
+    let mut numbers: HashMap<&str, usize> = HashMap::new();
+    let mut sentences: Vec<&str> = Vec::new();
+    let mut index = String::new();
+    for (key, sense) in senses.iter() {
+        let mut sense_numbers = Vec::new();
+        for sentence in sense.sent() {
+            let n = *numbers.entry(sentence.as_str()).or_insert_with(|| {
+                sentences.push(sentence.as_str());
+                sentences.len()
+            });
+            sense_numbers.push(n.to_string());
+        }
+        index.push_str(&format!("{key} {}\n", sense_numbers.join(",")));
+    }
+    std::fs::write(out_dir.join("sentidx.vrb"), index)?;
+
+    let mut out = String::new();
+    for (i, sentence) in sentences.iter().enumerate() {
+        out.push_str(&format!("{} {sentence}\n", i + 1));
+    }
+    std::fs::write(out_dir.join("sents.vrb"), out)?;
+    Ok(())
+}
+
+/// `verb.Framestext`: `{number} {frame}` for each of the lexicon's frames with a Princeton number
+/// `data.verb` can refer to (frames 36 and up are never written there - see `write_frames`)
+pub(super) fn write_frames_text(frames: &[(String, String)], out_dir: &Path) -> Result<()> {
+    let mut numbered: Vec<(u32, &str)> = frames
+        .iter()
+        .filter_map(|(_, description)| {
+            PRINCETON_FRAMES
+                .get(description.as_str())
+                .map(|n| (*n, description.as_str()))
+        })
+        .filter(|(n, _)| *n < 36)
+        .collect();
+    numbered.sort();
+    numbered.dedup();
+    let mut out = String::new();
+    for (n, description) in numbered {
+        out.push_str(&format!("{n} {description}\n"));
+    }
+    std::fs::write(out_dir.join("verb.Framestext"), out)?;
+    Ok(())
+}
@@ -1,21 +1,25 @@
 //! WNDB export - the classic Princeton WordNet database file set (`data.*`/`index.*`/
 //! `index.sense`/`*.exc`), a faithful port of `gwn-scala-api`'s `WNDB.write` (see [`writer`]'s
-//! doc comment). Write-only: WNDB is a legacy release target `ewe` produces, never a source of
-//! truth it reads back in.
+//! doc comment) plus the other files of a `WNHOME` it left out (see [`auxiliary`]) - and its
+//! import ([`reader`]), which builds a lexicon from such a directory - to bring in a wordnet
+//! only released as WNDB, and to round-trip-test the writer.
 
 use crate::wordnet::SynsetId;
 use std::path::PathBuf;
 use thiserror::Error;
 
+mod auxiliary;
+mod reader;
 mod sense_orders;
 mod tables;
 mod writer;
 
+pub use reader::read_wndb;
 pub use writer::write_wndb;
 
 /// `WNDB.write`'s options that actually matter for a from-scratch export - no
 /// `id`/`label`/`email`/etc: none of that lexicon metadata is ever read by the write path (only
-/// by `WNDB.read`, which `ewe` doesn't implement).
+/// by `WNDB.read`, whose counterpart here, [`read_wndb`], takes it from the caller instead).
 #[derive(Debug, Clone, Default)]
 pub struct WndbExportOptions {
     /// Prepended verbatim to every `data.*`/`index.*` file (never to `.exc`/`index.sense`) -
@@ -26,6 +30,10 @@ pub struct WndbExportOptions {
     /// `lemma,pos,synset_id1 synset_id2 ...` CSV recording sense order for lemmas that fold
     /// together case-insensitively in `index.{pos}` (see `sense_orders`'s doc comment).
     pub sense_orders: Option<PathBuf>,
+    /// Tag counts in `cntlist.rev`'s format (`sense_key sense_number tag_cnt`), e.g. Princeton's
+    /// own, for `cntlist`/`cntlist.rev` and `index.sense`'s last column. `None` means no counts:
+    /// those files are then empty, and that column all `0`.
+    pub sense_counts: Option<PathBuf>,
 }
 
 #[derive(Error, Debug)]
@@ -41,3 +49,19 @@ pub enum WndbExportError {
     #[error("Could not write WNDB: irregular form {0:?} contains a space, which WNDB's .exc format cannot represent")]
     FormWithSpace(String),
 }
+
+#[derive(Error, Debug)]
+pub enum WndbImportError {
+    #[error("Could not read WNDB: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Could not read WNDB: {0}")]
+    Lexicon(#[from] crate::wordnet::LexiconError),
+    #[error("Could not read WNDB: {0}")]
+    Load(#[from] crate::wordnet::WordNetYAMLIOError),
+    #[error("Malformed WNDB file {file}, line {line}: {message}")]
+    Malformed {
+        file: String,
+        line: usize,
+        message: String,
+    },
+}
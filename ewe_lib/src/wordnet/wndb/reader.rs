@@ -0,0 +1,673 @@
+//! WNDB import - builds a lexicon from a Princeton WordNet database directory, the inverse of
+//! [`super::writer`]. Reads `data.{adj,adv,noun,verb}` for the synsets, their members,
+//! pointers, verb frames and glosses; `index.sense` for the sense keys and sense order;
+//! `{pos}.exc` for the irregular forms; `sentidx.vrb`/`sents.vrb` for the verb senses' example
+//! sentences; and `lexnames`, if present, for the lexicographer file names (otherwise the
+//! standard numbering in `sense_keys::LEX_FILENUMS` is assumed).
+//!
+//! Synset ids are the WNDB offsets with the synset type appended (`00001740-n`), as OEWN's own
+//! ids are. Pointers are mapped back through `SynsetRelType::wndb_pointer`/
+//! `SenseRelType::wndb_pointer`, and stored in their canonical direction only: WNDB writes every
+//! relation from both ends (`@` and `~`), which collapse to the one `hypernym`. The `index.*`
+//! files are not read - everything in them is derived from the `data.*` files.
+
+use super::tables::{FRAME_IDS, PRINCETON_FRAMES};
+use super::WndbImportError;
+use crate::rels::{SenseRelType, SynsetRelType};
+use crate::sense_keys::LEX_FILENUMS;
+use crate::wordnet::lexicon::finalize_bulk_load;
+use crate::wordnet::util::Warnings;
+use crate::wordnet::{
+    Entry, Example, Lexicon, PartOfSpeech, PosKey, Sense, SenseId, SenseOrSynsetId, Synset,
+    SynsetId,
+};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::path::Path;
+
+type Result<T> = std::result::Result<T, WndbImportError>;
+
+/// The `data.*`/`*.exc` file name stems, and the synset types found in each
+const FILES: [(&str, &[&str]); 4] = [
+    ("adj", &["a", "s"]),
+    ("adv", &["r"]),
+    ("noun", &["n"]),
+    ("verb", &["v"]),
+];
+
+/// Every synset relation with a WNDB pointer symbol, to map symbols back through
+const SYNSET_RELS: [SynsetRelType; 21] = [
+    SynsetRelType::Also,
+    SynsetRelType::Attribute,
+    SynsetRelType::Causes,
+    SynsetRelType::DomainRegion,
+    SynsetRelType::DomainTopic,
+    SynsetRelType::Exemplifies,
+    SynsetRelType::Entails,
+    SynsetRelType::HasDomainRegion,
+    SynsetRelType::HasDomainTopic,
+    SynsetRelType::IsExemplifiedBy,
+    SynsetRelType::HoloMember,
+    SynsetRelType::HoloPart,
+    SynsetRelType::HoloSubstance,
+    SynsetRelType::Hypernym,
+    SynsetRelType::Hyponym,
+    SynsetRelType::InstanceHypernym,
+    SynsetRelType::InstanceHyponym,
+    SynsetRelType::MeroMember,
+    SynsetRelType::MeroPart,
+    SynsetRelType::MeroSubstance,
+    SynsetRelType::Similar,
+];
+
+/// Every sense relation with a WNDB pointer symbol, to map symbols back through
+const SENSE_RELS: [SenseRelType; 12] = [
+    SenseRelType::Antonym,
+    SenseRelType::Also,
+    SenseRelType::Participle,
+    SenseRelType::Pertainym,
+    SenseRelType::Derivation,
+    SenseRelType::DomainTopic,
+    SenseRelType::HasDomainTopic,
+    SenseRelType::DomainRegion,
+    SenseRelType::HasDomainRegion,
+    SenseRelType::Exemplifies,
+    SenseRelType::IsExemplifiedBy,
+    SenseRelType::Similar,
+];
+
+/// A pointer as written in a `data.*` line, its target not yet resolved to a synset id (the
+/// pointer's part of speech says `a` for a satellite, so that needs the target's own line)
+struct Pointer {
+    symbol: String,
+    /// Index into `FILES` of the file the target is in
+    file: usize,
+    offset: String,
+    /// 1-based member numbers, `0` for the synset as a whole
+    source: usize,
+    target: usize,
+}
+
+/// One `data.*` line
+struct DataSynset {
+    id: SynsetId,
+    part_of_speech: PartOfSpeech,
+    lexfile_num: usize,
+    /// `(lemma, adjposition, lex_id)` for each member, in order
+    words: Vec<(String, Option<String>, u32)>,
+    pointers: Vec<Pointer>,
+    /// `(frame number, member number or 0 for all)`
+    frames: Vec<(usize, usize)>,
+    gloss: String,
+}
+
+/// Reads the WNDB files in `dir` into `lexicon`, the same way `Lexicon::load` bulk-populates one
+/// from a YAML source tree. `data.*` files that are missing are taken to be empty.
+pub fn read_wndb<L: Lexicon>(mut lexicon: L, dir: &Path) -> Result<L> {
+    let lexnames = read_lexnames(dir)?;
+    let mut synsets: Vec<DataSynset> = Vec::new();
+    // (file index, offset) -> index into `synsets`
+    let mut by_offset: HashMap<(usize, String), usize> = HashMap::new();
+    for (file, (name, _)) in FILES.iter().enumerate() {
+        let file_name = format!("data.{name}");
+        let Some(text) = read_optional(dir, &file_name)? else {
+            continue;
+        };
+        for (n, line) in text.lines().enumerate() {
+            // License header lines start with spaces
+            if line.is_empty() || line.starts_with(' ') {
+                continue;
+            }
+            let synset =
+                parse_data_line(line).map_err(|message| malformed(&file_name, n + 1, message))?;
+            let offset = synset.id.as_str()[..8].to_string();
+            by_offset.insert((file, offset), synsets.len());
+            synsets.push(synset);
+        }
+    }
+    let sense_keys = read_sense_index(dir)?;
+
+    // The senses, by synset and member number, and what they belong to
+    let mut senses: HashMap<SenseId, Sense> = HashMap::new();
+    let mut entries: BTreeMap<(String, String), Vec<(usize, SenseId)>> = BTreeMap::new();
+    let mut member_senses: HashMap<(SynsetId, usize), SenseId> = HashMap::new();
+    let mut built: Vec<(String, SynsetId, Synset)> = Vec::new();
+    for data in synsets.iter() {
+        let mut synset = Synset::new(data.part_of_speech.clone());
+        let (definition, examples) = split_gloss(&data.gloss);
+        synset.definition.extend(definition);
+        synset.example = examples;
+        for (n, (lemma, adjposition, lex_id)) in data.words.iter().enumerate() {
+            let key = (lemma.replace(' ', "_").to_lowercase(), data.id.clone());
+            let (sense_id, sense_number) = match sense_keys.get(&key) {
+                Some((sense_id, sense_number)) => (sense_id.clone(), *sense_number),
+                None => {
+                    let head = head_word(&synsets, &by_offset, data);
+                    (sense_key(&key.0, data, *lex_id, head), usize::MAX)
+                }
+            };
+            let mut sense = Sense::new(sense_id.clone(), data.id.clone());
+            sense.adjposition = adjposition.clone();
+            senses.insert(sense_id.clone(), sense);
+            let poskey = data.part_of_speech.value().to_string();
+            entries
+                .entry((lemma.clone(), poskey))
+                .or_default()
+                .push((sense_number, sense_id.clone()));
+            member_senses.insert((data.id.clone(), n + 1), sense_id);
+            synset.members.push(lemma.clone());
+        }
+        let lexname = lexname(&lexnames, data.lexfile_num, FILES[file_of(data)].0);
+        built.push((lexname, data.id.clone(), synset));
+    }
+    let synset_index: HashMap<SynsetId, usize> = built
+        .iter()
+        .enumerate()
+        .map(|(i, (_, id, _))| (id.clone(), i))
+        .collect();
+
+    let mut warnings = Warnings::default();
+    let mut frames_used = BTreeSet::new();
+    for data in synsets.iter() {
+        for pointer in data.pointers.iter() {
+            let Some(&target) = by_offset.get(&(pointer.file, pointer.offset.clone())) else {
+                warnings.warn_once_for(
+                    "Pointer to a synset not in the data files",
+                    format!("from {} to offset {}", data.id.as_str(), pointer.offset),
+                );
+                continue;
+            };
+            let target = synsets[target].id.clone();
+            if pointer.source == 0 && pointer.target == 0 {
+                match synset_rel(&pointer.symbol, &data.part_of_speech) {
+                    Some(rel) => add_synset_rel(&mut built, &synset_index, rel, &data.id, &target),
+                    None => {
+                        warnings.warn_once(format!("Unsupported synset pointer {}", pointer.symbol))
+                    }
+                }
+                continue;
+            }
+            let source_sense = member_senses.get(&(data.id.clone(), pointer.source));
+            let target_sense = member_senses.get(&(target.clone(), pointer.target));
+            let (Some(source_sense), Some(target_sense)) = (source_sense, target_sense) else {
+                warnings.warn_once_for(
+                    "Lexical pointer naming a member its synset doesn't have",
+                    format!("from {} to {}", data.id.as_str(), target.as_str()),
+                );
+                continue;
+            };
+            match sense_rel(&pointer.symbol, &data.part_of_speech) {
+                Some(rel) => {
+                    let (forward, rel) = rel.to_canonical();
+                    let (on, to) = if forward {
+                        (source_sense, target_sense)
+                    } else {
+                        (target_sense, source_sense)
+                    };
+                    let to = SenseOrSynsetId::Sense(to.clone());
+                    senses
+                        .get_mut(on)
+                        .expect("Every member has a sense")
+                        .add_rel(rel, to);
+                }
+                // A lexical pointer of a kind only synsets have, e.g. a hypernym between senses
+                None => match synset_rel(&pointer.symbol, &data.part_of_speech) {
+                    Some(rel) => add_synset_rel(&mut built, &synset_index, rel, &data.id, &target),
+                    None => {
+                        warnings.warn_once(format!("Unsupported sense pointer {}", pointer.symbol))
+                    }
+                },
+            }
+        }
+        for (frame, member) in data.frames.iter() {
+            let Some(frame_id) = frame.checked_sub(1).and_then(|i| FRAME_IDS.get(i)) else {
+                warnings.warn_once_for("Unknown verb frame", frame);
+                continue;
+            };
+            frames_used.insert(*frame);
+            for (n, _) in data.words.iter().enumerate() {
+                if *member != 0 && *member != n + 1 {
+                    continue;
+                }
+                if let Some(sense_id) = member_senses.get(&(data.id.clone(), n + 1)) {
+                    let sense = senses.get_mut(sense_id).expect("Every member has a sense");
+                    if !sense.subcat.iter().any(|id| id == frame_id) {
+                        sense.subcat.push(frame_id.to_string());
+                    }
+                }
+            }
+        }
+    }
+
+    for (sense_id, sentences) in read_verb_sentences(dir)? {
+        if let Some(sense) = senses.get_mut(&SenseId::new(sense_id)) {
+            sense.set_sent(sentences);
+        }
+    }
+
+    let forms = read_exceptions(dir)?;
+    for ((lemma, poskey), mut sense_ids) in entries {
+        // In `index.sense` order, or failing that the order the senses were read in
+        sense_ids.sort_by_key(|(sense_number, _)| *sense_number);
+        let mut entry = Entry::new();
+        for (_, sense_id) in sense_ids {
+            if let Some(sense) = senses.remove(&sense_id) {
+                entry.sense.push(sense);
+            }
+        }
+        let file = FILES
+            .iter()
+            .position(|(_, types)| types.contains(&poskey.as_str()));
+        if let Some(forms) = file.and_then(|file| forms.get(&(file, lemma.to_lowercase()))) {
+            for form in forms {
+                if !entry.form.contains(form) {
+                    entry.form.push(form.clone());
+                }
+            }
+        }
+        lexicon.insert_entry(lemma, PosKey::new(poskey), entry)?;
+    }
+    for (lexname, id, synset) in built {
+        lexicon.insert_synset(lexname, id, synset)?;
+    }
+    let frames: Vec<(String, String)> = frames_used
+        .into_iter()
+        .filter_map(|frame| {
+            let description = PRINCETON_FRAMES
+                .iter()
+                .find(|(_, n)| **n as usize == frame)?
+                .0;
+            Some((FRAME_IDS[frame - 1].to_string(), description.to_string()))
+        })
+        .collect();
+    if !frames.is_empty() {
+        lexicon.frames_set(frames)?;
+    }
+
+    finalize_bulk_load(&mut lexicon)?;
+    warnings.report();
+    Ok(lexicon)
+}
+
+/// Adds a synset relation in its canonical direction, to `source` or else `target`
+fn add_synset_rel(
+    built: &mut [(String, SynsetId, Synset)],
+    synset_index: &HashMap<SynsetId, usize>,
+    rel: SynsetRelType,
+    source: &SynsetId,
+    target: &SynsetId,
+) {
+    let (forward, rel) = rel.to_yaml();
+    let (on, to) = if forward {
+        (source, target)
+    } else {
+        (target, source)
+    };
+    built[synset_index[on]].2.insert_rel(&rel, to);
+}
+
+fn malformed(file: &str, line: usize, message: String) -> WndbImportError {
+    WndbImportError::Malformed {
+        file: file.to_string(),
+        line,
+        message,
+    }
+}
+
+/// The contents of `dir/name`, or `None` if there is no such file. Not necessarily UTF-8:
+/// older releases are Latin-1, which is read as best it can be.
+fn read_optional(dir: &Path, name: &str) -> Result<Option<String>> {
+    let path = dir.join(name);
+    if !path.exists() {
+        return Ok(None);
+    }
+    let bytes = std::fs::read(path)?;
+    Ok(Some(String::from_utf8_lossy(&bytes).into_owned()))
+}
+
+fn file_of(data: &DataSynset) -> usize {
+    FILES
+        .iter()
+        .position(|(_, types)| types.contains(&data.part_of_speech.value()))
+        .expect("Every part of speech has a file")
+}
+
+fn parse_data_line(line: &str) -> std::result::Result<DataSynset, String> {
+    let (fields, gloss) = match line.split_once('|') {
+        Some((fields, gloss)) => (fields, gloss.trim()),
+        None => (line, ""),
+    };
+    let mut tokens = fields.split_whitespace();
+    let mut next = |what: &str| tokens.next().ok_or_else(|| format!("missing {what}"));
+    let offset = next("offset")?;
+    if offset.len() != 8 || !offset.bytes().all(|b| b.is_ascii_digit()) {
+        return Err(format!("bad offset {offset:?}"));
+    }
+    let lexfile_num = parse_number(next("lexicographer file number")?, 10)?;
+    let ss_type = next("synset type")?;
+    let part_of_speech = PosKey::new(ss_type)
+        .to_part_of_speech()
+        .filter(|_| ss_type.len() == 1)
+        .ok_or_else(|| format!("bad synset type {ss_type:?}"))?;
+    let id = SynsetId::new_owned(format!("{offset}-{ss_type}"));
+
+    let mut words = Vec::new();
+    for _ in 0..parse_number(next("word count")?, 16)? {
+        let word = next("word")?;
+        let lex_id = parse_number(next("lex id")?, 16)? as u32;
+        let (word, adjposition) = match word.strip_suffix(')').and_then(|w| w.rsplit_once('(')) {
+            Some((word, marker)) if ["a", "p", "ip"].contains(&marker) => {
+                (word, Some(marker.to_string()))
+            }
+            _ => (word, None),
+        };
+        words.push((word.replace('_', " "), adjposition, lex_id));
+    }
+
+    let mut pointers = Vec::new();
+    for _ in 0..parse_number(next("pointer count")?, 10)? {
+        let symbol = next("pointer symbol")?.to_string();
+        let offset = next("pointer offset")?.to_string();
+        let pos = next("pointer part of speech")?;
+        let file = FILES
+            .iter()
+            .position(|(_, types)| types.contains(&pos))
+            .ok_or_else(|| format!("bad pointer part of speech {pos:?}"))?;
+        let members = next("pointer source/target")?;
+        if members.len() != 4 {
+            return Err(format!("bad pointer source/target {members:?}"));
+        }
+        pointers.push(Pointer {
+            symbol,
+            file,
+            offset,
+            source: parse_number(&members[..2], 16)?,
+            target: parse_number(&members[2..], 16)?,
+        });
+    }
+
+    let mut frames = Vec::new();
+    if let Some(count) = tokens.next() {
+        for _ in 0..parse_number(count, 10)? {
+            let plus = tokens.next();
+            let frame = tokens.next();
+            let member = tokens.next();
+            match (plus, frame, member) {
+                (Some("+"), Some(frame), Some(member)) => {
+                    frames.push((parse_number(frame, 10)?, parse_number(member, 16)?));
+                }
+                _ => return Err("bad verb frame".to_string()),
+            }
+        }
+    }
+
+    Ok(DataSynset {
+        id,
+        part_of_speech,
+        lexfile_num,
+        words,
+        pointers,
+        frames,
+        gloss: gloss.to_string(),
+    })
+}
+
+fn parse_number(s: &str, radix: u32) -> std::result::Result<usize, String> {
+    usize::from_str_radix(s, radix).map_err(|_| format!("bad number {s:?}"))
+}
+
+/// A gloss's definition and examples: `definition; "example"; "example" - source`, as
+/// `write_data` writes them
+fn split_gloss(gloss: &str) -> (Option<String>, Vec<Example>) {
+    let (definition, rest) = match gloss.find("; \"") {
+        Some(i) => (&gloss[..i], Some(&gloss[i + 3..])),
+        None => (gloss, None),
+    };
+    let definition = Some(definition.trim().to_string()).filter(|d| !d.is_empty());
+    let mut examples = Vec::new();
+    for example in rest.into_iter().flat_map(|rest| rest.split("; \"")) {
+        let example = example.trim().trim_end_matches(';');
+        let example = match example.rsplit_once("\" - ") {
+            Some((text, source)) => Example::new(text.to_string(), Some(source.to_string())),
+            None => Example::new(example.trim_end_matches('"').to_string(), None),
+        };
+        examples.push(example);
+    }
+    (definition, examples)
+}
+
+/// `index.sense`: the sense key and sense number of each member, by its lemma as written there
+/// (lower case, with underscores) and its synset
+fn read_sense_index(dir: &Path) -> Result<HashMap<(String, SynsetId), (SenseId, usize)>> {
+    let mut keys = HashMap::new();
+    let Some(text) = read_optional(dir, "index.sense")? else {
+        return Ok(keys);
+    };
+    for (n, line) in text.lines().enumerate() {
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        if fields.is_empty() {
+            continue;
+        }
+        let bad = || malformed("index.sense", n + 1, format!("bad line {line:?}"));
+        let [key, offset, sense_number, ..] = fields[..] else {
+            return Err(bad());
+        };
+        let (lemma, lex_sense) = key.split_once('%').ok_or_else(bad)?;
+        let pos = match lex_sense.chars().next() {
+            Some('1') => "n",
+            Some('2') => "v",
+            Some('3') => "a",
+            Some('4') => "r",
+            Some('5') => "s",
+            _ => return Err(bad()),
+        };
+        let sense_number = sense_number.parse().map_err(|_| bad())?;
+        keys.insert(
+            (
+                lemma.to_string(),
+                SynsetId::new_owned(format!("{offset}-{pos}")),
+            ),
+            (SenseId::new(key), sense_number),
+        );
+    }
+    Ok(keys)
+}
+
+/// `lexnames`: the name of each lexicographer file number, if the directory has one
+fn read_lexnames(dir: &Path) -> Result<HashMap<usize, String>> {
+    let mut names = HashMap::new();
+    if let Some(text) = read_optional(dir, "lexnames")? {
+        for (n, line) in text.lines().enumerate() {
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            match fields[..] {
+                [] => {}
+                [number, name, ..] => {
+                    let number = parse_number(number, 10)
+                        .map_err(|message| malformed("lexnames", n + 1, message))?;
+                    names.insert(number, name.to_string());
+                }
+                _ => return Err(malformed("lexnames", n + 1, format!("bad line {line:?}"))),
+            }
+        }
+    }
+    Ok(names)
+}
+
+fn lexname(lexnames: &HashMap<usize, String>, number: usize, file: &str) -> String {
+    if let Some(name) = lexnames.get(&number) {
+        return name.clone();
+    }
+    match LEX_FILENUMS.iter().find(|(_, n)| **n == number) {
+        Some((name, _)) => name.to_string(),
+        None => format!("{file}.{number:02}"),
+    }
+}
+
+/// The `{pos}.exc` files: the irregular forms of each lemma (lower case), by file
+fn read_exceptions(dir: &Path) -> Result<HashMap<(usize, String), Vec<String>>> {
+    let mut forms: HashMap<(usize, String), Vec<String>> = HashMap::new();
+    for (file, (name, _)) in FILES.iter().enumerate() {
+        let Some(text) = read_optional(dir, &format!("{name}.exc"))? else {
+            continue;
+        };
+        for line in text.lines() {
+            let mut words = line.split_whitespace();
+            let Some(form) = words.next() else {
+                continue;
+            };
+            for lemma in words {
+                forms
+                    .entry((file, lemma.replace('_', " ").to_lowercase()))
+                    .or_default()
+                    .push(form.replace('_', " "));
+            }
+        }
+    }
+    Ok(forms)
+}
+
+/// `sentidx.vrb` and `sents.vrb`: the example sentences of each verb sense, by sense key
+fn read_verb_sentences(dir: &Path) -> Result<HashMap<String, Vec<String>>> {
+    let mut by_sense = HashMap::new();
+    let (Some(index), Some(text)) = (
+        read_optional(dir, "sentidx.vrb")?,
+        read_optional(dir, "sents.vrb")?,
+    ) else {
+        return Ok(by_sense);
+    };
+    let sentences: HashMap<&str, &str> = text
+        .lines()
+        .filter_map(|line| line.split_once(' '))
+        .collect();
+    for line in index.lines() {
+        let Some((key, numbers)) = line.split_once(' ') else {
+            continue;
+        };
+        let sent: Vec<String> = numbers
+            .trim()
+            .split(',')
+            .filter_map(|n| sentences.get(n).map(|s| s.trim().to_string()))
+            .collect();
+        if !sent.is_empty() {
+            by_sense.insert(key.to_string(), sent);
+        }
+    }
+    Ok(by_sense)
+}
+
+/// For a satellite, the first member of its head synset (the adjective it is `similar` to) and
+/// that member's lex id, which its sense keys end with
+fn head_word<'a>(
+    synsets: &'a [DataSynset],
+    by_offset: &HashMap<(usize, String), usize>,
+    data: &DataSynset,
+) -> Option<(&'a str, u32)> {
+    if data.part_of_speech != PartOfSpeech::s {
+        return None;
+    }
+    let pointer = data.pointers.iter().find(|p| p.symbol == "&")?;
+    let head = &synsets[*by_offset.get(&(pointer.file, pointer.offset.clone()))?];
+    head.words
+        .first()
+        .map(|(lemma, _, lex_id)| (lemma.as_str(), *lex_id))
+}
+
+/// The sense key of a member `index.sense` does not list
+fn sense_key(lemma: &str, data: &DataSynset, lex_id: u32, head: Option<(&str, u32)>) -> SenseId {
+    let (head_word, head_id) = match head {
+        Some((word, id)) => (word.replace(' ', "_").to_lowercase(), format!("{id:02}")),
+        None => (String::new(), String::new()),
+    };
+    SenseId::new(format!(
+        "{}%{}:{:02}:{:02}:{}:{}",
+        lemma,
+        data.part_of_speech.ss_type(),
+        data.lexfile_num,
+        lex_id,
+        head_word,
+        head_id
+    ))
+}
+
+fn synset_rel(symbol: &str, pos: &PartOfSpeech) -> Option<SynsetRelType> {
+    SYNSET_RELS
+        .iter()
+        .find(|rel| rel.wndb_pointer(pos) == Some(symbol))
+        .cloned()
+}
+
+fn sense_rel(symbol: &str, pos: &PartOfSpeech) -> Option<SenseRelType> {
+    SENSE_RELS
+        .iter()
+        .find(|rel| rel.wndb_pointer(pos) == Some(symbol))
+        .cloned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::wordnet::fixtures::round_trip_fixture;
+    use crate::wordnet::wndb::{write_wndb, WndbExportOptions};
+    use crate::wordnet::LexiconHashMapBackend;
+
+    #[test]
+    fn test_split_gloss() {
+        let (definition, examples) =
+            split_gloss("a female sheep; \"the ewe and her lamb\"; \"a ewe\" - Shakespeare");
+        assert_eq!(definition, Some("a female sheep".to_string()));
+        assert_eq!(examples.len(), 2);
+        assert_eq!(examples[0].text, "the ewe and her lamb");
+        assert_eq!(examples[1].text, "a ewe");
+        assert_eq!(examples[1].source, Some("Shakespeare".to_string()));
+        assert_eq!(split_gloss(""), (None, Vec::new()));
+    }
+
+    #[test]
+    fn test_read_wndb_round_trip() {
+        let wn = round_trip_fixture();
+
+        let dir = std::env::temp_dir().join(format!("ewe_wndb_read_{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        write_wndb(&wn, &dir, &WndbExportOptions::default()).unwrap();
+        assert_eq!(
+            std::fs::read_to_string(dir.join("lexnames")).unwrap(),
+            "05\tnoun.animal\t1\n32\tverb.communication\t2\n"
+        );
+        let read = read_wndb(LexiconHashMapBackend::new(), &dir).unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(read.n_synsets().unwrap(), 3);
+        assert_eq!(read.n_entries().unwrap(), 3);
+        let (_, _, ewe) = read
+            .get_sense_by_id(&SenseId::new("ewe%1:05:00::"))
+            .unwrap()
+            .unwrap();
+        let ewe = read.synset_by_id(&ewe.synset).unwrap().unwrap();
+        assert_eq!(ewe.definition, vec!["a female sheep".to_string()]);
+        assert_eq!(ewe.example[0].text, "the ewe and her lamb");
+        let (_, _, sheep) = read
+            .get_sense_by_id(&SenseId::new("sheep%1:05:00::"))
+            .unwrap()
+            .unwrap();
+        assert_eq!(ewe.hypernym, vec![sheep.synset.clone()]);
+        assert_eq!(
+            read.lex_name_for(&sheep.synset).unwrap(),
+            Some("noun.animal".to_string())
+        );
+        assert_eq!(
+            read.get_forms("sheep", &PosKey::new("n")).unwrap(),
+            vec!["sheeps".to_string()]
+        );
+
+        let (_, _, bleat) = read
+            .get_sense_by_id(&SenseId::new("bleat%2:32:00::"))
+            .unwrap()
+            .unwrap();
+        assert_eq!(bleat.subcat, vec!["via".to_string()]);
+        assert_eq!(bleat.sent(), &["The sheep %s".to_string()]);
+        assert_eq!(bleat.derivation, vec![SenseId::new("sheep%1:05:00::")]);
+        assert_eq!(
+            read.frames_get().unwrap().to_vec(),
+            vec![("via".to_string(), "Somebody ----s".to_string())]
+        );
+    }
+}
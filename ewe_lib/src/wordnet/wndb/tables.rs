@@ -56,3 +56,48 @@ lazy_static! {
         map
     };
 }
+
+/// The ids (`frames.yaml` keys, as `Sense::subcat` refers to them) of the Princeton verb frames,
+/// in frame-number order - `FRAME_IDS[n - 1]` is frame `n`'s. These are OEWN's own names, which
+/// the reader gives the frames of a WNDB directory since WNDB itself only numbers them.
+pub(crate) const FRAME_IDS: [&str; 39] = [
+    "vii",
+    "via",
+    "nonreferential",
+    "vii-pp",
+    "vtii-adj",
+    "vii-adj",
+    "via-adj",
+    "vtai",
+    "vtaa",
+    "vtia",
+    "vtii",
+    "vii-to",
+    "via-on-inanim",
+    "ditransitive",
+    "vtai-to",
+    "vtai-from",
+    "vtaa-with",
+    "vtaa-of",
+    "vtai-on",
+    "vtaa-pp",
+    "vtai-pp",
+    "via-pp",
+    "vibody",
+    "vtaa-to-inf",
+    "vtaa-inf",
+    "via-that",
+    "via-to",
+    "via-to-inf",
+    "via-whether-inf",
+    "vtaa-into-ger",
+    "vtai-with",
+    "via-inf",
+    "via-ger",
+    "nonreferential-sent",
+    "vii-inf",
+    "via-for",
+    "via-at",
+    "via-on-anim",
+    "via-out-of",
+];
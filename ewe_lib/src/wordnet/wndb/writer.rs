@@ -4,6 +4,7 @@
 //! at issue #28 for why (acceptance is byte-exact equivalence with the Scala tool's output).
 //! Comments reference the mirrored Scala function/lines where the logic is non-obvious.
 
+use super::auxiliary;
 use super::tables::PRINCETON_FRAMES;
 use super::{WndbExportError, WndbExportOptions};
 use crate::rels::{SenseRelType, SynsetRelType};
@@ -302,6 +303,7 @@ pub fn write_wndb<L: Lexicon>(wn: &L, out_dir: &Path, options: &WndbExportOption
         None => Vec::new(),
     };
     let sense_orders = super::sense_orders::load(options.sense_orders.as_deref())?;
+    let counts = auxiliary::load_counts(options.sense_counts.as_deref())?;
 
     let all_entries = collect_all_entries(wn)?;
     let entries_for_synset = index_entries_by_synset(&all_entries);
@@ -342,7 +344,18 @@ pub fn write_wndb<L: Lexicon>(wn: &L, out_dir: &Path, options: &WndbExportOption
         std::fs::write(out_dir.join(format!("index.{name}")), out)?;
     }
 
-    write_sense_index(&all_entries, &offsets, out_dir)?;
+    let sense_numbers = write_sense_index(&all_entries, &offsets, &counts, out_dir)?;
+
+    // The files beyond `WNDB.write`'s, which make the directory a complete `WNHOME`
+    auxiliary::write_lexnames(wn, &extra_lexnames, out_dir)?;
+    auxiliary::write_counts(&sense_numbers, &counts, out_dir)?;
+    let verb_senses = all_entries
+        .iter()
+        .filter(|entry| entry.part_of_speech == PartOfSpeech::v)
+        .flat_map(|entry| entry.senses.iter())
+        .map(|sense| (scala_sense_key(sense.id.as_str()), sense));
+    auxiliary::write_verb_sentences(verb_senses, out_dir)?;
+    auxiliary::write_frames_text(&wn.frames_get()?, out_dir)?;
 
     Ok(())
 }
@@ -922,7 +935,14 @@ fn scala_sense_key(sense_key: &str) -> String {
     }
 }
 
-fn write_sense_index(all_entries: &[AllEntry], offsets: &Offsets, out_dir: &Path) -> Result<()> {
+/// Also returns the sense keys as written, with their sense numbers, for `cntlist`/`cntlist.rev`.
+/// The tag counts are `0` unless `--sense-counts` gives them.
+fn write_sense_index(
+    all_entries: &[AllEntry],
+    offsets: &Offsets,
+    counts: &HashMap<String, u32>,
+    out_dir: &Path,
+) -> Result<Vec<(String, usize)>> {
     // Group by (lowercase lemma, part-of-speech char) preserving document order within a group -
     // mirrors `entriesByLowercaseLemma`. Iteration order across groups doesn't matter, since the
     // output is fully re-sorted as plain strings below.
@@ -933,6 +953,7 @@ fn write_sense_index(all_entries: &[AllEntry], offsets: &Offsets, out_dir: &Path
     }
 
     let mut lines: Vec<String> = Vec::new();
+    let mut sense_numbers: Vec<(String, usize)> = Vec::new();
     for entry_idxs in groups.values() {
         let mut i = 1usize;
         for &idx in entry_idxs {
@@ -943,7 +964,10 @@ fn write_sense_index(all_entries: &[AllEntry], offsets: &Offsets, out_dir: &Path
                     .get(&sense.synset)
                     .map(|(code, _)| code.clone())
                     .unwrap_or_else(|| "00000000".to_string());
-                lines.push(format!("{} {} {} 0", scala_sense_key(sense.id.as_str()), code, i));
+                let key = scala_sense_key(sense.id.as_str());
+                let count = counts.get(&key).copied().unwrap_or(0);
+                lines.push(format!("{} {} {} {}", key, code, i, count));
+                sense_numbers.push((key, i));
                 i += 1;
             }
         }
@@ -956,7 +980,7 @@ fn write_sense_index(all_entries: &[AllEntry], offsets: &Offsets, out_dir: &Path
         out.push('\n');
     }
     std::fs::write(out_dir.join("index.sense"), out)?;
-    Ok(())
+    Ok(sense_numbers)
 }
 
 #[cfg(test)]
@@ -1017,7 +1041,7 @@ mod tests {
         let n = COUNTER.fetch_add(1, Ordering::Relaxed);
         let out_dir = std::env::temp_dir().join(format!("ewe_wndb_test_{}_{n}", std::process::id()));
         let _ = std::fs::remove_dir_all(&out_dir);
-        write_wndb(wn, &out_dir, &WndbExportOptions { license_file, sense_orders: None }).unwrap();
+        write_wndb(wn, &out_dir, &WndbExportOptions { license_file, sense_orders: None, sense_counts: None }).unwrap();
         out_dir
     }
 
@@ -1112,4 +1136,41 @@ mod tests {
         let noun_exc = std::fs::read_to_string(out_dir.join("noun.exc")).unwrap();
         assert_eq!(noun_exc, "geese goose\n");
     }
+
+    #[test]
+    fn test_auxiliary_files() {
+        let mut wn = small_fixture();
+        let mut bleat_ss = Synset::new(Pos::v);
+        bleat_ss.definition.push("cry like a sheep".to_string());
+        bleat_ss.members.push("bleat".to_string());
+        wn.insert_synset("verb.communication".to_string(), SsId::new("00000002-v"), bleat_ss).unwrap();
+        let mut bleat_entry = Entry::new();
+        let mut bleat_sense = Sense::new(SId::new("bleat%2:32:00::"), SsId::new("00000002-v"));
+        bleat_sense.subcat.push("via".to_string());
+        bleat_sense.set_sent(vec!["The sheep %s".to_string(), "Sam cannot %s Sue".to_string()]);
+        bleat_entry.sense.push(bleat_sense);
+        wn.insert_entry("bleat".to_string(), PosKey::new("v"), bleat_entry).unwrap();
+        wn.frames_set(vec![("via".to_string(), "Somebody ----s".to_string())]).unwrap();
+
+        let counts = std::env::temp_dir().join(format!("ewe_wndb_counts_{}.txt", std::process::id()));
+        std::fs::write(&counts, "grandfather%1:01:00:: 1 3\nbleat%2:32:00:: 1 5\n").unwrap();
+        let out_dir = std::env::temp_dir().join(format!("ewe_wndb_auxiliary_{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&out_dir);
+        let options = WndbExportOptions { license_file: None, sense_orders: None, sense_counts: Some(counts.clone()) };
+        write_wndb(&wn, &out_dir, &options).unwrap();
+        let read = |name: &str| std::fs::read_to_string(out_dir.join(name)).unwrap();
+
+        assert_eq!(read("lexnames"), "32\tverb.communication\t2\n45\tnone\t1\n");
+        assert_eq!(read("cntlist.rev"), "bleat%2:32:00:: 1 5\ngrandfather%1:01:00:: 1 3\n");
+        assert_eq!(read("cntlist"), "5 bleat%2:32:00:: 1\n3 grandfather%1:01:00:: 1\n");
+        let index_sense = read("index.sense");
+        assert!(index_sense.lines().any(|l| l.starts_with("bleat%2:32:00:: ") && l.ends_with(" 1 5")));
+        assert!(index_sense.lines().any(|l| l.starts_with("paternal_grandfather%1:01:00:: ") && l.ends_with(" 1 0")));
+        assert_eq!(read("sentidx.vrb"), "bleat%2:32:00:: 1,2\n");
+        assert_eq!(read("sents.vrb"), "1 The sheep %s\n2 Sam cannot %s Sue\n");
+        assert_eq!(read("verb.Framestext"), "2 Somebody ----s\n");
+
+        std::fs::remove_dir_all(&out_dir).unwrap();
+        std::fs::remove_file(&counts).unwrap();
+    }
 }
@@ -32,13 +32,14 @@ use super::ids;
 use super::{LexiconMetadata, XmlImportError};
 use crate::rels::{SenseRelType, SynsetRelType, YamlSynsetRelType};
 use crate::wordnet::lexicon::finalize_bulk_load;
+use crate::wordnet::util::Warnings;
 use crate::wordnet::{
     Entry, Example, Lexicon, PartOfSpeech, PosKey, Pronunciation, Sense, SenseId, SenseOrSynsetId,
     Synset, SynsetId, UnresolvedSenseOrSynsetId, ILIID,
 };
 use quick_xml::events::{BytesStart, Event};
 use quick_xml::Reader;
-use std::collections::{HashMap, HashSet};
+use std::collections::HashMap;
 use std::io::{BufReader, Read};
 
 type Result<T> = std::result::Result<T, XmlImportError>;
@@ -83,7 +84,7 @@ struct Accumulator {
 
     frames: Vec<(String, String)>,
 
-    warned_rel_types: HashSet<String>,
+    warned_rel_types: Warnings,
 }
 
 /// Parses a WN-LMF XML document and bulk-populates `lexicon` from it, the same way
@@ -174,6 +175,7 @@ pub fn read_lexicon_xml<L: Lexicon, R: Read>(mut lexicon: L, reader: R) -> Resul
     }
 
     finalize_bulk_load(&mut lexicon)?;
+    acc.warned_rel_types.report();
 
     Ok((lexicon, metadata))
 }
@@ -212,12 +214,6 @@ fn part_of_speech_from_str(s: &str) -> Option<PartOfSpeech> {
     }
 }
 
-fn warn_once(warned: &mut HashSet<String>, message: String) {
-    if warned.insert(message.clone()) {
-        eprintln!("{message} (further occurrences of this message are suppressed)");
-    }
-}
-
 fn parse_lexical_entry<R: Read>(
     xml: &mut Reader<BufReader<R>>,
     buf: &mut Vec<u8>,
@@ -344,7 +340,7 @@ fn parse_sense_relations<R: Read>(
                 let rel_type_str = require_attr(&e, "relType", "SenseRelation")?;
                 let target_attr = require_attr(&e, "target", "SenseRelation")?;
                 let Some(rel) = SenseRelType::from(&rel_type_str) else {
-                    warn_once(&mut acc.warned_rel_types, format!("Unrecognized SenseRelation relType {rel_type_str:?}"));
+                    acc.warned_rel_types.warn_once(format!("Unrecognized SenseRelation relType {rel_type_str:?}"));
                     buf.clear();
                     continue;
                 };
@@ -448,7 +444,7 @@ fn parse_synset_children<R: Read>(
                 let rel_type_str = require_attr(&e, "relType", "SynsetRelation")?;
                 let target_attr = require_attr(&e, "target", "SynsetRelation")?;
                 let Some(rel) = SynsetRelType::from(&rel_type_str) else {
-                    warn_once(&mut acc.warned_rel_types, format!("Unrecognized SynsetRelation relType {rel_type_str:?}"));
+                    acc.warned_rel_types.warn_once(format!("Unrecognized SynsetRelation relType {rel_type_str:?}"));
                     buf.clear();
                     continue;
                 };
@@ -32,7 +32,7 @@ type Result<T> = std::result::Result<T, XmlExportError>;
 
 /// Key identifying a sense within a synset by (lemma, pos, synset) - the information a
 /// `SenseRelation`'s target carries - so an actual target sense id can be looked up.
-type SenseKeyLookup = HashMap<(String, PosKey, SynsetId), SenseId>;
+pub(crate) type SenseKeyLookup = HashMap<(String, PosKey, SynsetId), SenseId>;
 
 /// Export every synset in `wn` as a single self-contained WN-LMF document.
 pub fn write_lexicon_xml<L: Lexicon>(wn: &L, metadata: &LexiconMetadata) -> Result<Vec<u8>> {
@@ -251,8 +251,9 @@ fn write_sense<W: std::io::Write>(
 /// Only relation types that appear in the WN-LMF `SenseRelation` DTD enum are emitted here. The
 /// inverse "is_X_of" semantic-role relations our internal model tracks (is_agent_of,
 /// is_material_of, etc.) have no corresponding relType in the DTD - they're meant to be derived
-/// by reversing the forward relation, not stored - so they're intentionally skipped.
-fn sense_relations_xml(
+/// by reversing the forward relation, not stored - so they're intentionally skipped. Shared with
+/// the JSON writer, whose relations are the same.
+pub(crate) fn sense_relations_xml(
     prefix: &str,
     synset: &MemberSynset,
     lemma: &str,
@@ -394,7 +395,7 @@ fn write_synset<W: std::io::Write>(
     Ok(())
 }
 
-fn synset_relations_xml(prefix: &str, synset: &MemberSynset) -> Vec<(&'static str, String)> {
+pub(crate) fn synset_relations_xml(prefix: &str, synset: &MemberSynset) -> Vec<(&'static str, String)> {
     let mut out = Vec::new();
     macro_rules! rel {
         ($field:ident, $rel_type:expr) => {
@@ -7,7 +7,12 @@
 use clap::Parser;
 use ewe_lib::automaton::Action;
 use ewe_lib::change_manager::ChangeList;
+use ewe_lib::definitions::opt_in_errors;
+use ewe_lib::diagnostic::{diagnostics, Diagnostic};
 use ewe_lib::progress::NullProgress;
+use ewe_lib::user_rules::UserRules;
+use ewe_lib::validate::{IncrementalValidator, ValidationError};
+use ewe_lib::validation_profile::ValidationProfile;
 use ewe_lib::wordnet::{Lexicon, LexiconHashMapBackend, SenseId, SynsetId};
 use rmcp::ServiceExt;
 use rmcp::handler::server::wrapper::Parameters;
@@ -70,6 +75,16 @@ struct ServerState {
     /// couldn't be determined (e.g. `path` didn't exist), in which case the staleness check
     /// is skipped rather than blocking every future save.
     loaded_mtime: Option<SystemTime>,
+    /// Which validation checks block a save, read from the project's `settings.toml` at
+    /// startup and on `reload` (see `ValidationProfile::for_source`).
+    profile: ValidationProfile,
+    /// The project's own validation rules, read from its `validation-rules.yaml` at startup and
+    /// on `reload` (see `UserRules::for_source`).
+    rules: UserRules,
+    /// Validation results kept up to date with `wn`: built by the first validation, then
+    /// caught up with each change from the change log, so validating after an edit only
+    /// rechecks what it touched. Dropped on `reload`, which replaces `wn` wholesale.
+    validator: Option<IncrementalValidator>,
 }
 
 /// Best-effort snapshot of `path`'s current mtime - `None` rather than an error, since a
@@ -136,10 +151,33 @@ struct SaveParams {
     force: bool,
 }
 
+#[derive(Deserialize, schemars::JsonSchema)]
+struct UndoParams {
+    /// The id of the change to undo, as reported by `history`. Undoes the most recent change
+    /// not already undone if omitted.
+    #[serde(default)]
+    id: Option<u64>,
+}
+
+#[derive(Deserialize, schemars::JsonSchema)]
+struct HistoryParams {
+    /// Maximum number of changes to return, newest first. Defaults to 20.
+    #[serde(default)]
+    limit: Option<usize>,
+    /// Only return changes older than this id, to page back through the history.
+    #[serde(default)]
+    before: Option<u64>,
+}
+
 #[derive(Serialize)]
 struct ValidateReport {
     count: usize,
     errors: Vec<String>,
+    /// Checks the project's validation profile only warns about - these don't block a save.
+    warnings: Vec<String>,
+    /// The errors then warnings again, each with its stable code, fields and the source file
+    /// and line of the synset, sense or entry at fault (as `ewe validate --format json`).
+    diagnostics: Vec<Diagnostic>,
 }
 
 #[derive(Serialize)]
@@ -148,6 +186,7 @@ struct DryRunReport {
     apply_error: Option<String>,
     last_synset_id: Option<String>,
     validation_errors: Vec<String>,
+    validation_warnings: Vec<String>,
 }
 
 #[derive(Serialize)]
@@ -159,7 +198,33 @@ struct ApplyReport {
     stale: bool,
     last_synset_id: Option<String>,
     validation_errors: Vec<String>,
+    validation_warnings: Vec<String>,
+    change_summaries: Vec<String>,
+}
+
+#[derive(Serialize)]
+struct UndoReport {
+    /// The id of the change undone (or, for `redo`, re-applied) - `None` if there was nothing
+    /// to undo/redo, in which case nothing else happened either.
+    change_id: Option<u64>,
+    saved: bool,
+    /// As for `apply_automaton`.
+    stale: bool,
+    validation_errors: Vec<String>,
+    validation_warnings: Vec<String>,
+}
+
+#[derive(Serialize)]
+struct HistoryItem {
+    id: u64,
+    timestamp_ms: u64,
     change_summaries: Vec<String>,
+    /// Whether `undo` can be called on this change.
+    undoable: bool,
+    /// The change this one undid, if it was made by `undo`.
+    undo_of: Option<u64>,
+    /// The undo this one reversed, if it was made by `redo`.
+    redo_of: Option<u64>,
 }
 
 #[derive(Serialize)]
@@ -170,6 +235,7 @@ struct SaveReport {
     /// pass `force: true` to save over it anyway).
     stale: bool,
     validation_errors: Vec<String>,
+    validation_warnings: Vec<String>,
 }
 
 #[derive(Serialize)]
@@ -180,13 +246,48 @@ struct ReloadReport {
     discarded_unsaved_changes: bool,
 }
 
-fn validation_errors_of(wn: &LexiconHashMapBackend) -> Result<Vec<String>, String> {
-    let mut progress = NullProgress;
-    Ok(ewe_lib::validate::validate(wn, &mut progress)
-        .map_err(|e| e.to_string())?
-        .iter()
-        .map(|e| e.to_string())
-        .collect())
+/// Everything validating `wn` finds, followed by every breach of `rules` and what the opt-in
+/// checks `profile` turns on find (over the whole lexicon, so only if it turns any on). Catches
+/// `validator` up with `wn`'s changes, or builds it with a full validation (and a full check of
+/// `rules`) if there isn't one yet.
+fn found_in(
+    wn: &LexiconHashMapBackend,
+    validator: &mut Option<IncrementalValidator>,
+    profile: &ValidationProfile,
+    rules: &UserRules,
+) -> Result<Vec<ValidationError>, String> {
+    let validator = match validator {
+        Some(validator) => {
+            validator.catch_up(wn)?;
+            validator
+        }
+        None => {
+            let mut built =
+                IncrementalValidator::new(wn, &mut NullProgress).map_err(|e| e.to_string())?;
+            built.set_rules(wn, rules.clone()).map_err(|e| e.to_string())?;
+            validator.insert(built)
+        }
+    };
+    let mut found = validator.errors();
+    if profile.opts_in() {
+        found.extend(opt_in_errors(wn, profile, &mut NullProgress).map_err(|e| e.to_string())?);
+    }
+    Ok(found)
+}
+
+/// Validates `wn` (see `found_in`), returning `(errors, warnings)` as sorted by `profile` -
+/// only the errors block a save.
+fn validation_errors_of(
+    wn: &LexiconHashMapBackend,
+    validator: &mut Option<IncrementalValidator>,
+    profile: &ValidationProfile,
+    rules: &UserRules,
+) -> Result<(Vec<String>, Vec<String>), String> {
+    let (errors, warnings) = profile.classify(found_in(wn, validator, profile, rules)?);
+    Ok((
+        errors.iter().map(|e| e.to_string()).collect(),
+        warnings.iter().map(|e| e.to_string()).collect(),
+    ))
 }
 
 /// Whether the wordnet on disk at `state.path` has changed since this server last
@@ -203,9 +304,13 @@ fn is_stale(state: &ServerState) -> bool {
 /// Save `state.wn` to `state.path` unless validation errors or on-disk staleness block it
 /// (in which case `force` overrides both). Shared by the `save` tool and `apply_automaton`'s
 /// auto-save so the two can't drift out of sync on what "safe to save" means. Returns
-/// `(saved, validation_errors, stale)`.
-fn perform_save(state: &mut ServerState, force: bool) -> Result<(bool, Vec<String>, bool), String> {
-    let validation_errors = validation_errors_of(&state.wn)?;
+/// `(saved, validation_errors, validation_warnings, stale)`.
+fn perform_save(
+    state: &mut ServerState,
+    force: bool,
+) -> Result<(bool, Vec<String>, Vec<String>, bool), String> {
+    let (validation_errors, validation_warnings) =
+        validation_errors_of(&state.wn, &mut state.validator, &state.profile, &state.rules)?;
     let stale = is_stale(state);
     let saved = if (validation_errors.is_empty() && !stale) || force {
         let mut save_progress = NullProgress;
@@ -218,7 +323,23 @@ fn perform_save(state: &mut ServerState, force: bool) -> Result<(bool, Vec<Strin
     } else {
         false
     };
-    Ok((saved, validation_errors, stale && !saved))
+    Ok((saved, validation_errors, validation_warnings, stale && !saved))
+}
+
+/// Saves after an `undo`/`redo` that did something, and reports on it.
+fn undo_report(state: &mut ServerState, change_id: Option<u64>) -> Result<String, String> {
+    let (saved, validation_errors, validation_warnings, stale) = match change_id {
+        Some(_) => perform_save(state, false)?,
+        None => (false, Vec::new(), Vec::new(), false),
+    };
+    let report = UndoReport {
+        change_id,
+        saved,
+        stale,
+        validation_errors,
+        validation_warnings,
+    };
+    serde_json::to_string(&report).map_err(|e| e.to_string())
 }
 
 #[tool_router(server_handler)]
@@ -323,13 +444,22 @@ impl EweMcpServer {
         serde_json::to_string(&matches).map_err(|e| e.to_string())
     }
 
-    #[tool(description = "Run full validation over the loaded wordnet and return any errors found.")]
+    #[tool(
+        description = "Run full validation over the loaded wordnet, including the project's own \
+        rules from its `validation-rules.yaml` and any opt-in checks its validation profile turns \
+        on, and return any errors found, plus any warnings (checks the profile reports without \
+        letting them block a save)."
+    )]
     fn validate(&self) -> Result<String, String> {
-        let state = self.state.lock().unwrap();
-        let errors = validation_errors_of(&state.wn)?;
+        let mut guard = self.state.lock().unwrap();
+        let state = &mut *guard;
+        let found = found_in(&state.wn, &mut state.validator, &state.profile, &state.rules)?;
+        let (errors, warnings) = state.profile.classify(found.clone());
         let report = ValidateReport {
             count: errors.len(),
-            errors,
+            errors: errors.iter().map(|e| e.to_string()).collect(),
+            warnings: warnings.iter().map(|e| e.to_string()).collect(),
+            diagnostics: diagnostics(&state.wn, &state.path, &state.profile, found),
         };
         serde_json::to_string(&report).map_err(|e| e.to_string())
     }
@@ -348,7 +478,7 @@ impl EweMcpServer {
         &self,
         Parameters(ApplyAutomatonParams { actions, dry_run }): Parameters<ApplyAutomatonParams>,
     ) -> Result<String, String> {
-        if actions.iter().any(|a| matches!(a, Action::Validate)) {
+        if actions.iter().any(|a| matches!(a.unguarded(), Action::Validate)) {
             return Err(
                 "Action batches may not contain a `validate` action - call the `validate` tool instead."
                     .to_string(),
@@ -365,31 +495,44 @@ impl EweMcpServer {
         if dry_run {
             let mut scratch_wn = state.wn.clone();
             let mut scratch_changes = ChangeList::new();
+            let mut scratch_validator = state.validator.clone();
             let (would_succeed, apply_error, last_synset_id) =
-                match ewe_lib::automaton::apply_automaton(actions, &mut scratch_wn, &mut scratch_changes)
-                {
+                match ewe_lib::automaton::apply_automaton_with_profile(
+                    actions,
+                    &mut scratch_wn,
+                    &mut scratch_changes,
+                    &state.profile,
+                ) {
                     Ok((last_synset_id, _)) => (true, None, last_synset_id),
                     Err(e) => (false, Some(e), None),
                 };
-            let validation_errors = validation_errors_of(&scratch_wn).unwrap_or_default();
+            let (validation_errors, validation_warnings) = validation_errors_of(
+                &scratch_wn,
+                &mut scratch_validator,
+                &state.profile,
+                &state.rules,
+            )
+            .unwrap_or_default();
             let report = DryRunReport {
                 would_succeed,
                 apply_error,
                 last_synset_id: last_synset_id.map(|id| id.as_str().to_string()),
                 validation_errors,
+                validation_warnings,
             };
             return serde_json::to_string(&report).map_err(|e| e.to_string());
         }
 
         let change_summaries: Vec<String> = actions.iter().map(|a| a.summary()).collect();
-        let (last_synset_id, _) = ewe_lib::automaton::apply_automaton(
+        let (last_synset_id, _) = ewe_lib::automaton::apply_automaton_with_profile(
             actions,
             &mut state.wn,
             &mut state.changes,
+            &state.profile,
         )?;
         let last_synset_id = last_synset_id.map(|id| id.as_str().to_string());
 
-        let (saved, validation_errors, stale) = perform_save(state, false)?;
+        let (saved, validation_errors, validation_warnings, stale) = perform_save(state, false)?;
 
         let report = ApplyReport {
             applied: true,
@@ -397,26 +540,83 @@ impl EweMcpServer {
             stale,
             last_synset_id,
             validation_errors,
+            validation_warnings,
             change_summaries,
         };
         serde_json::to_string(&report).map_err(|e| e.to_string())
     }
 
+    #[tool(
+        description = "List the batches of changes applied to the loaded wordnet in this \
+        session, newest first, with the id `undo` takes for each."
+    )]
+    fn history(
+        &self,
+        Parameters(HistoryParams { limit, before }): Parameters<HistoryParams>,
+    ) -> Result<String, String> {
+        let state = self.state.lock().unwrap();
+        let items: Vec<HistoryItem> =
+            ewe_lib::automaton::changelog_recent(&state.wn, limit.unwrap_or(20), before)?
+                .into_iter()
+                .map(|(id, entry)| HistoryItem {
+                    id,
+                    timestamp_ms: entry.timestamp_ms,
+                    change_summaries: entry.actions.iter().map(|a| a.summary()).collect(),
+                    undoable: entry.is_undoable(),
+                    undo_of: entry.undo_of,
+                    redo_of: entry.redo_of,
+                })
+                .collect();
+        serde_json::to_string(&items).map_err(|e| e.to_string())
+    }
+
+    #[tool(
+        description = "Undo a batch of changes: the one with the given id (see `history`), or \
+        the most recent one not already undone. The undo is itself recorded as a new change, \
+        which `redo` reverses. Saves afterwards on the same terms as `apply_automaton`."
+    )]
+    fn undo(&self, Parameters(UndoParams { id }): Parameters<UndoParams>) -> Result<String, String> {
+        let mut guard = self.state.lock().unwrap();
+        let state = &mut *guard;
+        let change_id = match id {
+            Some(id) => {
+                ewe_lib::undo::undo(&mut state.wn, id, &mut state.changes)?;
+                Some(id)
+            }
+            None => ewe_lib::undo::undo_last(&mut state.wn, &mut state.changes)?,
+        };
+        undo_report(state, change_id)
+    }
+
+    #[tool(
+        description = "Re-apply the change most recently undone, as long as nothing else has \
+        changed since. Saves afterwards on the same terms as `apply_automaton`."
+    )]
+    fn redo(&self) -> Result<String, String> {
+        let mut guard = self.state.lock().unwrap();
+        let state = &mut *guard;
+        let change_id = ewe_lib::undo::redo_last(&mut state.wn, &mut state.changes)?;
+        undo_report(state, change_id)
+    }
+
     #[tool(
         description = "Persist any pending in-memory changes to disk. Skips saving (returning the \
-        validation errors instead) unless the wordnet currently validates cleanly or `force` is \
-        set. Also skips saving - reporting `stale: true` - if the wordnet files on disk have \
+        validation errors instead) unless the wordnet currently has no validation errors or `force` \
+        is set - checks the project's validation profile (the `[validation]` table of its \
+        settings.toml) marks as warnings are reported but don't block a save. Also skips saving - reporting `stale: true` - if the wordnet files on disk have \
         changed since this server last loaded/saved them (e.g. a `git checkout`/branch switch/ \
         hand edit made outside this MCP session); call `reload` first to pick up that change, or \
         pass `force: true` to save over it anyway."
     )]
     fn save(&self, Parameters(SaveParams { force }): Parameters<SaveParams>) -> Result<String, String> {
         let mut guard = self.state.lock().unwrap();
-        let (saved, validation_errors, stale) = perform_save(&mut guard, force)?;
+        let (saved, validation_errors, validation_warnings, stale) =
+            perform_save(&mut guard, force)?;
         let report = SaveReport {
             saved,
             stale,
             validation_errors,
+            validation_warnings,
         };
         serde_json::to_string(&report).map_err(|e| e.to_string())
     }
@@ -436,8 +636,13 @@ impl EweMcpServer {
         let wn = LexiconHashMapBackend::new()
             .load(&guard.path, &mut progress)
             .map_err(|e| e.to_string())?;
+        let profile = ValidationProfile::for_source(&guard.path)?;
+        let rules = UserRules::for_source(&guard.path)?;
         let discarded_unsaved_changes = guard.changes.changed();
         guard.wn = wn;
+        guard.profile = profile;
+        guard.rules = rules;
+        guard.validator = None;
         guard.changes = ChangeList::new();
         guard.loaded_mtime = source_mtime(&guard.path);
         let report = ReloadReport {
@@ -474,6 +679,9 @@ mod tests {
                 wn,
                 changes: ChangeList::new(),
                 loaded_mtime,
+                profile: ValidationProfile::default(),
+                rules: UserRules::default(),
+                validator: None,
             })),
         }
     }
@@ -485,6 +693,7 @@ mod tests {
             pos: Some(PosKey::new("n".to_string())),
             lemmas: vec![lemma.to_string()],
             subcats: Vec::new(),
+            name: None,
         }]
     }
 
@@ -546,6 +755,74 @@ mod tests {
         std::fs::remove_dir_all(&dir).ok();
     }
 
+    #[test]
+    fn validation_profile_warnings_do_not_block_save() {
+        let dir = isolated_test_wn_dir("validation_profile_warnings");
+        let path = dir.to_string_lossy().to_string();
+        let server = test_server(path);
+        // The seed synset `test_server` adds has no members, hence switching `EmptySynset` off.
+        server.state.lock().unwrap().profile = ValidationProfile::from_settings(
+            "[validation]\nNoHypernym = \"warning\"\nEmptySynset = \"off\"\n",
+        )
+        .unwrap();
+
+        let apply_result = server
+            .apply_automaton(Parameters(ApplyAutomatonParams {
+                actions: add_synset_action("warningcat"),
+                dry_run: false,
+            }))
+            .unwrap();
+        assert!(apply_result.contains("\"saved\":true"), "{}", apply_result);
+        assert!(apply_result.contains("\"validation_errors\":[]"), "{}", apply_result);
+        assert!(apply_result.contains("No hypernym for"), "{}", apply_result);
+        assert!(!apply_result.contains("Empty synset"), "{}", apply_result);
+
+        let validate_result = server.validate().unwrap();
+        assert!(validate_result.contains("\"count\":0"), "{}", validate_result);
+        assert!(validate_result.contains("\"code\":\"EWE022\""), "{}", validate_result);
+        assert!(validate_result.contains("\"severity\":\"warning\""), "{}", validate_result);
+
+        std::fs::remove_dir_all(dir.parent().unwrap().parent().unwrap()).ok();
+    }
+
+    #[test]
+    fn undo_and_redo_last_change() {
+        // Nothing here validates (no hypernyms), so nothing is ever saved to this path.
+        let server = test_server("/nonexistent/does-not-matter".to_string());
+        let lookup = |server: &EweMcpServer| {
+            server
+                .lookup_word(Parameters(LookupWordParams {
+                    word: "undotestcat".to_string(),
+                    ignore_case: false,
+                    sense_ids: false,
+                }))
+                .unwrap()
+        };
+
+        server
+            .apply_automaton(Parameters(ApplyAutomatonParams {
+                actions: add_synset_action("undotestcat"),
+                dry_run: false,
+            }))
+            .unwrap();
+        let history = server
+            .history(Parameters(HistoryParams { limit: None, before: None }))
+            .unwrap();
+        assert!(history.contains("\"undoable\":true"), "{}", history);
+
+        let undo_result = server.undo(Parameters(UndoParams { id: None })).unwrap();
+        assert!(undo_result.contains("\"change_id\":0"), "{}", undo_result);
+        assert_eq!(lookup(&server), "[]", "undo should remove the added synset");
+
+        let undo_result = server.undo(Parameters(UndoParams { id: None })).unwrap();
+        assert!(undo_result.contains("\"change_id\":null"), "{}", undo_result);
+        assert!(server.undo(Parameters(UndoParams { id: Some(0) })).is_err());
+
+        let redo_result = server.redo().unwrap();
+        assert!(redo_result.contains("\"change_id\":0"), "{}", redo_result);
+        assert_ne!(lookup(&server), "[]", "redo should bring the synset back");
+    }
+
     /// A fresh, uniquely-named `<temp>/<name>/src/yaml` directory to load/save a test
     /// wordnet against - nested under its own unique parent (rather than a bare
     /// `<temp>/<name>`) so that `Lexicon::save`'s sibling `../deprecations.csv` write
@@ -573,6 +850,9 @@ mod tests {
             wn: LexiconHashMapBackend::new(),
             changes: ChangeList::new(),
             loaded_mtime: source_mtime(&path),
+            profile: ValidationProfile::default(),
+            rules: UserRules::default(),
+            validator: None,
         };
         assert!(
             !is_stale(&state),
@@ -586,12 +866,12 @@ mod tests {
             "a file written after loaded_mtime must be detected as stale"
         );
 
-        let (saved, validation_errors, stale) = perform_save(&mut state, false).unwrap();
+        let (saved, validation_errors, _, stale) = perform_save(&mut state, false).unwrap();
         assert!(!saved, "save must refuse to write over an unseen on-disk change");
         assert!(validation_errors.is_empty());
         assert!(stale, "the report must say staleness is why save was skipped");
 
-        let (saved, _validation_errors, stale) = perform_save(&mut state, true).unwrap();
+        let (saved, _validation_errors, _, stale) = perform_save(&mut state, true).unwrap();
         assert!(saved, "force must override staleness (same escape hatch as validation errors)");
         assert!(!stale, "a successful save is never reported as stale");
 
@@ -646,6 +926,8 @@ async fn main() -> anyhow::Result<()> {
     let cli = Cli::parse();
     let (path, wn) = locate_wordnet(cli.wordnet).map_err(|e| anyhow::anyhow!(e))?;
     let loaded_mtime = source_mtime(&path);
+    let profile = ValidationProfile::for_source(&path).map_err(|e| anyhow::anyhow!(e))?;
+    let rules = UserRules::for_source(&path).map_err(|e| anyhow::anyhow!(e))?;
 
     let server = EweMcpServer {
         state: Arc::new(Mutex::new(ServerState {
@@ -653,6 +935,9 @@ async fn main() -> anyhow::Result<()> {
             wn,
             changes: ChangeList::new(),
             loaded_mtime,
+            profile,
+            rules,
+            validator: None,
         })),
     };
 